@@ -0,0 +1,98 @@
+//! Benchmarks of the engine parts the generator and the solvers spend their time in, for their
+//! performance to be compared across changes with `cargo bench`.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use std::hint::black_box;
+
+use rs_sudoku::game::Game;
+use rs_sudoku::generator::{Difficulty, GenerationConfig};
+use rs_sudoku::solver::{Solver, SolverKind};
+
+/// Hard 9x9 puzzles with the fewest clues a puzzle with a single solution can have (17), one
+/// per line.
+const PUZZLES: &str = include_str!("puzzles_17.sdm");
+
+/**
+ * Returns the games of the puzzles of `PUZZLES`.
+ */
+fn puzzles() -> Vec<Game> {
+    PUZZLES
+        .lines()
+        .map(|puzzle| Game::from_puzzle(puzzle, None).unwrap())
+        .collect()
+}
+
+/**
+ * Returns a filled 9x9 grid.
+ */
+fn filled_grid() -> Game {
+    let mut game = Game::new(3, None).unwrap();
+    assert!(game.fill_rng(0));
+    game
+}
+
+fn bench_valids(c: &mut Criterion) {
+    let games = puzzles();
+    c.bench_function("valids", |b| {
+        b.iter(|| {
+            for game in games.iter() {
+                for index in 0..game.grid.len() {
+                    black_box(game.valids(index));
+                }
+            }
+        })
+    });
+}
+
+fn bench_generation(c: &mut Criterion) {
+    c.bench_function("fill_rng", |b| {
+        b.iter_batched(
+            || Game::new(3, None).unwrap(),
+            |mut game| black_box(game.fill_rng(0)),
+            BatchSize::SmallInput,
+        )
+    });
+
+    // With the settings of the generator
+    let config = GenerationConfig::default();
+    let (min_clues, max_attempts) = (
+        config.min_clues(3),
+        config.max_unfill_attempts(3, Difficulty::Medium),
+    );
+    c.bench_function("unfill", |b| {
+        b.iter_batched(
+            filled_grid,
+            |mut game| game.unfill(config.solver, min_clues, max_attempts),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn bench_solvers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solve 17 clues");
+    // Backtracking takes a tenth of a second on the whole set
+    group.sample_size(20);
+    for kind in [
+        SolverKind::Dlx,
+        SolverKind::Backtracking,
+        SolverKind::Obvious,
+        SolverKind::Human,
+    ] {
+        // The solvers giving up where their techniques are not enough are timed until then
+        group.bench_function(format!("{:?}", kind), |b| {
+            b.iter_batched(
+                puzzles,
+                |mut games| {
+                    for game in games.iter_mut() {
+                        let _ = black_box(kind.solve(game));
+                    }
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_valids, bench_generation, bench_solvers);
+criterion_main!(benches);
@@ -0,0 +1,85 @@
+/*
+ * C API of the rs-sudoku engine, see src/ffi.rs. Build the library with
+ *   cargo build --lib --release --no-default-features --features ffi
+ * and link with -lrs_sudoku.
+ *
+ * The rows, columns and values start at 0, a value of 0 standing for an empty cell. Puzzles are
+ * written one character per cell, row after row: `.` for an empty cell and the digits, then the
+ * letters from `A` for 10, for the clues.
+ */
+
+#ifndef RS_SUDOKU_H
+#define RS_SUDOKU_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Returned when the call succeeded. */
+#define SUDOKU_OK 0
+/* The value is not in the range of the grid. */
+#define SUDOKU_ILLEGAL_VALUE -1
+/* The value conflicts with its row, column or box. */
+#define SUDOKU_INVALID_VALUE -2
+/* The row or the column is out of the grid. */
+#define SUDOKU_ILLEGAL_POSITION -3
+/* The cell holds a clue. */
+#define SUDOKU_NON_EMPTY_CELL -4
+/* The grid has no solution. */
+#define SUDOKU_NO_SOLUTION -5
+/* The game pointer is null. */
+#define SUDOKU_NULL_GAME -6
+/* Any other error of the engine. */
+#define SUDOKU_ERROR -7
+
+/* Difficulties of sudoku_generate. */
+#define SUDOKU_EASY 0
+#define SUDOKU_MEDIUM 1
+#define SUDOKU_HARD 2
+#define SUDOKU_EXPERT 3
+
+/* A game, released with sudoku_free. */
+typedef struct SudokuGame SudokuGame;
+
+/* Creates a game from a puzzle, or returns NULL if it does not describe a grid. */
+SudokuGame *sudoku_new(const char *puzzle);
+
+/* Generates a puzzle of the given size (2 to 4, 3 for the usual 9x9 grid) and difficulty, always
+ * the same one for a given seed. Returns NULL if the size or the difficulty is out of range. */
+SudokuGame *sudoku_generate(size_t size, int difficulty, uint64_t seed);
+
+/* Releases a game. */
+void sudoku_free(SudokuGame *game);
+
+/* Returns the number of rows of the grid, or 0 if game is NULL. */
+size_t sudoku_side(const SudokuGame *game);
+
+/* Returns the value of a cell, 0 when it is empty, or an error code. */
+int sudoku_value(const SudokuGame *game, size_t row, size_t column);
+
+/* Places a value in a cell if it does not conflict with its row, column and box, 0 clearing the
+ * cell. Returns SUDOKU_OK or why the move was refused. */
+int sudoku_do_move(SudokuGame *game, size_t row, size_t column, uint8_t value);
+
+/* Fills the grid with its solution. Returns SUDOKU_OK, or SUDOKU_NO_SOLUTION leaving the grid as
+ * it was. */
+int sudoku_solve(SudokuGame *game);
+
+/* Returns whether the grid is completed without conflicts. */
+bool sudoku_is_done(const SudokuGame *game);
+
+/* Returns the puzzle of the game, to release with sudoku_string_free. */
+char *sudoku_puzzle(const SudokuGame *game);
+
+/* Releases a string returned by the API. */
+void sudoku_string_free(char *string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* RS_SUDOKU_H */
@@ -0,0 +1,161 @@
+use crate::errors::GameError;
+use crate::game::{Cell, Game};
+use crate::killer::Cage;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/**
+ * A group of cells with a label, such as a cage and its sum.
+ */
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Region {
+    /// Indexes of the cells of the region.
+    pub cells: Vec<usize>,
+    /// Text shown with the region, possibly empty.
+    pub label: String,
+}
+
+/**
+ * Extra data a setter attached to a puzzle, drawn over the grid.
+ */
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, Debug)]
+pub struct Annotations {
+    /// Cages, drawn as dotted outlines with their label in their first cell.
+    pub cages: Vec<Region>,
+    /// Texts written in cells.
+    pub texts: Vec<Region>,
+    /// Background colors of cells, as `#RRGGBB` or `#RRGGBBAA`.
+    pub colors: Vec<(usize, String)>,
+}
+
+impl Annotations {
+    pub fn is_empty(&self) -> bool {
+        self.cages.is_empty() && self.texts.is_empty() && self.colors.is_empty()
+    }
+}
+
+/// A cell of an f-puzzles grid.
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct FCell {
+    value: Option<u8>,
+    given: bool,
+    center_pencil_marks: Vec<u8>,
+    given_pencil_marks: Vec<u8>,
+    c: Option<String>,
+}
+
+/// A group of cells of an f-puzzles grid, its value being either a string or a number.
+#[derive(Deserialize)]
+struct FRegion {
+    cells: Vec<String>,
+    #[serde(default)]
+    value: Option<Value>,
+}
+
+/// A puzzle in the f-puzzles JSON format.
+#[derive(Deserialize)]
+struct FPuzzle {
+    size: usize,
+    grid: Vec<Vec<FCell>>,
+    #[serde(default)]
+    killercage: Vec<FRegion>,
+    #[serde(default)]
+    cage: Vec<FRegion>,
+    #[serde(default)]
+    text: Vec<FRegion>,
+}
+
+/**
+ * Returns the index of the cell written `R<row>C<column>`, one based, in a grid of `side_size`.
+ */
+fn parse_cell(cell: &str, side_size: usize) -> Option<usize> {
+    let cell = cell.to_ascii_uppercase();
+    let (row, column) = cell.strip_prefix('R')?.split_once('C')?;
+    let (row, column) = (row.parse::<usize>().ok()?, column.parse::<usize>().ok()?);
+    match (1..=side_size).contains(&row) && (1..=side_size).contains(&column) {
+        true => Some((row - 1) * side_size + column - 1),
+        false => None,
+    }
+}
+
+/**
+ * Converts f-puzzles regions, dropping the ones with invalid cells.
+ */
+fn regions(regions: Vec<FRegion>, side_size: usize) -> Vec<Region> {
+    regions
+        .into_iter()
+        .filter_map(|region| {
+            let cells = region
+                .cells
+                .iter()
+                .map(|c| parse_cell(c, side_size))
+                .collect::<Option<Vec<usize>>>()?;
+            let label = match region.value {
+                Some(Value::String(s)) => s,
+                Some(Value::Number(n)) => n.to_string(),
+                _ => String::new(),
+            };
+            Some(Region { cells, label })
+        })
+        .collect()
+}
+
+/**
+ * Creates a game from a puzzle in the f-puzzles JSON format, keeping its given candidates as
+ * notes, its killer cages with a sum as cages of the game and its other cages, texts and colors
+ * as annotations.
+ */
+pub fn import_fpuzzles(json: &str, saving_path: Option<&str>) -> Result<Game, GameError> {
+    let puzzle: FPuzzle = serde_json::from_str(json).map_err(|_| GameError::InvalidPuzzle)?;
+    let size = (2..=5)
+        .find(|size: &usize| size * size == puzzle.size)
+        .ok_or(GameError::InvalidPuzzle)?;
+    if puzzle.grid.len() != puzzle.size || puzzle.grid.iter().any(|r| r.len() != puzzle.size) {
+        return Err(GameError::InvalidPuzzle);
+    }
+
+    let mut cells = Vec::with_capacity(puzzle.size * puzzle.size);
+    let mut annotations = Annotations::default();
+    for (i, cell) in puzzle.grid.iter().flatten().enumerate() {
+        let value = cell.value.unwrap_or(0);
+        if value as usize > puzzle.size {
+            return Err(GameError::InvalidPuzzle);
+        }
+
+        let mut new_cell = Cell::new(value, cell.given && value != 0);
+        let notes = cell
+            .center_pencil_marks
+            .iter()
+            .chain(cell.given_pencil_marks.iter())
+            .filter(|n| (1..=puzzle.size).contains(&(**n as usize)))
+            .fold(0, |notes, n| notes | 1 << n);
+        new_cell.set_notes(notes);
+        cells.push(new_cell);
+
+        if let Some(color) = cell.c.as_ref() {
+            annotations.colors.push((i, color.clone()));
+        }
+    }
+
+    let mut cages = Vec::new();
+    for region in regions(puzzle.killercage, puzzle.size) {
+        match Cage::from_region(&region, puzzle.size) {
+            Some(cage) => cages.push(cage),
+            None => annotations.cages.push(region),
+        }
+    }
+    annotations.cages.extend(regions(puzzle.cage, puzzle.size));
+    annotations.texts = regions(puzzle.text, puzzle.size);
+
+    // The save file is only created once the whole puzzle is known to be valid
+    let mut game = Game::new(size, saving_path)?;
+    game.grid = cells;
+    game.cages = cages;
+    if !annotations.is_empty() {
+        game.annotations = Some(annotations);
+    }
+
+    Ok(game)
+}
@@ -0,0 +1,356 @@
+use crate::export::ExportOptions;
+use crate::generator::{self, Difficulty};
+use crate::server;
+use crate::solver::SolverKind;
+
+/// Usage of the program, listing its subcommands.
+pub const USAGE: &str = "Usage:
+  sudocurs play [CONFIGURATION_PATH] CLI|TUI|GUI [PUZZLE.json|PUZZLE.samurai]
+      Play at a prompt, in a full-screen terminal interface or in a window, optionally an
+      f-puzzles JSON puzzle, or a samurai puzzle at the prompt or in a window. Without a path,
+      the configuration of the platform configuration folder is used.
+  sudocurs pack play [CONFIGURATION_PATH] <FILE.pack|FILE.sdm>
+      Play the puzzles of a pack one after the other at the prompt, from the first one not
+      solved yet, keeping track of the progress in the save folder.
+  sudocurs --init-config
+      Write a commented default configuration, its assets and save folder in the platform
+      configuration folder.
+  sudocurs generate [--size <N>] [--difficulty easy|medium|hard|expert] [--count <N>]
+                    [--out <PATH>] [--format lines|json]
+      Generate puzzles, one per line or as a JSON array with their solution and rating, written
+      to PATH or printed.
+  sudocurs solve <PUZZLE|COLLECTION_PATH|FILE.sdk|FILE.game|FILE.csv|FILE.samurai>
+                 [--solver dlx|backtracking|obvious|human] [--trace] [--explain]
+      Print the solution of each puzzle and the time taken, along with the techniques solving it
+      step by step with --trace, and the candidates each step places or removes with --explain.
+      Exits with an error status if a puzzle cannot be solved.
+  sudocurs check <PUZZLE|COLLECTION_PATH|FILE.sdk|FILE.game|FILE.csv> [--json]
+      Tell whether the clues of each puzzle conflict and whether it has no, one or several
+      solutions, as text or as a JSON array.
+  sudocurs print <PUZZLE|COLLECTION_PATH> [--with-solutions] [--ascii]
+      Render puzzles as text for printing.
+  sudocurs export <PUZZLE|FILE.sdk|FILE.game|FILE.csv> <OUT.svg|OUT.pdf|OUT.csv>
+                  [--cell-size <N>] [--font <FAMILY>] [--candidates]
+      Draw a grid, with the values placed in a save, as an SVG image or a PDF document, the
+      empty cells showing their candidates with --candidates, or write it as CSV for the
+      spreadsheets.
+  sudocurs import-code <CODE> [--out <FILE.game>]
+      Print the puzzle of a share code, as copied with Ctrl+C in the window, or write it as a
+      save to resume.
+  sudocurs replay <FILE.game>
+      Print the grid of a save after each of its moves, with the play time it was made at.
+  sudocurs serve [--address <HOST:PORT>]
+      Play games for web front ends or bots over an HTTP/JSON API (POST /new, /move, /hint and
+      /solve), on 127.0.0.1:8080 unless another address is given.
+
+'sudocurs [CONFIGURATION_PATH] CLI|TUI|GUI [PUZZLE.json]' is short for 'sudocurs play ...'.";
+
+/**
+ * The interface a game is played in.
+ */
+#[derive(Debug, PartialEq, Eq)]
+pub enum Mode {
+    Cli,
+    Tui,
+    Gui,
+}
+
+/**
+ * How generated puzzles are written.
+ */
+#[derive(Debug, Default, PartialEq, Eq)]
+pub enum BatchFormat {
+    /// One puzzle per line, as in the `.sdm` packs.
+    #[default]
+    Lines,
+    /// A JSON array of the puzzles along with their solution, difficulty and rating.
+    Json,
+}
+
+/**
+ * What the program was asked to do.
+ */
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    /// Play with the given configuration file, or the one of the platform configuration folder,
+    /// optionally an f-puzzles JSON puzzle.
+    Play {
+        config: Option<String>,
+        mode: Mode,
+        puzzle: Option<String>,
+    },
+    /// Generate `count` puzzles, writing them to `out` or printing them.
+    Generate {
+        size: usize,
+        difficulty: Difficulty,
+        count: usize,
+        out: Option<String>,
+        format: BatchFormat,
+    },
+    /// Print the solutions of the puzzles of `source` found by `solver`, see `print::load_games`,
+    /// along with the hints solving them if `trace` is set, and the steps of `solver` solving
+    /// them with human techniques if `explain` is set.
+    Solve {
+        source: String,
+        solver: SolverKind,
+        trace: bool,
+        explain: bool,
+    },
+    /// Tell whether the puzzles of `source` have conflicting clues and a single solution, as
+    /// JSON if `json` is set.
+    Check { source: String, json: bool },
+    /// Render the puzzles of `source` for printing.
+    Print {
+        source: String,
+        with_solutions: bool,
+        ascii: bool,
+    },
+    /// Draw the first puzzle of `source` in the file `out`.
+    Export {
+        source: String,
+        out: String,
+        options: ExportOptions,
+    },
+    /// Print the puzzle of the share code `code`, or save it in the file `out`, see
+    /// `Game::from_share_code`.
+    ImportCode { code: String, out: Option<String> },
+    /// Print the grid of the save `source` after each of its moves, see `Replay`.
+    Replay { source: String },
+    /// Answer the requests of the HTTP/JSON API on `address`, see `server::Server`.
+    Serve { address: String },
+    /// Play the puzzles of the pack `pack` at the prompt, with the given configuration file or
+    /// the one of the platform configuration folder.
+    PackPlay {
+        config: Option<String>,
+        pack: String,
+    },
+    /// Write the default configuration in the platform configuration folder.
+    InitConfig,
+}
+
+/**
+ * Parses the value following the option `name`.
+ */
+fn option_value<T: std::str::FromStr>(name: &str, value: Option<&String>) -> Result<T, String> {
+    value
+        .and_then(|v| v.parse::<T>().ok())
+        .ok_or_else(|| format!("Option '{}' expects a valid value.", name))
+}
+
+/**
+ * Parses the command line arguments, the program name excluded. Errors explain what is wrong,
+ * to be shown along with `USAGE`.
+ */
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    let words: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+    match words.as_slice() {
+        ["play", rest @ ..] => parse_play(rest),
+        ["--init-config" | "init-config"] => Ok(Command::InitConfig),
+        ["generate", ..] => {
+            let (mut size, mut difficulty, mut count, mut out, mut format) =
+                (3, Difficulty::default(), 1, None, BatchFormat::default());
+            let mut options = args[1..].iter();
+            while let Some(option) = options.next() {
+                match option.as_str() {
+                    "--size" => size = option_value("--size", options.next())?,
+                    "--difficulty" => {
+                        difficulty =
+                            match options.next().map(|d| d.as_str()) {
+                                Some("easy") => Difficulty::Easy,
+                                Some("medium") => Difficulty::Medium,
+                                Some("hard") => Difficulty::Hard,
+                                Some("expert") => Difficulty::Expert,
+                                _ => return Err(String::from(
+                                    "Option '--difficulty' expects easy, medium, hard or expert.",
+                                )),
+                            }
+                    }
+                    "--count" => count = option_value("--count", options.next())?,
+                    "--out" => out = Some(option_value("--out", options.next())?),
+                    "--format" => {
+                        format = match options.next().map(|f| f.as_str()) {
+                            Some("lines") => BatchFormat::Lines,
+                            Some("json") => BatchFormat::Json,
+                            _ => {
+                                return Err(String::from(
+                                    "Option '--format' expects lines or json.",
+                                ))
+                            }
+                        }
+                    }
+                    _ => return Err(format!("Unknown option '{}'.", option)),
+                }
+            }
+            if !generator::SIZES.contains(&size) {
+                return Err(String::from("The size must be between 2 and 4."));
+            }
+            Ok(Command::Generate {
+                size,
+                difficulty,
+                count,
+                out,
+                format,
+            })
+        }
+        ["solve", source, ..] => {
+            let (mut solver, mut trace, mut explain) = (SolverKind::default(), false, false);
+            let mut options = args[2..].iter();
+            while let Some(option) = options.next() {
+                match option.as_str() {
+                    "--solver" => {
+                        solver = match options.next().map(|s| s.as_str()) {
+                            Some("dlx") => SolverKind::Dlx,
+                            Some("backtracking") => SolverKind::Backtracking,
+                            Some("obvious") => SolverKind::Obvious,
+                            Some("human") => SolverKind::Human,
+                            _ => return Err(String::from(
+                                "Option '--solver' expects dlx, backtracking, obvious or human.",
+                            )),
+                        }
+                    }
+                    "--trace" => trace = true,
+                    "--explain" => explain = true,
+                    _ => return Err(format!("Unknown option '{}'.", option)),
+                }
+            }
+            Ok(Command::Solve {
+                source: source.to_string(),
+                solver,
+                trace,
+                explain,
+            })
+        }
+        ["check", source, options @ ..] => {
+            let mut json = false;
+            for option in options {
+                match *option {
+                    "--json" => json = true,
+                    _ => return Err(format!("Unknown option '{}'.", option)),
+                }
+            }
+            Ok(Command::Check {
+                source: source.to_string(),
+                json,
+            })
+        }
+        ["print", source, options @ ..] => {
+            let (mut with_solutions, mut ascii) = (false, false);
+            for option in options {
+                match *option {
+                    "--with-solutions" => with_solutions = true,
+                    "--ascii" => ascii = true,
+                    _ => return Err(format!("Unknown option '{}'.", option)),
+                }
+            }
+            Ok(Command::Print {
+                source: source.to_string(),
+                with_solutions,
+                ascii,
+            })
+        }
+        ["export", source, out, ..] => {
+            let mut options = ExportOptions::default();
+            let mut words = args[3..].iter();
+            while let Some(option) = words.next() {
+                match option.as_str() {
+                    "--cell-size" => options.cell_size = option_value("--cell-size", words.next())?,
+                    "--font" => options.font = option_value("--font", words.next())?,
+                    "--candidates" => options.candidates = true,
+                    _ => return Err(format!("Unknown option '{}'.", option)),
+                }
+            }
+            if options.cell_size == 0 {
+                return Err(String::from("The cell size must not be 0."));
+            }
+            Ok(Command::Export {
+                source: source.to_string(),
+                out: out.to_string(),
+                options,
+            })
+        }
+        ["import-code", code, ..] => {
+            let mut out = None;
+            let mut options = args[2..].iter();
+            while let Some(option) = options.next() {
+                match option.as_str() {
+                    "--out" => out = Some(option_value("--out", options.next())?),
+                    _ => return Err(format!("Unknown option '{}'.", option)),
+                }
+            }
+            Ok(Command::ImportCode {
+                code: code.to_string(),
+                out,
+            })
+        }
+        ["import-code"] => Err(String::from("'import-code' expects a share code.")),
+        ["replay", source] => Ok(Command::Replay {
+            source: source.to_string(),
+        }),
+        ["replay", ..] => Err(String::from("'replay' expects a save.")),
+        ["serve"] => Ok(Command::Serve {
+            address: server::DEFAULT_ADDRESS.to_string(),
+        }),
+        ["serve", "--address", address] => Ok(Command::Serve {
+            address: address.to_string(),
+        }),
+        ["pack", "play", pack] => Ok(Command::PackPlay {
+            config: None,
+            pack: pack.to_string(),
+        }),
+        ["pack", "play", config, pack] => Ok(Command::PackPlay {
+            config: Some(config.to_string()),
+            pack: pack.to_string(),
+        }),
+        ["pack", ..] => Err(String::from(
+            "'pack play' expects an optional configuration file and a pack.",
+        )),
+        ["serve", ..] => Err(String::from(
+            "'serve' expects nothing, or '--address' and the address to serve on.",
+        )),
+        ["export", ..] => Err(String::from(
+            "'export' expects a puzzle or a save, and the file to write.",
+        )),
+        ["solve" | "check" | "print", ..] => {
+            Err(format!("'{}' expects a puzzle or a collection.", words[0]))
+        }
+        [] => Err(String::from("A command is expected.")),
+        // Without a subcommand, the arguments are the ones of 'play'
+        rest => parse_play(rest),
+    }
+}
+
+/**
+ * Parses the arguments of the `play` subcommand.
+ */
+fn parse_play(args: &[&str]) -> Result<Command, String> {
+    // The configuration is left out when the mode comes first
+    let is_mode = |word: &str| ["CLI", "TUI", "GUI"].contains(&word);
+    let (config, mode, puzzle) = match args {
+        [mode] => (None, mode, None),
+        [mode, puzzle] if is_mode(mode) => (None, mode, Some(puzzle.to_string())),
+        [config, mode] => (Some(config.to_string()), mode, None),
+        [config, mode, puzzle] => (Some(config.to_string()), mode, Some(puzzle.to_string())),
+        _ => {
+            return Err(String::from(
+                "'play' expects an optional configuration file and CLI, TUI or GUI.",
+            ))
+        }
+    };
+    let mode = match *mode {
+        "CLI" => Mode::Cli,
+        "TUI" => Mode::Tui,
+        "GUI" => Mode::Gui,
+        _ => {
+            return Err(format!(
+                "The mode should be one of 'CLI', 'TUI' or 'GUI', not '{}'.",
+                mode
+            ))
+        }
+    };
+
+    Ok(Command::Play {
+        config,
+        mode,
+        puzzle,
+    })
+}
@@ -0,0 +1,157 @@
+use sdl2::mixer::{self, Channel, Chunk, InitFlag, Music, Sdl2MixerContext, MAX_VOLUME};
+
+use crate::errors::UiError;
+use crate::events::GameEvent;
+use crate::traits::AudioConfig;
+
+/// Audio frequency, in samples per second.
+const FREQUENCY: i32 = 44_100;
+/// Samples mixed at once, a trade-off between latency and compatibility.
+const CHUNK_SIZE: i32 = 1_024;
+/// Sounds that can be played at the same time.
+const CHANNELS: i32 = 8;
+
+/**
+ * The sound effects of the game.
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Sound {
+    /// A value was placed.
+    Place,
+    /// A move or an action was refused.
+    Error,
+    /// The grid was solved.
+    Victory,
+}
+
+impl Sound {
+    /**
+     * Returns the sound of the game event, if it has one.
+     */
+    pub fn of(event: &GameEvent) -> Option<Sound> {
+        match event {
+            GameEvent::Move { .. } => Some(Sound::Place),
+            GameEvent::Error(_) => Some(Sound::Error),
+            _ => None,
+        }
+    }
+}
+
+/**
+ * Plays the configured sound effects and loops the background music, at a volume that can be
+ * muted.
+ */
+pub struct Audio {
+    place: Option<Chunk>,
+    error: Option<Chunk>,
+    victory: Option<Chunk>,
+    music: Option<Music<'static>>,
+
+    /// Volume of the sounds and the music, from 0 to 100.
+    volume: u8,
+    /// Whether the sounds and the music are silenced.
+    muted: bool,
+
+    /// Keeps the decoders of the compressed formats loaded, if they are available.
+    _context: Option<Sdl2MixerContext>,
+}
+
+/**
+ * Loads the sound effect at `path`, none when it is empty.
+ */
+fn load_sound(path: &str) -> Result<Option<Chunk>, UiError> {
+    if path.is_empty() {
+        return Ok(None);
+    }
+
+    Chunk::from_file(path)
+        .map(Some)
+        .map_err(|e| UiError::LoadSoundError(e.into()))
+}
+
+impl Audio {
+    /**
+     * Opens the audio device, loads the sounds of `config` and starts its music.
+     */
+    pub fn new(config: &AudioConfig) -> Result<Self, UiError> {
+        mixer::open_audio(
+            FREQUENCY,
+            mixer::DEFAULT_FORMAT,
+            mixer::DEFAULT_CHANNELS,
+            CHUNK_SIZE,
+        )
+        .map_err(UiError::sdl2)?;
+        mixer::allocate_channels(CHANNELS);
+
+        // The WAV sounds play without the decoders, only the compressed music needs them
+        let context = mixer::init(InitFlag::OGG | InitFlag::MP3).ok();
+        let music = match config.music_path.is_empty() {
+            true => None,
+            false => Some(
+                Music::from_file(&config.music_path)
+                    .map_err(|e| UiError::LoadSoundError(e.into()))?,
+            ),
+        };
+
+        let audio = Audio {
+            place: load_sound(&config.place_sound_path)?,
+            error: load_sound(&config.error_sound_path)?,
+            victory: load_sound(&config.victory_sound_path)?,
+            music,
+            volume: config.volume.min(100),
+            muted: config.muted,
+            _context: context,
+        };
+        audio.apply_volume();
+        if let Some(music) = audio.music.as_ref() {
+            music.play(-1).map_err(UiError::sdl2)?;
+        }
+
+        Ok(audio)
+    }
+
+    /**
+     * Plays `sound` if it is configured. A sound failing to play is not worth stopping the game,
+     * e.g. when every channel is busy.
+     */
+    pub fn play(&self, sound: Sound) {
+        let chunk = match sound {
+            Sound::Place => self.place.as_ref(),
+            Sound::Error => self.error.as_ref(),
+            Sound::Victory => self.victory.as_ref(),
+        };
+        if let Some(chunk) = chunk {
+            let _ = Channel::all().play(chunk, 0);
+        }
+    }
+
+    /**
+     * Silences the sounds and the music, or brings them back. Returns whether they are muted.
+     */
+    pub fn toggle_mute(&mut self) -> bool {
+        self.muted = !self.muted;
+        self.apply_volume();
+
+        self.muted
+    }
+
+    /**
+     * Changes the volume of the sounds and the music, from 0 to 100.
+     */
+    pub fn set_volume(&mut self, volume: u8) {
+        self.volume = volume.min(100);
+        self.apply_volume();
+    }
+
+    /**
+     * Sets the volume of the mixer from the configured one, nothing being heard while muted.
+     */
+    fn apply_volume(&self) {
+        let volume = match self.muted {
+            true => 0,
+            false => self.volume as i32 * MAX_VOLUME / 100,
+        };
+        Channel::all().set_volume(volume);
+        Music::set_volume(volume);
+    }
+}
@@ -0,0 +1,96 @@
+use crate::errors::{GameError, UiError};
+use crate::events::GameEvent;
+use crate::game::Game;
+use crate::lock;
+use crate::saves::SavedGame;
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+/// Extension of the files marking the running sessions, named after their process id.
+const SESSION_EXTENSION: &str = "session";
+
+/**
+ * Keeps track of the running session, so that the game of a session that crashed is resumed
+ * at the next launch.
+ *
+ * The games save themselves after every accepted move, and the interfaces save them again when
+ * they close for the play time. A `<pid>.session` file is written in the save folder and locked
+ * while the game runs, and removed when it exits cleanly: one left without a lock, which the
+ * system releases when the process ends, means that session crashed, the most recent save being
+ * then the game to recover.
+ */
+pub struct Autosave {
+    /// The file marking this session.
+    path: PathBuf,
+    /// The session file, locked as long as it is open, see `lock::is_held`.
+    _file: File,
+    /// The save to resume, if the previous session crashed.
+    recovered: Option<String>,
+}
+
+impl Autosave {
+    /**
+     * Starts a session saving its games in `save_folder`, looking for the sessions that crashed
+     * before.
+     */
+    pub fn start(save_folder: &str) -> Result<Self, UiError> {
+        let folder = Path::new(save_folder);
+        let mut crashed = false;
+        if let Ok(entries) = fs::read_dir(folder) {
+            for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+                if path.extension().and_then(|e| e.to_str()) != Some(SESSION_EXTENSION) {
+                    continue;
+                }
+                if !lock::is_held(&path) {
+                    crashed = true;
+                    let _ = fs::remove_file(&path);
+                }
+            }
+        }
+
+        let path = folder.join(format!("{}.{}", std::process::id(), SESSION_EXTENSION));
+        let file = File::create(&path).map_err(UiError::CreateSaveFileError)?;
+        // Already locked when another session of this process runs
+        let _ = file.try_lock();
+        let recovered = match crashed {
+            true => SavedGame::list(save_folder)
+                .into_iter()
+                .next()
+                .map(|s| s.path),
+            false => None,
+        };
+
+        Ok(Autosave {
+            path,
+            _file: file,
+            recovered,
+        })
+    }
+
+    /**
+     * Returns the save to resume because the previous session crashed, once.
+     */
+    pub fn recovered(&mut self) -> Option<String> {
+        self.recovered.take()
+    }
+
+    /**
+     * Saves `game` if it is attached to a save file.
+     */
+    pub fn save(game: &mut Game) -> Result<(), GameError> {
+        if game.save_path.is_some() {
+            game.save()?;
+            game.log(GameEvent::Autosave);
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Ends the session cleanly, its games having been saved.
+     */
+    pub fn finish(&self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
@@ -0,0 +1,26 @@
+//! The game server alone, which builds without SDL2 (`cargo run --no-default-features --bin
+//! rs-sudoku-server [HOST:PORT]`), see `rs_sudoku::server::Server`.
+
+use rs_sudoku::generator::GenerationConfig;
+use rs_sudoku::server::{self, Server};
+
+use std::env;
+use std::process;
+
+fn main() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+    let address = env::args()
+        .nth(1)
+        .unwrap_or_else(|| server::DEFAULT_ADDRESS.to_string());
+
+    match server::bind(&address) {
+        Ok(listener) => {
+            println!("Serving the games on http://{}", address);
+            Server::new(GenerationConfig::default()).serve(listener);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
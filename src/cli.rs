@@ -1,6 +1,16 @@
-use crate::errors::UiError;
-use crate::game::Game;
-use crate::solver;
+use crate::annotations;
+use crate::autosave::Autosave;
+use crate::config;
+use crate::errors::{GameError, UiError};
+use crate::favorites::Favorite;
+use crate::game::{symbol_value, CellDiff, Game, NotesClear, Validation, MARKER_COLORS};
+use crate::generator;
+use crate::hint;
+use crate::hotseat::HotSeat;
+use crate::packs::{Pack, Progress};
+use crate::profile::Profiles;
+use crate::samurai::{SamuraiGame, SIDE};
+use crate::saves::SavedGame;
 use crate::traits::{CliConfig, Ui};
 
 use colored::*;
@@ -10,6 +20,7 @@ use std::fs;
 use std::io;
 use std::io::prelude::*;
 use std::io::Write;
+use std::path::Path;
 
 #[allow(dead_code)]
 fn pause() {
@@ -24,58 +35,292 @@ fn pause() {
     let _ = stdin.read(&mut [0u8]).unwrap();
 }
 
+/// Help message listing the commands available in the CLI.
+const HELP: &str = "Commands:
+  <row> <column> <value>  Place a value, e.g. '3 5 9', replacing the one placed there if any.
+                          Values above 9 are typed as numbers or letters, '3 5 16' or '3 5 G'.
+  clear <row> <column>    Empty a cell, e.g. 'clear 3 5'.
+  <empty line>            Place a value, asking for each number separately.
+  where <n>               Show the cells where the value n can still go.
+  note <row> <column> <n> Add the note n to a cell, or remove it.
+  notes mode              Switch between placing values and notes with '<row> <column> <value>'.
+  notes                   Show the notes of the empty cells.
+  notes auto              Fill the notes of every empty cell with its candidates.
+  notes auto on|off       Keep the notes equal to the candidates after every move, or stop.
+  notes clear <which>     Clear the notes of a 'digit <n>', 'row <n>', 'column <n>' or 'box <n>',
+                          or the 'invalid' ones.
+  color <row> <column> [n]
+                          Paint a cell with the marker color n from 1 to 6, 0 removing it, or
+                          with the next color.
+  colors clear            Remove the marker colors of every cell.
+  hint, h                 Show the next logical move and the technique behind it.
+  undo, u                 Undo the last move.
+  redo, r                 Play the last undone move again.
+  rollback                Revert the last moves until the grid contains no mistake.
+  guess                   Start a guess, its moves can then be discarded all at once.
+  guess commit            End the current guess, keeping its moves.
+  guess discard           End the current guess, reverting its moves.
+  pause, p                Pause the game, hiding the grid and stopping the clock.
+  log                     Show what happened since the game was launched.
+  favorite                Add the current puzzle to the favorites, or remove it.
+  favorites               List the favorite puzzles of the profile, with their share codes.
+  favorites play <n>      Give up on this puzzle and play the favorite n.
+  giveup                  Give up on this puzzle, showing how the grid compares to the solution.
+  next                    Give up on this puzzle and start a new one with the same settings.
+  help                    Show this help.";
+
+/**
+ * The commands the user can type at the CLI prompt.
+ */
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    /// Place a value at the given (one based) row and column.
+    Move(usize, usize, u8),
+    /// Place a value, asking for the row, column and value one by one.
+    GuidedMove,
+    /// Show the cells where the value can still go.
+    Where(u8),
+    /// Toggle a note of the cell at the given (one based) row and column.
+    Note(usize, usize, u8),
+    /// Empty the cell at the given (one based) row and column.
+    Clear(usize, usize),
+    /// Switch between placing values and notes.
+    NotesMode,
+    /// Print the notes of the empty cells.
+    Notes,
+    /// Fill the notes of every empty cell with its candidates.
+    AutoNotes,
+    /// Keep the notes equal to the candidates after every move, or stop.
+    SetAutoNotes(bool),
+    /// Clear some of the notes.
+    ClearNotes(NotesClear),
+    /// Paint the cell at the given (one based) row and column with a marker color, or with the
+    /// next one.
+    Color(usize, usize, Option<u8>),
+    /// Remove the marker colors of every cell.
+    ClearColors,
+    /// Show the next logical move.
+    Hint,
+    /// Undo the last move.
+    Undo,
+    /// Play the last undone move again.
+    Redo,
+    /// Revert the last moves until the grid contains no mistake.
+    Rollback,
+    /// Start a guess.
+    StartGuess,
+    /// End the current guess, keeping its moves.
+    CommitGuess,
+    /// End the current guess, reverting its moves.
+    DiscardGuess,
+    /// Hide the grid and stop the clock until the player comes back.
+    Pause,
+    /// Print the events of the session.
+    Log,
+    /// Give up, comparing the grid to the solution.
+    GiveUp,
+    /// Start a new puzzle with the same settings.
+    Next,
+    /// Toggle whether the current puzzle is a favorite.
+    Favorite,
+    /// Print the favorite puzzles.
+    Favorites,
+    /// Start the favorite puzzle at the given (one based) position.
+    PlayFavorite(usize),
+    /// Print the available commands.
+    Help,
+}
+
+/**
+ * Parses a value typed as a number, or as a letter from A for 10 on the grids bigger than 9x9.
+ */
+fn parse_value(text: &str) -> Result<u8, ()> {
+    if let Ok(value) = text.parse::<u8>() {
+        return Ok(value);
+    }
+
+    match text.chars().collect::<Vec<char>>().as_slice() {
+        [symbol] if symbol.is_ascii_alphabetic() => symbol_value(*symbol).ok_or(()),
+        _ => Err(()),
+    }
+}
+
+impl FromStr for Command {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        match words.as_slice() {
+            [] => Ok(Command::GuidedMove),
+            ["where", n] => Ok(Command::Where(parse_value(n)?)),
+            ["note", row, column, value] => {
+                let row = row.parse::<usize>().map_err(|_| ())?;
+                let column = column.parse::<usize>().map_err(|_| ())?;
+                let value = parse_value(value)?;
+                // Rows and columns are one based
+                if row == 0 || column == 0 {
+                    return Err(());
+                }
+                Ok(Command::Note(row, column, value))
+            }
+            ["clear", row, column] => {
+                let row = row.parse::<usize>().map_err(|_| ())?;
+                let column = column.parse::<usize>().map_err(|_| ())?;
+                // Rows and columns are one based
+                if row == 0 || column == 0 {
+                    return Err(());
+                }
+                Ok(Command::Clear(row, column))
+            }
+            ["color", row, column, rest @ ..] if rest.len() <= 1 => {
+                let row = row.parse::<usize>().map_err(|_| ())?;
+                let column = column.parse::<usize>().map_err(|_| ())?;
+                let color = match rest {
+                    [color] => Some(color.parse::<u8>().map_err(|_| ())?),
+                    _ => None,
+                };
+                // Rows and columns are one based
+                if row == 0 || column == 0 {
+                    return Err(());
+                }
+                Ok(Command::Color(row, column, color))
+            }
+            ["colors", "clear"] => Ok(Command::ClearColors),
+            ["notes", "mode"] => Ok(Command::NotesMode),
+            ["notes"] => Ok(Command::Notes),
+            ["notes", "auto"] => Ok(Command::AutoNotes),
+            ["notes", "auto", "on"] => Ok(Command::SetAutoNotes(true)),
+            ["notes", "auto", "off"] => Ok(Command::SetAutoNotes(false)),
+            ["notes", "clear", "invalid"] => Ok(Command::ClearNotes(NotesClear::Invalid)),
+            ["notes", "clear", which, n] => {
+                // Every number is one based
+                let n = n.parse::<usize>().map_err(|_| ())?;
+                if n == 0 {
+                    return Err(());
+                }
+                match *which {
+                    "digit" => Ok(Command::ClearNotes(NotesClear::Digit(
+                        u8::try_from(n).map_err(|_| ())?,
+                    ))),
+                    "row" => Ok(Command::ClearNotes(NotesClear::Row(n - 1))),
+                    "column" => Ok(Command::ClearNotes(NotesClear::Column(n - 1))),
+                    "box" => Ok(Command::ClearNotes(NotesClear::Group(n - 1))),
+                    _ => Err(()),
+                }
+            }
+            ["hint"] | ["h"] => Ok(Command::Hint),
+            ["undo"] | ["u"] => Ok(Command::Undo),
+            ["redo"] | ["r"] => Ok(Command::Redo),
+            ["rollback"] => Ok(Command::Rollback),
+            ["guess"] => Ok(Command::StartGuess),
+            ["guess", "commit"] => Ok(Command::CommitGuess),
+            ["guess", "discard"] => Ok(Command::DiscardGuess),
+            ["pause"] | ["p"] => Ok(Command::Pause),
+            ["log"] => Ok(Command::Log),
+            ["giveup"] => Ok(Command::GiveUp),
+            ["next"] => Ok(Command::Next),
+            ["favorite"] => Ok(Command::Favorite),
+            ["favorites"] => Ok(Command::Favorites),
+            ["favorites", "play", n] => match n.parse::<usize>() {
+                Ok(n) if n > 0 => Ok(Command::PlayFavorite(n)),
+                _ => Err(()),
+            },
+            ["help"] => Ok(Command::Help),
+            [row, column, value] => {
+                let row = row.parse::<usize>().map_err(|_| ())?;
+                let column = column.parse::<usize>().map_err(|_| ())?;
+                let value = parse_value(value)?;
+                // Rows and columns are one based
+                if row == 0 || column == 0 {
+                    return Err(());
+                }
+                Ok(Command::Move(row, column, value))
+            }
+            _ => Err(()),
+        }
+    }
+}
+
 /**
  * This represents a Command Line Interface (CLI) for the user to play with.
  */
 pub struct Cli {
     /// Loaded configuration file
-    _config: CliConfig,
+    config: CliConfig,
     /// The game instance currently being played.
     game: Game,
     /// The current value that ought to be highlighted when printing the grid.
     highlighted_value: Option<u8>,
+    /// The value whose possible cells are marked when printing the grid.
+    where_value: Option<u8>,
+    /// Whether moves typed at the prompt place notes instead of values.
+    notes_mode: bool,
+    /// How each cell compares to the solution, when showing it.
+    diff: Option<Vec<CellDiff>>,
+    /// The players, the favorite puzzles being the ones of the profile playing.
+    profiles: Profiles,
+    /// Puzzles generated in the background with the configured settings.
+    pool: generator::Pool,
+    /// The running session, to recover its game if it crashes.
+    autosave: Autosave,
+    /// The samurai puzzle played instead of the game, if one was loaded.
+    samurai: Option<SamuraiGame>,
+    /// The pack whose puzzles are played one after the other instead of random ones, if one was
+    /// loaded, with the progress of the player and the position of the puzzle being played.
+    pack: Option<(Pack, Progress, usize)>,
 }
 
 impl Cli {
     pub fn new(config_path: &str) -> Result<Self, UiError> {
         // Config loader.
-        let config_txt = match fs::read_to_string(config_path) {
+        let config_txt = match config::read_config(config_path) {
             Ok(config) => config,
             Err(e) => {
-                eprintln!(
+                log::error!(
                     "Error while loading the configuration file {}: {}",
-                    config_path, e
+                    config_path,
+                    e
                 );
 
-                return Err(UiError::LoadConfigError);
+                return Err(UiError::LoadConfigError(e));
             }
         };
 
         let config: CliConfig = match serde_json::from_str(&config_txt) {
             Ok(config) => config,
             Err(e) => {
-                eprintln!(
+                log::error!(
                     "Error while loading the configuration file {}: {}",
-                    config_path, e
+                    config_path,
+                    e
                 );
 
-                return Err(UiError::ConfigSyntaxError);
+                return Err(UiError::ConfigSyntaxError(e));
             }
         };
+        config.generation.validate()?;
+        generator::validate_size(config.game_size)?;
 
-        // Get the save folder path
-        // Generate the game's saving path
-        let current_utc = chrono::offset::Utc::now();
-        let saving_path = format!("{}{}.game", config.save_folder_path, current_utc);
-
-        // Instanciate a game from its size
-        let game = Game::new(config.game_size, Some(&saving_path))?;
+        // Instanciate a game from its size, it is replaced by a saved one when the game starts
+        let game = Game::new(config.game_size, None)?;
+        let profiles = Profiles::load(&config.save_folder_path)?;
+        // Start generating puzzles right away
+        let pool = generator::Pool::new(config.game_size, config.difficulty, &config.generation);
+        let autosave = Autosave::start(&config.save_folder_path)?;
         // Instanciate Self.
         Ok(Cli {
             game,
-            _config: config,
+            config,
 
             highlighted_value: None,
+            where_value: None,
+            notes_mode: false,
+            diff: None,
+            profiles,
+            pool,
+            autosave,
+            samurai: None,
+            pack: None,
         })
     }
 
@@ -108,13 +353,98 @@ impl Cli {
         ret
     }
 
+    /**
+     * Resumes the game of the previous session if it crashed, returning whether it was.
+     */
+    fn recover_game(&mut self) -> bool {
+        let Some(path) = self.autosave.recovered() else {
+            return false;
+        };
+        match Game::from_file(&path) {
+            Ok(mut game) => {
+                // The turns of a resumed hot-seat game are kept
+                game.hot_seat = game
+                    .hot_seat
+                    .take()
+                    .or_else(|| self.config.hot_seat.then(HotSeat::default));
+                game.remove_notes = self.config.remove_notes;
+                self.game = game;
+                true
+            }
+            Err(e) => {
+                println!("Unable to recover the game {}: {}", path, e);
+                false
+            }
+        }
+    }
+
+    /**
+     * Lists the saved games with their size, progress and last save date, and lets the player
+     * pick one to resume. Returns whether one was resumed.
+     */
+    fn resume_saved_game(&mut self) -> Result<bool, UiError> {
+        let saves = SavedGame::list(&self.config.save_folder_path);
+        if saves.is_empty() {
+            return Ok(false);
+        }
+
+        println!("Saved games:");
+        for (i, save) in saves.iter().enumerate() {
+            println!("  {}. {}", i + 1, save);
+        }
+        let new_game = saves.len() + 1;
+        println!("  {}. New game", new_game);
+
+        loop {
+            let choice = Self::ask_number::<usize>(1..=new_game, Some("Your choice: "));
+            if choice == new_game {
+                return Ok(false);
+            }
+            match Game::from_file(&saves[choice - 1].path) {
+                Ok(mut game) => {
+                    // The turns of a resumed hot-seat game are kept
+                    game.hot_seat = game
+                        .hot_seat
+                        .take()
+                        .or_else(|| self.config.hot_seat.then(HotSeat::default));
+                    game.remove_notes = self.config.remove_notes;
+                    self.game = game;
+                    return Ok(true);
+                }
+                Err(e) => println!("{}", e),
+            }
+        }
+    }
+
     /**
      * Main game loop.
      *
      */
     pub fn run(&mut self) -> Result<(), UiError> {
-        self.new_random_game().unwrap();
-        while !self.game.is_done() {
+        if self.samurai.is_some() {
+            self.run_samurai();
+            return Ok(());
+        }
+
+        // Unless a puzzle was imported, go back to the game of the previous session if it
+        // crashed, or resume a saved game, or start with a random one
+        if self.game.save_path.is_none() && !self.recover_game() && !self.resume_saved_game()? {
+            self.new_random_game()?;
+        }
+        loop {
+            // Once the grid is filled, offer to go on with a new puzzle
+            if self.game.is_filled() {
+                if self.game.is_done() {
+                    if let Some((pack, progress, position)) = self.pack.as_mut() {
+                        let seconds = self.game.timer.elapsed().as_secs();
+                        progress.mark_solved_in(pack, *position, seconds)?;
+                    }
+                }
+                if !self.ended(false) || !self.next_game()? {
+                    break;
+                }
+            }
+
             // Reset the screen
             print!("{esc}[2J{esc}[1;1H", esc = 27 as char);
 
@@ -122,14 +452,279 @@ impl Cli {
             // Print the grid
             println!("{}", self);
 
-            // Ask for move: row, column and value
-            println!("Your move:");
-            let row = Self::ask_number::<usize>(1..=self.game.side_size, Some("Row: "));
-            let column = Self::ask_number::<usize>(1..=self.game.side_size, Some("Column: "));
-            let value = Self::ask_number::<u8>(1..=(self.game.side_size as u8), Some("Value: "));
+            // Ask for a command, to the player whose turn it is in hot-seat games
+            if !self.game.guesses.is_empty() {
+                print!("[Guess depth {}] ", self.game.guesses.len());
+            }
+            if self.notes_mode {
+                print!("[Notes] ");
+            }
+            if self.game.auto_notes {
+                print!("[Auto notes] ");
+            }
+            if let Some(hot_seat) = self.game.hot_seat.as_ref() {
+                print!("Player {}, ", hot_seat.current + 1);
+            }
+            print!("Your move ('row column value', or 'help'): ");
+            io::stdout().flush().unwrap();
+            let mut input_text = String::new();
+            io::stdin()
+                .read_line(&mut input_text)
+                .expect("failed to read from stdin");
+
+            let (row, column, value) = match input_text.parse::<Command>() {
+                Ok(Command::Move(row, column, value)) => (row, column, value),
+                Ok(Command::GuidedMove) => {
+                    // Ask for move: row, column and value
+                    println!("Your move:");
+                    let row = Self::ask_number::<usize>(1..=self.game.side_size, Some("Row: "));
+                    let column =
+                        Self::ask_number::<usize>(1..=self.game.side_size, Some("Column: "));
+                    let value =
+                        Self::ask_number::<u8>(1..=(self.game.side_size as u8), Some("Value: "));
+                    (row, column, value)
+                }
+                Ok(Command::Where(value)) => {
+                    if value == 0 || value as usize > self.game.side_size {
+                        println!("{}", GameError::IllegalValue);
+                    } else {
+                        // Print the grid once with the possible cells marked
+                        self.where_value = Some(value);
+                        print!("{esc}[2J{esc}[1;1H", esc = 27 as char);
+                        println!("{}", self);
+                        println!("'?' marks the cells where {} can go.", value);
+                        self.where_value = None;
+                    }
+                    pause();
+                    continue;
+                }
+                Ok(Command::Note(row, column, value)) => {
+                    self.toggle_note(row, column, value);
+                    continue;
+                }
+                Ok(Command::Clear(row, column)) => {
+                    if let Err(e) = self.game.clear_cell(row - 1, column - 1) {
+                        println!("{}", e);
+                        pause();
+                    }
+                    continue;
+                }
+                Ok(Command::NotesMode) => {
+                    self.notes_mode = !self.notes_mode;
+                    continue;
+                }
+                Ok(Command::Notes) => {
+                    for (index, cell) in self.game.grid.iter().enumerate() {
+                        if cell.value() == 0 && cell.notes() != 0 {
+                            let (row, column) = self.game.coordinates(index);
+                            let notes = (1..=self.game.side_size as u8)
+                                .filter(|v| cell.has_note(*v))
+                                .map(|v| v.to_string())
+                                .collect::<Vec<String>>()
+                                .join(" ");
+                            println!("Row {}, column {}: {}", row + 1, column + 1, notes);
+                        }
+                    }
+                    pause();
+                    continue;
+                }
+                Ok(Command::AutoNotes) => {
+                    if let Err(e) = self.game.fill_notes() {
+                        println!("{}", e);
+                        pause();
+                    }
+                    continue;
+                }
+                Ok(Command::SetAutoNotes(on)) => {
+                    if let Err(e) = self.game.set_auto_notes(on) {
+                        println!("{}", e);
+                        pause();
+                    }
+                    continue;
+                }
+                Ok(Command::ClearNotes(which)) => {
+                    if let Err(e) = self.game.clear_notes(which) {
+                        println!("{}", e);
+                        pause();
+                    }
+                    continue;
+                }
+                Ok(Command::Color(row, column, color)) => {
+                    let side_size = self.game.side_size;
+                    let outcome = match row <= side_size && column <= side_size {
+                        true => {
+                            let index = self.game.index(row - 1, column - 1);
+                            match color {
+                                Some(color) => self.game.paint(&[index], color),
+                                None => self.game.cycle_color(&[index]).map(|_| ()),
+                            }
+                        }
+                        false => Err(GameError::IllegalPosition),
+                    };
+                    if let Err(e) = outcome {
+                        println!("{}", e);
+                        pause();
+                    }
+                    continue;
+                }
+                Ok(Command::ClearColors) => {
+                    let cells: Vec<usize> = (0..self.game.grid.len()).collect();
+                    if let Err(e) = self.game.paint(&cells, 0) {
+                        println!("{}", e);
+                        pause();
+                    }
+                    continue;
+                }
+                Ok(Command::Hint) => {
+                    match hint::next_hint(&self.game) {
+                        Some(hint) => {
+                            let description = hint.describe(&self.game);
+                            println!("{}", description);
+                            self.game.count_hint(description);
+                        }
+                        None => println!("No hint found with the known techniques."),
+                    }
+                    pause();
+                    continue;
+                }
+                Ok(Command::Pause) => {
+                    self.game.timer.pause();
+                    print!("{esc}[2J{esc}[1;1H", esc = 27 as char);
+                    print!("Paused, press Enter to resume.");
+                    io::stdout().flush().unwrap();
+                    let mut input_text = String::new();
+                    io::stdin()
+                        .read_line(&mut input_text)
+                        .expect("failed to read from stdin");
+                    self.game.timer.resume();
+                    continue;
+                }
+                Ok(Command::Undo) => {
+                    if let Err(e) = self.game.undo() {
+                        println!("{}", e);
+                        pause();
+                    }
+                    continue;
+                }
+                Ok(Command::Redo) => {
+                    if let Err(e) = self.game.redo() {
+                        println!("{}", e);
+                        pause();
+                    }
+                    continue;
+                }
+                Ok(Command::Rollback) => {
+                    match self.game.rollback() {
+                        Ok(reverted) => println!("Reverted {} moves.", reverted),
+                        Err(e) => println!("{}", e),
+                    }
+                    pause();
+                    continue;
+                }
+                Ok(Command::StartGuess) => {
+                    self.game.start_guess();
+                    continue;
+                }
+                Ok(Command::CommitGuess) => {
+                    if let Err(e) = self.game.commit_guess() {
+                        println!("{}", e);
+                        pause();
+                    }
+                    continue;
+                }
+                Ok(Command::DiscardGuess) => {
+                    match self.game.discard_guess() {
+                        Ok(reverted) => println!("Reverted {} moves.", reverted),
+                        Err(e) => println!("{}", e),
+                    }
+                    pause();
+                    continue;
+                }
+                Ok(Command::GiveUp) => {
+                    if !self.ended(true) {
+                        break;
+                    }
+                    match self.next_game() {
+                        Ok(true) => {}
+                        Ok(false) => break,
+                        Err(e) => {
+                            println!("{}", e);
+                            pause();
+                        }
+                    }
+                    continue;
+                }
+                Ok(Command::Next) => {
+                    match self.next_game() {
+                        Ok(true) => {}
+                        Ok(false) => break,
+                        Err(e) => {
+                            println!("{}", e);
+                            pause();
+                        }
+                    }
+                    continue;
+                }
+                Ok(Command::Log) => {
+                    for event in self.game.events.iter() {
+                        println!("{}", event);
+                    }
+                    pause();
+                    continue;
+                }
+                Ok(Command::Favorite) => {
+                    match self.profiles.toggle_favorite(&self.game) {
+                        Ok(true) => println!("Added this puzzle to the favorites."),
+                        Ok(false) => println!("Removed this puzzle from the favorites."),
+                        Err(e) => println!("{}", e),
+                    }
+                    pause();
+                    continue;
+                }
+                Ok(Command::Favorites) => {
+                    let favorites = &self.profiles.current().favorites.puzzles;
+                    if favorites.is_empty() {
+                        println!("No favorite puzzle yet, add one with 'favorite'.");
+                    }
+                    for (i, favorite) in favorites.iter().enumerate() {
+                        println!("  {}. {} - {}", i + 1, favorite, favorite.code);
+                    }
+                    pause();
+                    continue;
+                }
+                Ok(Command::PlayFavorite(n)) => {
+                    let favorite = self.profiles.current().favorites.puzzles.get(n - 1);
+                    match favorite.cloned() {
+                        Some(favorite) => {
+                            if let Err(e) = self.start_favorite(&favorite) {
+                                println!("{}", e);
+                                pause();
+                            }
+                        }
+                        None => {
+                            println!("There is no favorite {}.", n);
+                            pause();
+                        }
+                    }
+                    continue;
+                }
+                Ok(Command::Help) | Err(_) => {
+                    println!("{}", HELP);
+                    pause();
+                    continue;
+                }
+            };
+
+            if self.notes_mode {
+                self.toggle_note(row, column, value);
+                continue;
+            }
 
             // Do the move if it is valid, otherwise display why it is not.
-            match self.game.do_move(row - 1, column - 1, value) {
+            match self
+                .game
+                .do_move(row - 1, column - 1, value, self.config.validation_mode)
+            {
                 Ok(_) => continue,
                 Err(e) => {
                     println!("{}", e);
@@ -138,6 +733,196 @@ impl Cli {
             };
         }
 
+        // Keep the play time of the game, then end the session cleanly
+        if let Err(e) = Autosave::save(&mut self.game) {
+            println!("{}", e);
+        }
+        self.autosave.finish();
+
+        Ok(())
+    }
+
+    /**
+     * Game loop of a samurai puzzle: its five grids are printed in place and played with the
+     * board's (one based) rows and columns, until it is solved or the player quits.
+     */
+    fn run_samurai(&mut self) {
+        let samurai = self.samurai.as_mut().unwrap();
+        loop {
+            print!("{esc}[2J{esc}[1;1H", esc = 27 as char);
+            println!("Samurai\n\n{}", samurai);
+            if samurai.is_done() {
+                println!("Grid completed !");
+                break;
+            }
+
+            print!("Your move ('row column value', 'clear row column', 'solve' or 'quit'): ");
+            io::stdout().flush().unwrap();
+            let mut input_text = String::new();
+            io::stdin()
+                .read_line(&mut input_text)
+                .expect("failed to read from stdin");
+
+            let result = match input_text.trim() {
+                "quit" | "q" => break,
+                "solve" => samurai.solve().map_err(|e| e.to_string()),
+                text => match text.parse::<Command>() {
+                    Ok(Command::Move(row, column, value)) if row <= SIDE && column <= SIDE => {
+                        samurai
+                            .do_move(row - 1, column - 1, value)
+                            .map_err(|e| e.to_string())
+                    }
+                    Ok(Command::Clear(row, column)) if row <= SIDE && column <= SIDE => samurai
+                        .do_move(row - 1, column - 1, 0)
+                        .map_err(|e| e.to_string()),
+                    _ => Err(String::from("Unknown command.")),
+                },
+            };
+            if let Err(e) = result {
+                println!("{}", e);
+                pause();
+            }
+        }
+    }
+
+    /**
+     * Toggles the note of `value` in the cell at the given (one based) row and column,
+     * displaying why it cannot be done if so.
+     */
+    fn toggle_note(&mut self, row: usize, column: usize, value: u8) {
+        if let Err(e) = self.game.toggle_note(row - 1, column - 1, value) {
+            println!("{}", e);
+            pause();
+        }
+    }
+
+    /**
+     * Shows the finished grid and, in hot-seat games, who did what. Unless the moves were
+     * checked as they were played, the grid is compared to the solution. Returns whether the
+     * player wants to play the next puzzle.
+     */
+    fn ended(&mut self, gave_up: bool) -> bool {
+        let verdict = match (gave_up, self.game.is_done()) {
+            (true, _) => "You gave up.",
+            (false, true) => "Grid completed !",
+            (false, false) => "The grid is filled but contains mistakes.",
+        };
+
+        if gave_up || !self.game.is_done() || self.config.validation_mode == Validation::Free {
+            match self.game.diff() {
+                Ok(diff) => self.diff = Some(diff),
+                Err(e) => println!("{}", e),
+            }
+        }
+
+        print!("{esc}[2J{esc}[1;1H", esc = 27 as char);
+        println!("{}", self);
+        if self.diff.take().is_some() {
+            println!(
+                "{} correct, {} wrong (solution shown), {} empty (solution shown)",
+                "green".bright_green(),
+                "red".bright_red(),
+                "grey".bright_black()
+            );
+        }
+        if let Some(hot_seat) = self.game.hot_seat.as_ref() {
+            println!("{}", hot_seat.summary());
+        }
+
+        print!(
+            "{} Type 'next' for another puzzle, anything else to quit: ",
+            verdict
+        );
+        io::stdout().flush().unwrap();
+        let mut input_text = String::new();
+        io::stdin()
+            .read_line(&mut input_text)
+            .expect("failed to read from stdin");
+
+        input_text.parse::<Command>() == Ok(Command::Next)
+    }
+
+    /**
+     * Starts the next puzzle: the next unsolved one of the pack being played, or a random one.
+     * Returns false once every puzzle of the pack is solved.
+     */
+    fn next_game(&mut self) -> Result<bool, UiError> {
+        let next = match self.pack.as_ref() {
+            Some((pack, progress, position)) => {
+                match pack.next_unsolved_from(progress, position + 1) {
+                    Some(next) => next,
+                    None => {
+                        println!("Every puzzle of the pack {} is solved!", pack.name);
+                        return Ok(false);
+                    }
+                }
+            }
+            None => {
+                self.new_random_game()?;
+                return Ok(true);
+            }
+        };
+        self.start_pack_puzzle(next)?;
+
+        Ok(true)
+    }
+
+    /**
+     * Plays the pack at `path`, from its first unsolved puzzle, or from its first puzzle when
+     * they are all solved.
+     */
+    fn load_pack(&mut self, path: &str) -> Result<(), UiError> {
+        let pack = Pack::from_file(Path::new(path))?;
+        if pack.puzzles.is_empty() {
+            return Err(UiError::LoadPacksError(io::ErrorKind::InvalidData.into()));
+        }
+        let progress = Progress::load(&self.config.save_folder_path)?;
+        let position = pack.next_unsolved_from(&progress, 0).unwrap_or(0);
+
+        self.pack = Some((pack, progress, position));
+        self.start_pack_puzzle(position)
+    }
+
+    /**
+     * This function initialises the `self.game` instance with the `favorite` puzzle, leaving the
+     * pack being played.
+     */
+    fn start_favorite(&mut self, favorite: &Favorite) -> Result<(), UiError> {
+        let current_utc = chrono::offset::Utc::now();
+        let saving_path = format!("{}{}.game", self.config.save_folder_path, current_utc);
+
+        self.game = favorite.to_game(Some(&saving_path))?;
+        self.pack = None;
+        self.game.hot_seat = self.config.hot_seat.then(HotSeat::default);
+        self.game.remove_notes = self.config.remove_notes;
+        if self.config.auto_notes {
+            self.game.auto_notes = true;
+            self.game.fill_notes()?;
+        }
+        self.game.save()?;
+
+        Ok(())
+    }
+
+    /**
+     * This function initialises the `self.game` instance with the puzzle at `position` in the
+     * pack being played.
+     */
+    fn start_pack_puzzle(&mut self, position: usize) -> Result<(), UiError> {
+        let (pack, _, current) = self.pack.as_mut().unwrap();
+        let current_utc = chrono::offset::Utc::now();
+        let saving_path = format!("{}{}.game", self.config.save_folder_path, current_utc);
+
+        self.game = Game::from_puzzle(&pack.puzzles[position], Some(&saving_path))?;
+        *current = position;
+        self.game.hot_seat = self.config.hot_seat.then(HotSeat::default);
+        self.game.remove_notes = self.config.remove_notes;
+        if self.config.auto_notes {
+            self.game.auto_notes = true;
+            self.game.fill_notes()?;
+        }
+        self.game.save()?;
+
         Ok(())
     }
 }
@@ -147,54 +932,104 @@ impl fmt::Display for Cli {
      * Displays the grid.
      */
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "Game:\n")?;
+        if let Some((pack, _, position)) = self.pack.as_ref() {
+            writeln!(
+                f,
+                "{} - puzzle {}/{}",
+                pack.name,
+                position + 1,
+                pack.puzzles.len()
+            )?;
+        }
+        let elapsed = self.game.timer.elapsed().as_secs();
+        writeln!(
+            f,
+            "Game - {:02}:{:02} - {}\n",
+            elapsed / 60,
+            elapsed % 60,
+            self.game.rating()
+        )?;
+        let spots = self
+            .where_value
+            .map(|value| self.game.cells_for(value))
+            .unwrap_or_default();
+        // The wrong entries accepted in lenient mode, shown on a red background
+        let conflicts = match self.config.validation_mode == Validation::Lenient {
+            true => self.game.conflicts(),
+            false => vec![],
+        };
+
+        // The cells are as wide as the biggest value, 16 and 25 taking two characters. The values
+        // are padded before being colored, the colors not taking any room.
+        let width = self.game.side_size.to_string().len();
+        let pad = |text: String| format!("{:>width$}", text, width = width);
+        let dashes = "-".repeat(width + 2);
+        let segment = format!("+{}", dashes);
 
         // For each row
         for i in 0..self.game.side_size {
-            // Print the horizontal line in color if its zero modulo the grid size.
-            if (i % self.game.size) == 0 {
-                writeln!(
-                    f,
-                    "{}{}",
-                    "+---".repeat(self.game.side_size).bright_blue().bold(),
-                    "+".bright_blue().bold()
-                )?;
-            }
-            // Else, print it in normal color
-            else {
-                writeln!(
-                    f,
-                    "{}{}",
-                    format!(
-                        "{}---{}",
-                        "+".bright_blue().bold(),
-                        "+---".repeat(self.game.size - 1)
-                    )
-                    .repeat(self.game.size),
-                    "+".bright_blue().bold()
-                )?;
+            // Print the horizontal line in color where it borders a box, or a jigsaw region.
+            for j in 0..self.game.side_size {
+                match self.game.borders(i, j) {
+                    (true, _) => write!(f, "{}", segment.bright_blue().bold())?,
+                    (false, true) => write!(f, "{}{}", "+".bright_blue().bold(), dashes)?,
+                    // Else, print it in normal color
+                    (false, false) => write!(f, "{}", segment)?,
+                }
             }
+            writeln!(f, "{}", "+".bright_blue().bold())?;
 
             // Printing the number row
             for j in 0..self.game.side_size {
-                // Print the separator in color if its index is equal to zero modulo the grid size.
-                if (j % self.game.size) == 0 {
-                    write!(f, "{} ", "|".bright_blue().bold())?;
-                }
-                // Else, print it in normal color
-                else {
-                    write!(f, "| ")?;
+                // Print the separator in color if it borders a box, or a jigsaw region.
+                match self.game.borders(i, j).1 {
+                    true => write!(f, "{} ", "|".bright_blue().bold())?,
+                    // Else, print it in normal color
+                    false => write!(f, "| ")?,
                 }
 
-                // Get the cell's value as a string or a space if it's zero.
-                let value = self.game.grid[self.game.index(i, j)].value;
-                let mut value_string = match value {
-                    0 => " ".to_string(),
-                    _ => value.to_string(),
+                // Get the cell's value as a string or a space if it's zero, a '?' if the value
+                // asked with 'where' can go there.
+                let index = self.game.index(i, j);
+                let value = self.game.grid[index].value();
+                let mut value_string = match (value, self.diff.as_ref().map(|d| d[index])) {
+                    (_, Some(CellDiff::Empty(expected))) => {
+                        pad(expected.to_string()).bright_black().to_string()
+                    }
+                    (_, Some(CellDiff::Wrong(expected))) => {
+                        pad(expected.to_string()).bright_red().to_string()
+                    }
+                    (_, Some(CellDiff::Correct)) if !self.game.grid[index].initial() => {
+                        pad(value.to_string()).bright_green().to_string()
+                    }
+                    (_, None) if conflicts.contains(&index) => {
+                        pad(value.to_string()).on_red().to_string()
+                    }
+                    (0, _) if spots.contains(&index) => {
+                        pad(String::from("?")).bright_green().to_string()
+                    }
+                    (0, _) => pad(String::new()),
+                    _ => pad(value.to_string()),
                 };
 
+                // The cells painted with a marker color show their value in it, or a '*' when
+                // they are empty
+                let color = self.game.grid[index].color();
+                if color != 0
+                    && self.diff.is_none()
+                    && !conflicts.contains(&index)
+                    && !(value == 0 && spots.contains(&index))
+                {
+                    let text = match value {
+                        0 => String::from("*"),
+                        _ => value.to_string(),
+                    };
+                    let (red, green, blue) = MARKER_COLORS[color as usize - 1];
+                    value_string = pad(text).truecolor(red, green, blue).bold().to_string();
+                }
+
                 // If the value is the currently highlighted one, highlight it.
-                if self.highlighted_value.unwrap_or(0) == value {
+                if self.diff.is_none() && self.highlighted_value.unwrap_or(0) == value {
                     value_string = value_string.bright_red().to_string();
                 }
 
@@ -209,10 +1044,25 @@ impl fmt::Display for Cli {
         writeln!(
             f,
             "{}{}",
-            "+---".repeat(self.game.side_size).bright_blue().bold(),
+            segment.repeat(self.game.side_size).bright_blue().bold(),
             "+".bright_blue().bold()
         )?;
 
+        // How many of each value are left to place, the ones all placed being greyed out
+        let remaining = self
+            .game
+            .remaining_counts()
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| {
+                let text = format!("{}:{}", i + 1, count);
+                match count {
+                    0 => text.bright_black().to_string(),
+                    _ => text,
+                }
+            });
+        writeln!(f, "Left {}", remaining.collect::<Vec<_>>().join(" "))?;
+
         Ok(())
     }
 }
@@ -226,10 +1076,55 @@ impl Ui for Cli {
      * This function initialises the `self.game` instance with a new random solvable game.
      */
     fn new_random_game(&mut self) -> Result<(), UiError> {
-        self.game.clear();
-        self.game.fill_rng(0);
-        let solver = solver::Obvious;
-        self.game.unfill(solver);
+        // Generate the game's saving path
+        let current_utc = chrono::offset::Utc::now();
+        let saving_path = format!("{}{}.game", self.config.save_folder_path, current_utc);
+
+        // Instanciate the game with the next puzzle of the pool
+        self.game = Game::new(self.config.game_size, Some(&saving_path))?;
+        let puzzle = self.pool.take();
+        self.game.set_regions(puzzle.regions().to_vec());
+        self.game.grid = puzzle.grid;
+        self.game.solution = puzzle.solution;
+        self.game.variant = puzzle.variant;
+        self.game.hot_seat = self.config.hot_seat.then(HotSeat::default);
+        self.game.remove_notes = self.config.remove_notes;
+        if self.config.auto_notes {
+            self.game.auto_notes = true;
+            self.game.fill_notes()?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * This function initialises the `self.game` instance with the f-puzzles JSON puzzle at
+     * `path`, or plays the pack at `path`, see `load_pack`.
+     */
+    fn load_puzzle(&mut self, path: &str) -> Result<(), UiError> {
+        if path.ends_with(".pack") || path.ends_with(".sdm") {
+            return self.load_pack(path);
+        }
+
+        // Samurai puzzles are played on their own, without a save
+        if path.ends_with(".samurai") {
+            let samurai = SamuraiGame::from_file(path).map_err(|_| UiError::ImportPuzzleError)?;
+            self.samurai = Some(samurai);
+            return Ok(());
+        }
+
+        let json = fs::read_to_string(path).map_err(|_| UiError::ImportPuzzleError)?;
+        let current_utc = chrono::offset::Utc::now();
+        let saving_path = format!("{}{}.game", self.config.save_folder_path, current_utc);
+
+        self.game = annotations::import_fpuzzles(&json, Some(&saving_path))?;
+        self.game.hot_seat = self.config.hot_seat.then(HotSeat::default);
+        self.game.remove_notes = self.config.remove_notes;
+        if self.config.auto_notes {
+            self.game.auto_notes = true;
+            self.game.fill_notes()?;
+        }
+        self.game.save()?;
 
         Ok(())
     }
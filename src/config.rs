@@ -0,0 +1,232 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::traits::GUIConfig;
+
+/// Name of the folder of the program in the platform configuration folder.
+const APP_FOLDER: &str = "sudocurs";
+/// Name of the configuration file found without being given.
+const CONFIG_FILE: &str = "config.json";
+
+/// The assets written along the default configuration, with the name they are written under.
+const ASSETS: [(&str, &[u8]); 7] = [
+    (
+        "roboto_font.ttf",
+        include_bytes!("../resources/roboto_font.ttf"),
+    ),
+    (
+        "btn_resume.png",
+        include_bytes!("../resources/btn_resume.png"),
+    ),
+    (
+        "btn_new_game.png",
+        include_bytes!("../resources/btn_new_game.png"),
+    ),
+    ("btn_exit.png", include_bytes!("../resources/btn_exit.png")),
+    (
+        "btn_resume_hover.png",
+        include_bytes!("../resources/btn_resume_hover.png"),
+    ),
+    (
+        "btn_new_game_hover.png",
+        include_bytes!("../resources/btn_new_game_hover.png"),
+    ),
+    (
+        "btn_exit_hover.png",
+        include_bytes!("../resources/btn_exit_hover.png"),
+    ),
+];
+
+/// The default configuration, suiting both interfaces. `{save_folder}` and `{resources}` are
+/// replaced by the JSON strings of the save folder and the assets folder.
+const DEFAULT_CONFIG: &str = r#"{
+  // Folder the games, packs, profiles and sessions are saved in
+  "save_folder_path": {save_folder},
+  // Size of the boxes: 2 for 4x4 grids, 3 for 9x9 and 4 for 16x16
+  "game_size": 3,
+  // Whether two players take turns on the same board
+  "hot_seat": false,
+  // Whether the notes of the new games are kept equal to the candidates after every move, each
+  // game keeping its own setting
+  "auto_notes": false,
+  // Whether placing a value removes its notes from the row, column and box of its cell
+  "remove_notes": true,
+  // How the moves are checked: strict, solution, lenient or off
+  "validation_mode": "strict",
+  // Difficulty of the generated puzzles: easy, medium, hard or expert
+  "difficulty": "medium",
+  // Clues of the generated puzzles (calibrated for each size when null), solver checking them
+  // (dlx, backtracking, obvious or human),
+  // their rules (classic, diagonal for the diagonals to hold every value once too, or hyper for
+  // the windows between the boxes to do so) and whether their boxes are replaced by irregular
+  // jigsaw regions
+  "generation": {
+    "max_unfill_attempts": null,
+    "min_clues_percent": null,
+    "easy_clues_percent": null,
+    "medium_clues_percent": null,
+    "solver": "dlx",
+    "variant": "classic",
+    "jigsaw": false
+  },
+  // Size of the game window, in pixels
+  "res_x": 800,
+  "res_y": 600,
+  // Path of the window icon, the default one being kept when empty
+  "window_icon_path": "",
+  // Margins and lines of the grid, centered or anchored with a side panel
+  "layout": {
+    "margin": 40,
+    "thin_line": 1,
+    "thick_line": 3,
+    "placement": "centered",
+    "side_panel_width": 200
+  },
+  // Whether a digit or a cell is picked first with the mouse: digit-first or cell-first
+  "input_mode": "digit-first",
+  // What a right-click on a cell does: note (erase the placed value or toggle the note of the
+  // highlighted value), erase or off, and the mouse wheel over a cell: cycle (its value through
+  // the candidates, upwards to the next one), reversed or off
+  "mouse": {
+    "right_click": "note",
+    "wheel": "cycle"
+  },
+  // Delay between the steps of the solver watched with the W key, in milliseconds, halved or
+  // doubled by the + and - keys while watching, the space key playing them one at a time
+  "watch_step_ms": 1000,
+  // Colors of the game screen: the dark or light preset, switched with the other one by the T
+  // key, any of its colors (background, lines, text, initial, entered, entered_cell, highlight,
+  // error) being replaced when set to a #RRGGBB or #RRGGBBAA color
+  "theme": {
+    "preset": "dark"
+  },
+  // Sounds played when a value is placed, a move refused and the grid solved, and music looped
+  // in the background, all left out when empty, at a volume from 0 to 100 muted by the F9 key;
+  // they are only played when built with the audio feature
+  "audio": {
+    "place_sound_path": "",
+    "error_sound_path": "",
+    "victory_sound_path": "",
+    "music_path": "",
+    "volume": 80,
+    "muted": false
+  },
+  // Key saving a screenshot of the window in the save folder, none when empty
+  "screenshot_key": "F12",
+  // HTTP endpoint of the daily puzzle, answering a sugoku-style JSON board, e.g.
+  // "http://localhost:3000/board?difficulty=medium", offered on the main screen when set and
+  // built with the fetch feature
+  "daily_puzzle_url": "",
+  // Font and buttons of the game window, the buttons left empty or missing being drawn as text
+  "font_path": {resources}/roboto_font.ttf",
+  "btn_resume_path": {resources}/btn_resume.png",
+  "btn_new_game_path": {resources}/btn_new_game.png",
+  "btn_exit_path": {resources}/btn_exit.png",
+  "btn_resume_hover_path": {resources}/btn_resume_hover.png",
+  "btn_new_game_hover_path": {resources}/btn_new_game_hover.png",
+  "btn_exit_hover_path": {resources}/btn_exit_hover.png"
+}
+"#;
+
+/**
+ * Returns the folder of the program in the platform configuration folder: `%APPDATA%` on
+ * Windows, `~/Library/Application Support` on macOS and `$XDG_CONFIG_HOME`, or `~/.config`,
+ * elsewhere.
+ */
+pub fn config_dir() -> Option<PathBuf> {
+    let home = || env::var_os("HOME").map(PathBuf::from);
+    let base = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home().map(|h| h.join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| home().map(|h| h.join(".config")))
+    };
+
+    base.map(|b| b.join(APP_FOLDER))
+}
+
+/**
+ * Returns the path of the configuration used when none is given, in `config_dir`.
+ */
+pub fn default_config_path() -> Option<PathBuf> {
+    config_dir().map(|d| d.join(CONFIG_FILE))
+}
+
+/**
+ * Reads the configuration file at `path`, leaving out its comment lines, the ones starting with
+ * `//`.
+ */
+pub fn read_config(path: &str) -> io::Result<String> {
+    Ok(strip_comments(&fs::read_to_string(path)?))
+}
+
+/**
+ * Writes `config` to the configuration file at `path`, as plain JSON: the comments of the
+ * default configuration are not kept.
+ */
+pub fn write_config(path: &str, config: &GUIConfig) -> io::Result<()> {
+    fs::write(path, serde_json::to_string_pretty(config)? + "\n")
+}
+
+/**
+ * Removes the lines of `text` starting with `//`, JSON having no comments.
+ */
+pub fn strip_comments(text: &str) -> String {
+    text.lines()
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/**
+ * Returns the commented default configuration, saving the games in `save_folder` and using the
+ * assets of `resources`.
+ */
+pub fn default_config(save_folder: &Path, resources: &Path) -> String {
+    // The folders are written as JSON strings, the file names being appended to the resources one
+    let quote = |path: &Path| serde_json::to_string(&path.to_string_lossy()).unwrap();
+    let mut save_folder = save_folder.to_string_lossy().to_string();
+    if !save_folder.ends_with(std::path::MAIN_SEPARATOR) {
+        save_folder.push(std::path::MAIN_SEPARATOR);
+    }
+    let resources = quote(resources);
+
+    DEFAULT_CONFIG
+        .replace("{save_folder}", &quote(Path::new(&save_folder)))
+        .replace("{resources}", resources.trim_end_matches('"'))
+}
+
+/**
+ * Writes the default configuration in `config_dir`, along with the assets and the save folder it
+ * refers to, and returns its path. An existing configuration is never overwritten.
+ */
+pub fn init_config() -> Result<PathBuf, String> {
+    let dir = config_dir().ok_or("No configuration folder was found for this platform.")?;
+    let path = dir.join(CONFIG_FILE);
+    if path.exists() {
+        return Err(format!("{} already exists.", path.display()));
+    }
+
+    let (save_folder, resources) = (dir.join("games"), dir.join("resources"));
+    let error = |e: io::Error| {
+        format!(
+            "Unable to write the configuration in {}: {}",
+            dir.display(),
+            e
+        )
+    };
+    fs::create_dir_all(&save_folder).map_err(error)?;
+    fs::create_dir_all(&resources).map_err(error)?;
+    for (name, content) in ASSETS {
+        fs::write(resources.join(name), content).map_err(error)?;
+    }
+    fs::write(&path, default_config(&save_folder, &resources)).map_err(error)?;
+
+    Ok(path)
+}
@@ -0,0 +1,242 @@
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, Canvas};
+use sdl2::ttf::Font;
+use sdl2::video::Window;
+
+use crate::errors::UiError;
+
+/// Color laid over the screen behind the dialog, dimming it.
+static COLOR_SHADE: Color = Color::RGBA(0, 0, 0, 160);
+static COLOR_BCK: Color = Color::RGBA(40, 40, 40, 255);
+static COLOR_BORDER: Color = Color::RGBA(255, 220, 0, 255);
+static COLOR_BUTTON: Color = Color::RGBA(75, 75, 75, 255);
+static COLOR_HOVER: Color = Color::RGBA(255, 110, 50, 255);
+static COLOR_FONT: Color = Color::WHITE;
+
+/// Size of the dialog, shrunk to fit in small windows.
+const DIALOG_SIZE: (u32, u32) = (440, 160);
+/// Size of the answer buttons.
+const BUTTON_SIZE: (u32, u32) = (120, 40);
+/// Space between the border of the dialog and its content.
+const PADDING: i32 = 20;
+
+/**
+ * The answers to a dialog.
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Answer {
+    Yes,
+    No,
+}
+
+/**
+ * What an event did to a dialog.
+ */
+#[derive(Debug, PartialEq, Eq)]
+pub enum DialogOutcome<T> {
+    /// Nothing changed.
+    Unchanged,
+    /// The dialog must be drawn again, e.g. a button being hovered.
+    Updated,
+    /// The question was confirmed, the action being returned for the screen to carry it out.
+    Confirmed(T),
+    /// The question was dismissed.
+    Cancelled,
+}
+
+/**
+ * A yes or no question drawn over the current screen, which gets none of the events until it is
+ * answered. It holds the action to carry out once the question is confirmed.
+ *
+ * `Y` and `Enter` confirm it, `N` and `Escape` dismiss it, as do its two buttons.
+ */
+pub struct Dialog<T> {
+    question: String,
+    /// What to do once the question is confirmed.
+    action: T,
+
+    /// The button under the mouse cursor.
+    hovered: Option<Answer>,
+    /// Area of the screen the dialog is drawn over.
+    viewport: Rect,
+}
+
+impl<T: Copy> Dialog<T> {
+    /**
+     * Returns a dialog asking `question`, carrying out `action` once it is confirmed.
+     */
+    pub fn new(question: &str, action: T) -> Self {
+        Dialog {
+            question: question.to_string(),
+            action,
+            hovered: None,
+            viewport: Rect::new(0, 0, 1, 1),
+        }
+    }
+
+    /**
+     * Returns the area of the dialog, centered on the screen.
+     */
+    fn frame(&self) -> Rect {
+        let width = DIALOG_SIZE.0.min(self.viewport.width());
+        let height = DIALOG_SIZE.1.min(self.viewport.height());
+        Rect::new(
+            self.viewport.x() + (self.viewport.width() - width) as i32 / 2,
+            self.viewport.y() + (self.viewport.height() - height) as i32 / 2,
+            width,
+            height,
+        )
+    }
+
+    /**
+     * Returns the area of the button of `answer`, at the bottom of the dialog.
+     */
+    fn button(&self, answer: Answer) -> Rect {
+        let frame = self.frame();
+        let width = BUTTON_SIZE.0.min(frame.width() / 2);
+        let height = BUTTON_SIZE.1.min(frame.height() / 2);
+        let y = frame.bottom() - PADDING.min(frame.height() as i32 / 4) - height as i32;
+        let x = match answer {
+            Answer::Yes => frame.x() + frame.width() as i32 / 4 - width as i32 / 2,
+            Answer::No => frame.x() + 3 * frame.width() as i32 / 4 - width as i32 / 2,
+        };
+        Rect::new(x, y, width, height)
+    }
+
+    /**
+     * Returns the button at the given window position, if any.
+     */
+    fn button_at(&self, x: i32, y: i32) -> Option<Answer> {
+        [Answer::Yes, Answer::No]
+            .into_iter()
+            .find(|answer| self.button(*answer).contains_point((x, y)))
+    }
+
+    /**
+     * Returns the outcome of `answer`.
+     */
+    fn answer(&self, answer: Answer) -> DialogOutcome<T> {
+        match answer {
+            Answer::Yes => DialogOutcome::Confirmed(self.action),
+            Answer::No => DialogOutcome::Cancelled,
+        }
+    }
+
+    /**
+     * Handles `event`, the screen below getting none of them while the dialog is open.
+     */
+    pub fn update(&mut self, event: &Event) -> DialogOutcome<T> {
+        match event {
+            Event::KeyDown {
+                keycode: Some(Keycode::Y | Keycode::Return | Keycode::KpEnter),
+                ..
+            } => self.answer(Answer::Yes),
+            Event::KeyDown {
+                keycode: Some(Keycode::N | Keycode::Escape),
+                ..
+            } => self.answer(Answer::No),
+            Event::MouseMotion { x, y, .. } => {
+                let hovered = self.button_at(*x, *y);
+                match hovered != self.hovered {
+                    true => {
+                        self.hovered = hovered;
+                        DialogOutcome::Updated
+                    }
+                    false => DialogOutcome::Unchanged,
+                }
+            }
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => match self.button_at(*x, *y) {
+                Some(answer) => self.answer(answer),
+                None => DialogOutcome::Unchanged,
+            },
+            _ => DialogOutcome::Unchanged,
+        }
+    }
+
+    /**
+     * Draws the dialog over what is on `canvas` with `font`, leaving it to the caller to present
+     * the canvas.
+     */
+    pub fn draw(&mut self, canvas: &mut Canvas<Window>, font: &Font) -> Result<(), UiError> {
+        self.viewport = canvas.viewport();
+
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(COLOR_SHADE);
+        canvas.fill_rect(self.viewport).map_err(UiError::sdl2)?;
+        canvas.set_blend_mode(BlendMode::None);
+
+        let frame = self.frame();
+        canvas.set_draw_color(COLOR_BCK);
+        canvas.fill_rect(frame).map_err(UiError::sdl2)?;
+        canvas.set_draw_color(COLOR_BORDER);
+        canvas.draw_rect(frame).map_err(UiError::sdl2)?;
+
+        let yes = self.button(Answer::Yes);
+        let question = Rect::new(
+            frame.x() + PADDING,
+            frame.y(),
+            (frame.width() as i32 - 2 * PADDING).max(1) as u32,
+            (yes.y() - frame.y()).max(1) as u32,
+        );
+        draw_text(canvas, font, &self.question, question)?;
+
+        for (answer, text) in [(Answer::Yes, "Yes (Y)"), (Answer::No, "No (N)")] {
+            let button = self.button(answer);
+            canvas.set_draw_color(match self.hovered == Some(answer) {
+                true => COLOR_HOVER,
+                false => COLOR_BUTTON,
+            });
+            canvas.fill_rect(button).map_err(UiError::sdl2)?;
+            draw_text(canvas, font, text, button)?;
+        }
+
+        Ok(())
+    }
+}
+
+/**
+ * Draws `text` with `font`, centered in `area` and shrunk to fit in it.
+ */
+fn draw_text(
+    canvas: &mut Canvas<Window>,
+    font: &Font,
+    text: &str,
+    area: Rect,
+) -> Result<(), UiError> {
+    let texture_creator = canvas.texture_creator();
+    let surface = font
+        .render(text)
+        .blended(COLOR_FONT)
+        .map_err(UiError::sdl2)?;
+    let texture = texture_creator
+        .create_texture_from_surface(surface)
+        .map_err(UiError::sdl2)?;
+
+    let query = texture.query();
+    let scale = (area.width() as f32 / query.width as f32)
+        .min(area.height() as f32 / query.height as f32)
+        .min(1.0);
+    let width = ((query.width as f32 * scale) as u32).max(1);
+    let height = ((query.height as f32 * scale) as u32).max(1);
+    canvas
+        .copy(
+            &texture,
+            None,
+            Rect::new(
+                area.x() + (area.width() - width) as i32 / 2,
+                area.y() + (area.height() - height) as i32 / 2,
+                width,
+                height,
+            ),
+        )
+        .map_err(UiError::sdl2)
+}
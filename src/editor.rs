@@ -0,0 +1,90 @@
+use crate::errors::GameError;
+use crate::game::{Cell, Game, Variant};
+use crate::solver::PuzzleCheck;
+
+/**
+ * A puzzle built by hand, one clue at a time, checked again after each change: whether its clues
+ * conflict and whether it has no, one or several solutions.
+ */
+pub struct Editor {
+    /// The puzzle being built, its clues being its initial cells.
+    pub game: Game,
+    /// What is known about the puzzle as it stands.
+    pub check: PuzzleCheck,
+}
+
+impl Editor {
+    /**
+     * Starts an empty puzzle of the given size and variant.
+     */
+    pub fn new(size: usize, variant: Variant) -> Result<Self, GameError> {
+        let mut game = Game::new(size, None)?;
+        game.variant = variant;
+        let check = PuzzleCheck::new(&mut game.copy_puzzle());
+
+        Ok(Editor { game, check })
+    }
+
+    /**
+     * Places the clue `value` in the cell at row `r` and column `c`, 0 removing the clue. The
+     * clues may conflict, the check telling which ones do.
+     */
+    pub fn set_clue(&mut self, r: usize, c: usize, value: u8) -> Result<(), GameError> {
+        if r >= self.game.side_size || c >= self.game.side_size {
+            return Err(GameError::IllegalPosition);
+        }
+        if value as usize > self.game.side_size {
+            return Err(GameError::IllegalValue);
+        }
+
+        let index = self.game.index(r, c);
+        self.game.grid[index] = Cell::new(value, value != 0);
+        self.recheck();
+
+        Ok(())
+    }
+
+    /**
+     * Changes the constraints the puzzle is built for.
+     */
+    pub fn set_variant(&mut self, variant: Variant) {
+        self.game.variant = variant;
+        self.recheck();
+    }
+
+    /**
+     * Returns whether the puzzle can be played: its clues do not conflict and it has a single
+     * solution.
+     */
+    pub fn is_playable(&self) -> bool {
+        self.check.conflicts.is_empty() && self.check.solutions == 1
+    }
+
+    /**
+     * Returns a game of the puzzle, attached to the save file at `saving_path` if given. Only
+     * playable puzzles are turned into games.
+     */
+    pub fn to_game(&self, saving_path: Option<&str>) -> Result<Game, GameError> {
+        if !self.is_playable() {
+            return Err(GameError::InvalidPuzzle);
+        }
+
+        let mut game = Game::from_puzzle(&self.game.puzzle_id(), saving_path)?;
+        game.variant = self.game.variant;
+        Ok(game)
+    }
+
+    /**
+     * Writes the puzzle as a save at `path`, ready to be resumed, if it is playable.
+     */
+    pub fn save(&self, path: &str) -> Result<(), GameError> {
+        self.to_game(Some(path))?.save()
+    }
+
+    /**
+     * Checks the puzzle again, on a copy as the grade of a game is only computed once.
+     */
+    fn recheck(&mut self) {
+        self.check = PuzzleCheck::new(&mut self.game.copy_puzzle());
+    }
+}
@@ -0,0 +1,426 @@
+use sdl2::event::Event;
+use sdl2::keyboard::{Keycode, Mod};
+use sdl2::mouse::MouseButton;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::Canvas;
+use sdl2::ttf::Font;
+use sdl2::video::Window;
+
+use std::rc::Rc;
+
+use crate::dialog::{Dialog, DialogOutcome};
+use crate::editor::Editor;
+use crate::errors::UiError;
+use crate::game::{value_symbol, Variant};
+use crate::game_screen::{digit_from_keycode, letter_value_from_keycode};
+use crate::layout::{fit_centered, Layout};
+use crate::traits::{Displayable, GUIConfig, LayoutConfig, ScreenOutcome};
+
+static COLOR_BCK: Color = Color::BLACK;
+static COLOR_LINES: Color = Color::RGBA(255, 220, 0, 255);
+static COLOR_FONT: Color = Color::WHITE;
+static COLOR_SELECTED: Color = Color::RGBA(255, 110, 50, 255);
+/// Background of the cells of the diagonals or windows of the variant.
+static COLOR_UNIT: Color = Color::RGBA(40, 40, 90, 255);
+static COLOR_CONFLICT: Color = Color::RED;
+static COLOR_GOOD_MSG: Color = Color::GREEN;
+static COLOR_BAD_MSG: Color = Color::RED;
+
+/// Height of a line of text of the panel.
+const ROW_HEIGHT: i32 = 40;
+
+/**
+ * Builds a puzzle by hand: clues are placed in an empty grid of the configured size, which is
+ * checked after each change for conflicting clues and for its number of solutions. A puzzle with
+ * a single solution can then be saved or played.
+ */
+#[derive(Default)]
+pub struct EditorScreen<'a> {
+    font: Option<Rc<Font<'a, 'a>>>,
+
+    /// The puzzle being built.
+    pub editor: Option<Editor>,
+    /// The (row, column) of the selected cell.
+    selected: (usize, usize),
+    /// The outcome of the last save, or why a key was refused, in green when it is good news.
+    message: Option<(String, bool)>,
+    /// Whether the puzzle changed since it was last saved.
+    dirty: bool,
+
+    /// Where the puzzles are saved.
+    save_folder: String,
+    /// Size of the new puzzles.
+    size: usize,
+
+    /// Configured margins and line thicknesses of the grid.
+    layout_config: LayoutConfig,
+    layout: Layout,
+
+    /// The question asked before leaving a puzzle that is not saved, while it is open.
+    dialog: Option<Dialog<ScreenOutcome>>,
+}
+
+impl<'a> EditorScreen<'a> {
+    pub fn set_font(&mut self, new_font: Rc<Font<'a, 'a>>) {
+        self.font = Some(new_font);
+    }
+
+    /**
+     * Starts building an empty classic puzzle of the given size, the selection on the top left
+     * cell. The screen is to be resized afterwards, the grid being laid out for the new size.
+     */
+    pub fn reset(&mut self, size: usize) -> Result<(), UiError> {
+        self.size = size;
+        self.editor = Some(Editor::new(size, Variant::Classic)?);
+        self.selected = (0, 0);
+        self.message = None;
+        self.dirty = false;
+        self.dialog = None;
+
+        Ok(())
+    }
+
+    /**
+     * Returns `outcome` if the puzzle is saved, otherwise asks `question` first.
+     */
+    fn leave(&mut self, question: &str, outcome: ScreenOutcome) -> ScreenOutcome {
+        match self.dirty {
+            true => {
+                self.dialog = Some(Dialog::new(question, outcome));
+                ScreenOutcome::Updated
+            }
+            false => outcome,
+        }
+    }
+
+    /**
+     * Draws `text` with the screen's font, centered in `area` and shrunk to fit in it.
+     */
+    fn draw_text(
+        &self,
+        canvas: &mut Canvas<Window>,
+        text: &str,
+        color: Color,
+        area: Rect,
+    ) -> Result<(), UiError> {
+        let texture_creator = canvas.texture_creator();
+        let surface = self
+            .font
+            .as_ref()
+            .unwrap()
+            .render(text)
+            .blended(color)
+            .map_err(UiError::sdl2)?;
+        let texture = texture_creator
+            .create_texture_from_surface(surface)
+            .map_err(UiError::sdl2)?;
+
+        let query = texture.query();
+        canvas
+            .copy(
+                &texture,
+                None,
+                fit_centered(query.width, query.height, area),
+            )
+            .map_err(UiError::sdl2)
+    }
+
+    /**
+     * Draws the grid with its clues, the conflicting ones in red, the selected cell and the cells
+     * of the extra units of the variant.
+     */
+    fn draw_grid(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        let editor = self.editor.as_ref().unwrap();
+        let game = &editor.game;
+        let layout = &self.layout;
+
+        for r in 0..game.side_size {
+            for c in 0..game.side_size {
+                let index = game.index(r, c);
+                let area = layout.cell_rect(r, c);
+                let in_unit =
+                    game.diagonals(r, c).next().is_some() || game.window_of(index).is_some();
+                if self.selected == (r, c) {
+                    canvas.set_draw_color(COLOR_SELECTED);
+                    canvas.fill_rect(area).map_err(UiError::sdl2)?;
+                } else if in_unit {
+                    canvas.set_draw_color(COLOR_UNIT);
+                    canvas.fill_rect(area).map_err(UiError::sdl2)?;
+                }
+
+                let value = game.grid[index].value();
+                if value != 0 {
+                    let color = match editor.check.conflicts.contains(&(r + 1, c + 1)) {
+                        true => COLOR_CONFLICT,
+                        false => COLOR_FONT,
+                    };
+                    self.draw_text(canvas, &value_symbol(value).to_string(), color, area)?;
+                }
+            }
+        }
+
+        canvas.set_draw_color(COLOR_LINES);
+        let grid_side = layout.grid_side();
+        for n in 0..=game.side_size {
+            let thickness = layout.line_thickness(n);
+            let offset = n as i32 * layout.box_size;
+            for line in [
+                Rect::new(
+                    layout.grid_x,
+                    layout.grid_y + offset,
+                    grid_side as u32 + thickness,
+                    thickness,
+                ),
+                Rect::new(
+                    layout.grid_x + offset,
+                    layout.grid_y,
+                    thickness,
+                    grid_side as u32,
+                ),
+            ] {
+                canvas.fill_rect(line).map_err(UiError::sdl2)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Draws the panel: the variant, what the check found, the last message and the keys.
+     */
+    fn draw_panel(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        let editor = self.editor.as_ref().unwrap();
+        let panel = self.layout.panel;
+        let row = |i: i32| {
+            Rect::new(
+                panel.x(),
+                panel.y() + i * ROW_HEIGHT,
+                panel.width(),
+                ROW_HEIGHT.min(panel.height() as i32).max(1) as u32,
+            )
+        };
+
+        let variant = match editor.game.variant {
+            Variant::Classic => "Classic",
+            Variant::Diagonal => "Diagonal (X-Sudoku)",
+            Variant::Hyper => "Hyper (windoku)",
+        };
+        self.draw_text(
+            canvas,
+            &format!("Variant: {} (Tab to change)", variant),
+            COLOR_FONT,
+            row(0),
+        )?;
+        let status_color = match editor.is_playable() {
+            true => COLOR_GOOD_MSG,
+            false => COLOR_BAD_MSG,
+        };
+        let status = editor.check.to_string();
+        let mut status = status.chars();
+        let status: String = status
+            .next()
+            .map(|first| first.to_uppercase().chain(status).collect())
+            .unwrap_or_default();
+        self.draw_text(canvas, &status, status_color, row(1))?;
+        if let Some((message, good)) = self.message.as_ref() {
+            let color = match good {
+                true => COLOR_GOOD_MSG,
+                false => COLOR_BAD_MSG,
+            };
+            self.draw_text(canvas, message, color, row(2))?;
+        }
+        self.draw_text(
+            canvas,
+            "Ctrl+S: save, Enter: play, Escape: main menu",
+            COLOR_FONT,
+            row(3),
+        )?;
+
+        Ok(())
+    }
+
+    /**
+     * Places the clue `value` in the selected cell, 0 removing it.
+     */
+    fn enter_value(&mut self, value: u8) -> ScreenOutcome {
+        let Some(editor) = self.editor.as_mut() else {
+            return ScreenOutcome::Unchanged;
+        };
+        let (r, c) = self.selected;
+        if editor.game.grid[editor.game.index(r, c)].value() == value {
+            return ScreenOutcome::Unchanged;
+        }
+        self.message = match editor.set_clue(r, c, value) {
+            Ok(()) => {
+                self.dirty = true;
+                None
+            }
+            Err(e) => Some((e.to_string(), false)),
+        };
+
+        ScreenOutcome::Updated
+    }
+
+    /**
+     * Moves the selection by `dr` rows and `dc` columns, unless it would leave the grid.
+     */
+    fn move_selection(&mut self, dr: isize, dc: isize) -> ScreenOutcome {
+        let Some(editor) = self.editor.as_ref() else {
+            return ScreenOutcome::Unchanged;
+        };
+        let side = editor.game.side_size as isize;
+        let (r, c) = (self.selected.0 as isize + dr, self.selected.1 as isize + dc);
+        if !(0..side).contains(&r) || !(0..side).contains(&c) {
+            return ScreenOutcome::Unchanged;
+        }
+
+        self.selected = (r as usize, c as usize);
+        ScreenOutcome::Updated
+    }
+
+    /**
+     * Switches to the next variant, from classic to diagonal, to hyper and back.
+     */
+    fn next_variant(&mut self) -> ScreenOutcome {
+        let Some(editor) = self.editor.as_mut() else {
+            return ScreenOutcome::Unchanged;
+        };
+        editor.set_variant(match editor.game.variant {
+            Variant::Classic => Variant::Diagonal,
+            Variant::Diagonal => Variant::Hyper,
+            Variant::Hyper => Variant::Classic,
+        });
+        self.dirty = true;
+
+        ScreenOutcome::Updated
+    }
+
+    /**
+     * Saves the puzzle in the save folder, named after the current time, if it is playable.
+     */
+    fn save(&mut self) -> ScreenOutcome {
+        let Some(editor) = self.editor.as_ref() else {
+            return ScreenOutcome::Unchanged;
+        };
+        let path = format!("{}{}.game", self.save_folder, chrono::offset::Utc::now());
+        self.message = match editor.save(&path) {
+            Ok(()) => {
+                self.dirty = false;
+                Some((format!("Saved to {}", path), true))
+            }
+            Err(e) => Some((format!("Not saved: {}", e), false)),
+        };
+
+        ScreenOutcome::Updated
+    }
+}
+
+impl<'a> Displayable for EditorScreen<'a> {
+    fn new() -> Self {
+        EditorScreen {
+            ..Default::default()
+        }
+    }
+
+    fn init(&mut self, canvas: &mut Canvas<Window>, config: &GUIConfig) -> Result<(), UiError> {
+        self.save_folder = config.save_folder_path.clone();
+        self.layout_config = config.layout.clone();
+        self.reset(config.game_size)?;
+
+        self.resize(canvas)
+    }
+
+    fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        canvas.set_draw_color(COLOR_BCK);
+        canvas.clear();
+
+        if self.editor.is_none() {
+            return Ok(());
+        }
+        self.draw_grid(canvas)?;
+        self.draw_panel(canvas)?;
+        if let Some(dialog) = self.dialog.as_mut() {
+            dialog.draw(canvas, self.font.as_ref().unwrap())?;
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        self.layout = Layout::new(&self.layout_config, canvas.viewport(), self.size);
+
+        Ok(())
+    }
+
+    fn update(&mut self, event: &Event) -> Result<ScreenOutcome, UiError> {
+        // The grid waits for the open question to be answered
+        if let Some(dialog) = self.dialog.as_mut() {
+            return Ok(match dialog.update(event) {
+                DialogOutcome::Unchanged => ScreenOutcome::Unchanged,
+                DialogOutcome::Updated => ScreenOutcome::Updated,
+                DialogOutcome::Confirmed(outcome) => {
+                    self.dialog = None;
+                    outcome
+                }
+                DialogOutcome::Cancelled => {
+                    self.dialog = None;
+                    ScreenOutcome::Updated
+                }
+            });
+        }
+
+        match event {
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => {
+                if let Some(cell) = self.layout.cell_at(*x, *y) {
+                    self.selected = cell;
+                    return Ok(ScreenOutcome::Updated);
+                }
+            }
+            Event::KeyDown {
+                keycode: Some(keycode),
+                keymod,
+                ..
+            } => {
+                let side_size = self.size * self.size;
+                return Ok(match keycode {
+                    Keycode::S if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) => self.save(),
+                    Keycode::Return | Keycode::KpEnter => {
+                        match self.editor.as_ref().is_some_and(Editor::is_playable) {
+                            true => ScreenOutcome::PlayEdited,
+                            false => {
+                                self.message =
+                                    Some((String::from("Only a unique solution is played"), false));
+                                ScreenOutcome::Updated
+                            }
+                        }
+                    }
+                    Keycode::Escape => self.leave("Leave the unsaved puzzle?", ScreenOutcome::Menu),
+                    Keycode::Tab => self.next_variant(),
+                    Keycode::Up => self.move_selection(-1, 0),
+                    Keycode::Down => self.move_selection(1, 0),
+                    Keycode::Left => self.move_selection(0, -1),
+                    Keycode::Right => self.move_selection(0, 1),
+                    Keycode::Backspace | Keycode::Delete | Keycode::Num0 | Keycode::Kp0 => {
+                        self.enter_value(0)
+                    }
+                    _ => match digit_from_keycode(*keycode)
+                        .filter(|digit| (*digit as usize) <= side_size)
+                        .or_else(|| letter_value_from_keycode(*keycode, side_size))
+                    {
+                        Some(value) => self.enter_value(value),
+                        None => ScreenOutcome::Unchanged,
+                    },
+                });
+            }
+            _ => {}
+        }
+
+        Ok(ScreenOutcome::Unchanged)
+    }
+}
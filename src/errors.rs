@@ -1,4 +1,5 @@
-use std::fmt;
+use std::error::Error;
+use std::{fmt, io};
 
 /**
  * Contains `game.rs` related errors.
@@ -14,19 +15,35 @@ pub enum GameError {
     /// When trying to set a value in a cell that already contains a non modifiable (initial) value.
     NonEmptyCell,
     /// Occurs when there is an error during the save file creation.
-    CreateSaveFileError,
+    CreateSaveFileError(io::Error),
     /// Occurs when trying to save a game that does not have an attached file.
     NoSaveFile,
     /// Occurs when there is an error whilst writing the game save in a file.
-    WriteSaveError,
+    WriteSaveError(io::Error),
     /// Occurs when unable to read a save file content.
-    OpenFileError,
+    OpenFileError(io::Error),
     /// Occurs when there is an error whilst parsing a save file.
     ParseSaveFileError,
     /// Occurs when the save file was loaded but contains erroneous values.
     IncorrectSaveFile,
     /// Occurs when unable to open an existing save file.
-    OpenSaveFileError,
+    OpenSaveFileError(io::Error),
+    /// Occurs when the solution of the grid is neither stored nor found by solving it.
+    NoSolution,
+    /// Occurs when trying to end a guess whilst none was started.
+    NoGuess,
+    /// Occurs when a puzzle does not describe a valid grid.
+    InvalidPuzzle,
+    /// Occurs when another running instance is using the same save file.
+    SaveLocked,
+    /// Occurs when trying to undo whilst no move was played.
+    NothingToUndo,
+    /// Occurs when trying to redo whilst no move was undone.
+    NothingToRedo,
+    /// Occurs when trying to replay a game whose moves were not recorded.
+    NothingToReplay,
+    /// Occurs when a puzzle cannot be downloaded.
+    FetchError,
 }
 
 impl fmt::Display for GameError {
@@ -36,16 +53,27 @@ impl fmt::Display for GameError {
             GameError::InvalidValue => write!(f, "Invalid value for this cell."),
             GameError::IllegalPosition => write!(f, "This cell position is invalid."),
             GameError::NonEmptyCell => write!(f, "This cell already contain a value."),
-            GameError::CreateSaveFileError => write!(f, "Unable to create the save file."),
+            GameError::CreateSaveFileError(_) => write!(f, "Unable to create the save file."),
             GameError::NoSaveFile => write!(
                 f,
                 "Cannot save the game because it does not have a save file attached."
             ),
-            GameError::WriteSaveError => write!(f, "Unable to save to file."),
-            GameError::OpenFileError => write!(f, "Unable to read the save file content."),
+            GameError::WriteSaveError(_) => write!(f, "Unable to save to file."),
+            GameError::OpenFileError(_) => write!(f, "Unable to read the save file content."),
             GameError::ParseSaveFileError => write!(f, "Unable to parse the save file correctly."),
             GameError::IncorrectSaveFile => write!(f, "The save file contains erroneous data."),
-            GameError::OpenSaveFileError => write!(f, "Unable to open the save file again."),
+            GameError::OpenSaveFileError(_) => write!(f, "Unable to open the save file again."),
+            GameError::NoSolution => write!(f, "Unable to find the solution of this grid."),
+            GameError::NoGuess => write!(f, "There is no ongoing guess."),
+            GameError::InvalidPuzzle => write!(f, "The puzzle does not describe a valid grid."),
+            GameError::SaveLocked => write!(
+                f,
+                "This save file is already used by another running instance of the game."
+            ),
+            GameError::NothingToUndo => write!(f, "There is no move to undo."),
+            GameError::NothingToRedo => write!(f, "There is no move to redo."),
+            GameError::NothingToReplay => write!(f, "There is no recorded move to replay."),
+            GameError::FetchError => write!(f, "Unable to download the puzzle."),
         }
     }
 }
@@ -53,42 +81,76 @@ impl fmt::Display for GameError {
 /**
  * Contains `solver.rs` related errors.
  */
+#[derive(Debug)]
 pub enum SolverError {
     /// When a solver does not succeed in solving a game.
     FailedToSolve,
+    /// When a solver gives up on a game, having tried as many cells as it was allowed to.
+    OutOfNodes,
 }
 
 impl fmt::Display for SolverError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             SolverError::FailedToSolve => write!(f, "Failed to solve the grid."),
+            SolverError::OutOfNodes => write!(f, "Gave up solving the grid, taking too long."),
         }
     }
 }
 
 /**
- * Contains errors related to the `Ui` trait of `ui.rs`.
- *
- * TODO: Implement the From::<GuiError> trait (or the other way around) so that the implementations
- * of Gui and Cli can return their own errors.
+ * Contains errors related to the `Ui` trait of `ui.rs`, the game errors being converted into them.
  */
 #[derive(Debug)]
 pub enum UiError {
     /// Occurs when there is a an error whilst loading the configuration file.
-    LoadConfigError,
+    LoadConfigError(io::Error),
     /// Occurs when there is a syntax error in the configuration file.
-    ConfigSyntaxError,
+    ConfigSyntaxError(serde_json::Error),
     /// Occurs when there is an error whilst loading the font file.
-    LoadFontError,
-    LoadSpriteError,
+    LoadFontError(Box<dyn Error + Send + Sync>),
+    /// Occurs when there is an error whilst loading an image file.
+    LoadSpriteError(Box<dyn Error + Send + Sync>),
     /// Occurs when there is an error during the save file creation.
-    CreateSaveFileError,
+    CreateSaveFileError(io::Error),
     /// Occurs when the loaded textures are missing a texture.
     MissingLoadedTexture,
-    /// Occurs when a SDL2 error occurs.
-    SDL2Error,
+    /// Occurs when a SDL2 error occurs, SDL2 mostly describing its errors as strings.
+    SDL2Error(Box<dyn Error + Send + Sync>),
     /// occurs when there is an error writting the updated configuration file.
-    WriteConfigError,
+    WriteConfigError(io::Error),
+    /// Occurs when the profiles file exists but cannot be parsed.
+    LoadProfilesError(serde_json::Error),
+    /// Occurs when the profiles file cannot be written.
+    WriteProfilesError(io::Error),
+    /// Occurs when the puzzle packs or the progress file cannot be read.
+    LoadPacksError(io::Error),
+    /// Occurs when the progress file cannot be written.
+    WriteProgressError(io::Error),
+    /// Occurs when the generation settings of the configuration file are inconsistent.
+    InvalidGenerationConfig,
+    /// Occurs when the game size of the configuration file is not one puzzles are generated for.
+    InvalidGameSize,
+    /// Occurs when the game to play is already played by another running instance.
+    SaveLocked,
+    /// Occurs when the puzzle to import cannot be read or is not a valid f-puzzles JSON.
+    ImportPuzzleError,
+    /// Occurs when the terminal cannot be put in raw mode for the TUI.
+    TerminalError(io::Error),
+    /// Occurs when a sound effect or the music of the configuration cannot be loaded.
+    LoadSoundError(Box<dyn Error + Send + Sync>),
+    /// Occurs when the window cannot be captured or its image written.
+    ScreenshotError(Box<dyn Error + Send + Sync>),
+    /// Occurs when a color of the theme in the configuration file is not `#RRGGBB(AA)`.
+    InvalidThemeColor,
+    /// Occurs when the other player of a network game cannot be reached, or waited for.
+    NetworkError(io::Error),
+    /// Occurs when the other player of a network game runs another version of the messages.
+    ProtocolMismatch,
+    /// Occurs when the games cannot be served on the asked address.
+    ServeError(io::Error),
+    /// Occurs when an operation on the game fails, the game error telling why.
+    Game(GameError),
 }
 
 impl fmt::Display for UiError {
@@ -96,26 +158,119 @@ impl fmt::Display for UiError {
         match self {
             // UiError::SaveError => write!(f, "Failed to save the game."),
             // UiError::FileWriteError => write!(f, "Failed to write to file."),
-            UiError::LoadConfigError => write!(f, "Failed to load the configuration file."),
-            UiError::ConfigSyntaxError => write!(f, "Configuration file syntax error."),
-            UiError::LoadSpriteError => write!(f, "Failed to load the sprite."),
-            UiError::LoadFontError => write!(f, "Unable to load font file."),
-            UiError::CreateSaveFileError => write!(f, "Unable to create the save file."),
-            UiError::SDL2Error => write!(f, "Generic SDL2 Error"),
+            UiError::LoadConfigError(_) => write!(f, "Failed to load the configuration file."),
+            UiError::ConfigSyntaxError(_) => write!(f, "Configuration file syntax error."),
+            UiError::LoadSpriteError(_) => write!(f, "Failed to load the sprite."),
+            UiError::LoadFontError(_) => write!(f, "Unable to load font file."),
+            UiError::CreateSaveFileError(_) => write!(f, "Unable to create the save file."),
+            UiError::SDL2Error(_) => write!(f, "Generic SDL2 Error"),
             UiError::MissingLoadedTexture => write!(f, "Missing loaded texture."),
-            UiError::WriteConfigError => write!(
+            UiError::WriteConfigError(_) => write!(
                 f,
                 "An error occured when trying to write the updated configuration file."
             ),
+            UiError::LoadProfilesError(_) => write!(f, "Unable to load the profiles file."),
+            UiError::WriteProfilesError(_) => write!(f, "Unable to write the profiles file."),
+            UiError::LoadPacksError(_) => write!(f, "Unable to load the puzzle packs."),
+            UiError::WriteProgressError(_) => write!(f, "Unable to write the progress file."),
+            UiError::SaveLocked => write!(
+                f,
+                "The game is already being played by another running instance."
+            ),
+            UiError::ImportPuzzleError => write!(f, "Unable to import the puzzle."),
+            UiError::TerminalError(_) => write!(f, "Unable to set up the terminal."),
+            UiError::ScreenshotError(_) => write!(f, "Unable to save the screenshot."),
+            UiError::LoadSoundError(_) => write!(f, "Unable to load the sound."),
+            UiError::InvalidThemeColor => write!(
+                f,
+                "Invalid theme color in the configuration file, expected #RRGGBB or #RRGGBBAA."
+            ),
+            UiError::NetworkError(_) => write!(f, "Unable to reach the other player."),
+            UiError::ProtocolMismatch => {
+                write!(f, "The other player runs another version of the game.")
+            }
+            UiError::ServeError(_) => write!(f, "Unable to serve the games on this address."),
+            UiError::Game(game_error) => write!(f, "{}", game_error),
+            UiError::InvalidGameSize => write!(
+                f,
+                "The game size of the configuration file must be between 2 and 4."
+            ),
+            UiError::InvalidGenerationConfig => write!(
+                f,
+                "The clue percentages of the generation settings must be ordered as min <= medium <= easy <= 100, and the unfill attempts must not be 0."
+            ),
         }
     }
 }
 
+impl UiError {
+    /// Wraps the error of a SDL2 call, usually its message, in a `UiError::SDL2Error`.
+    pub fn sdl2(error: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        UiError::SDL2Error(error.into())
+    }
+}
+
 impl From<GameError> for UiError {
     fn from(game_error: GameError) -> Self {
         match game_error {
-            GameError::CreateSaveFileError => Self::CreateSaveFileError,
-            _ => todo!(),
+            GameError::CreateSaveFileError(e) => Self::CreateSaveFileError(e),
+            GameError::SaveLocked => Self::SaveLocked,
+            GameError::InvalidPuzzle => Self::ImportPuzzleError,
+            game_error => Self::Game(game_error),
         }
     }
 }
+
+impl Error for GameError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GameError::CreateSaveFileError(e)
+            | GameError::WriteSaveError(e)
+            | GameError::OpenFileError(e)
+            | GameError::OpenSaveFileError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Error for SolverError {}
+
+impl Error for UiError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            UiError::LoadConfigError(e)
+            | UiError::WriteConfigError(e)
+            | UiError::CreateSaveFileError(e)
+            | UiError::WriteProfilesError(e)
+            | UiError::LoadPacksError(e)
+            | UiError::WriteProgressError(e)
+            | UiError::TerminalError(e)
+            | UiError::NetworkError(e)
+            | UiError::ServeError(e) => Some(e),
+            UiError::ConfigSyntaxError(e) | UiError::LoadProfilesError(e) => Some(e),
+            UiError::LoadFontError(e)
+            | UiError::LoadSpriteError(e)
+            | UiError::SDL2Error(e)
+            | UiError::ScreenshotError(e)
+            | UiError::LoadSoundError(e) => Some(e.as_ref()),
+            // The game error already gives the message, only its own source is left
+            UiError::Game(game_error) => game_error.source(),
+            _ => None,
+        }
+    }
+}
+
+/**
+ * Formats `error` followed by the errors it comes from, as `error: source: source of the
+ * source`, for the diagnostics printed when leaving the game.
+ */
+pub fn report(error: &dyn Error) -> String {
+    let mut report = error.to_string();
+    let mut source = error.source();
+    while let Some(error) = source {
+        report.push_str(&format!(": {}", error));
+        source = error.source();
+    }
+
+    report
+}
@@ -0,0 +1,167 @@
+use crate::game::NotesClear;
+
+use chrono::{DateTime, Local};
+use std::fmt;
+
+/**
+ * Something that happened during a game session.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    /// A value was placed in the cell at the given (zero based) row and column.
+    Move {
+        row: usize,
+        column: usize,
+        value: u8,
+    },
+    /// The cell at the given (zero based) row and column was emptied.
+    Clear { row: usize, column: usize },
+    /// The note of a value was added to or removed from the cell at the given (zero based) row
+    /// and column.
+    NoteToggled {
+        row: usize,
+        column: usize,
+        value: u8,
+        set: bool,
+    },
+    /// The note of a value was added to or removed from several cells at once.
+    NotesToggled { value: u8, cells: usize, set: bool },
+    /// The notes of every empty cell were set to its candidates.
+    NotesFilled,
+    /// The notes were set to be kept equal to the candidates after every change, or no longer.
+    AutoNotes(bool),
+    /// Some notes were cleared.
+    NotesCleared(NotesClear),
+    /// Some cells were painted with a marker color, or had their color removed with 0.
+    Painted { cells: usize, color: u8 },
+    /// Another player sharing the grid changed the cell at (row, column) to hold `value`, 0
+    /// for none.
+    Shared {
+        row: usize,
+        column: usize,
+        value: u8,
+    },
+    /// The last move was undone.
+    Undo,
+    /// The last undone move was played again.
+    Redo,
+    /// The given number of moves were reverted to get back to a correct grid.
+    Rollback(usize),
+    /// A guess was started, reaching the given depth of nested guesses.
+    GuessStarted(usize),
+    /// The current guess was ended, keeping its moves.
+    GuessCommitted,
+    /// The current guess was ended, reverting the given number of moves.
+    GuessDiscarded(usize),
+    /// A hint was shown, explained by the given sentence.
+    Hint(String),
+    /// An action was refused.
+    Error(String),
+    /// The game was saved by the session, when it closed.
+    Autosave,
+    /// The grid was correctly completed.
+    Completed,
+}
+
+impl fmt::Display for GameEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameEvent::Move { row, column, value } => write!(
+                f,
+                "Placed {} at row {}, column {}.",
+                value,
+                row + 1,
+                column + 1
+            ),
+            GameEvent::Clear { row, column } => {
+                write!(f, "Cleared row {}, column {}.", row + 1, column + 1)
+            }
+            GameEvent::NoteToggled {
+                row,
+                column,
+                value,
+                set,
+            } => write!(
+                f,
+                "{} the note {} at row {}, column {}.",
+                match set {
+                    true => "Added",
+                    false => "Removed",
+                },
+                value,
+                row + 1,
+                column + 1
+            ),
+            GameEvent::NotesToggled { value, cells, set } => write!(
+                f,
+                "{} the note {} in {} cells.",
+                match set {
+                    true => "Added",
+                    false => "Removed",
+                },
+                value,
+                cells
+            ),
+            GameEvent::NotesFilled => write!(f, "Filled the notes with the candidates."),
+            GameEvent::AutoNotes(true) => write!(f, "Turned the automatic notes on."),
+            GameEvent::AutoNotes(false) => write!(f, "Turned the automatic notes off."),
+            GameEvent::NotesCleared(which) => write!(f, "Cleared the notes of {}.", which),
+            GameEvent::Painted { cells, color: 0 } => {
+                write!(f, "Removed the color of {} cells.", cells)
+            }
+            GameEvent::Painted { cells, color } => {
+                write!(f, "Painted {} cells with the color {}.", cells, color)
+            }
+            GameEvent::Shared { row, column, value } => write!(
+                f,
+                "The other player set ({}, {}) to {}.",
+                row + 1,
+                column + 1,
+                value
+            ),
+            GameEvent::Undo => write!(f, "Undid the last move."),
+            GameEvent::Redo => write!(f, "Redid the last undone move."),
+            GameEvent::Rollback(moves) => write!(f, "Rolled back {} moves.", moves),
+            GameEvent::GuessStarted(depth) => write!(f, "Started a guess (depth {}).", depth),
+            GameEvent::GuessCommitted => write!(f, "Kept the current guess."),
+            GameEvent::GuessDiscarded(moves) => {
+                write!(f, "Discarded the current guess ({} moves).", moves)
+            }
+            GameEvent::Error(message) => write!(f, "Error: {}", message),
+            GameEvent::Hint(description) => write!(f, "Hint: {}", description),
+            GameEvent::Autosave => write!(f, "Game saved."),
+            GameEvent::Completed => write!(f, "Grid completed !"),
+        }
+    }
+}
+
+/**
+ * A game event along with the moment it happened.
+ */
+#[derive(Clone, Debug)]
+pub struct LoggedEvent {
+    pub time: DateTime<Local>,
+    pub event: GameEvent,
+    /// The (zero based) player that caused the event, in hot-seat games.
+    pub player: Option<usize>,
+}
+
+impl LoggedEvent {
+    pub fn new(event: GameEvent, player: Option<usize>) -> Self {
+        LoggedEvent {
+            time: Local::now(),
+            event,
+            player,
+        }
+    }
+}
+
+impl fmt::Display for LoggedEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}] ", self.time.format("%H:%M:%S"))?;
+        if let Some(player) = self.player {
+            write!(f, "Player {}: ", player + 1)?;
+        }
+        write!(f, "{}", self.event)
+    }
+}
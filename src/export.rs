@@ -0,0 +1,276 @@
+use crate::game::{value_symbol, Game};
+
+/// Thickness of the lines between cells, and of the borders of the regions.
+const THIN_LINE: f64 = 1.0;
+const THICK_LINE: f64 = 3.0;
+/// Color of the values placed by the player, the clues being black.
+const COLOR_PLAYED: (u8, u8, u8) = (31, 79, 191);
+/// Color of the candidate marks.
+const COLOR_CANDIDATE: (u8, u8, u8) = (110, 110, 110);
+
+/**
+ * How a grid is exported.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// Side of a cell, in pixels for SVG and points for PDF.
+    pub cell_size: u32,
+    /// Font family of the values. PDF files always use Helvetica.
+    pub font: String,
+    /// Whether the empty cells show the values they can still hold.
+    pub candidates: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        ExportOptions {
+            cell_size: 40,
+            font: String::from("sans-serif"),
+            candidates: false,
+        }
+    }
+}
+
+/**
+ * What an exported grid is drawn with, from the top left corner of the page.
+ */
+enum Shape {
+    Line {
+        from: (f64, f64),
+        to: (f64, f64),
+        width: f64,
+    },
+    /// A text centered on `center`, in bold for the clues.
+    Text {
+        center: (f64, f64),
+        size: f64,
+        text: String,
+        color: (u8, u8, u8),
+        bold: bool,
+    },
+}
+
+/**
+ * Returns the side of the page of `game`, and the shapes drawing it: its values, the candidates
+ * of its empty cells if asked for, then its lines, thicker around its regions.
+ */
+fn shapes(game: &Game, options: &ExportOptions) -> (f64, Vec<Shape>) {
+    let cell = options.cell_size as f64;
+    let margin = cell / 2.0;
+    let side = game.side_size;
+    let corner = |r: usize, c: usize| (margin + c as f64 * cell, margin + r as f64 * cell);
+    let mut shapes = Vec::new();
+
+    for index in 0..game.grid.len() {
+        let (r, c) = game.coordinates(index);
+        let (x, y) = corner(r, c);
+        let value = game.grid[index];
+        if value.value() != 0 {
+            shapes.push(Shape::Text {
+                center: (x + cell / 2.0, y + cell / 2.0),
+                size: cell * 0.6,
+                text: value_symbol(value.value()).to_string(),
+                color: match value.initial() {
+                    true => (0, 0, 0),
+                    false => COLOR_PLAYED,
+                },
+                bold: value.initial(),
+            });
+        } else if options.candidates {
+            // Laid out like a box, 1 at its top left
+            let mark = cell / game.size as f64;
+            for candidate in game.valids(index) {
+                let (mr, mc) = (
+                    (candidate as usize - 1) / game.size,
+                    (candidate as usize - 1) % game.size,
+                );
+                shapes.push(Shape::Text {
+                    center: (x + (mc as f64 + 0.5) * mark, y + (mr as f64 + 0.5) * mark),
+                    size: mark * 0.7,
+                    text: value_symbol(candidate).to_string(),
+                    color: COLOR_CANDIDATE,
+                    bold: false,
+                });
+            }
+        }
+    }
+
+    for r in 0..=side {
+        for c in 0..=side {
+            let (x, y) = corner(r, c);
+            let (top, left) = game.borders(r, c);
+            let width = |border: bool| match border {
+                true => THICK_LINE,
+                false => THIN_LINE,
+            };
+            if c < side {
+                shapes.push(Shape::Line {
+                    from: (x, y),
+                    to: (x + cell, y),
+                    width: width(top),
+                });
+            }
+            if r < side {
+                shapes.push(Shape::Line {
+                    from: (x, y),
+                    to: (x, y + cell),
+                    width: width(left),
+                });
+            }
+        }
+    }
+
+    (side as f64 * cell + 2.0 * margin, shapes)
+}
+
+/**
+ * Escapes the characters of `text` that cannot appear as is in an XML attribute.
+ */
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/**
+ * Renders `game` as an SVG image.
+ */
+pub fn to_svg(game: &Game, options: &ExportOptions) -> String {
+    let (page, shapes) = shapes(game, options);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" viewBox=\"0 0 {0} {0}\">\n<rect width=\"{0}\" height=\"{0}\" fill=\"white\"/>\n",
+        page
+    );
+
+    for shape in shapes {
+        match shape {
+            Shape::Line { from, to, width } => svg.push_str(&format!(
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"black\" stroke-width=\"{}\" stroke-linecap=\"square\"/>\n",
+                from.0, from.1, to.0, to.1, width
+            )),
+            Shape::Text {
+                center,
+                size,
+                text,
+                color,
+                bold,
+            } => svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" font-family=\"{}\" font-size=\"{}\"{} fill=\"rgb({},{},{})\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+                center.0,
+                center.1,
+                xml_escape(&options.font),
+                size,
+                match bold {
+                    true => " font-weight=\"bold\"",
+                    false => "",
+                },
+                color.0,
+                color.1,
+                color.2,
+                xml_escape(&text)
+            )),
+        }
+    }
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+/**
+ * Renders `game` as a single page PDF document, its values written in Helvetica.
+ */
+pub fn to_pdf(game: &Game, options: &ExportOptions) -> Vec<u8> {
+    let (page, shapes) = shapes(game, options);
+
+    // The origin of a PDF page is its bottom left corner
+    let mut content = String::new();
+    for shape in shapes {
+        match shape {
+            Shape::Line { from, to, width } => content.push_str(&format!(
+                "{} w {:.2} {:.2} m {:.2} {:.2} l S\n",
+                width,
+                from.0,
+                page - from.1,
+                to.0,
+                page - to.1
+            )),
+            Shape::Text {
+                center,
+                size,
+                text,
+                color,
+                bold,
+            } => {
+                // The symbols are about 0.56 em wide, and 0.7 em high
+                let width = 0.56 * size * text.chars().count() as f64;
+                content.push_str(&format!(
+                    "BT /{} {:.2} Tf {:.3} {:.3} {:.3} rg {:.2} {:.2} Td ({}) Tj ET\n",
+                    match bold {
+                        true => "F2",
+                        false => "F1",
+                    },
+                    size,
+                    color.0 as f64 / 255.0,
+                    color.1 as f64 / 255.0,
+                    color.2 as f64 / 255.0,
+                    center.0 - width / 2.0,
+                    page - center.1 - 0.35 * size,
+                    text
+                ));
+            }
+        }
+    }
+
+    let objects = [
+        String::from("<< /Type /Catalog /Pages 2 0 R >>"),
+        String::from("<< /Type /Pages /Kids [3 0 R] /Count 1 >>"),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {0} {0}] /Contents 4 0 R /Resources << /Font << /F1 5 0 R /F2 6 0 R >> >> >>",
+            page
+        ),
+        format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ),
+        String::from("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>"),
+        String::from("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold >>"),
+    ];
+
+    // Each object is listed with its position in the file
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+    }
+    let xref = pdf.len();
+    pdf.push_str(&format!(
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.len() + 1
+    ));
+    for offset in offsets {
+        pdf.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    pdf.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    ));
+
+    pdf.into_bytes()
+}
+
+/**
+ * Renders `game` as a PDF document if `path` ends with `.pdf`, as CSV (see `Game::to_csv`) if it
+ * ends with `.csv`, as an SVG image otherwise, and writes it at `path`.
+ */
+pub fn export(game: &Game, path: &str, options: &ExportOptions) -> std::io::Result<()> {
+    let lowercase = path.to_ascii_lowercase();
+    match lowercase.rsplit_once('.').map(|(_, extension)| extension) {
+        Some("pdf") => std::fs::write(path, to_pdf(game, options)),
+        Some("csv") => std::fs::write(path, game.to_csv()),
+        _ => std::fs::write(path, to_svg(game, options)),
+    }
+}
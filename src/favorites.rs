@@ -0,0 +1,87 @@
+use crate::errors::GameError;
+use crate::game::Game;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/**
+ * A puzzle the player marked as favorite.
+ */
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Favorite {
+    /// Canonical form of the puzzle, see `Game::puzzle_id`.
+    pub id: String,
+    /// Share code of the puzzle, keeping its variant, regions and cages, see
+    /// `Game::to_share_code`.
+    pub code: String,
+    /// Size of the puzzle's board.
+    pub size: usize,
+    /// When the puzzle was marked as favorite.
+    pub added: String,
+}
+
+impl Favorite {
+    /**
+     * Returns the puzzle of `game` as a favorite added now.
+     */
+    pub fn new(game: &Game) -> Self {
+        Favorite {
+            id: game.puzzle_id(),
+            code: game.to_share_code(),
+            size: game.size,
+            added: chrono::offset::Local::now()
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+        }
+    }
+
+    /**
+     * Creates a game of the favorite puzzle, with its variant, saved at `saving_path` if given.
+     */
+    pub fn to_game(&self, saving_path: Option<&str>) -> Result<Game, GameError> {
+        Game::from_share_code(&self.code, saving_path)
+    }
+}
+
+impl fmt::Display for Favorite {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let side_size = self.size * self.size;
+        write!(f, "{}x{} - added {}", side_size, side_size, self.added)
+    }
+}
+
+/**
+ * The favorite puzzles of a player, kept in their profile.
+ */
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, Debug)]
+#[serde(transparent)]
+pub struct Favorites {
+    pub puzzles: Vec<Favorite>,
+}
+
+impl Favorites {
+    /**
+     * Returns whether the puzzle with the given share code is a favorite.
+     */
+    pub fn contains(&self, code: &str) -> bool {
+        self.puzzles.iter().any(|p| p.code == code)
+    }
+
+    /**
+     * Adds the puzzle of `game` to the favorites if it is not one yet, removes it otherwise.
+     * Returns whether the puzzle is now a favorite.
+     */
+    pub fn toggle(&mut self, game: &Game) -> bool {
+        let code = game.to_share_code();
+        match self.contains(&code) {
+            true => {
+                self.puzzles.retain(|p| p.code != code);
+                false
+            }
+            false => {
+                self.puzzles.push(Favorite::new(game));
+                true
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use crate::errors::GameError;
+use crate::game::Game;
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::time::Duration;
+
+/// Name of the file keeping the last downloaded daily puzzle, stored in the save folder.
+const DAILY_FILE: &str = "daily.json";
+/// How long connecting to the endpoint, and waiting for its answer, may take.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/**
+ * The daily puzzle kept in the save folder, to be played again without downloading it.
+ */
+#[derive(Serialize, Deserialize)]
+struct DailyPuzzle {
+    /// The day it was downloaded, as `YYYY-MM-DD`.
+    date: String,
+    /// Canonical form of the puzzle, see `Game::puzzle_id`.
+    puzzle: String,
+}
+
+/**
+ * What the endpoint answers: either the rows of the board with 0 for the empty cells, as the
+ * sugoku API does, or the canonical form of the puzzle.
+ */
+#[derive(Deserialize)]
+struct Answer {
+    #[serde(default)]
+    board: Option<Vec<Vec<u8>>>,
+    #[serde(default)]
+    puzzle: Option<String>,
+}
+
+/**
+ * Returns the canonical form of the puzzle of the JSON answer `body`, see `Answer`.
+ */
+pub fn parse_answer(body: &str) -> Result<String, GameError> {
+    let answer: Answer = serde_json::from_str(body).map_err(|_| GameError::InvalidPuzzle)?;
+    let puzzle = match (answer.board, answer.puzzle) {
+        (Some(board), _) => board
+            .iter()
+            .flatten()
+            .map(|value| match value {
+                0 => '.',
+                _ => char::from_digit(*value as u32, 36).unwrap_or('?'),
+            })
+            .collect(),
+        (None, Some(puzzle)) => puzzle,
+        (None, None) => return Err(GameError::InvalidPuzzle),
+    };
+
+    // Only valid grids are kept
+    Game::from_puzzle(&puzzle, None).map(|game| game.puzzle_id())
+}
+
+/**
+ * Decodes the body of an HTTP answer sent in chunks, each preceded by its size.
+ */
+fn dechunk(body: &str) -> Result<String, GameError> {
+    let mut decoded = String::new();
+    let mut rest = body;
+    loop {
+        let (size, chunk) = rest.split_once("\r\n").ok_or(GameError::FetchError)?;
+        let size = size.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16).map_err(|_| GameError::FetchError)?;
+        if size == 0 {
+            return Ok(decoded);
+        }
+        decoded.push_str(chunk.get(..size).ok_or(GameError::FetchError)?);
+        rest = chunk
+            .get(size..)
+            .and_then(|r| r.strip_prefix("\r\n"))
+            .ok_or(GameError::FetchError)?;
+    }
+}
+
+/**
+ * Downloads the body of the page at `url` with an HTTP GET request. Only plain `http://` URLs
+ * are supported.
+ */
+pub fn http_get(url: &str) -> Result<String, GameError> {
+    let rest = url.strip_prefix("http://").ok_or(GameError::FetchError)?;
+    let (host, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let address = match host.contains(':') {
+        true => host.to_string(),
+        false => format!("{}:80", host),
+    };
+
+    let address = address
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addresses| addresses.next())
+        .ok_or(GameError::FetchError)?;
+    let mut stream =
+        TcpStream::connect_timeout(&address, TIMEOUT).map_err(|_| GameError::FetchError)?;
+    stream
+        .set_read_timeout(Some(TIMEOUT))
+        .map_err(|_| GameError::FetchError)?;
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: {}\r\nAccept: application/json\r\nConnection: close\r\n\r\n",
+        path, host
+    )
+    .map_err(|_| GameError::FetchError)?;
+
+    let mut answer = Vec::new();
+    stream
+        .read_to_end(&mut answer)
+        .map_err(|_| GameError::FetchError)?;
+    let answer = String::from_utf8_lossy(&answer);
+    let (head, body) = answer.split_once("\r\n\r\n").ok_or(GameError::FetchError)?;
+
+    let status = head
+        .lines()
+        .next()
+        .and_then(|l| l.split_whitespace().nth(1));
+    if status != Some("200") {
+        return Err(GameError::FetchError);
+    }
+    let chunked = head.lines().any(|line| {
+        let line = line.to_ascii_lowercase();
+        line.starts_with("transfer-encoding:") && line.contains("chunked")
+    });
+
+    match chunked {
+        true => dechunk(body),
+        false => Ok(body.to_string()),
+    }
+}
+
+/**
+ * Returns the puzzle of the day, downloaded from `url` the first time it is asked for on a given
+ * day and kept in `save_folder` for the next ones. Offline, the last downloaded puzzle is played
+ * again.
+ */
+pub fn daily_puzzle(
+    url: &str,
+    save_folder: &str,
+    saving_path: Option<&str>,
+) -> Result<Game, GameError> {
+    let path = Path::new(save_folder).join(DAILY_FILE);
+    let today = chrono::offset::Local::now().format("%Y-%m-%d").to_string();
+    let cached = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<DailyPuzzle>(&content).ok());
+
+    let puzzle = match cached {
+        Some(daily) if daily.date == today => daily.puzzle,
+        cached => match http_get(url).and_then(|body| parse_answer(&body)) {
+            Ok(puzzle) => {
+                let daily = DailyPuzzle {
+                    date: today,
+                    puzzle,
+                };
+                let content = serde_json::to_string(&daily)
+                    .map_err(|e| GameError::WriteSaveError(e.into()))?;
+                fs::write(&path, content).map_err(GameError::WriteSaveError)?;
+                daily.puzzle
+            }
+            Err(e) => cached.map(|daily| daily.puzzle).ok_or(e)?,
+        },
+    };
+
+    Game::from_puzzle(&puzzle, saving_path)
+}
@@ -0,0 +1,233 @@
+//! C API of the engine, declared in `include/rs_sudoku.h`, for the other languages to play games
+//! through the `cdylib` built with `cargo build --lib --release --no-default-features --features
+//! ffi`. The games are opaque pointers, created by `sudoku_new` or `sudoku_generate` and released
+//! by `sudoku_free`; the rows, columns and values follow the conventions of `Game`.
+
+use crate::errors::GameError;
+use crate::game::{Cell, Game, Validation};
+use crate::generator::{self, Difficulty, GenerationConfig};
+
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::ptr;
+
+/// Returned when the call succeeded.
+pub const SUDOKU_OK: c_int = 0;
+/// The value is not in the range of the grid.
+pub const SUDOKU_ILLEGAL_VALUE: c_int = -1;
+/// The value conflicts with its row, column or box.
+pub const SUDOKU_INVALID_VALUE: c_int = -2;
+/// The row or the column is out of the grid.
+pub const SUDOKU_ILLEGAL_POSITION: c_int = -3;
+/// The cell holds a clue.
+pub const SUDOKU_NON_EMPTY_CELL: c_int = -4;
+/// The grid has no solution.
+pub const SUDOKU_NO_SOLUTION: c_int = -5;
+/// The game pointer is null.
+pub const SUDOKU_NULL_GAME: c_int = -6;
+/// Any other error of the engine.
+pub const SUDOKU_ERROR: c_int = -7;
+
+/**
+ * Returns the code standing for `error` in the C API.
+ */
+fn error_code(error: &GameError) -> c_int {
+    match error {
+        GameError::IllegalValue => SUDOKU_ILLEGAL_VALUE,
+        GameError::InvalidValue => SUDOKU_INVALID_VALUE,
+        GameError::IllegalPosition => SUDOKU_ILLEGAL_POSITION,
+        GameError::NonEmptyCell => SUDOKU_NON_EMPTY_CELL,
+        GameError::NoSolution => SUDOKU_NO_SOLUTION,
+        _ => SUDOKU_ERROR,
+    }
+}
+
+/**
+ * Returns the code of the outcome of a call on a game.
+ */
+fn outcome(result: Result<(), GameError>) -> c_int {
+    match result {
+        Ok(()) => SUDOKU_OK,
+        Err(e) => error_code(&e),
+    }
+}
+
+/**
+ * Creates a game from a puzzle written like `Game::puzzle_id`, or returns null if it does not
+ * describe a grid.
+ *
+ * # Safety
+ *
+ * `puzzle` must be null or a nul terminated string.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_new(puzzle: *const c_char) -> *mut Game {
+    if puzzle.is_null() {
+        return ptr::null_mut();
+    }
+    let Ok(puzzle) = CStr::from_ptr(puzzle).to_str() else {
+        return ptr::null_mut();
+    };
+
+    match Game::from_puzzle(puzzle, None) {
+        Ok(game) => Box::into_raw(Box::new(game)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/**
+ * Generates a puzzle of the given size (2 to 4, see `generator::SIZES`) and difficulty (0 for easy
+ * to 3 for expert), always the same one for a given `seed`. Returns null if the size or the
+ * difficulty is out of range.
+ */
+#[no_mangle]
+pub extern "C" fn sudoku_generate(size: usize, difficulty: c_int, seed: u64) -> *mut Game {
+    let difficulty = match difficulty {
+        0 => Difficulty::Easy,
+        1 => Difficulty::Medium,
+        2 => Difficulty::Hard,
+        3 => Difficulty::Expert,
+        _ => return ptr::null_mut(),
+    };
+    if !generator::SIZES.contains(&size) {
+        return ptr::null_mut();
+    }
+
+    let game = generator::generate_seeded(size, difficulty, &GenerationConfig::default(), seed);
+    Box::into_raw(Box::new(game))
+}
+
+/**
+ * Releases a game.
+ *
+ * # Safety
+ *
+ * `game` must be null or a game returned by `sudoku_new` or `sudoku_generate`, not released yet.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_free(game: *mut Game) {
+    if !game.is_null() {
+        drop(Box::from_raw(game));
+    }
+}
+
+/**
+ * Returns the number of rows of the grid, or 0 if `game` is null.
+ *
+ * # Safety
+ *
+ * `game` must be null or a game that is not released.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_side(game: *const Game) -> usize {
+    game.as_ref().map_or(0, |game| game.side_size)
+}
+
+/**
+ * Returns the value of the cell at `row` and `column`, 0 when it is empty, or an error code.
+ *
+ * # Safety
+ *
+ * `game` must be null or a game that is not released.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_value(game: *const Game, row: usize, column: usize) -> c_int {
+    let Some(game) = game.as_ref() else {
+        return SUDOKU_NULL_GAME;
+    };
+    if row >= game.side_size || column >= game.side_size {
+        return SUDOKU_ILLEGAL_POSITION;
+    }
+
+    game.grid[game.index(row, column)].value().into()
+}
+
+/**
+ * Places `value` in the cell at `row` and `column` if it does not conflict with its row, column
+ * and box, 0 clearing the cell. Returns `SUDOKU_OK` or why the move was refused.
+ *
+ * # Safety
+ *
+ * `game` must be null or a game that is not released.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_do_move(
+    game: *mut Game,
+    row: usize,
+    column: usize,
+    value: u8,
+) -> c_int {
+    let Some(game) = game.as_mut() else {
+        return SUDOKU_NULL_GAME;
+    };
+
+    outcome(match value {
+        0 => game.clear_cell(row, column),
+        _ => game.do_move(row, column, value, Validation::Strict),
+    })
+}
+
+/**
+ * Fills the grid with its solution, replacing the values placed by the player. Returns
+ * `SUDOKU_OK`, or `SUDOKU_NO_SOLUTION` leaving the grid as it was.
+ *
+ * # Safety
+ *
+ * `game` must be null or a game that is not released.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_solve(game: *mut Game) -> c_int {
+    let Some(game) = game.as_mut() else {
+        return SUDOKU_NULL_GAME;
+    };
+
+    outcome(game.solution().map(|solution| {
+        for (cell, value) in game.grid.iter_mut().zip(solution) {
+            if !cell.initial() {
+                *cell = Cell::new(value, false);
+            }
+        }
+    }))
+}
+
+/**
+ * Returns whether the grid is completed without conflicts, false if `game` is null.
+ *
+ * # Safety
+ *
+ * `game` must be null or a game that is not released.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_is_done(game: *const Game) -> bool {
+    game.as_ref().is_some_and(|game| game.is_done())
+}
+
+/**
+ * Returns the puzzle of the game written like `Game::puzzle_id`, to release with
+ * `sudoku_string_free`, or null if `game` is null.
+ *
+ * # Safety
+ *
+ * `game` must be null or a game that is not released.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_puzzle(game: *const Game) -> *mut c_char {
+    match game.as_ref() {
+        // Puzzle ids are made of digits, letters and dots
+        Some(game) => CString::new(game.puzzle_id()).unwrap().into_raw(),
+        None => ptr::null_mut(),
+    }
+}
+
+/**
+ * Releases a string returned by the API.
+ *
+ * # Safety
+ *
+ * `string` must be null or a string returned by `sudoku_puzzle`, not released yet.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
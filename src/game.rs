@@ -1,52 +1,255 @@
+use crate::annotations::Annotations;
 use crate::errors::GameError;
-use crate::solver::Solver;
+use crate::events::{GameEvent, LoggedEvent};
+use crate::generator::Difficulty;
+use crate::grader::{self, Grade};
+use crate::history::Move;
+use crate::hotseat::HotSeat;
+use crate::killer::Cage;
+use crate::lock::SaveLock;
+use crate::session::Timer;
+use crate::solver::{self, Masks, Solver};
 
 use colored::*;
 use lazy_static::lazy_static;
+use rand::seq::SliceRandom;
 use rand::Rng;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cell::OnceCell;
 use std::collections::HashSet;
 use std::fmt;
 use std::fs;
 use std::fs::File;
 use std::fs::OpenOptions;
-use std::io::{Seek, Write};
-use std::path::PathBuf;
-
-const MAX_UNFILL_ATTEMPTS: usize = 3;
-const MIN_CLUES: usize = 17;
+use std::io::Seek;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 lazy_static! {
     static ref RE_GAME_SIZE: regex::Regex = Regex::new(r"(?m)^game_size: ([345])$").unwrap();
     static ref RE_SELECTED: regex::Regex = Regex::new(r"(?m)^selected: (\d+)$").unwrap();
     static ref RE_CELLS: regex::Regex = Regex::new(r"(?m)^cells: (\d/[IN],?)+$?").unwrap();
     static ref RE_CELL: regex::Regex = Regex::new(r"(\d)/([IN]),?").unwrap();
+    static ref RE_NOTES: regex::Regex = Regex::new(r"(?m)^notes: ([\d,]+)$").unwrap();
+    static ref RE_HISTORY: regex::Regex = Regex::new(r"(?m)^history: ([\d:,]+)$").unwrap();
+    static ref RE_UNDONE: regex::Regex = Regex::new(r"(?m)^undone: ([\d:,]+)$").unwrap();
+    static ref RE_ANNOTATIONS: regex::Regex = Regex::new(r"(?m)^annotations: (.+)$").unwrap();
+}
+
+/// Version of the save format written by `Game::save`, increased when it changes.
+const SAVE_VERSION: u32 = 1;
+
+/// Bits of a packed cell holding its notes.
+const CELL_NOTES: u64 = 0xFFFF_FFFF;
+/// Offset of the byte of a packed cell holding its value.
+const CELL_VALUE_SHIFT: u32 = 32;
+/// Bit of a packed cell set when its value is a clue.
+const CELL_INITIAL: u64 = 1 << 40;
+/// Offset of the bits of a packed cell holding its marker color.
+const CELL_COLOR_SHIFT: u32 = 48;
+/// Bits of a packed cell holding its marker color.
+const CELL_COLOR: u64 = 0xF << CELL_COLOR_SHIFT;
+
+/// The colors the player can paint the cells with, as RGB, for the coloring techniques. The
+/// color `n` of a cell is the `n - 1`th one, 0 standing for an unpainted cell.
+pub const MARKER_COLORS: [(u8, u8, u8); 6] = [
+    (230, 80, 80),
+    (70, 150, 255),
+    (80, 200, 90),
+    (240, 200, 50),
+    (190, 90, 230),
+    (250, 140, 50),
+];
+
+/**
+ * A cell of the grid, packed in a single word so that large boards with notes and history stay
+ * small and cache friendly:
+ *  - bits 0 to 31: the notes, the bit `n` being set when `n` is noted,
+ *  - bits 32 to 39: the value, 0 when empty,
+ *  - bit 40: whether the value is a clue,
+ *  - bits 48 to 51: the marker color the player painted the cell with, 0 when unpainted,
+ *  - bits 52 to 63: reserved for a marker of the cell.
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+pub struct Cell(u64);
+
+impl Cell {
+    /// Returns a cell holding `value`, without notes.
+    pub fn new(value: u8, initial: bool) -> Self {
+        let initial = match initial {
+            true => CELL_INITIAL,
+            false => 0,
+        };
+        Cell((value as u64) << CELL_VALUE_SHIFT | initial)
+    }
+
+    /// Returns the value of the cell, 0 when empty.
+    pub fn value(&self) -> u8 {
+        (self.0 >> CELL_VALUE_SHIFT) as u8
+    }
+
+    /// Sets the value of the cell, keeping its other attributes.
+    pub fn set_value(&mut self, value: u8) {
+        self.0 = self.0 & !(0xFF << CELL_VALUE_SHIFT) | (value as u64) << CELL_VALUE_SHIFT;
+    }
+
+    /// Returns whether the value of the cell is a clue.
+    pub fn initial(&self) -> bool {
+        self.0 & CELL_INITIAL != 0
+    }
+
+    /// Returns the pencil marks of the cell, the bit `n` being set when `n` is noted.
+    pub fn notes(&self) -> u32 {
+        (self.0 & CELL_NOTES) as u32
+    }
+
+    /// Sets the pencil marks of the cell.
+    pub fn set_notes(&mut self, notes: u32) {
+        self.0 = self.0 & !CELL_NOTES | notes as u64;
+    }
+
+    /// Returns whether `value` is noted in this cell.
+    pub fn has_note(&self, value: u8) -> bool {
+        self.notes() & (1 << value) != 0
+    }
+
+    /// Returns the marker color of the cell, see `MARKER_COLORS`, 0 when it is not painted.
+    pub fn color(&self) -> u8 {
+        ((self.0 & CELL_COLOR) >> CELL_COLOR_SHIFT) as u8
+    }
+
+    /// Paints the cell with the marker color `color`, 0 removing its color.
+    pub fn set_color(&mut self, color: u8) {
+        self.0 = self.0 & !CELL_COLOR | ((color as u64) << CELL_COLOR_SHIFT) & CELL_COLOR;
+    }
+
+    /// Returns the packed bits of the cell, as written in the save file.
+    pub fn bits(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the cell packed in `bits`, see `bits`.
+    pub fn from_bits(bits: u64) -> Self {
+        Cell(bits)
+    }
+
+    /// Returns whether the value and the notes of the cell fit a grid of `side_size` rows, as
+    /// the cells read from a file or the network must.
+    pub fn fits(&self, side_size: usize) -> bool {
+        let notes = (1..=side_size).fold(0, |mask, v| mask | 1 << v);
+        self.value() as usize <= side_size && self.notes() & !notes == 0
+    }
+}
+
+/// Returns the character standing for `value` in puzzle ids and on the grids bigger than 9x9: its
+/// digit, or a letter from `A` for 10, as in base 36.
+pub fn value_symbol(value: u8) -> char {
+    std::char::from_digit(value as u32, 36)
+        .unwrap_or('?')
+        .to_ascii_uppercase()
 }
 
-#[derive(Clone)]
-pub struct Cell {
-    pub value: u8,
-    pub initial: bool,
+/// Returns the value `symbol` stands for, see `value_symbol`.
+pub fn symbol_value(symbol: char) -> Option<u8> {
+    symbol.to_digit(36).map(|value| value as u8)
 }
 
 impl PartialEq for Cell {
     fn eq(&self, other: &Self) -> bool {
-        self.value == other.value
+        self.value() == other.value()
     }
 }
 
 impl PartialEq<u8> for Cell {
     fn eq(&self, other: &u8) -> bool {
-        self.value == *other
+        self.value() == *other
     }
 }
 
 impl PartialEq<u8> for &Cell {
     fn eq(&self, other: &u8) -> bool {
-        self.value == *other
+        self.value() == *other
     }
 }
 
+/**
+ * How a move is checked before being played.
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Validation {
+    /// The value must not conflict with the values of the cell's row, column and box.
+    #[default]
+    Strict,
+    /// The value must be the one of the solution.
+    Solution,
+    /// Any value in range is accepted, the ones conflicting with the other values being counted
+    /// as mistakes and highlighted.
+    Lenient,
+    /// Any value in range is accepted, only the completed grid being checked.
+    #[serde(alias = "off")]
+    Free,
+}
+
+/**
+ * How a cell compares to the solution, see `Game::diff`.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellDiff {
+    /// The cell holds its solution value.
+    Correct,
+    /// The cell holds another value than its solution value, given here.
+    Wrong(u8),
+    /// The cell is empty, its solution value being given here.
+    Empty(u8),
+}
+
+/**
+ * Selects which notes to clear in `Game::clear_notes`.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotesClear {
+    /// Every note of the given value.
+    Digit(u8),
+    /// Every note of the given (zero based) row.
+    Row(usize),
+    /// Every note of the given (zero based) column.
+    Column(usize),
+    /// Every note of the given (zero based, row by row) group.
+    Group(usize),
+    /// Every note of a value that is already placed in a neighbor.
+    Invalid,
+}
+
+impl fmt::Display for NotesClear {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotesClear::Digit(value) => write!(f, "every {}", value),
+            NotesClear::Row(r) => write!(f, "row {}", r + 1),
+            NotesClear::Column(c) => write!(f, "column {}", c + 1),
+            NotesClear::Group(g) => write!(f, "box {}", g + 1),
+            NotesClear::Invalid => write!(f, "invalid notes"),
+        }
+    }
+}
+
+/**
+ * The rules of the puzzle, on top of the rows, columns and boxes holding every value once.
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Variant {
+    #[default]
+    Classic,
+    /// Both diagonals of the grid also hold every value once (X-Sudoku).
+    Diagonal,
+    /// The windows of the grid, boxes set one cell away from the edges and from each other (four
+    /// of them on 9x9 grids), also hold every value once (windoku).
+    #[serde(alias = "windoku")]
+    Hyper,
+}
+
 // const COLOR: <(dyn colored::Colorize + 'static)>::Fn = colored::Colorize::blue;
 // const COLOR: fn(String) -> ColoredString = colored::Colorize::blue;
 // const COLOR: <(dyn colored::Colorize + 'static) as Trait>::Fn = colored::Colorize::blue;
@@ -64,17 +267,282 @@ pub struct Game {
     pub selected_value: Option<u8>,
     /// The actual grid.
     pub grid: Vec<Cell>,
+    /// Everything that happened to this game during the current session.
+    pub events: Vec<LoggedEvent>,
+    /// Turns and statistics of the players, if they take turns on this game.
+    pub hot_seat: Option<HotSeat>,
+    /// Whether the notes are kept equal to the candidates of each cell after every change, see
+    /// `set_auto_notes`.
+    pub auto_notes: bool,
+    /// Whether placing a value removes its notes from the cells it sees, see `do_move`.
+    pub remove_notes: bool,
+    /// The moves played since the game was started, the last one at the end.
+    pub history: Vec<Move>,
+    /// The values of the solved grid, when known.
+    pub solution: Option<Vec<u8>>,
+    /// The moves that were undone, the last undone one at the end.
+    pub undone: Vec<Move>,
+    /// Every change of the cells since the game was started, the undone and reverted moves
+    /// included, for the game to be replayed.
+    pub timeline: Vec<Move>,
+    /// Length of the history when each of the ongoing (nested) guesses started.
+    pub guesses: Vec<usize>,
+    /// Cages, texts and colors drawn over the grid, for imported puzzles.
+    pub annotations: Option<Annotations>,
+    /// Metadata lines of puzzles read from `.sdk` files, as their tag (such as `A` for the
+    /// author) and text.
+    pub metadata: Vec<(char, String)>,
+    /// Play time of the game, only running while the game is played.
+    pub timer: Timer,
+    /// The difficulty the puzzle was generated for, if it was generated.
+    pub difficulty: Option<Difficulty>,
+    /// The rules of the puzzle, see `Variant`.
+    pub variant: Variant,
+    /// The killer cages of the puzzle, none for the other puzzles.
+    pub cages: Vec<Cage>,
+    /// The region of each cell, numbered row by row from the top left one: the boxes of `size`
+    /// by `size` cells (see `boxes`), unless the puzzle is a jigsaw one. Set with `set_regions`.
+    regions: Vec<usize>,
+    /// The cells of each region, computed from `regions` when they are set.
+    region_cells: Vec<Vec<usize>>,
+    /// Number of moves refused because of their value, or accepted despite their conflicts in
+    /// lenient mode, see `Validation`.
+    pub mistakes: usize,
+    /// Number of hints the player asked for.
+    pub hints: usize,
+    /// The grade of the puzzle, computed the first time it is asked for, see `rating`.
+    rating: OnceCell<Grade>,
+    /// The hold on the save file, released when the game is dropped.
+    _lock: Option<SaveLock>,
+}
+
+/**
+ * A game as written in its save file, in JSON.
+ */
+#[derive(Serialize, Deserialize)]
+struct SaveData {
+    /// Version of the format, see `SAVE_VERSION`.
+    version: u32,
+    size: usize,
+    #[serde(default)]
+    selected: Option<usize>,
+    /// The cells with their notes, as their packed bits.
+    cells: Vec<Cell>,
+    #[serde(default)]
+    history: Vec<Move>,
+    /// Length of the history when each of the ongoing guesses started, see `Game::guesses`.
+    #[serde(default)]
+    guesses: Vec<usize>,
+    #[serde(default)]
+    undone: Vec<Move>,
+    /// Every change of the cells, see `Game::timeline`.
+    #[serde(default)]
+    timeline: Vec<Move>,
+    /// Play time, in seconds.
+    #[serde(default)]
+    played: u64,
+    #[serde(default)]
+    difficulty: Option<Difficulty>,
+    #[serde(default)]
+    variant: Variant,
+    #[serde(default)]
+    cages: Vec<Cage>,
+    /// The region of each cell, for jigsaw puzzles only.
+    #[serde(default)]
+    regions: Option<Vec<usize>>,
+    #[serde(default)]
+    mistakes: usize,
+    #[serde(default)]
+    hints: usize,
+    #[serde(default)]
+    annotations: Option<Annotations>,
+    #[serde(default)]
+    metadata: Vec<(char, String)>,
+    /// Whether the notes are kept equal to the candidates, see `Game::auto_notes`.
+    #[serde(default)]
+    auto_notes: bool,
+    /// The turns and scores of a hot-seat game.
+    #[serde(default)]
+    hot_seat: Option<HotSeat>,
+    /// The values of the solved grid, when known.
+    #[serde(default)]
+    solution: Option<Vec<u8>>,
+}
+
+/**
+ * Returns the region of each cell of a grid of boxes of `size` by `size` cells: the (zero based,
+ * row by row) box it is in.
+ */
+pub fn boxes(size: usize) -> Vec<usize> {
+    let side_size = size * size;
+    (0..side_size * side_size)
+        .map(|i| (i / side_size / size) * size + (i % side_size) / size)
+        .collect()
+}
+
+/**
+ * Returns the cells of each region of a grid of `side_size` rows, given the region of each cell.
+ */
+fn region_cells(regions: &[usize], side_size: usize) -> Vec<Vec<usize>> {
+    let mut cells = vec![Vec::with_capacity(side_size); side_size];
+    for (index, region) in regions.iter().enumerate() {
+        cells[*region].push(index);
+    }
+
+    cells
+}
+
+/**
+ * Returns whether `regions` gives a region to each cell of a grid of `side_size` rows, each of
+ * the `side_size` regions having `side_size` cells.
+ */
+pub fn valid_regions(regions: &[usize], side_size: usize) -> bool {
+    let mut counts = vec![0; side_size];
+    for region in regions {
+        match counts.get_mut(*region) {
+            Some(count) => *count += 1,
+            None => return false,
+        }
+    }
+
+    regions.len() == side_size * side_size && counts.iter().all(|c| *c == side_size)
+}
+
+/// Returns whether the file at `path` is in the SadMan Sudoku format, given its extension.
+fn is_sdk_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("sdk"))
+}
+
+/// Returns whether the file at `path` is a grid in the CSV format, given its extension.
+fn is_csv_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("csv"))
+}
+
+/// Returns whether `content` only holds `#` metadata lines and rows of digits and `.`, as the
+/// SadMan Sudoku format does.
+fn looks_like_sdk(content: &str) -> bool {
+    let lines: Vec<&str> = content
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .collect();
+    lines.iter().any(|l| !l.starts_with('#'))
+        && lines
+            .iter()
+            .all(|l| l.starts_with('#') || l.chars().all(|c| c == '.' || c.is_ascii_alphanumeric()))
+}
+
+/// Characters standing for an empty cell in the grids pasted as text.
+const BLANK_SYMBOLS: [char; 4] = ['.', '0', '*', '_'];
+/// Characters drawn between the boxes of a row in the grids pasted as text.
+const VERTICAL_BORDERS: [char; 4] = ['|', '!', '│', '║'];
+
+/// Returns the symbol of the cell written `c` in a grid pasted as text, `.` for an empty cell, or
+/// `None` if `c` is a separator or a border.
+fn pasted_symbol(c: char) -> Option<char> {
+    match c {
+        c if BLANK_SYMBOLS.contains(&c) => Some('.'),
+        c if c.is_ascii_alphanumeric() => Some(c),
+        _ => None,
+    }
+}
+
+/// Returns the cells of `line` as a row of a pasted grid of the given size, `.` standing for the
+/// empty ones, if it is one: its symbols separated by spaces or borders, or boxes between
+/// vertical borders where each cell has its column and the empty ones are left blank.
+fn pasted_row(line: &str, size: usize) -> Option<Vec<char>> {
+    let side_size = size * size;
+    let is_value = |symbol: &char| {
+        *symbol == '.'
+            || symbol_value(*symbol).is_some_and(|v| (1..=side_size).contains(&(v as usize)))
+    };
+
+    let symbols: Vec<char> = line.chars().filter_map(pasted_symbol).collect();
+    if symbols.len() == side_size && symbols.iter().all(is_value) {
+        return Some(symbols);
+    }
+
+    // The boxes of the row, drawn with the empty cells left blank
+    let line = line.trim_end();
+    let mut boxes: Vec<Vec<char>> = line
+        .split(|c| VERTICAL_BORDERS.contains(&c))
+        .map(|b| b.chars().collect())
+        .collect();
+    if boxes.len() < 2
+        || boxes
+            .first()
+            .is_some_and(|b| !b.iter().all(|c| c.is_whitespace()))
+    {
+        return None;
+    }
+    boxes.remove(0);
+    if boxes.last().is_some_and(|b| b.is_empty()) {
+        boxes.pop();
+    }
+    if boxes.len() != size {
+        return None;
+    }
+    let mut symbols = Vec::with_capacity(side_size);
+    for b in boxes {
+        // Either a column per cell, or a space between the cells and maybe around them
+        let (first, step) = match b.len() {
+            len if len == size => (0, 1),
+            len if len == 2 * size - 1 => (0, 2),
+            len if len == 2 * size + 1 => (1, 2),
+            _ => return None,
+        };
+        for (i, c) in b.iter().enumerate() {
+            let is_cell = i >= first && (i - first) % step == 0 && i - first < size * step;
+            match (is_cell, c) {
+                (true, c) if c.is_whitespace() => symbols.push('.'),
+                (true, c) => symbols.push(pasted_symbol(*c).filter(is_value)?),
+                (false, c) if !c.is_whitespace() => return None,
+                _ => {}
+            }
+        }
+    }
+
+    Some(symbols)
+}
+
+/// Returns the puzzle (see `Game::puzzle_id`) of a grid pasted as text, as found on forums: a
+/// line per row, the cells possibly separated by spaces or borders, with `.`, `0`, `*` or `_`
+/// for the empty cells, or left blank when the boxes are drawn between vertical borders. The
+/// lines of borders between the rows are left out, as are the lines around the grid such as its
+/// title or its rating.
+fn pasted_grid(text: &str) -> Option<String> {
+    // The most common size first, the rows of a size never being rows of another one
+    [3, 2, 4, 5].into_iter().find_map(|size| {
+        let mut rows: Vec<Vec<char>> = Vec::new();
+        for line in text.lines() {
+            match pasted_row(line, size) {
+                Some(row) => rows.push(row),
+                // Lines of borders, or blank lines
+                None if !line.chars().any(|c| c.is_ascii_alphanumeric()) => {}
+                // A line of text, which the rows found so far are not part of
+                None => rows.clear(),
+            }
+            if rows.len() == size * size {
+                return Some(rows.concat().into_iter().collect());
+            }
+        }
+        None
+    })
 }
 
 impl Game {
     pub fn new(size: usize, saving_path: Option<&str>) -> Result<Self, GameError> {
         let side_size = size * size;
+        // Make sure no other instance uses this save before overwriting it
+        let lock = saving_path.map(SaveLock::acquire).transpose()?;
         let (save_path, save_file) = match saving_path {
             Some(path) => match File::create(path) {
                 Ok(file_handle) => (Some(PathBuf::from(saving_path.unwrap())), Some(file_handle)),
-                Err(_) => {
-                    dbg!(path);
-                    return Err(GameError::CreateSaveFileError);
+                Err(e) => {
+                    log::error!("Unable to create the save file {}: {}", path, e);
+                    return Err(GameError::CreateSaveFileError(e));
                 }
             },
             None => (None, None),
@@ -87,23 +555,187 @@ impl Game {
             selected_value: None,
             save_file,
             save_path,
-            grid: vec![
-                Cell {
-                    value: 0,
-                    initial: false
-                };
-                side_size * side_size
-            ],
+            grid: vec![Cell::new(0, false); side_size * side_size],
+            events: Vec::new(),
+            hot_seat: None,
+            auto_notes: false,
+            remove_notes: false,
+            history: Vec::new(),
+            undone: Vec::new(),
+            timeline: Vec::new(),
+            solution: None,
+            guesses: Vec::new(),
+            annotations: None,
+            metadata: Vec::new(),
+            timer: Timer::start(),
+            difficulty: None,
+            variant: Variant::Classic,
+            cages: Vec::new(),
+            regions: boxes(size),
+            region_cells: region_cells(&boxes(size), side_size),
+            mistakes: 0,
+            hints: 0,
+            rating: OnceCell::new(),
+            _lock: lock,
         })
     }
 
+    /**
+     * Resumes the game saved at `path`, which keeps being saved there. Saves in the JSON format,
+     * in the line based format of the previous versions, puzzles in the SadMan Sudoku `.sdk`
+     * format and grids in the CSV format (see `from_csv`) are all accepted, the `.sdk` and `.csv`
+     * files being left untouched while their progress is saved next to them (see
+     * `from_puzzle_file`).
+     */
     pub fn from_file(path: &str) -> Result<Self, GameError> {
+        if is_sdk_path(Path::new(path)) || is_csv_path(Path::new(path)) {
+            return Game::from_puzzle_file(path);
+        }
+
+        // Make sure no other instance is playing this game
+        let lock = SaveLock::acquire(path)?;
+        let mut game = Game::read(path)?;
+
+        // Finally open the save file in order to continue saving in it
+        game.save_file = match OpenOptions::new().read(true).write(true).open(path) {
+            Ok(fd) => Some(fd),
+            Err(e) => return Err(GameError::OpenSaveFileError(e)),
+        };
+        game.save_path = Some(PathBuf::from(path));
+        game._lock = Some(lock);
+
+        Ok(game)
+    }
+
+    /**
+     * Plays the puzzle of the `.sdk` or `.csv` file at `path`, which only holds the puzzle: the
+     * game is saved in a `.game` file of the same name next to it, resumed when it holds that
+     * puzzle.
+     */
+    fn from_puzzle_file(path: &str) -> Result<Self, GameError> {
+        let content = fs::read_to_string(path).map_err(GameError::OpenFileError)?;
+        let parse = |saving_path| match is_csv_path(Path::new(path)) {
+            true => Game::from_csv(&content, saving_path),
+            false => Game::from_sdk(&content, saving_path),
+        };
+        let puzzle = parse(None)?.puzzle_id();
+        let progress_path = Path::new(path).with_extension("game");
+        let progress = progress_path.to_string_lossy();
+        if Game::read(&progress).is_ok_and(|g| g.puzzle_id() == puzzle) {
+            return Game::from_file(&progress);
+        }
+
+        let mut game = parse(Some(&progress))?;
+        game.save()?;
+
+        Ok(game)
+    }
+
+    /**
+     * Reads the game saved at `path` like `from_file`, without taking it over: it is neither
+     * locked nor saved, so that saves played by another instance can be looked at.
+     */
+    pub fn read(path: &str) -> Result<Self, GameError> {
         let file_content = match fs::read_to_string(path) {
             Ok(fc) => fc,
-            Err(_) => return Err(GameError::OpenFileError),
+            Err(e) => return Err(GameError::OpenFileError(e)),
         };
 
-        let game_size = match RE_GAME_SIZE.captures(&file_content) {
+        // Tell the format from the content, the puzzles in the SadMan Sudoku format and the CSV
+        // grids told by their extension being read as new games
+        match file_content.trim_start().starts_with('{') {
+            true => Game::from_json(&file_content),
+            false if is_csv_path(Path::new(path)) => Game::from_csv(&file_content, None),
+            false
+                if is_sdk_path(Path::new(path))
+                    || (!RE_GAME_SIZE.is_match(&file_content) && looks_like_sdk(&file_content)) =>
+            {
+                Game::from_sdk(&file_content, None)
+            }
+            false => Game::from_legacy(&file_content),
+        }
+    }
+
+    /**
+     * Creates a game from a save in the JSON format, see `SaveData`.
+     */
+    fn from_json(json: &str) -> Result<Self, GameError> {
+        let data: SaveData =
+            serde_json::from_str(json).map_err(|_| GameError::ParseSaveFileError)?;
+        if data.version > SAVE_VERSION || !(2..=5).contains(&data.size) {
+            return Err(GameError::IncorrectSaveFile);
+        }
+
+        let mut game = Game::new(data.size, None)?;
+        let cells = game.grid.len();
+        let side_size = game.side_size;
+        let moves_valid = data
+            .history
+            .iter()
+            .chain(&data.undone)
+            .chain(&data.timeline)
+            .all(|m| m.index < cells && m.before.fits(side_size) && m.after.fits(side_size))
+            && data.guesses.is_sorted()
+            && data.guesses.last().is_none_or(|g| *g <= data.history.len())
+            && data.cells.iter().all(|c| c.fits(side_size))
+            && data.annotations.as_ref().is_none_or(|a| {
+                a.cages
+                    .iter()
+                    .chain(&a.texts)
+                    .flat_map(|r| &r.cells)
+                    .chain(a.colors.iter().map(|(i, _)| i))
+                    .all(|i| *i < cells)
+            })
+            && data.cages.iter().flat_map(|c| &c.cells).all(|i| *i < cells)
+            && data
+                .regions
+                .as_ref()
+                .is_none_or(|r| valid_regions(r, game.side_size))
+            && data.solution.as_ref().is_none_or(|solution| {
+                solution.len() == cells
+                    && solution.iter().zip(&data.cells).all(|(value, cell)| {
+                        (1..=side_size).contains(&(*value as usize))
+                            && (!cell.initial() || cell.value() == *value)
+                    })
+            });
+        if data.cells.len() != cells || data.selected.is_some_and(|i| i >= cells) || !moves_valid {
+            return Err(GameError::IncorrectSaveFile);
+        }
+
+        game.grid = data.cells;
+        game.selected_index = data.selected;
+        game.selected_value = data
+            .selected
+            .map(|i| game.grid[i].value())
+            .filter(|v| *v != 0);
+        game.history = data.history;
+        game.guesses = data.guesses;
+        game.undone = data.undone;
+        game.timeline = data.timeline;
+        game.timer = Timer::resume_from(Duration::from_secs(data.played));
+        game.difficulty = data.difficulty;
+        game.variant = data.variant;
+        game.cages = data.cages;
+        if let Some(regions) = data.regions {
+            game.set_regions(regions);
+        }
+        game.mistakes = data.mistakes;
+        game.hints = data.hints;
+        game.annotations = data.annotations;
+        game.metadata = data.metadata;
+        game.auto_notes = data.auto_notes;
+        game.hot_seat = data.hot_seat;
+        game.solution = data.solution;
+
+        Ok(game)
+    }
+
+    /**
+     * Creates a game from a save in the line based format of the previous versions, made of
+     * `game_size:`, `selected:`, `cells:`... lines.
+     */
+    fn from_legacy(file_content: &str) -> Result<Self, GameError> {
+        let game_size = match RE_GAME_SIZE.captures(file_content) {
             Some(m) => {
                 let parsed = match m.get(1).unwrap().as_str().parse::<usize>() {
                     Ok(p) => p,
@@ -114,7 +746,7 @@ impl Game {
             None => return Err(GameError::ParseSaveFileError),
         };
 
-        let selected_index = match RE_SELECTED.captures(&file_content) {
+        let selected_index = match RE_SELECTED.captures(file_content) {
             Some(m) => {
                 let parsed = match m.get(1).unwrap().as_str().parse::<usize>() {
                     Ok(p) => p,
@@ -126,7 +758,7 @@ impl Game {
             None => None,
         };
 
-        let cells = match RE_CELLS.captures(&file_content) {
+        let mut cells = match RE_CELLS.captures(file_content) {
             Some(m) => {
                 let mut cells = Vec::new();
                 for mat in RE_CELL.captures_iter(m.get(0).unwrap().as_str()) {
@@ -141,7 +773,7 @@ impl Game {
                         _ => return Err(GameError::ParseSaveFileError),
                     };
 
-                    cells.push(Cell { value, initial });
+                    cells.push(Cell::new(value, initial));
                 }
 
                 cells
@@ -153,63 +785,311 @@ impl Game {
         let side_size = game_size * game_size;
         // Double check that we loaded exactly the good number of cells
         if cells.len() != (side_size * side_size) {
-            println!("{} != {}", cells.len(), side_size);
+            log::warn!(
+                "The save has {} cells instead of {}",
+                cells.len(),
+                side_size * side_size
+            );
             return Err(GameError::IncorrectSaveFile);
         }
 
         // Double check that if we have a selected cell, its index is valid
-        if selected_index.is_some() && selected_index.unwrap() >= (side_size * side_size) {
-            println!("Invalid selected: {}", selected_index.unwrap());
+        if let Some(index) = selected_index.filter(|index| *index >= side_size * side_size) {
+            log::warn!("The selected cell {} of the save is out of the grid", index);
             return Err(GameError::IncorrectSaveFile);
         }
 
-        let selected_value = match selected_index.is_some() && cells[selected_index.unwrap()] != 0 {
-            true => Some(cells[selected_index.unwrap()].value),
-            false => None,
+        // Restore the notes, if any were saved
+        if let Some(m) = RE_NOTES.captures(file_content) {
+            let notes = m.get(1).unwrap().as_str().split(',');
+            if notes.clone().count() != cells.len() {
+                return Err(GameError::IncorrectSaveFile);
+            }
+            for (cell, notes) in cells.iter_mut().zip(notes) {
+                match notes.parse::<u32>() {
+                    Ok(n) => cell.set_notes(n),
+                    Err(_) => return Err(GameError::ParseSaveFileError),
+                }
+            }
+        }
+
+        // Restore the moves played and undone, so that they can still be undone and redone
+        let mut moves = Vec::new();
+        for re in [&*RE_HISTORY, &*RE_UNDONE] {
+            let parsed = match re.captures(file_content) {
+                Some(m) => match m
+                    .get(1)
+                    .unwrap()
+                    .as_str()
+                    .split(',')
+                    .map(Move::parse)
+                    .collect::<Option<Vec<Move>>>()
+                {
+                    Some(parsed) => parsed,
+                    None => return Err(GameError::ParseSaveFileError),
+                },
+                None => Vec::new(),
+            };
+            if parsed.iter().any(|m| m.index >= cells.len()) {
+                return Err(GameError::IncorrectSaveFile);
+            }
+            moves.push(parsed);
+        }
+        let undone = moves.pop().unwrap();
+        let history = moves.pop().unwrap();
+
+        let annotations = match RE_ANNOTATIONS.captures(file_content) {
+            Some(m) => match serde_json::from_str(m.get(1).unwrap().as_str()) {
+                Ok(a) => Some(a),
+                Err(_) => return Err(GameError::ParseSaveFileError),
+            },
+            None => None,
         };
 
-        // Finally open the save file in order to continue saving in it
-        let file_handle = match OpenOptions::new().read(true).write(true).open(path) {
-            Ok(fd) => Some(fd),
-            Err(_) => return Err(GameError::OpenSaveFileError),
+        let selected_value = match selected_index.is_some() && cells[selected_index.unwrap()] != 0 {
+            true => Some(cells[selected_index.unwrap()].value()),
+            false => None,
         };
 
         Ok(Game {
             size: game_size,
             side_size,
-            save_path: Some(PathBuf::from(path)),
-            save_file: file_handle,
+            save_path: None,
+            save_file: None,
             selected_index,
             selected_value,
             grid: cells,
+            events: Vec::new(),
+            hot_seat: None,
+            auto_notes: false,
+            remove_notes: false,
+            history,
+            undone,
+            timeline: Vec::new(),
+            solution: None,
+            guesses: Vec::new(),
+            annotations,
+            metadata: Vec::new(),
+            timer: Timer::start(),
+            difficulty: None,
+            variant: Variant::Classic,
+            cages: Vec::new(),
+            regions: boxes(game_size),
+            region_cells: region_cells(&boxes(game_size), side_size),
+            mistakes: 0,
+            hints: 0,
+            rating: OnceCell::new(),
+            _lock: None,
         })
     }
 
+    /**
+     * Creates a game from the canonical form of a puzzle (see `puzzle_id`), its size being
+     * deduced from the number of cells.
+     */
+    pub fn from_puzzle(puzzle: &str, saving_path: Option<&str>) -> Result<Self, GameError> {
+        let size = (2..=5)
+            .find(|size: &usize| size.pow(4) == puzzle.len())
+            .ok_or(GameError::InvalidPuzzle)?;
+        let mut game = Game::new(size, saving_path)?;
+
+        for (i, c) in puzzle.chars().enumerate() {
+            game.grid[i] = match c {
+                '.' | '0' => Cell::new(0, false),
+                _ => match c.to_digit(36) {
+                    Some(value) if value as usize <= game.side_size => Cell::new(value as u8, true),
+                    _ => return Err(GameError::InvalidPuzzle),
+                },
+            };
+        }
+
+        Ok(game)
+    }
+
+    /**
+     * Creates a game from a puzzle in the SadMan Sudoku `.sdk` format: metadata lines starting
+     * with `#` and a tag (`#A` for the author, `#D` for the description, `#C` for a comment...)
+     * followed by the rows of the grid, with `.` or `0` for the empty cells.
+     */
+    pub fn from_sdk(sdk: &str, saving_path: Option<&str>) -> Result<Self, GameError> {
+        let mut metadata = Vec::new();
+        let mut puzzle = String::new();
+        for line in sdk.lines().map(|l| l.trim()) {
+            match line.strip_prefix('#') {
+                Some(meta) => {
+                    let mut chars = meta.chars();
+                    if let Some(tag) = chars.next() {
+                        metadata.push((tag, chars.as_str().trim().to_string()));
+                    }
+                }
+                None => puzzle.extend(line.chars().filter(|c| !c.is_whitespace())),
+            }
+        }
+
+        let mut game = Game::from_puzzle(&puzzle, saving_path)?;
+        game.metadata = metadata;
+
+        Ok(game)
+    }
+
+    /**
+     * Creates a game from text pasted by the player: a puzzle (see `from_puzzle`), possibly
+     * split in rows and drawn with borders as by `sudocurs print` or on forums (see
+     * `pasted_grid`), or a share code (see `from_share_code`).
+     */
+    pub fn from_text(text: &str, saving_path: Option<&str>) -> Result<Self, GameError> {
+        // The rows of a grid, otherwise its symbols without spaces, line breaks and borders
+        let puzzle = pasted_grid(text)
+            .unwrap_or_else(|| text.chars().filter_map(pasted_symbol).collect::<String>());
+        // Parsed without a save first, for the save of a share code not to be created twice
+        match Game::from_puzzle(&puzzle, None) {
+            Ok(_) => Game::from_puzzle(&puzzle, saving_path),
+            Err(_) => Game::from_share_code(text, saving_path),
+        }
+    }
+
+    /**
+     * Returns the puzzle in the SadMan Sudoku `.sdk` format: its metadata lines, then one line
+     * per row with its initial values and `.` for the other cells.
+     */
+    pub fn to_sdk(&self) -> String {
+        let mut sdk: String = self
+            .metadata
+            .iter()
+            .map(|(tag, text)| format!("#{}{}\n", tag, text))
+            .collect();
+        let puzzle: Vec<char> = self.puzzle_id().chars().collect();
+        for row in puzzle.chunks(self.side_size) {
+            sdk.extend(row);
+            sdk.push('\n');
+        }
+
+        sdk
+    }
+
+    /**
+     * Creates a game from a grid in the CSV format of the spreadsheets: one line per row, its
+     * values separated by commas (or semicolons, or tabs) with 0 or nothing for the empty cells.
+     * A last column of `I` and `N` may tell for each cell of the row whether its value is a clue
+     * (initial) or was placed by the player, every value being a clue otherwise.
+     */
+    pub fn from_csv(csv: &str, saving_path: Option<&str>) -> Result<Self, GameError> {
+        let rows: Vec<&str> = csv
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .collect();
+        let size = (2..=5)
+            .find(|size: &usize| size * size == rows.len())
+            .ok_or(GameError::InvalidPuzzle)?;
+        let side_size = size * size;
+
+        let mut cells = Vec::with_capacity(side_size * side_size);
+        for row in rows {
+            let fields: Vec<&str> = row.split([',', ';', '\t']).map(|f| f.trim()).collect();
+            let flags: Vec<bool> = match fields.len() {
+                len if len == side_size => vec![true; side_size],
+                len if len == side_size + 1 => fields[side_size]
+                    .chars()
+                    .map(|flag| match flag.to_ascii_uppercase() {
+                        'I' => Ok(true),
+                        'N' => Ok(false),
+                        _ => Err(GameError::InvalidPuzzle),
+                    })
+                    .collect::<Result<_, _>>()?,
+                _ => return Err(GameError::InvalidPuzzle),
+            };
+            if flags.len() != side_size {
+                return Err(GameError::InvalidPuzzle);
+            }
+            for (field, initial) in fields.iter().zip(flags) {
+                let value = match field.is_empty() {
+                    true => 0,
+                    false => field.parse::<u8>().map_err(|_| GameError::InvalidPuzzle)?,
+                };
+                if value as usize > side_size {
+                    return Err(GameError::InvalidPuzzle);
+                }
+                cells.push(Cell::new(value, value != 0 && initial));
+            }
+        }
+
+        let mut game = Game::new(size, saving_path)?;
+        game.grid = cells;
+
+        Ok(game)
+    }
+
+    /**
+     * Returns the grid in the CSV format, see `from_csv`: one line per row with 0 for the empty
+     * cells, followed by the column of `I` and `N` flags when values were placed by the player.
+     */
+    pub fn to_csv(&self) -> String {
+        let flagged = self
+            .grid
+            .iter()
+            .any(|cell| cell.value() != 0 && !cell.initial());
+
+        let mut csv = String::new();
+        for row in self.grid.chunks(self.side_size) {
+            let mut fields: Vec<String> = row.iter().map(|c| c.value().to_string()).collect();
+            if flagged {
+                fields.push(
+                    row.iter()
+                        .map(|c| match c.value() != 0 && c.initial() {
+                            true => 'I',
+                            false => 'N',
+                        })
+                        .collect(),
+                );
+            }
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+
+        csv
+    }
+
     /// Resets the grid with all zeros.
     pub fn clear(&mut self) {
-        self.grid = vec![
-            Cell {
-                value: 0,
-                initial: false
-            };
-            self.side_size * self.side_size
-        ];
+        self.grid = vec![Cell::new(0, false); self.side_size * self.side_size];
     }
 
     /// Counts the number of **empty** boxes in the grid.
-    #[cfg(test)]
     pub fn nb_empty(&self) -> usize {
-        return self.grid.iter().filter(|&x| *x == 0u8).count();
+        self.grid.iter().filter(|&x| *x == 0u8).count()
     }
 
     /// Returns an iterator over the empty cells of the grid.
     pub fn _empties(&self) -> impl Iterator<Item = usize> + '_ {
-        (0..(self.side_size * self.side_size)).filter(|i| self.grid[*i].value == 0)
+        (0..(self.side_size * self.side_size)).filter(|i| self.grid[*i].value() == 0)
     }
 
     /// Counts the number of **non empty** boxes in the grid.
     pub fn nb_non_empty(&self) -> usize {
-        return self.grid.iter().filter(|&x| *x != 0u8).count();
+        self.grid.iter().filter(|&x| *x != 0u8).count()
+    }
+
+    /// Counts the number of initial values (clues) of the grid.
+    pub fn nb_clues(&self) -> usize {
+        self.grid.iter().filter(|x| x.initial()).count()
+    }
+
+    /// Returns the percentage of the grid's cells that are filled.
+    pub fn completion(&self) -> usize {
+        self.nb_non_empty() * 100 / self.grid.len()
+    }
+
+    /// Returns the canonical form of the puzzle: its initial values, row by row, written in base
+    /// 36 with `.` for the cells that are not initial.
+    pub fn puzzle_id(&self) -> String {
+        self.grid
+            .iter()
+            .map(|x| match x.initial() {
+                true => value_symbol(x.value()),
+                false => '.',
+            })
+            .collect()
     }
 
     /// Returns the coordinates of a given index in the grid, as (row, column).
@@ -233,52 +1113,275 @@ impl Game {
     }
 
     /// Function that gets the `self.side_size` elements that are in the group of the
-    /// grid's value located in row `r` and column `c`.
+    /// grid's value located in row `r` and column `c`, its box or its jigsaw region.
     pub fn group(&self, r: usize, c: usize) -> impl Iterator<Item = usize> + '_ {
-        let start_row = (r / self.size) * self.size;
-        let start_col = (c / self.size) * self.size;
+        self.region(self.regions[self.index(r, c)])
+    }
 
-        (start_row..start_row + self.size)
-            .flat_map(move |r| (start_col..start_col + self.size).map(move |c| self.index(r, c)))
+    /// Function that gets the `self.side_size` elements that are in the (zero based) region
+    /// `g`, see `regions`.
+    pub fn region(&self, g: usize) -> impl Iterator<Item = usize> + '_ {
+        self.region_cells[g].iter().copied()
     }
 
-    /// Returns the concatenation of `row()`, `column()` and `group()` functions.
-    pub fn neighbors(&self, r: usize, c: usize) -> impl Iterator<Item = usize> + '_ {
-        self.column(c).chain(self.row(r)).chain(self.group(r, c))
+    /// Returns the region of each cell, see `regions`.
+    pub fn regions(&self) -> &[usize] {
+        &self.regions
     }
 
-    /// Returns the values that are not taken by any neighbor.
-    pub fn valids(&self, index: usize) -> Vec<u8> {
-        let (r, c) = self.coordinates(index);
-        let mut possibles: HashSet<u8> = (1..=self.side_size as u8).collect();
-        let used: Vec<u8> = self.neighbors(r, c).map(|i| self.grid[i].value).collect();
+    /// Sets the region of each cell, see `regions`, which must be valid ones (see
+    /// `valid_regions`), along with the cells of each region.
+    pub fn set_regions(&mut self, regions: Vec<usize>) {
+        self.region_cells = region_cells(&regions, self.side_size);
+        self.regions = regions;
+    }
 
-        for value in used {
-            possibles.remove(&value);
+    /// Returns whether the regions of the puzzle are irregular ones rather than boxes.
+    pub fn is_jigsaw(&self) -> bool {
+        self.regions != boxes(self.size)
+    }
 
-            // Stop if theren are possible values
-            if possibles.is_empty() {
-                break;
-            }
+    /// Returns whether the top and the left sides of the cell at row `r` and column `c` are
+    /// borders of its region, the ones on the edges of the grid included. Rows and columns past
+    /// the last ones stand for the bottom and right edges.
+    pub fn borders(&self, r: usize, c: usize) -> (bool, bool) {
+        let region = |r: usize, c: usize| match r < self.side_size && c < self.side_size {
+            true => Some(self.regions[self.index(r, c)]),
+            false => None,
+        };
+        (
+            r == 0 || region(r - 1, c) != region(r, c),
+            c == 0 || region(r, c - 1) != region(r, c),
+        )
+    }
+
+    /// Returns a color for each region, as a small number, neighboring regions never sharing
+    /// theirs.
+    pub fn region_colors(&self) -> Vec<usize> {
+        let mut colors: Vec<Option<usize>> = vec![None; self.side_size];
+        for region in 0..self.side_size {
+            // The colors of the regions touching this one, above, below or on its sides
+            let taken: HashSet<usize> = self
+                .region(region)
+                .flat_map(|i| {
+                    let (r, c) = self.coordinates(i);
+                    [
+                        r.checked_sub(1).map(|r| (r, c)),
+                        Some((r + 1, c)),
+                        c.checked_sub(1).map(|c| (r, c)),
+                        Some((r, c + 1)),
+                    ]
+                })
+                .flatten()
+                .filter(|(r, c)| *r < self.side_size && *c < self.side_size)
+                .filter_map(|(r, c)| colors[self.regions[self.index(r, c)]])
+                .collect();
+            colors[region] = (0..).find(|color| !taken.contains(color));
         }
 
-        possibles.into_iter().collect()
+        colors.into_iter().map(|c| c.unwrap_or(0)).collect()
     }
 
-    /// Checks if the grid is correctly completed.
-    /// Returns `true` if yes, `false` otherwise.
-    pub fn is_done(&self) -> bool {
-        // Check that all the grid is filled.
-        if self.grid.iter().any(|x| x.value == 0) {
-            return false;
+    /// Function that gets the elements of the diagonals going through the grid's value located
+    /// in row `r` and column `c`, if the puzzle is a `Variant::Diagonal` one, none otherwise.
+    pub fn diagonals(&self, r: usize, c: usize) -> impl Iterator<Item = usize> + '_ {
+        let diagonal = self.variant == Variant::Diagonal;
+        let main = (diagonal && r == c).then_some(0..self.side_size);
+        let anti = (diagonal && r + c == self.side_size - 1).then_some(0..self.side_size);
+
+        main.into_iter()
+            .flatten()
+            .map(move |x| self.index(x, x))
+            .chain(
+                anti.into_iter()
+                    .flatten()
+                    .map(move |x| self.index(x, self.side_size - 1 - x)),
+            )
+    }
+
+    /// Function that gets the elements of the killer cages of the grid's value located in row
+    /// `r` and column `c`, their values being distinct too.
+    pub fn cage_cells(&self, r: usize, c: usize) -> impl Iterator<Item = usize> + '_ {
+        let index = self.index(r, c);
+        self.cages
+            .iter()
+            .filter(move |cage| cage.cells.contains(&index))
+            .flat_map(|cage| cage.cells.iter().copied())
+    }
+
+    /// Returns the number of windows of the grid, `(size - 1)²` for `Variant::Hyper` puzzles and
+    /// none for the others.
+    pub fn windows_count(&self) -> usize {
+        match self.variant {
+            Variant::Hyper => (self.size - 1) * (self.size - 1),
+            _ => 0,
         }
+    }
 
-        // Check row
-        for row in 0..self.side_size {
-            if (1..=self.side_size).any(|v| {
+    /// Returns the (zero based, row by row) window the cell at `index` is in, if any.
+    pub fn window_of(&self, index: usize) -> Option<usize> {
+        // The windows of a row or column start one cell after the edge and after each other
+        let window = |x: usize| match x.checked_sub(1) {
+            Some(x) if x % (self.size + 1) < self.size && x / (self.size + 1) < self.size - 1 => {
+                Some(x / (self.size + 1))
+            }
+            _ => None,
+        };
+        let (r, c) = self.coordinates(index);
+
+        match self.variant {
+            Variant::Hyper => Some(window(r)? * (self.size - 1) + window(c)?),
+            _ => None,
+        }
+    }
+
+    /// Function that gets the `self.side_size` elements that are in the (zero based) window `w`,
+    /// see `window_of`.
+    pub fn window(&self, w: usize) -> impl Iterator<Item = usize> + '_ {
+        let start_row = 1 + (w / (self.size - 1)) * (self.size + 1);
+        let start_col = 1 + (w % (self.size - 1)) * (self.size + 1);
+
+        (start_row..start_row + self.size)
+            .flat_map(move |r| (start_col..start_col + self.size).map(move |c| self.index(r, c)))
+    }
+
+    /// Function that gets the elements of the window of the grid's value located in row `r` and
+    /// column `c`, if it is in one.
+    pub fn windows(&self, r: usize, c: usize) -> impl Iterator<Item = usize> + '_ {
+        self.window_of(self.index(r, c))
+            .into_iter()
+            .flat_map(|w| self.window(w))
+    }
+
+    /// Returns the concatenation of `row()`, `column()`, `group()`, `diagonals()`, `windows()`
+    /// and `cage_cells()` functions.
+    pub fn neighbors(&self, r: usize, c: usize) -> impl Iterator<Item = usize> + '_ {
+        self.column(c)
+            .chain(self.row(r))
+            .chain(self.group(r, c))
+            .chain(self.diagonals(r, c))
+            .chain(self.windows(r, c))
+            .chain(self.cage_cells(r, c))
+    }
+
+    /// Returns whether the sums of the killer cages of the cell at `index` can still be made up
+    /// with `value` placed there, see `Cage::allows`.
+    pub fn cage_allows(&self, index: usize, value: u8) -> bool {
+        self.cages
+            .iter()
+            .all(|cage| cage.allows(&self.grid, index, value, self.side_size))
+    }
+
+    /// Returns the values that are not taken by any neighbor, and fit the sums of the cages.
+    pub fn valids(&self, index: usize) -> Vec<u8> {
+        let (r, c) = self.coordinates(index);
+        let mut possibles: HashSet<u8> = (1..=self.side_size as u8).collect();
+        let used: Vec<u8> = self.neighbors(r, c).map(|i| self.grid[i].value()).collect();
+
+        for value in used {
+            possibles.remove(&value);
+
+            // Stop if theren are possible values
+            if possibles.is_empty() {
+                break;
+            }
+        }
+
+        possibles
+            .into_iter()
+            .filter(|v| self.cage_allows(index, *v))
+            .collect()
+    }
+
+    /// Returns the empty cells where `value` can still be placed.
+    pub fn cells_for(&self, value: u8) -> Vec<usize> {
+        (0..self.grid.len())
+            .filter(|i| self.grid[*i] == 0 && self.valids(*i).contains(&value))
+            .collect()
+    }
+
+    /// Returns how many times each value is still to be placed, the first count being the 1's.
+    pub fn remaining_counts(&self) -> Vec<usize> {
+        let mut counts = vec![self.side_size; self.side_size];
+        for cell in self.grid.iter().filter(|cell| cell.value() != 0) {
+            let count = &mut counts[cell.value() as usize - 1];
+            *count = count.saturating_sub(1);
+        }
+
+        counts
+    }
+
+    /// Returns how hard the puzzle is for a human, from its initial values, see `grader::grade`.
+    pub fn rating(&self) -> Grade {
+        *self.rating.get_or_init(|| grader::grade(self))
+    }
+
+    /// Returns the neighbors of the cell at `index` holding `value`, the ones placing it there
+    /// conflicts with, along with the filled cells of the cages whose sum it does not fit, the
+    /// cell itself excluded.
+    pub fn conflicting_cells(&self, index: usize, value: u8) -> Vec<usize> {
+        let (r, c) = self.coordinates(index);
+        let overflown = self
+            .cages
+            .iter()
+            .filter(|cage| !cage.allows(&self.grid, index, value, self.side_size))
+            .flat_map(|cage| cage.cells.iter().copied())
+            .filter(|n| self.grid[*n] != 0);
+        let mut cells: Vec<usize> = self
+            .neighbors(r, c)
+            .filter(|n| self.grid[*n] == value)
+            .chain(overflown)
+            .filter(|n| *n != index)
+            .collect();
+        cells.sort();
+        cells.dedup();
+        cells
+    }
+
+    /// Returns whether `value` is already in one of the neighbors of the cell at `index`, the
+    /// cell itself excluded, or does not fit the sum of its cage.
+    fn conflicts_with(&self, index: usize, value: u8) -> bool {
+        let (r, c) = self.coordinates(index);
+        self.neighbors(r, c)
+            .any(|n| n != index && self.grid[n] == value)
+            || !self.cage_allows(index, value)
+    }
+
+    /// Returns the cells placed by the player whose value is also in one of their neighbors.
+    pub fn conflicts(&self) -> Vec<usize> {
+        (0..self.grid.len())
+            .filter(|i| self.grid[*i] != 0 && !self.grid[*i].initial())
+            .filter(|i| self.conflicts_with(*i, self.grid[*i].value()))
+            .collect()
+    }
+
+    /// Returns the clues whose value is also the one of another clue of one of their units.
+    pub fn clue_conflicts(&self) -> Vec<usize> {
+        (0..self.grid.len())
+            .filter(|i| self.grid[*i].initial())
+            .filter(|i| self.conflicts_with(*i, self.grid[*i].value()))
+            .collect()
+    }
+
+    /// Returns whether every cell of the grid holds a value, correct or not.
+    pub fn is_filled(&self) -> bool {
+        self.grid.iter().all(|x| x.value() != 0)
+    }
+
+    /// Checks if the grid is correctly completed.
+    /// Returns `true` if yes, `false` otherwise.
+    pub fn is_done(&self) -> bool {
+        // Check that all the grid is filled.
+        if self.grid.iter().any(|x| x.value() == 0) {
+            return false;
+        }
+
+        // Check row
+        for row in 0..self.side_size {
+            if (1..=self.side_size).any(|v| {
                 !self
                     .row(row)
-                    .map(|i| self.grid[i].value)
+                    .map(|i| self.grid[i].value())
                     .any(|x| x == (v as u8))
             }) {
                 return false;
@@ -290,7 +1393,7 @@ impl Game {
             if (1..=self.side_size).any(|v| {
                 !self
                     .column(col)
-                    .map(|i| self.grid[i].value)
+                    .map(|i| self.grid[i].value())
                     .any(|x| x == (v as u8))
             }) {
                 return false;
@@ -298,23 +1401,88 @@ impl Game {
         }
 
         // Check groups
-        for group_x in 0..self.size {
-            for group_y in 0..self.size {
-                if (1..=self.side_size).any(|v| {
-                    !self
-                        .group(group_x * self.size, group_y * self.size)
-                        .map(|i| self.grid[i].value)
-                        .any(|x| x == (v as u8))
-                }) {
-                    return false;
-                }
+        for group in 0..self.side_size {
+            if (1..=self.side_size).any(|v| {
+                !self
+                    .region(group)
+                    .map(|i| self.grid[i].value())
+                    .any(|x| x == (v as u8))
+            }) {
+                return false;
+            }
+        }
+
+        // Check diagonals, the first cell being on the main one only and the last of the first
+        // row on the anti-diagonal only
+        let last = self.side_size - 1;
+        for diagonal in [self.diagonals(0, 0), self.diagonals(0, last)] {
+            let values: HashSet<u8> = diagonal.map(|i| self.grid[i].value()).collect();
+            if self.variant == Variant::Diagonal && values.len() != self.side_size {
+                return false;
+            }
+        }
+
+        // Check windows
+        for window in 0..self.windows_count() {
+            let values: HashSet<u8> = self.window(window).map(|i| self.grid[i].value()).collect();
+            if values.len() != self.side_size {
+                return false;
             }
         }
 
-        true
+        // Check cages
+        self.cages.iter().all(|cage| cage.is_satisfied(&self.grid))
+    }
+
+    /// Records an event in the game's log, attributed to the current player in hot-seat games.
+    pub fn log(&mut self, event: GameEvent) {
+        let player = self.hot_seat.as_ref().map(|h| h.current);
+        self.events.push(LoggedEvent::new(event, player));
+    }
+
+    /// Counts a hint the player was shown, logging the sentence explaining it.
+    pub fn count_hint(&mut self, description: String) {
+        self.hints += 1;
+        self.log(GameEvent::Hint(description));
     }
 
-    pub fn do_move(&mut self, r: usize, c: usize, value: u8) -> Result<(), GameError> {
+    /// Replaces the cell at `index`, keeping a trace of the change in the history. The moves
+    /// that were undone cannot be redone anymore. The cell keeps its marker color.
+    fn set_cell(&mut self, index: usize, mut cell: Cell) {
+        cell.set_color(self.grid[index].color());
+        let before = std::mem::replace(&mut self.grid[index], cell);
+        let played = Move {
+            index,
+            before,
+            after: cell,
+            at: self.timer.elapsed().as_millis() as u64,
+        };
+        self.timeline.push(played.clone());
+        self.history.push(played);
+        self.undone.clear();
+    }
+
+    /// Puts back the cell at `index` as it was before or after a move of the history, the
+    /// marker colors not being moves. The change is kept in the timeline.
+    fn restore_cell(&mut self, index: usize, mut cell: Cell) {
+        cell.set_color(self.grid[index].color());
+        let before = std::mem::replace(&mut self.grid[index], cell);
+        self.timeline.push(Move {
+            index,
+            before,
+            after: cell,
+            at: self.timer.elapsed().as_millis() as u64,
+        });
+    }
+
+    /// Checks that `value` can be placed in the cell located at row `r` and column `c`.
+    fn check_move(
+        &self,
+        r: usize,
+        c: usize,
+        value: u8,
+        validation: Validation,
+    ) -> Result<(), GameError> {
         // Check the position is legal
         if r >= self.side_size || c >= self.side_size {
             return Err(GameError::IllegalPosition);
@@ -328,21 +1496,78 @@ impl Game {
         // Get the index of the target box
         let index = self.index(r, c);
 
-        // If the box already contains a value
-        if self.grid[index] != 0 {
+        // Initial values can never be changed, the ones placed by the player are overwritten
+        if self.grid[index].initial() {
             return Err(GameError::NonEmptyCell);
         }
 
-        // Check the new value is valid
-        if !self.valids(index).contains(&value) {
+        // Check the new value is valid according to the validation policy
+        let valid = match validation {
+            Validation::Strict => !self.conflicts_with(index, value),
+            Validation::Solution => self.solution()?[index] == value,
+            Validation::Lenient | Validation::Free => true,
+        };
+        if !valid {
             return Err(GameError::InvalidValue);
         }
 
-        // Set the new value
-        self.grid[index] = Cell {
+        Ok(())
+    }
+
+    /**
+     * Places `value` in the cell at row `r` and column `c` if the move passes `validation`,
+     * replacing the value the player may have placed there. The notes follow the candidates with
+     * `auto_notes`, or lose `value` in the cells seeing this one with `remove_notes`.
+     */
+    pub fn do_move(
+        &mut self,
+        r: usize,
+        c: usize,
+        value: u8,
+        validation: Validation,
+    ) -> Result<(), GameError> {
+        // The solution is found once for the moves checked against it, a puzzle without any
+        // failing the check below
+        if validation == Validation::Solution {
+            let _ = self.keep_solution();
+        }
+
+        // Refuse the move, and keep a trace of it, if it is not possible
+        if let Err(e) = self.check_move(r, c, value, validation) {
+            self.log(GameEvent::Error(e.to_string()));
+            // Only a wrong value is a mistake passing the turn, not a typo or a click on a clue
+            if matches!(e, GameError::InvalidValue) {
+                self.mistakes += 1;
+                if let Some(hot_seat) = self.hot_seat.as_mut() {
+                    hot_seat.end_turn(false);
+                }
+            }
+            return Err(e);
+        }
+
+        // Set the new value, counting it as a mistake if it conflicts in lenient mode
+        let index = self.index(r, c);
+        let conflicts = validation == Validation::Lenient && self.conflicts_with(index, value);
+        if conflicts {
+            self.mistakes += 1;
+        }
+        self.set_cell(index, Cell::new(value, false));
+        if self.auto_notes {
+            self.notes_from_candidates();
+        } else if self.remove_notes {
+            self.remove_neighbor_notes(r, c, value);
+        }
+        self.log(GameEvent::Move {
+            row: r,
+            column: c,
             value,
-            initial: false,
-        };
+        });
+        if self.is_done() {
+            self.log(GameEvent::Completed);
+        }
+        if let Some(hot_seat) = self.hot_seat.as_mut() {
+            hot_seat.end_turn(!conflicts);
+        }
 
         // If this game is attached to a save file, save the game after doing the move
         if self.save_file.is_some() {
@@ -352,83 +1577,701 @@ impl Game {
         Ok(())
     }
 
-    pub fn fill_rng(&mut self, current_cell: usize) -> bool {
-        if current_cell >= self.side_size * self.side_size {
-            return true;
+    /**
+     * Empties the non initial cell located at row `r` and column `c`.
+     */
+    pub fn clear_cell(&mut self, r: usize, c: usize) -> Result<(), GameError> {
+        // Check the position is legal
+        if r >= self.side_size || c >= self.side_size {
+            return Err(GameError::IllegalPosition);
         }
-        let v = self.valids(current_cell);
 
-        for n in v {
-            self.grid[current_cell] = Cell {
-                value: n,
-                initial: true,
-            };
+        // Initial values can never be changed
+        let index = self.index(r, c);
+        if self.grid[index].initial() {
+            self.log(GameEvent::Error(GameError::NonEmptyCell.to_string()));
+            return Err(GameError::NonEmptyCell);
+        }
+        if self.grid[index].value() == 0 {
+            return Ok(());
+        }
 
-            if self.fill_rng(current_cell + 1) {
-                return true;
+        self.set_cell(index, Cell::new(0, false));
+        if self.auto_notes {
+            self.notes_from_candidates();
+        }
+        self.log(GameEvent::Clear { row: r, column: c });
+
+        // If this game is attached to a save file, save the game after clearing the cell
+        if self.save_file.is_some() {
+            self.save()?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Returns the values of the solved grid: the stored solution if any, otherwise the solution
+     * found by solving the initial values, see `keep_solution` to store it.
+     */
+    pub fn solution(&self) -> Result<Vec<u8>, GameError> {
+        if let Some(solution) = self.solution.as_ref() {
+            return Ok(solution.clone());
+        }
+
+        let mut puzzle = self.copy_puzzle();
+        match solver::Dlx.solve(&mut puzzle) {
+            Ok(_) => Ok(puzzle.grid.iter().map(|x| x.value()).collect()),
+            Err(_) => Err(GameError::NoSolution),
+        }
+    }
+
+    /**
+     * Stores the solution of the grid if it is not known yet, so that the moves checked against
+     * it don't solve the puzzle again. It is kept in the save file too.
+     */
+    pub fn keep_solution(&mut self) -> Result<(), GameError> {
+        if self.solution.is_none() {
+            self.solution = Some(self.solution()?);
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Compares every cell of the grid to the solution, in the grid's order.
+     */
+    pub fn diff(&self) -> Result<Vec<CellDiff>, GameError> {
+        let solution = self.solution()?;
+        Ok(self
+            .grid
+            .iter()
+            .zip(solution)
+            .map(|(cell, expected)| match cell.value() {
+                0 => CellDiff::Empty(expected),
+                value if value == expected => CellDiff::Correct,
+                _ => CellDiff::Wrong(expected),
+            })
+            .collect())
+    }
+
+    /**
+     * Returns a copy of this game, without its save file, containing only the initial values.
+     */
+    pub fn copy_puzzle(&self) -> Game {
+        let mut puzzle = Game::new(self.size, None).unwrap();
+        puzzle.variant = self.variant;
+        puzzle.cages = self.cages.clone();
+        puzzle.set_regions(self.regions.clone());
+        for (i, cell) in self.grid.iter().enumerate() {
+            if cell.initial() {
+                puzzle.grid[i] = *cell;
             }
         }
 
-        self.grid[current_cell] = Cell {
-            value: 0,
-            initial: false,
+        puzzle
+    }
+
+    /**
+     * Reverts the last moves until every value of the grid matches the solution, discarding
+     * those moves. Returns the number of reverted moves.
+     */
+    pub fn rollback(&mut self) -> Result<usize, GameError> {
+        self.keep_solution()?;
+        let solution = self.solution()?;
+        let is_correct = |game: &Game| {
+            game.grid
+                .iter()
+                .zip(solution.iter())
+                .all(|(cell, value)| cell.value() == 0 || cell.value() == *value)
+        };
+
+        let mut reverted = 0;
+        while !is_correct(self) && !self.history.is_empty() {
+            self.revert_to(self.history.len() - 1);
+            reverted += 1;
+        }
+
+        if self.auto_notes {
+            self.notes_from_candidates();
+        }
+        self.log(GameEvent::Rollback(reverted));
+
+        // If this game is attached to a save file, save the game after the rollback
+        if self.save_file.is_some() {
+            self.save()?;
+        }
+
+        Ok(reverted)
+    }
+
+    /**
+     * Reverts the moves of the history until it is `length` moves long, abandoning the guesses
+     * started after that point.
+     */
+    fn revert_to(&mut self, length: usize) {
+        while self.history.len() > length {
+            let last = self.history.pop().unwrap();
+            self.restore_cell(last.index, last.before);
+        }
+        self.undone.clear();
+        self.guesses.retain(|start| *start <= length);
+    }
+
+    /**
+     * Reverts the last move of the history, so that it can be played again with `redo`.
+     */
+    pub fn undo(&mut self) -> Result<(), GameError> {
+        let last = self.history.pop().ok_or(GameError::NothingToUndo)?;
+        self.restore_cell(last.index, last.before);
+        self.undone.push(last);
+        // Undoing the first move of a guess abandons it
+        let length = self.history.len();
+        self.guesses.retain(|start| *start <= length);
+
+        self.after_history_change(GameEvent::Undo)
+    }
+
+    /**
+     * Plays again the last move reverted by `undo`.
+     */
+    pub fn redo(&mut self) -> Result<(), GameError> {
+        let next = self.undone.pop().ok_or(GameError::NothingToRedo)?;
+        self.restore_cell(next.index, next.after);
+        self.history.push(next);
+
+        self.after_history_change(GameEvent::Redo)
+    }
+
+    /**
+     * Keeps the notes in sync, logs `event` and saves the game after moving through the history.
+     */
+    fn after_history_change(&mut self, event: GameEvent) -> Result<(), GameError> {
+        if self.auto_notes {
+            self.notes_from_candidates();
+        }
+        self.log(event);
+
+        // If this game is attached to a save file, save the game after the change
+        if self.save_file.is_some() {
+            self.save()?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Starts a guess: the following moves can be discarded all at once with `discard_guess`.
+     * Guesses can be nested.
+     */
+    pub fn start_guess(&mut self) {
+        self.guesses.push(self.history.len());
+        self.log(GameEvent::GuessStarted(self.guesses.len()));
+    }
+
+    /**
+     * Ends the current guess, keeping its moves.
+     */
+    pub fn commit_guess(&mut self) -> Result<(), GameError> {
+        if self.guesses.pop().is_none() {
+            return Err(GameError::NoGuess);
+        }
+        self.log(GameEvent::GuessCommitted);
+
+        Ok(())
+    }
+
+    /**
+     * Ends the current guess, reverting all of its moves. Returns the number of reverted moves.
+     */
+    pub fn discard_guess(&mut self) -> Result<usize, GameError> {
+        let start = self.guesses.pop().ok_or(GameError::NoGuess)?;
+        let reverted = self.history.len() - start;
+        self.revert_to(start);
+
+        if self.auto_notes {
+            self.notes_from_candidates();
+        }
+        self.log(GameEvent::GuessDiscarded(reverted));
+
+        // If this game is attached to a save file, save the game after reverting the guess
+        if self.save_file.is_some() {
+            self.save()?;
+        }
+
+        Ok(reverted)
+    }
+
+    /**
+     * Cycles the non initial cell located at row `r` and column `c` through its currently valid
+     * candidates, in ascending order if `forward` is `true` and descending order otherwise.
+     *
+     * Note: The empty value sits between the last and the first candidate so that cycling can
+     * also be used to clear a cell.
+     *
+     * Returns the value the cell now contains.
+     */
+    pub fn cycle_value(&mut self, r: usize, c: usize, forward: bool) -> Result<u8, GameError> {
+        // Check the position is legal
+        if r >= self.side_size || c >= self.side_size {
+            return Err(GameError::IllegalPosition);
+        }
+
+        // Initial values can never be changed
+        let index = self.index(r, c);
+        if self.grid[index].initial() {
+            self.log(GameEvent::Error(GameError::NonEmptyCell.to_string()));
+            return Err(GameError::NonEmptyCell);
+        }
+
+        // Empty the cell so that its own value is not seen as a conflict, then list the values
+        // the cell can take, starting with the empty value.
+        let current = self.grid[index].value();
+        self.grid[index].set_value(0);
+        let mut cycle = self.valids(index);
+        cycle.sort();
+        cycle.insert(0, 0);
+
+        // Find the next value, if the current one is not a candidate anymore start from empty.
+        let position = cycle.iter().position(|&v| v == current).unwrap_or(0);
+        let next = match forward {
+            true => cycle[(position + 1) % cycle.len()],
+            false => cycle[(position + cycle.len() - 1) % cycle.len()],
         };
-        false
+        self.grid[index].set_value(current);
+        if next == current {
+            return Ok(current);
+        }
+
+        // Set the new value
+        self.set_cell(index, Cell::new(next, false));
+        if self.auto_notes {
+            self.notes_from_candidates();
+        }
+        self.log(match next {
+            0 => GameEvent::Clear { row: r, column: c },
+            _ => GameEvent::Move {
+                row: r,
+                column: c,
+                value: next,
+            },
+        });
+        if self.is_done() {
+            self.log(GameEvent::Completed);
+        }
+
+        // If this game is attached to a save file, save the game after changing the value
+        if self.save_file.is_some() {
+            self.save()?;
+        }
+
+        Ok(next)
+    }
+
+    /**
+     * Sets the notes of every empty cell to the values it can currently take, and removes the
+     * notes of the filled ones.
+     */
+    pub(crate) fn notes_from_candidates(&mut self) {
+        for i in 0..self.grid.len() {
+            let notes = match self.grid[i].value() {
+                0 => self.valids(i).iter().fold(0, |notes, v| notes | 1 << v),
+                _ => 0,
+            };
+            self.grid[i].set_notes(notes);
+        }
+    }
+
+    /**
+     * Removes the notes of `value` from the cells seen by the one at row `r` and column `c`,
+     * where it cannot go anymore once placed there.
+     */
+    pub fn remove_neighbor_notes(&mut self, r: usize, c: usize, value: u8) {
+        let neighbors: Vec<usize> = self.neighbors(r, c).collect();
+        for neighbor in neighbors {
+            let notes = self.grid[neighbor].notes() & !(1 << value);
+            self.grid[neighbor].set_notes(notes);
+        }
+    }
+
+    /**
+     * Fills the notes of every empty cell with its candidates, in one step.
+     */
+    pub fn fill_notes(&mut self) -> Result<(), GameError> {
+        self.notes_from_candidates();
+        self.log(GameEvent::NotesFilled);
+
+        // If this game is attached to a save file, save the game after filling the notes
+        if self.save_file.is_some() {
+            self.save()?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Keeps the notes equal to the candidates of each cell after every change if `on` is set,
+     * filling them right away, or leaves them to the player.
+     */
+    pub fn set_auto_notes(&mut self, on: bool) -> Result<(), GameError> {
+        self.auto_notes = on;
+        if on {
+            self.notes_from_candidates();
+        }
+        self.log(GameEvent::AutoNotes(on));
+
+        // If this game is attached to a save file, save the game after changing the setting
+        if self.save_file.is_some() {
+            self.save()?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Adds the note of `value` to the empty cell at row `r` and column `c`, or removes it if it
+     * is already there. Returns whether the note is now set.
+     */
+    pub fn toggle_note(&mut self, r: usize, c: usize, value: u8) -> Result<bool, GameError> {
+        if r >= self.side_size || c >= self.side_size {
+            return Err(GameError::IllegalPosition);
+        }
+        if value == 0 || value > self.side_size as u8 {
+            return Err(GameError::IllegalValue);
+        }
+        let index = self.index(r, c);
+        if self.grid[index].value() != 0 {
+            return Err(GameError::NonEmptyCell);
+        }
+
+        let notes = self.grid[index].notes() ^ 1 << value;
+        self.grid[index].set_notes(notes);
+        let set = self.grid[index].has_note(value);
+        self.log(GameEvent::NoteToggled {
+            row: r,
+            column: c,
+            value,
+            set,
+        });
+
+        // If this game is attached to a save file, save the game after changing the notes
+        if self.save_file.is_some() {
+            self.save()?;
+        }
+
+        Ok(set)
     }
 
     /**
-     * This function unfills the grid as long as the given `solder` can solve it.
+     * Adds the note of `value` to the empty cells among `indexes`, or removes it from them if
+     * they all have it already, in one step. Returns whether the note is now set.
+     */
+    pub fn toggle_notes(&mut self, indexes: &[usize], value: u8) -> Result<bool, GameError> {
+        if value == 0 || value > self.side_size as u8 {
+            return Err(GameError::IllegalValue);
+        }
+        if indexes.iter().any(|i| *i >= self.grid.len()) {
+            return Err(GameError::IllegalPosition);
+        }
+        let empty: Vec<usize> = indexes
+            .iter()
+            .copied()
+            .filter(|i| self.grid[*i].value() == 0)
+            .collect();
+        if empty.is_empty() {
+            return Err(GameError::NonEmptyCell);
+        }
+
+        let set = !empty.iter().all(|i| self.grid[*i].has_note(value));
+        for i in empty.iter() {
+            let notes = match set {
+                true => self.grid[*i].notes() | 1 << value,
+                false => self.grid[*i].notes() & !(1 << value),
+            };
+            self.grid[*i].set_notes(notes);
+        }
+        self.log(GameEvent::NotesToggled {
+            value,
+            cells: empty.len(),
+            set,
+        });
+
+        // If this game is attached to a save file, save the game after changing the notes
+        if self.save_file.is_some() {
+            self.save()?;
+        }
+
+        Ok(set)
+    }
+
+    /**
+     * Clears the notes selected by `which`.
+     */
+    pub fn clear_notes(&mut self, which: NotesClear) -> Result<(), GameError> {
+        // Find out the cells and the values to clear
+        let (cells, mask): (Vec<usize>, u32) = match which {
+            NotesClear::Digit(value) => {
+                if value == 0 || value > self.side_size as u8 {
+                    return Err(GameError::IllegalValue);
+                }
+                ((0..self.grid.len()).collect(), 1 << value)
+            }
+            NotesClear::Row(r) | NotesClear::Column(r) | NotesClear::Group(r)
+                if r >= self.side_size =>
+            {
+                return Err(GameError::IllegalPosition);
+            }
+            NotesClear::Row(r) => (self.row(r).collect(), u32::MAX),
+            NotesClear::Column(c) => (self.column(c).collect(), u32::MAX),
+            NotesClear::Group(g) => (self.region(g).collect(), u32::MAX),
+            NotesClear::Invalid => {
+                for i in 0..self.grid.len() {
+                    if self.grid[i].notes() != 0 {
+                        let valids = self.valids(i).iter().fold(0, |notes, v| notes | 1 << v);
+                        let notes = self.grid[i].notes() & valids;
+                        self.grid[i].set_notes(notes);
+                    }
+                }
+                (Vec::new(), 0)
+            }
+        };
+
+        for i in cells {
+            let notes = self.grid[i].notes() & !mask;
+            self.grid[i].set_notes(notes);
+        }
+        self.log(GameEvent::NotesCleared(which));
+
+        // If this game is attached to a save file, save the game after clearing the notes
+        if self.save_file.is_some() {
+            self.save()?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Paints the cells at `indexes` with the marker color `color`, from 1 to the number of
+     * `MARKER_COLORS`, or removes their color with 0. Unlike the moves, the colors are not part
+     * of the history.
+     */
+    pub fn paint(&mut self, indexes: &[usize], color: u8) -> Result<(), GameError> {
+        if color as usize > MARKER_COLORS.len() {
+            return Err(GameError::IllegalValue);
+        }
+        if indexes.iter().any(|i| *i >= self.grid.len()) {
+            return Err(GameError::IllegalPosition);
+        }
+
+        for i in indexes {
+            self.grid[*i].set_color(color);
+        }
+        self.log(GameEvent::Painted {
+            cells: indexes.len(),
+            color,
+        });
+
+        // If this game is attached to a save file, save the game after painting the cells
+        if self.save_file.is_some() {
+            self.save()?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Replaces the cell at `index` with the one another player set on the same grid, keeping
+     * the marker color of this one. The change is kept in the timeline but not in the history,
+     * the moves of the other player not being undone here. As it comes from the network, the
+     * cell is refused unless its value and notes fit the grid and it is not a clue.
+     */
+    pub fn apply_shared_cell(&mut self, index: usize, mut cell: Cell) -> Result<(), GameError> {
+        let current = *self.grid.get(index).ok_or(GameError::IllegalPosition)?;
+        if current.initial() {
+            return Err(GameError::NonEmptyCell);
+        }
+        if !cell.fits(self.side_size) || cell.initial() {
+            return Err(GameError::IllegalValue);
+        }
+        cell.set_color(current.color());
+        if cell.bits() == current.bits() {
+            return Ok(());
+        }
+
+        self.restore_cell(index, cell);
+        let (row, column) = self.coordinates(index);
+        self.log(GameEvent::Shared {
+            row,
+            column,
+            value: cell.value(),
+        });
+
+        // If this game is attached to a save file, save the game after the change
+        if self.save_file.is_some() {
+            self.save()?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Paints the cells at `indexes` with the marker color following the one of the first cell,
+     * the last color being followed by none. Returns the new color.
+     */
+    pub fn cycle_color(&mut self, indexes: &[usize]) -> Result<u8, GameError> {
+        let first = indexes.first().ok_or(GameError::IllegalPosition)?;
+        let current = self
+            .grid
+            .get(*first)
+            .ok_or(GameError::IllegalPosition)?
+            .color();
+        let next = (current + 1) % (MARKER_COLORS.len() as u8 + 1);
+        self.paint(indexes, next)?;
+
+        Ok(next)
+    }
+
+    /**
+     * Fills the empty cells from `current_cell` on with random values forming a valid grid,
+     * returning whether it could.
+     */
+    pub fn fill_rng(&mut self, current_cell: usize) -> bool {
+        self.fill_with(current_cell, &mut rand::thread_rng())
+    }
+
+    /**
+     * Same as `fill_rng`, the order in which the values are tried coming from `rng`, so that a
+     * seeded generator always fills the grid the same way.
      *
-     * Note: It also uses the constant `MIN_CLUES` and will leave at least `MIN_CLUES`
-     * values set in the grid.
+     * The most constrained cell is filled first, backtracking with an explicit stack of the
+     * values left to try in each filled cell, and the candidates come from the masks of the values
+     * used in each row, column and box. As big grids sometimes get stuck far from the value that
+     * made them impossible, the filling starts over after too many dead ends, twice as many being
+     * allowed each time so that it still ends on grids that cannot be filled.
+     */
+    pub fn fill_with<R: Rng>(&mut self, current_cell: usize, rng: &mut R) -> bool {
+        self.fill_within(current_cell, rng, usize::MAX)
+    }
+
+    /**
+     * Same as `fill_with`, giving up after `restarts` restarts: the grids that cannot be filled
+     * may take ages to tell apart, like some jigsaw regions, while the others are filled within
+     * a few restarts.
+     */
+    pub fn fill_within<R: Rng>(
+        &mut self,
+        current_cell: usize,
+        rng: &mut R,
+        restarts: usize,
+    ) -> bool {
+        let mut restarts = restarts;
+        let mut masks = Masks::new(self);
+        let mut path: Vec<(usize, Vec<u8>)> = Vec::new();
+        let (mut dead_ends, mut max_dead_ends) = (0, self.grid.len());
+        loop {
+            // Pick the empty cell with the fewest candidates, the grid is filled if there is none
+            let next = (current_cell..self.grid.len())
+                .filter(|i| self.grid[*i] == 0)
+                .min_by_key(|i| masks.candidates(*i).count_ones());
+            let Some(index) = next else {
+                return true;
+            };
+
+            // Its candidates are shuffled and reversed, to be popped in the shuffled order
+            let mut values = solver::values_of(masks.candidates(index));
+            values.shuffle(rng);
+            values.reverse();
+            path.push((index, values));
+
+            // Place its next value, going back to the previous cells while none is left
+            loop {
+                let Some((index, values)) = path.last_mut() else {
+                    return false;
+                };
+                let current = self.grid[*index].value();
+                if current != 0 {
+                    masks.remove(*index, current);
+                    self.grid[*index] = Cell::new(0, false);
+                }
+                match values.pop() {
+                    Some(value) => {
+                        self.grid[*index] = Cell::new(value, true);
+                        masks.place(*index, value);
+                        break;
+                    }
+                    None => {
+                        path.pop();
+                        dead_ends += 1;
+                    }
+                }
+
+                if dead_ends > max_dead_ends {
+                    for (index, _) in path.drain(..) {
+                        let value = self.grid[index].value();
+                        if value != 0 {
+                            masks.remove(index, value);
+                            self.grid[index] = Cell::new(0, false);
+                        }
+                    }
+                    if restarts == 0 {
+                        return false;
+                    }
+                    restarts -= 1;
+                    dead_ends = 0;
+                    max_dead_ends *= 2;
+                    break;
+                }
+            }
+        }
+    }
+
+    /**
+     * This function unfills the grid as long as the given `solver` can solve it and its solution
+     * stays unique.
+     *
+     * Note: It will leave at least `min_clues` values set in the grid.
      *
-     * Note: It also will do at most `MAX_UNFILL_ATTEMPT` at unfilling until there are `MIN_CLUES`
-     * values set left.
+     * Note: It will stop after `max_attempts` failed attempts at emptying a cell.
      */
-    pub fn unfill<S: Solver>(&mut self, solver: S) {
-        // TODO: Use difficulty to define ranges of number of clues that'll represent
-        // the difficulty of the grid.
-        // Example:
-        //  - [17;30] = Very hard
-        //  ......
-        //  - [45;55] = Easy
-        //
+    pub fn unfill<S: Solver>(&mut self, solver: S, min_clues: usize, max_attempts: usize) {
+        self.unfill_with(solver, min_clues, max_attempts, &mut rand::thread_rng());
+    }
 
-        // Attempt counter and random number generator
-        let (mut attempt, mut rng) = (MAX_UNFILL_ATTEMPTS, rand::thread_rng());
+    /**
+     * Same as `unfill`, the cells to empty being picked by `rng`.
+     */
+    pub fn unfill_with<S: Solver, R: Rng>(
+        &mut self,
+        solver: S,
+        min_clues: usize,
+        max_attempts: usize,
+        rng: &mut R,
+    ) {
+        // The grid is full, keep it as the solution of the puzzle
+        if self.grid.iter().all(|x| x.value() != 0) {
+            self.solution = Some(self.grid.iter().map(|x| x.value()).collect());
+        }
+
+        // Attempt counter
+        let mut attempt = max_attempts;
 
         // As long as we have attempts left and more than the minimum clues set in the grid
-        while attempt > 0 && self.nb_non_empty() >= MIN_CLUES {
-            // Find a random non empty box
-            let mut random_index = rng.gen_range(0..(self.side_size * self.side_size));
+        while attempt > 0 && self.nb_non_empty() > min_clues {
+            // Find a random non empty box, drawn as a u32 for the seeded puzzles to be the same
+            // whatever the size of usize
+            let cells = (self.side_size * self.side_size) as u32;
+            let mut random_index = rng.gen_range(0..cells) as usize;
             while self.grid[random_index] == 0 {
-                random_index = rng.gen_range(0..(self.side_size * self.side_size));
+                random_index = rng.gen_range(0..cells) as usize;
             }
 
             // Keep a track of the old value of the random box and empty it
-            let old_value = self.grid[random_index].value;
-            self.grid[random_index] = Cell {
-                value: 0,
-                initial: false,
-            };
-            // Make a copy of new modified game
-            let mut game_copy = Game {
-                size: self.size,
-                side_size: self.side_size,
-                selected_index: None,
-                selected_value: None,
-                save_path: None,
-                save_file: None,
-                grid: self.grid.clone(),
-            };
-
-            // Check if we can still solve the grid, if not reverse the change (emptying a
-            // random box) and decrement the number of attempts left
-            if solver.solve(&mut game_copy).is_err() {
-                self.grid[random_index] = Cell {
-                    value: old_value,
-                    initial: true,
-                };
+            let old_value = self.grid[random_index].value();
+            self.grid[random_index] = Cell::new(0, false);
+            // Check if the puzzle left keeps a single solution, if not reverse the change
+            // (emptying a random box) and decrement the number of attempts left
+            if !solver.has_single_solution(&mut self.copy_puzzle()) {
+                self.grid[random_index] = Cell::new(old_value, true);
                 attempt -= 1;
             }
         }
@@ -442,50 +2285,46 @@ impl Game {
 
         match self.save_file.as_ref().unwrap().rewind() {
             Ok(_) => (),
-            Err(_) => return Err(GameError::WriteSaveError),
+            Err(e) => return Err(GameError::WriteSaveError(e)),
         }
 
-        // Write the game size on the first line
-        match writeln!(
-            &mut self.save_file.as_ref().unwrap(),
-            "game_size: {}",
-            self.size
-        ) {
+        // Write the game as JSON, on a single line
+        let data = SaveData {
+            version: SAVE_VERSION,
+            size: self.size,
+            selected: self.selected_index,
+            cells: self.grid.clone(),
+            history: self.history.clone(),
+            guesses: self.guesses.clone(),
+            undone: self.undone.clone(),
+            timeline: self.timeline.clone(),
+            played: self.timer.elapsed().as_secs(),
+            difficulty: self.difficulty,
+            variant: self.variant,
+            cages: self.cages.clone(),
+            regions: self.is_jigsaw().then(|| self.regions.clone()),
+            mistakes: self.mistakes,
+            hints: self.hints,
+            annotations: self.annotations.clone(),
+            metadata: self.metadata.clone(),
+            auto_notes: self.auto_notes,
+            hot_seat: self.hot_seat.clone(),
+            solution: self.solution.clone(),
+        };
+        match serde_json::to_writer(self.save_file.as_ref().unwrap(), &data) {
             Ok(_) => (),
-            Err(_) => return Err(GameError::WriteSaveError),
-        }
-
-        // Then if any, write the currently selected cell
-        if self.selected_index.is_some() {
-            match writeln!(
-                &mut self.save_file.as_ref().unwrap(),
-                "selected: {}",
-                self.selected_index.unwrap()
-            ) {
-                Ok(_) => (),
-                Err(_) => return Err(GameError::WriteSaveError),
-            }
+            Err(e) => return Err(GameError::WriteSaveError(e.into())),
         }
 
-        // Generate a string containing comma-separated values and "I" for initial "N" for non
-        // initial value.
-        let values = self
-            .grid
-            .iter()
-            .map(|x| {
-                let c = match x.initial {
-                    true => "I",
-                    false => "N",
-                };
-                format!("{}/{}", x.value, c)
-            })
-            .collect::<Vec<String>>()
-            .join(",");
+        self.end_save()
+    }
 
-        // Write the grid's values to the file
-        match writeln!(&mut self.save_file.as_ref().unwrap(), "cells: {}", values) {
+    /// Drops what is left of a longer previous save after what was just written.
+    fn end_save(&mut self) -> Result<(), GameError> {
+        let file = self.save_file.as_mut().unwrap();
+        match file.stream_position().and_then(|end| file.set_len(end)) {
             Ok(_) => (),
-            Err(_) => return Err(GameError::WriteSaveError),
+            Err(e) => return Err(GameError::WriteSaveError(e)),
         }
 
         Ok(())
@@ -501,37 +2340,33 @@ impl fmt::Display for Game {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "Game:\n")?;
 
+        // The cells are as wide as the biggest value, 16 and 25 taking two characters
+        let width = self.side_size.to_string().len();
+        let dashes = "-".repeat(width + 2);
+
         for i in 0..self.side_size {
-            if (i % self.size) == 0 {
-                writeln!(
-                    f,
-                    "{}{}",
-                    "+---".repeat(self.side_size).blue().bold(),
-                    "+".blue().bold()
-                )?;
-            } else {
-                writeln!(
-                    f,
-                    "{}{}",
-                    format!("{}---{}", "+".blue().bold(), "+---".repeat(self.size - 1))
-                        .repeat(self.size),
-                    "+".blue().bold()
-                )?;
+            // The borders of the regions are colored
+            for j in 0..self.side_size {
+                match self.borders(i, j) {
+                    (true, _) => write!(f, "{}", format!("+{}", dashes).blue().bold())?,
+                    (false, true) => write!(f, "{}{}", "+".blue().bold(), dashes)?,
+                    (false, false) => write!(f, "+{}", dashes)?,
+                }
             }
+            writeln!(f, "{}", "+".blue().bold())?;
 
             for j in 0..self.side_size {
-                if (j % self.size) == 0 {
-                    write!(f, "{} ", "|".blue().bold())?;
-                } else {
-                    write!(f, "| ")?;
+                match self.borders(i, j).1 {
+                    true => write!(f, "{} ", "|".blue().bold())?,
+                    false => write!(f, "| ")?,
                 }
 
-                let val = match self.grid[self.index(i, j)].value {
-                    0 => " ".to_string(),
-                    _ => self.grid[self.index(i, j)].value.to_string(),
+                let val = match self.grid[self.index(i, j)].value() {
+                    0 => String::new(),
+                    value => value.to_string(),
                 };
 
-                write!(f, "{} ", val)?;
+                write!(f, "{:>width$} ", val, width = width)?;
             }
             writeln!(f, "{}", "|".blue().bold())?;
         }
@@ -539,7 +2374,7 @@ impl fmt::Display for Game {
         writeln!(
             f,
             "{}{}",
-            "+---".repeat(self.side_size).blue().bold(),
+            format!("+{}", dashes).repeat(self.side_size).blue().bold(),
             "+".blue().bold()
         )?;
 
@@ -1,45 +1,1757 @@
+use sdl2::clipboard::ClipboardUtil;
 use sdl2::event::Event;
-use sdl2::keyboard::Keycode;
+use sdl2::keyboard::{KeyboardUtil, Keycode, Mod};
 use sdl2::mouse::MouseButton;
 use sdl2::pixels::Color;
 use sdl2::rect::Rect;
-use sdl2::render::Canvas;
+use sdl2::render::{BlendMode, Canvas};
 use sdl2::ttf::Font;
 use sdl2::video::Window;
 
+use std::collections::VecDeque;
+use std::path::Path;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
-use crate::errors::UiError;
-use crate::game::Game;
-use crate::traits::{Displayable, GUIConfig, ScreenOutcome};
+use crate::autosave::Autosave;
+use crate::dialog::{Dialog, DialogOutcome};
+use crate::errors::{GameError, UiError};
+use crate::export::{self, ExportOptions};
+use crate::game::{
+    symbol_value, Cell, CellDiff, Game, NotesClear, Validation, Variant, MARKER_COLORS,
+};
+use crate::glyphs::GlyphCache;
+use crate::grader::{self, SolveStep};
+use crate::hint::{self, Hint};
+use crate::layout::{fit_centered, Layout};
+use crate::logging;
+use crate::replay::Replay;
+use crate::session::Session;
+use crate::theme::Theme;
+use crate::traits::{
+    Displayable, GUIConfig, GridPlacement, InputMode, LayoutConfig, MouseConfig, RightClick,
+    ScreenOutcome, ThemePreset, Wheel,
+};
 
-const OFFSET_X: i32 = 40;
-const OFFSET_Y: i32 = 40;
-const BOX_SIZE: i32 = 35;
+static COLOR_GOOD_MSG: Color = Color::GREEN;
+static COLOR_BAD_MSG: Color = Color::RED;
+static COLOR_MESSAGE_BAR: Color = Color::RGBA(40, 40, 40, 220);
+static COLOR_DIGIT_DONE: Color = Color::RGBA(90, 90, 90, 255);
+static COLOR_DIGIT_COUNT: Color = Color::RGBA(170, 170, 170, 255);
+static COLOR_CANDIDATE: Color = Color::RGBA(0, 90, 40, 255);
+static COLOR_ELIMINATED: Color = Color::RGBA(40, 40, 40, 255);
+static COLOR_CAGE: Color = Color::RGBA(200, 200, 200, 255);
+static COLOR_DIAGONALS: Color = Color::RGBA(255, 220, 0, 110);
+static COLOR_WINDOWS: Color = Color::RGBA(120, 120, 120, 70);
+/// Colors of the jigsaw regions, neighboring regions never sharing theirs.
+static COLOR_REGIONS: [Color; 6] = [
+    Color::RGBA(20, 30, 60, 255),
+    Color::RGBA(20, 55, 30, 255),
+    Color::RGBA(60, 30, 20, 255),
+    Color::RGBA(50, 50, 20, 255),
+    Color::RGBA(20, 50, 55, 255),
+    Color::RGBA(50, 25, 55, 255),
+];
+static COLOR_HINT_CELLS: Color = Color::RGBA(60, 120, 255, 70);
+static COLOR_HINT_TARGETS: Color = Color::RGBA(60, 120, 255, 160);
+static COLOR_DIFF_CORRECT: Color = Color::RGBA(0, 200, 0, 90);
+static COLOR_DIFF_WRONG: Color = Color::RGBA(220, 0, 0, 120);
+static COLOR_DIFF_EMPTY: Color = Color::RGBA(120, 120, 120, 120);
+static COLOR_UNITS: Color = Color::RGBA(255, 255, 255, 25);
+/// Opacity of the marker colors painted over the cells, their value staying readable.
+const MARKER_ALPHA: u8 = 110;
+/// Color of the cells selected together with Ctrl+click or by dragging over them.
+static COLOR_SELECTION: Color = Color::RGBA(255, 210, 60, 80);
+/// Color of the cells flashed when a move is refused, its alpha fading out over the flash.
+static COLOR_FLASH: Color = Color::RGBA(255, 0, 0, 170);
+/// Colors of the notes the watched solver step places, and of the ones it removes.
+static COLOR_WATCH_PLACED: Color = Color::RGBA(0, 220, 90, 255);
+static COLOR_WATCH_REMOVED: Color = Color::RGBA(255, 70, 70, 255);
+/// The progress bar of the other player of a network versus game, over its empty part.
+static COLOR_OPPONENT: Color = Color::RGBA(255, 140, 0, 255);
+static COLOR_OPPONENT_LEFT: Color = Color::RGBA(60, 60, 60, 255);
 
-static COLOR_BCK: Color = Color::BLACK;
-static COLOR_NOT_INIT: Color = Color::RGBA(75, 75, 75, 255);
-// static COLOR_LINES: Color = Color::BLACK;
-static COLOR_LINES: Color = Color::RGBA(255, 220, 0, 255);
-static COLOR_HIGHLIGHT: Color = Color::RGBA(255, 110, 50, 255);
-static COLOR_FONT: Color = Color::WHITE;
-static _COLOR_GOOD_MSG: Color = Color::GREEN;
-static _COLOR_BAD_MSG: Color = Color::RED;
+/// Size of the hint button, at the top of the companion panel.
+const HINT_BUTTON_SIZE: (u32, u32) = (140, 40);
+/// Side of the tiles of the digits panel, at most.
+const DIGIT_TILE_SIZE: u32 = 40;
+
+/// Distance between the outline of a cage and the borders of its cells, in pixels.
+const CAGE_INSET: i32 = 4;
+/// Length of the dashes outlining the killer cages, and of the gaps between them, in pixels.
+const CAGE_DASH: i32 = 4;
+
+/// Space between the border of the log box and its text.
+const LOG_PADDING: i32 = 10;
+
+/// How long the cells a refused move conflicts with are flashed.
+const FLASH_DURATION: Duration = Duration::from_millis(600);
+/// How long a message stays under the grid, the last `MESSAGE_FADE` of it fading out.
+const MESSAGE_DURATION: Duration = Duration::from_secs(4);
+const MESSAGE_FADE: Duration = Duration::from_secs(1);
+
+/// Bounds of the delay between the steps of the watched solver, halved or doubled by the +
+/// and - keys.
+const WATCH_FASTEST: Duration = Duration::from_millis(125);
+const WATCH_SLOWEST: Duration = Duration::from_secs(8);
+
+/// Bounds of the delay between the moves of a replay, which are played back at the pace they
+/// were made, the long thoughts being shortened.
+const REPLAY_FASTEST: Duration = Duration::from_millis(150);
+const REPLAY_SLOWEST: Duration = Duration::from_secs(2);
+/// Highest number of times faster than their pace the moves of a replay are played back, the
+/// + and - keys doubling or halving it.
+const REPLAY_MAX_SPEED: u32 = 16;
+/// Height of the progress bar of the other player of a network versus game.
+const OPPONENT_BAR_HEIGHT: u32 = 6;
+
+/**
+ * Returns the digit typed with the given key, from either the main row or the keypad.
+ */
+pub fn digit_from_keycode(keycode: Keycode) -> Option<u8> {
+    match keycode {
+        Keycode::Num1 | Keycode::Kp1 => Some(1),
+        Keycode::Num2 | Keycode::Kp2 => Some(2),
+        Keycode::Num3 | Keycode::Kp3 => Some(3),
+        Keycode::Num4 | Keycode::Kp4 => Some(4),
+        Keycode::Num5 | Keycode::Kp5 => Some(5),
+        Keycode::Num6 | Keycode::Kp6 => Some(6),
+        Keycode::Num7 | Keycode::Kp7 => Some(7),
+        Keycode::Num8 | Keycode::Kp8 => Some(8),
+        Keycode::Num9 | Keycode::Kp9 => Some(9),
+        _ => None,
+    }
+}
+
+/**
+ * Returns the value above 9 typed with the given letter key on a grid of `side_size` values, A
+ * standing for 10 as in the puzzle ids, or `None` if the letter is not a value of the grid.
+ */
+pub fn letter_value_from_keycode(keycode: Keycode, side_size: usize) -> Option<u8> {
+    let offset = keycode as i32 - Keycode::A as i32;
+    if !(0..26).contains(&offset) {
+        return None;
+    }
+
+    symbol_value((b'A' + offset as u8) as char).filter(|value| *value as usize <= side_size)
+}
+
+/**
+ * Parses a color written `#RRGGBB` or `#RRGGBBAA`.
+ */
+pub fn parse_color(text: &str) -> Option<Color> {
+    let hex = text.strip_prefix('#')?;
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = match hex.len() {
+        8 => channel(6)?,
+        _ => 255,
+    };
+    Some(Color::RGBA(channel(0)?, channel(2)?, channel(4)?, alpha))
+}
+
+/**
+ * The steps of the human techniques played on the grid one at a time, for the player to watch
+ * the solver at work.
+ */
+struct Watch {
+    /// The steps left, the first one being shown on the grid until it is played.
+    steps: VecDeque<SolveStep>,
+    /// Whether the steps are played by themselves, rather than with the space key.
+    running: bool,
+    /// When the first step was shown.
+    shown: Instant,
+}
+
+/**
+ * A solved game played back on the grid move by move.
+ */
+struct ReplayView {
+    replay: Replay,
+    /// The grid of the solved game, put back once the replay is left.
+    grid: Vec<Cell>,
+    /// Whether the moves are played back by themselves, rather than with the arrow keys.
+    running: bool,
+    /// When the last move was played back.
+    shown: Instant,
+    /// How many times faster than their pace the moves are played back.
+    speed: u32,
+}
 
 #[derive(Default)]
 pub struct GameScreen<'a> {
     pub game: Option<Game>,
     font: Option<Rc<Font<'a, 'a>>>,
+    notes_font: Option<Rc<Font<'a, 'a>>>,
+    /// The values rendered with the font, for the grid.
+    glyphs: GlyphCache,
+    /// The values rendered with the notes font, for the notes and the digits panel.
+    notes_glyphs: GlyphCache,
+
+    message: Option<String>,
+    /// Whether the message is good news rather than an error.
+    message_good: bool,
+    /// When the message was shown, for it to fade out, `None` for the ones lasting until the
+    /// game moves on.
+    message_shown: Option<Instant>,
+
+    /// The index of the cell currently under the mouse cursor, if any.
+    hovered_index: Option<usize>,
+    /// Whether the player paused the current game, hiding its grid.
+    paused: bool,
+    /// The play time shown when the screen was last drawn, in seconds.
+    drawn_seconds: u64,
+    /// The other open games, the next one to switch to first.
+    parked: Vec<Session>,
+
+    /// Configured margins, line thicknesses and placement of the grid.
+    layout_config: LayoutConfig,
+    /// Area of the canvas the screen is drawn in.
+    viewport: Option<Rect>,
+    /// Geometry of the grid of the current game.
+    layout: Layout,
+
+    /// Whether the events log is displayed over the grid.
+    show_log: bool,
+
+    /// The colors the screen is drawn with.
+    theme: Theme,
+    /// The colors switched to by the theme toggle key.
+    other_theme: Theme,
+
+    /// How the moves are checked.
+    validation: Validation,
+    /// Whether a digit or a cell is picked first to place a value with the mouse.
+    input_mode: InputMode,
+    /// What the right-click and the mouse wheel do on the grid.
+    mouse: MouseConfig,
+    /// The digit whose possible cells are shown, if any.
+    where_digit: Option<u8>,
+    /// How each cell compares to the solution, once the player gave up or filled the grid
+    /// without their moves being checked.
+    diff: Option<Vec<CellDiff>>,
+    /// The hint shown on the grid, until the next move.
+    hint: Option<Hint>,
+    /// The cells the last refused move conflicts with, flashed in red, and when it was refused.
+    flash: Option<(Vec<usize>, Instant)>,
+    /// The solver steps being watched, if any.
+    watch: Option<Watch>,
+    /// The delay between the steps of the watched solver.
+    watch_delay: Duration,
+    /// The replay of the solved game being played back, if any.
+    replay: Option<ReplayView>,
+    /// The question asked before an export overwrites the image of the game, while it is open.
+    dialog: Option<Dialog<()>>,
+    /// The system clipboard, the share codes of the puzzles being copied to it.
+    clipboard: Option<ClipboardUtil>,
+    /// The state of the modifier keys, for Ctrl+click to add a cell to the selection.
+    keyboard: Option<KeyboardUtil>,
+    /// The cells selected along with the selected cell of the game, with Ctrl+click or by
+    /// dragging the mouse over them, empty while a single cell is selected.
+    selection: Vec<usize>,
+    /// The cell the left button was pressed on, while it is held.
+    drag_start: Option<usize>,
+    /// Whether the mouse was dragged over other cells since the left button was pressed, its
+    /// release then ending the drag rather than clicking a cell.
+    dragged: bool,
+    /// The filled cells of the grid of the other player of a network versus game and their
+    /// number, shown as a bar above the grid.
+    pub opponent: Option<(usize, usize)>,
+}
+
+impl<'a> GameScreen<'a> {
+    /**
+     * Returns wether or not the screen contains an undone game.
+     */
+    pub fn is_over(&self) -> bool {
+        self.replay.is_none() && self.game.is_some() && self.game.as_ref().unwrap().is_done()
+    }
+
+    /**
+     * Returns whether the current game can't be played anymore: either solved, or compared to
+     * the solution.
+     */
+    pub fn is_ended(&self) -> bool {
+        self.is_over() || self.diff.is_some()
+    }
+
+    /**
+     * Once the grid is filled, compares it to the solution unless its moves were checked as they
+     * were played, and shows the completion message if the game ended.
+     */
+    fn check_ended(&mut self) {
+        self.hint = None;
+        let game = self.game.as_ref().unwrap();
+        if game.is_filled() && (!game.is_done() || self.validation == Validation::Free) {
+            self.compare_to_solution();
+        }
+        match self.is_ended() {
+            true => self.show_lasting_message(self.completion_message(), self.is_over()),
+            false => self.message = None,
+        }
+    }
+
+    /**
+     * Looks for the next logical move and shows it, with the technique justifying it.
+     */
+    fn show_hint(&mut self) {
+        let game = self.game.as_ref().unwrap();
+        self.hint = logging::timed("hint", || hint::next_hint(game));
+        match self.hint.as_ref().map(|hint| hint.describe(game)) {
+            Some(description) => {
+                self.game.as_mut().unwrap().count_hint(description.clone());
+                self.show_message(description, true);
+            }
+            None => self.show_message(
+                String::from("No hint found with the known techniques."),
+                false,
+            ),
+        }
+    }
+
+    /**
+     * Starts watching the solver play the human techniques on the grid, its notes filled with
+     * the candidates for the steps to remove them, or stops watching it.
+     */
+    fn toggle_watch(&mut self) {
+        if self.watch.take().is_some() {
+            self.show_message(String::from("Stopped watching the solver."), true);
+            return;
+        }
+
+        self.hint = None;
+        if let Err(e) = self.game.as_mut().unwrap().fill_notes() {
+            self.show_message(e.to_string(), false);
+            return;
+        }
+        let game = self.game.as_ref().unwrap();
+        let (steps, _) = logging::timed("solver trace", || grader::trace(game, &grader::LADDER));
+        if steps.is_empty() {
+            self.show_message(
+                String::from("No step found with the known techniques."),
+                false,
+            );
+            return;
+        }
+
+        self.watch = Some(Watch {
+            steps: steps.into(),
+            running: true,
+            shown: Instant::now(),
+        });
+        self.show_watched();
+    }
+
+    /**
+     * Describes the next step of the watched solver until it is played.
+     */
+    fn show_watched(&mut self) {
+        let watch = self.watch.as_mut().unwrap();
+        watch.shown = Instant::now();
+        let description = watch.steps[0].describe(self.game.as_ref().unwrap());
+        self.show_lasting_message(description, true);
+    }
+
+    /**
+     * Plays the shown step of the watched solver, counted as a hint, then shows the next one.
+     * Watching stops once the steps are over, the human techniques having solved the grid or
+     * not being enough to go further.
+     */
+    fn play_watched(&mut self) {
+        let step = self.watch.as_mut().unwrap().steps.pop_front().unwrap();
+        let game = self.game.as_mut().unwrap();
+        game.count_hint(step.describe(game));
+
+        let played = match step.placed {
+            Some((index, value)) => {
+                let (r, c) = game.coordinates(index);
+                let played = game.do_move(r, c, value, self.validation);
+
+                // The value can't be noted anymore in the cells it sees
+                game.remove_neighbor_notes(r, c, value);
+                played
+            }
+            None => step
+                .eliminated
+                .iter()
+                .filter(|(index, value)| game.grid[*index].has_note(*value))
+                .map(|(index, value)| (game.coordinates(*index), *value))
+                .collect::<Vec<_>>()
+                .into_iter()
+                .try_for_each(|((r, c), value)| game.toggle_note(r, c, value).map(|_| ())),
+        };
+
+        if let Err(e) = played {
+            self.watch = None;
+            self.show_message(e.to_string(), false);
+        } else if self.watch.as_ref().unwrap().steps.is_empty() {
+            self.watch = None;
+            self.check_ended();
+            if !self.is_ended() {
+                self.show_lasting_message(
+                    String::from("The human techniques are not enough to go further."),
+                    false,
+                );
+            }
+        } else {
+            self.show_watched();
+        }
+    }
+
+    /**
+     * Plays the shown step of the watched solver once its delay is over, unless it is stepped
+     * through with the space key or the game is paused. Returns whether a step was played.
+     */
+    pub fn watch_tick(&mut self) -> bool {
+        let due = self
+            .watch
+            .as_ref()
+            .is_some_and(|watch| watch.running && watch.shown.elapsed() >= self.watch_delay);
+        if due && !self.paused {
+            self.play_watched();
+        }
+
+        due && !self.paused
+    }
+
+    /**
+     * Halves or doubles the delay between the steps of the watched solver, which plays them by
+     * itself again.
+     */
+    fn adjust_watch_speed(&mut self, faster: bool) {
+        self.watch_delay = match faster {
+            true => self.watch_delay / 2,
+            false => self.watch_delay * 2,
+        }
+        .clamp(WATCH_FASTEST, WATCH_SLOWEST);
+        self.watch.as_mut().unwrap().running = true;
+        self.show_message(
+            format!("One step every {:.2} s.", self.watch_delay.as_secs_f32()),
+            true,
+        );
+    }
+
+    /**
+     * Plays back the solved game from its first move on the grid, which is put back once the
+     * replay is left.
+     */
+    pub fn start_replay(&mut self) -> Result<(), GameError> {
+        let game = self.game.as_mut().unwrap();
+        let replay = Replay::new(game)?;
+        let grid = std::mem::replace(&mut game.grid, replay.grid.clone());
+        game.selected_index = None;
+        game.selected_value = None;
+        self.hint = None;
+        self.selection.clear();
+        self.replay = Some(ReplayView {
+            replay,
+            grid,
+            running: true,
+            shown: Instant::now(),
+            speed: 1,
+        });
+        self.show_replayed();
+
+        Ok(())
+    }
+
+    /**
+     * Leaves the replay, putting back the grid of the solved game.
+     */
+    fn stop_replay(&mut self) {
+        if let Some(view) = self.replay.take() {
+            self.game.as_mut().unwrap().grid = view.grid;
+        }
+        self.message = None;
+    }
+
+    /**
+     * Plays back the next move of the replay, or takes back the last one, selecting its cell.
+     */
+    fn step_replay(&mut self, forward: bool) {
+        let view = self.replay.as_mut().unwrap();
+        let played = match forward {
+            true => view.replay.step(),
+            false => view.replay.step_back(),
+        }
+        .map(|played| played.index);
+        view.shown = Instant::now();
+        if view.replay.is_over() {
+            view.running = false;
+        }
+
+        let game = self.game.as_mut().unwrap();
+        game.grid.clone_from(&view.replay.grid);
+        if played.is_some() {
+            game.selected_index = played;
+        }
+        self.show_replayed();
+    }
+
+    /**
+     * Describes the last move played back, and the keys of the replay once it is over.
+     */
+    fn show_replayed(&mut self) {
+        let replay = &self.replay.as_ref().unwrap().replay;
+        let mut description = replay.describe();
+        if replay.is_over() {
+            description.push_str(" Left: back, M: menu.");
+        }
+        self.show_lasting_message(description, true);
+    }
+
+    /**
+     * Returns how long until the next move of the running replay is played back.
+     */
+    fn replay_delay(&self) -> Option<Duration> {
+        let view = self.replay.as_ref().filter(|view| view.running)?;
+        let pace = view.replay.next_delay()?;
+
+        Some(pace.clamp(REPLAY_FASTEST, REPLAY_SLOWEST) / view.speed)
+    }
+
+    /**
+     * Plays back the next move of the running replay once its delay is over. Returns whether a
+     * move was played back.
+     */
+    pub fn replay_tick(&mut self) -> bool {
+        let due = self.replay_delay().is_some_and(|delay| {
+            let view = self.replay.as_ref().unwrap();
+            view.shown.elapsed() >= delay
+        });
+        if due {
+            self.step_replay(true);
+        }
+
+        due
+    }
+
+    /**
+     * Draws the hint, pause, export and menu buttons, then the play time next to them.
+     */
+    fn draw_buttons(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        let pause_label = match self.paused {
+            true => "Resume (P)",
+            false => "Pause (P)",
+        };
+        for (button, text) in [
+            (self.hint_button(), "Hint (H)"),
+            (self.pause_button(), pause_label),
+            (self.export_button(), "Export (E)"),
+            (self.menu_button(), "Menu (M)"),
+        ] {
+            canvas.set_draw_color(self.theme.entered_cell);
+            canvas.fill_rect(button).map_err(UiError::sdl2)?;
+            canvas.set_draw_color(self.theme.lines);
+            canvas.draw_rect(button).map_err(UiError::sdl2)?;
+            let label = Rect::new(
+                button.x() + LOG_PADDING,
+                button.y() + LOG_PADDING / 2,
+                (button.width() as i32 - LOG_PADDING).max(1) as u32,
+                button.height(),
+            );
+            self.draw_text(canvas, text, self.theme.text, label)?;
+        }
+
+        self.drawn_seconds = self.elapsed().as_secs();
+        let menu = self.menu_button();
+        let clock = Rect::new(
+            menu.right() + LOG_PADDING,
+            menu.y() + LOG_PADDING / 2,
+            HINT_BUTTON_SIZE.0,
+            menu.height(),
+        );
+        let text = format!(
+            "{:02}:{:02}",
+            self.drawn_seconds / 60,
+            self.drawn_seconds % 60
+        );
+        self.draw_text(canvas, &text, self.theme.text, clock)?;
+
+        Ok(())
+    }
+
+    /**
+     * Pauses the current game, hiding its grid and stopping its timer, or resumes it.
+     */
+    fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        let timer = &mut self.game.as_mut().unwrap().timer;
+        match self.paused {
+            true => timer.pause(),
+            false => timer.resume(),
+        }
+    }
+
+    /**
+     * Returns the area of the pause button, next to the hint button.
+     */
+    fn pause_button(&self) -> Rect {
+        let hint = self.hint_button();
+        let panel = self.layout.panel;
+        let x = hint.right() + LOG_PADDING;
+        Rect::new(
+            x,
+            panel.y(),
+            HINT_BUTTON_SIZE.0.min((panel.right() - x).max(1) as u32),
+            hint.height(),
+        )
+    }
+
+    /**
+     * Returns the area of the export button, next to the pause button.
+     */
+    fn export_button(&self) -> Rect {
+        let pause = self.pause_button();
+        let panel = self.layout.panel;
+        let x = pause.right() + LOG_PADDING;
+        Rect::new(
+            x,
+            panel.y(),
+            HINT_BUTTON_SIZE.0.min((panel.right() - x).max(1) as u32),
+            pause.height(),
+        )
+    }
+
+    /**
+     * Returns where the grid is exported, next to its save, if it has one.
+     */
+    fn export_path(&self) -> Option<String> {
+        let game = self.game.as_ref().unwrap();
+        game.save_path
+            .as_ref()
+            .map(|p| p.with_extension("svg").to_string_lossy().to_string())
+    }
+
+    /**
+     * Draws the grid as an SVG image next to its save, asking first whether to overwrite the one
+     * exported before.
+     */
+    fn export(&mut self) {
+        let Some(path) = self.export_path() else {
+            self.show_message(
+                String::from("This game has no save to export next to."),
+                false,
+            );
+            return;
+        };
+        if Path::new(&path).exists() {
+            self.dialog = Some(Dialog::new("Overwrite the exported image?", ()));
+            return;
+        }
+        self.write_export(&path);
+    }
+
+    /**
+     * Draws the grid as an SVG image at `path`, telling where it was written.
+     */
+    fn write_export(&mut self, path: &str) {
+        let game = self.game.as_ref().unwrap();
+        match export::export(game, path, &ExportOptions::default()) {
+            Ok(()) => self.show_message(format!("Exported to {}.", path), true),
+            Err(e) => self.show_message(format!("Unable to export the grid: {}", e), false),
+        }
+    }
+
+    /**
+     * Copies the share code of the puzzle to the system clipboard, see `Game::to_share_code`.
+     */
+    fn copy_share_code(&mut self) {
+        let code = self.game.as_ref().unwrap().to_share_code();
+        let copied = match self.clipboard.as_ref() {
+            Some(clipboard) => clipboard.set_clipboard_text(&code),
+            None => Err(String::from("no clipboard")),
+        };
+        match copied {
+            Ok(()) => self.show_message(format!("Share code copied: {}", code), true),
+            Err(e) => self.show_message(format!("Unable to copy the share code: {}", e), false),
+        }
+    }
+
+    /**
+     * Returns the area of the menu button, next to the export button.
+     */
+    fn menu_button(&self) -> Rect {
+        let export = self.export_button();
+        let panel = self.layout.panel;
+        let x = export.right() + LOG_PADDING;
+        Rect::new(
+            x,
+            panel.y(),
+            HINT_BUTTON_SIZE.0.min((panel.right() - x).max(1) as u32),
+            export.height(),
+        )
+    }
+
+    /**
+     * Returns the area of the hint button, at the top of the companion panel.
+     */
+    fn hint_button(&self) -> Rect {
+        let panel = self.layout.panel;
+        Rect::new(
+            panel.x(),
+            panel.y(),
+            HINT_BUTTON_SIZE.0.min(panel.width()),
+            HINT_BUTTON_SIZE.1.min(panel.height()),
+        )
+    }
+
+    /**
+     * Returns the area of the tile of `value` in the digits panel, below the buttons. The tiles
+     * are laid out as a keypad in the side panel, and on a single row below the grid.
+     */
+    fn digit_tile(&self, value: u8) -> Rect {
+        let panel = self.layout.panel;
+        let (size, side_size) = (self.layout.group_size, self.layout.side_size);
+        let columns = match self.layout_config.placement {
+            GridPlacement::Centered => side_size,
+            GridPlacement::Anchored => size,
+        };
+        let tile = (panel.width() / columns.max(1) as u32).clamp(1, DIGIT_TILE_SIZE) as i32;
+        let top = self.hint_button().bottom() + LOG_PADDING;
+        let position = value as usize - 1;
+        Rect::new(
+            panel.x() + (position % columns) as i32 * tile,
+            top + (position / columns) as i32 * tile,
+            tile as u32,
+            tile as u32,
+        )
+    }
+
+    /**
+     * Returns the value whose tile is at the given window position, if any.
+     */
+    fn digit_at(&self, x: i32, y: i32) -> Option<u8> {
+        (1..=self.layout.side_size as u8)
+            .find(|value| self.digit_tile(*value).contains_point((x, y)))
+    }
+
+    /**
+     * Returns the bottom of the digits panel, the rest of the companion panel being left to the
+     * message.
+     */
+    fn digits_bottom(&self) -> i32 {
+        self.digit_tile(self.layout.side_size.max(1) as u8).bottom() + LOG_PADDING
+    }
+
+    /**
+     * Draws the digits panel: each value with how many of it are left to place, greyed out once
+     * all are placed, and outlined when it is the one selected for placement.
+     */
+    fn draw_digits(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        let game = self.game.as_ref().unwrap();
+        for (position, remaining) in game.remaining_counts().into_iter().enumerate() {
+            let value = position as u8 + 1;
+            let tile = self.digit_tile(value);
+            let (background, color) = match remaining {
+                0 => (COLOR_ELIMINATED, COLOR_DIGIT_DONE),
+                _ => (self.theme.entered_cell, self.theme.text),
+            };
+            canvas.set_draw_color(background);
+            canvas.fill_rect(tile).map_err(UiError::sdl2)?;
+            canvas.set_draw_color(match game.selected_value == Some(value) {
+                true => self.theme.highlight,
+                false => self.theme.background,
+            });
+            canvas.draw_rect(tile).map_err(UiError::sdl2)?;
+
+            let center = tile.center();
+            let quarter = tile.height() as i32 / 4;
+            let symbol = self.notes_glyphs.get(value, color)?;
+            let query = symbol.query();
+            canvas
+                .copy(
+                    symbol,
+                    None,
+                    Rect::from_center(
+                        (center.x(), center.y() - quarter / 2),
+                        query.width,
+                        query.height,
+                    ),
+                )
+                .map_err(UiError::sdl2)?;
+            if remaining > 0 {
+                let count = remaining.to_string();
+                self.draw_label(
+                    canvas,
+                    &count,
+                    COLOR_DIGIT_COUNT,
+                    center.x(),
+                    center.y() + quarter,
+                    true,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Ends the current game by comparing its grid to the solution.
+     */
+    fn compare_to_solution(&mut self) {
+        let game = self.game.as_ref().unwrap();
+        match logging::timed("solution", || game.diff()) {
+            Ok(diff) => {
+                self.diff = Some(diff);
+                self.game.as_mut().unwrap().timer.pause();
+            }
+            Err(e) => self.show_message(e.to_string(), false),
+        }
+    }
+
+    /**
+     * Returns the message shown once the grid is completed: who did what in hot-seat games,
+     * and how to go on with the next puzzle.
+     */
+    fn completion_message(&self) -> String {
+        let game = self.game.as_ref().unwrap();
+        let mut message = match game.hot_seat.as_ref() {
+            Some(hot_seat) => hot_seat.summary() + "\n",
+            None if game.is_done() => String::from("Grid completed !\n"),
+            None => String::from("Not solved this time.\n"),
+        };
+        if self.diff.is_some() {
+            message.push_str("Green cells are correct, red ones wrong, grey ones were empty.\n");
+        }
+        message.push_str("Press N for the next puzzle.");
+
+        message
+    }
+
+    /**
+     * Returns a short status of the current game: its difficulty, or number of clues when it is
+     * unknown, elapsed time and completion percentage.
+     */
+    pub fn status(&self) -> Option<String> {
+        let game = self.game.as_ref()?;
+        let elapsed = game.timer.elapsed().as_secs();
+
+        let mut status = String::new();
+        if !self.parked.is_empty() {
+            status.push_str(&format!("Board 1/{} - ", self.parked.len() + 1));
+        }
+        // The clue count stands for the difficulty of the puzzles that were not generated
+        let difficulty = match game.difficulty {
+            Some(difficulty) => format!("{:?}", difficulty),
+            None => format!("{} clues", game.nb_clues()),
+        };
+        status.push_str(&format!(
+            "{} - {:02}:{:02} - {}%",
+            difficulty,
+            elapsed / 60,
+            elapsed % 60,
+            game.completion()
+        ));
+        if !game.guesses.is_empty() {
+            status.push_str(&format!(" - Guess depth {}", game.guesses.len()));
+        }
+        if game.auto_notes {
+            status.push_str(" - Auto notes");
+        }
+        if let Some(hot_seat) = game.hot_seat.as_ref() {
+            status.push_str(&format!(" - Player {}'s turn", hot_seat.current + 1));
+        }
+        if let Some((filled, cells)) = self.opponent {
+            status.push_str(&format!(" - Other player {}/{}", filled, cells));
+        }
+
+        Some(status)
+    }
+
+    /**
+     * Returns the (row, column) of the grid's cell located at the given window position, if any.
+     */
+    fn cell_at(&self, x: i32, y: i32) -> Option<(usize, usize)> {
+        self.layout.cell_at(x, y)
+    }
+
+    /**
+     * Draws `text` with the screen's font, wrapped to the width of `area` and clipped to it.
+     * Returns the height of the drawn text.
+     */
+    fn draw_text(
+        &self,
+        canvas: &mut Canvas<Window>,
+        text: &str,
+        color: Color,
+        area: Rect,
+    ) -> Result<u32, UiError> {
+        let texture_creator = canvas.texture_creator();
+        let surface = self
+            .font
+            .as_ref()
+            .unwrap()
+            .render(text)
+            .blended_wrapped(color, area.width())
+            .map_err(UiError::sdl2)?;
+        let mut texture = texture_creator
+            .create_texture_from_surface(surface)
+            .map_err(UiError::sdl2)?;
+        texture.set_alpha_mod(color.a);
+
+        // Only copy the part of the text that fits in the area
+        let width = texture.query().width.min(area.width());
+        let height = texture.query().height.min(area.height());
+        canvas
+            .copy(
+                &texture,
+                Rect::new(0, 0, width, height),
+                Rect::new(area.x(), area.y(), width, height),
+            )
+            .map_err(UiError::sdl2)?;
+
+        Ok(height)
+    }
+
+    /**
+     * Draws the message, if any, in a bar of the companion panel below the digits, fading it
+     * out once shown for `MESSAGE_DURATION`.
+     */
+    fn draw_message(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        let alpha = self.message_alpha();
+        if alpha == 0 {
+            self.message = None;
+        }
+        let Some(message) = self.message.as_ref() else {
+            return Ok(());
+        };
+
+        let panel = self.layout.panel;
+        let offset = (self.digits_bottom() - panel.y()).max(0) as u32;
+        let area = Rect::new(
+            panel.x(),
+            panel.y() + offset as i32,
+            panel.width(),
+            panel.height().saturating_sub(offset).max(1),
+        );
+        let mut color = match self.message_good {
+            true => COLOR_GOOD_MSG,
+            false => COLOR_BAD_MSG,
+        };
+        color.a = alpha;
+        let text_area = Rect::new(
+            area.x() + LOG_PADDING,
+            area.y() + LOG_PADDING,
+            area.width().saturating_sub(2 * LOG_PADDING as u32).max(1),
+            area.height().saturating_sub(2 * LOG_PADDING as u32).max(1),
+        );
+
+        // The bar only takes the height of the text
+        let surface = self
+            .font
+            .as_ref()
+            .unwrap()
+            .render(message)
+            .blended_wrapped(color, text_area.width())
+            .map_err(UiError::sdl2)?;
+        let height = (surface.height() + 2 * LOG_PADDING as u32).min(area.height());
+        let mut bar_color = COLOR_MESSAGE_BAR;
+        bar_color.a = (bar_color.a as u32 * alpha as u32 / 255) as u8;
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(bar_color);
+        canvas
+            .fill_rect(Rect::new(area.x(), area.y(), area.width(), height))
+            .map_err(UiError::sdl2)?;
+        canvas.set_blend_mode(BlendMode::None);
+        self.draw_text(canvas, message, color, text_area)?;
+
+        Ok(())
+    }
+
+    /**
+     * Draws the notes of the cell at row `r` and column `c`, each value having its own spot in
+     * a `size` x `size` grid inside the cell.
+     */
+    fn draw_notes(&self, canvas: &mut Canvas<Window>, r: usize, c: usize) -> Result<(), UiError> {
+        let game = self.game.as_ref().unwrap();
+        let cell = &game.grid[game.index(r, c)];
+        if cell.notes() == 0 {
+            return Ok(());
+        }
+
+        let area = self.layout.cell_rect(r, c);
+        let spot_size = self.layout.box_size / game.size as i32;
+
+        // The notes the watched solver step places or removes stand out
+        let step = self.watch.as_ref().map(|watch| &watch.steps[0]);
+        let index = game.index(r, c);
+        for value in (1..=game.side_size as u8).filter(|v| cell.has_note(*v)) {
+            let color = match step {
+                Some(step) if step.placed == Some((index, value)) => COLOR_WATCH_PLACED,
+                Some(step) if step.eliminated.contains(&(index, value)) => COLOR_WATCH_REMOVED,
+                _ => self.theme.text,
+            };
+            let tex_note = self.notes_glyphs.get(value, color)?;
+
+            // Centering the note in its spot, shrinking it on the big grids
+            let spot = (value - 1) as i32;
+            let spot_area = Rect::new(
+                area.x() + (spot % game.size as i32) * spot_size + 1,
+                area.y() + (spot / game.size as i32) * spot_size + 1,
+                spot_size.max(1) as u32,
+                spot_size.max(1) as u32,
+            );
+            // The note of the highlighted value stands out like the cells holding it
+            if game.selected_value == Some(value) {
+                canvas.set_draw_color(self.theme.highlight);
+                canvas.fill_rect(spot_area).map_err(UiError::sdl2)?;
+            }
+            let query = tex_note.query();
+            canvas
+                .copy(
+                    tex_note,
+                    None,
+                    fit_centered(query.width, query.height, spot_area),
+                )
+                .map_err(UiError::sdl2)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Draws `text` with the notes font, at (`x`, `y`) or centered on it if `centered` is set.
+     */
+    fn draw_label(
+        &self,
+        canvas: &mut Canvas<Window>,
+        text: &str,
+        color: Color,
+        x: i32,
+        y: i32,
+        centered: bool,
+    ) -> Result<(), UiError> {
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        let texture_creator = canvas.texture_creator();
+        let surface = self
+            .notes_font
+            .as_ref()
+            .unwrap()
+            .render(text)
+            .blended(color)
+            .map_err(UiError::sdl2)?;
+        let texture = texture_creator
+            .create_texture_from_surface(surface)
+            .map_err(UiError::sdl2)?;
+
+        let (width, height) = (texture.query().width, texture.query().height);
+        let (x, y) = match centered {
+            true => (x - width as i32 / 2, y - height as i32 / 2),
+            false => (x, y),
+        };
+        canvas
+            .copy(&texture, None, Rect::new(x, y, width, height))
+            .map_err(UiError::sdl2)
+    }
+
+    /**
+     * Flashes the cells a refused move conflicts with, fading out over `FLASH_DURATION`, and
+     * forgets them once it is over.
+     */
+    fn draw_flash(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        let Some((cells, start)) = self.flash.as_ref() else {
+            return Ok(());
+        };
+        let elapsed = start.elapsed();
+        if elapsed >= FLASH_DURATION {
+            self.flash = None;
+            return Ok(());
+        }
+
+        let left = 1.0 - elapsed.as_secs_f32() / FLASH_DURATION.as_secs_f32();
+        let side = self.game.as_ref().unwrap().side_size;
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(Color::RGBA(
+            COLOR_FLASH.r,
+            COLOR_FLASH.g,
+            COLOR_FLASH.b,
+            (COLOR_FLASH.a as f32 * left) as u8,
+        ));
+        for index in cells.iter() {
+            canvas
+                .fill_rect(self.layout.cell_rect(index / side, index % side))
+                .map_err(UiError::sdl2)?;
+        }
+        canvas.set_blend_mode(BlendMode::None);
+
+        Ok(())
+    }
+
+    /**
+     * Shades every cell the player filled or left empty depending on how it compares to the
+     * solution, writing the expected value in the wrong and empty ones.
+     */
+    fn draw_diff(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        let game = self.game.as_ref().unwrap();
+        let diff = match self.diff.as_ref() {
+            Some(diff) => diff,
+            None => return Ok(()),
+        };
+
+        canvas.set_blend_mode(BlendMode::Blend);
+        for (index, cell_diff) in diff.iter().enumerate() {
+            if game.grid[index].initial() {
+                continue;
+            }
+            let cell = self
+                .layout
+                .cell_rect(index / game.side_size, index % game.side_size);
+            canvas.set_draw_color(match cell_diff {
+                CellDiff::Correct => COLOR_DIFF_CORRECT,
+                CellDiff::Wrong(_) => COLOR_DIFF_WRONG,
+                CellDiff::Empty(_) => COLOR_DIFF_EMPTY,
+            });
+            canvas.fill_rect(cell).map_err(UiError::sdl2)?;
+
+            match cell_diff {
+                CellDiff::Correct => {}
+                CellDiff::Wrong(expected) => self.draw_label(
+                    canvas,
+                    &expected.to_string(),
+                    self.theme.text,
+                    cell.x() + 3,
+                    cell.y() + 1,
+                    false,
+                )?,
+                CellDiff::Empty(expected) => {
+                    let center = cell.center();
+                    self.draw_label(
+                        canvas,
+                        &expected.to_string(),
+                        self.theme.text,
+                        center.x(),
+                        center.y(),
+                        true,
+                    )?
+                }
+            }
+        }
+        canvas.set_blend_mode(BlendMode::None);
 
-    message: Option<String>,
-}
+        Ok(())
+    }
 
-impl<'a> GameScreen<'a> {
     /**
-     * Returns wether or not the screen contains an undone game.
+     * Fills the cells colored by the annotations of the puzzle, under everything else.
      */
-    pub fn is_over(&self) -> bool {
-        self.game.is_some() && self.game.as_ref().unwrap().is_done()
+    fn draw_cell_colors(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        let game = self.game.as_ref().unwrap();
+        let annotations = match game.annotations.as_ref() {
+            Some(annotations) => annotations,
+            None => return Ok(()),
+        };
+
+        canvas.set_blend_mode(BlendMode::Blend);
+        for (index, color) in annotations.colors.iter() {
+            if let Some(color) = parse_color(color) {
+                canvas.set_draw_color(color);
+                canvas
+                    .fill_rect(
+                        self.layout
+                            .cell_rect(index / game.side_size, index % game.side_size),
+                    )
+                    .map_err(UiError::sdl2)?;
+            }
+        }
+        canvas.set_blend_mode(BlendMode::None);
+
+        Ok(())
+    }
+
+    /**
+     * Tints the cells the player painted with a marker color.
+     */
+    fn draw_markers(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        let game = self.game.as_ref().unwrap();
+        canvas.set_blend_mode(BlendMode::Blend);
+        for (index, cell) in game.grid.iter().enumerate() {
+            if cell.color() == 0 {
+                continue;
+            }
+            let (red, green, blue) = MARKER_COLORS[cell.color() as usize - 1];
+            let (r, c) = game.coordinates(index);
+            canvas.set_draw_color(Color::RGBA(red, green, blue, MARKER_ALPHA));
+            canvas
+                .fill_rect(self.layout.cell_rect(r, c))
+                .map_err(UiError::sdl2)?;
+        }
+        canvas.set_blend_mode(BlendMode::None);
+
+        Ok(())
+    }
+
+    /**
+     * Draws the borders of the jigsaw regions of the grid as thick lines, the sides of the cells
+     * between two regions.
+     */
+    fn draw_regions(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        let game = self.game.as_ref().unwrap();
+        let (box_size, thick) = (self.layout.box_size, self.layout.thick_line);
+        canvas.set_draw_color(self.theme.lines);
+        for r in 0..game.side_size {
+            for c in 0..game.side_size {
+                let (top, left) = game.borders(r, c);
+                let (x, y) = (
+                    self.layout.grid_x + c as i32 * box_size,
+                    self.layout.grid_y + r as i32 * box_size,
+                );
+                if top {
+                    let side = Rect::new(x, y, box_size as u32 + thick, thick);
+                    canvas.fill_rect(side).map_err(UiError::sdl2)?;
+                }
+                if left {
+                    let side = Rect::new(x, y, thick, box_size as u32 + thick);
+                    canvas.fill_rect(side).map_err(UiError::sdl2)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Shades the cells of the jigsaw regions of the grid, neighboring regions never sharing
+     * their color.
+     */
+    fn draw_region_colors(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        let game = self.game.as_ref().unwrap();
+        if !game.is_jigsaw() {
+            return Ok(());
+        }
+
+        let colors = game.region_colors();
+        for (index, region) in game.regions().iter().enumerate() {
+            canvas.set_draw_color(COLOR_REGIONS[colors[*region] % COLOR_REGIONS.len()]);
+            canvas
+                .fill_rect(
+                    self.layout
+                        .cell_rect(index / game.side_size, index % game.side_size),
+                )
+                .map_err(UiError::sdl2)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Shades the cells of the windows of the grid, for the puzzles where they are units.
+     */
+    fn draw_windows(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        let game = self.game.as_ref().unwrap();
+
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(COLOR_WINDOWS);
+        for w in 0..game.windows_count() {
+            for index in game.window(w) {
+                canvas
+                    .fill_rect(
+                        self.layout
+                            .cell_rect(index / game.side_size, index % game.side_size),
+                    )
+                    .map_err(UiError::sdl2)?;
+            }
+        }
+        canvas.set_blend_mode(BlendMode::None);
+
+        Ok(())
+    }
+
+    /**
+     * Draws both diagonals of the grid across it, for the puzzles where they are units.
+     */
+    fn draw_diagonals(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        if self.game.as_ref().unwrap().variant != Variant::Diagonal {
+            return Ok(());
+        }
+
+        let (left, top) = (self.layout.grid_x, self.layout.grid_y);
+        let (right, bottom) = (
+            left + self.layout.grid_side(),
+            top + self.layout.grid_side(),
+        );
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(COLOR_DIAGONALS);
+        for (from, to) in [
+            ((left, top), (right, bottom)),
+            ((right, top), (left, bottom)),
+        ] {
+            canvas.draw_line(from, to).map_err(UiError::sdl2)?;
+        }
+        canvas.set_blend_mode(BlendMode::None);
+
+        Ok(())
+    }
+
+    /**
+     * Returns the sides outlining the cells `cells` of a grid of `side` rows, inside the cells and
+     * only where they border cells out of them.
+     */
+    fn cage_outline(&self, cells: &[usize], side: usize) -> Vec<((i32, i32), (i32, i32))> {
+        let inside = |r: Option<usize>, c: Option<usize>| match (r, c) {
+            (Some(r), Some(c)) if r < side && c < side => cells.contains(&(r * side + c)),
+            _ => false,
+        };
+
+        let mut sides = Vec::new();
+        for index in cells.iter() {
+            let (r, c) = (index / side, index % side);
+            let cell = self.layout.cell_rect(r, c);
+            let (left, top) = (cell.x() + CAGE_INSET, cell.y() + CAGE_INSET);
+            let (right, bottom) = (cell.right() - CAGE_INSET, cell.bottom() - CAGE_INSET);
+            if !inside(r.checked_sub(1), Some(c)) {
+                sides.push(((left, top), (right, top)));
+            }
+            if !inside(Some(r + 1), Some(c)) {
+                sides.push(((left, bottom), (right, bottom)));
+            }
+            if !inside(Some(r), c.checked_sub(1)) {
+                sides.push(((left, top), (left, bottom)));
+            }
+            if !inside(Some(r), Some(c + 1)) {
+                sides.push(((right, top), (right, bottom)));
+            }
+        }
+
+        sides
+    }
+
+    /**
+     * Writes `label` in the corner of the first of the cells `cells`.
+     */
+    fn draw_cage_label(
+        &self,
+        canvas: &mut Canvas<Window>,
+        cells: &[usize],
+        label: &str,
+        side: usize,
+    ) -> Result<(), UiError> {
+        match cells.iter().min() {
+            Some(first) => {
+                let cell = self.layout.cell_rect(first / side, first % side);
+                self.draw_label(
+                    canvas,
+                    label,
+                    self.theme.text,
+                    cell.x() + 2,
+                    cell.y() + 1,
+                    false,
+                )
+            }
+            None => Ok(()),
+        }
+    }
+
+    /**
+     * Draws the killer cages of the puzzle as dashed outlines inside their cells, with their sum
+     * in the corner of their first cell.
+     */
+    fn draw_cages(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        let game = self.game.as_ref().unwrap();
+        let side = game.side_size;
+
+        canvas.set_draw_color(COLOR_CAGE);
+        for cage in game.cages.iter() {
+            for ((x1, y1), (x2, y2)) in self.cage_outline(&cage.cells, side) {
+                // The sides are either horizontal or vertical
+                let length = (x2 - x1).abs().max((y2 - y1).abs());
+                for start in (0..length).step_by(2 * CAGE_DASH as usize) {
+                    let end = (start + CAGE_DASH).min(length);
+                    let at = |d: i32| (x1 + (x2 - x1).signum() * d, y1 + (y2 - y1).signum() * d);
+                    canvas
+                        .draw_line(at(start), at(end))
+                        .map_err(UiError::sdl2)?;
+                }
+            }
+            self.draw_cage_label(canvas, &cage.cells, &cage.sum.to_string(), side)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Draws the cages and texts of the annotations of the puzzle. A cage is outlined inside its
+     * cells, with its label in the corner of its first cell.
+     */
+    fn draw_annotations(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        let game = self.game.as_ref().unwrap();
+        let annotations = match game.annotations.as_ref() {
+            Some(annotations) => annotations,
+            None => return Ok(()),
+        };
+        let side = game.side_size;
+
+        for cage in annotations.cages.iter() {
+            canvas.set_draw_color(COLOR_CAGE);
+            for (from, to) in self.cage_outline(&cage.cells, side) {
+                canvas.draw_line(from, to).map_err(UiError::sdl2)?;
+            }
+            self.draw_cage_label(canvas, &cage.cells, &cage.label, side)?;
+        }
+
+        for text in annotations.texts.iter() {
+            if let Some(first) = text.cells.first() {
+                let cell = self.layout.cell_rect(first / side, first % side);
+                let center = cell.center();
+                self.draw_label(
+                    canvas,
+                    &text.label,
+                    self.theme.text,
+                    center.x(),
+                    center.y(),
+                    true,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Draws the game's events, most recent first, in a box covering the screen.
+     */
+    fn draw_log(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        let viewport = self.viewport.unwrap_or_else(|| canvas.viewport());
+        let margin = self.layout_config.margin as i32 / 2;
+        let area = Rect::new(
+            viewport.x() + margin,
+            viewport.y() + margin,
+            (viewport.width() as i32 - 2 * margin).max(1) as u32,
+            (viewport.height() as i32 - 2 * margin).max(1) as u32,
+        );
+
+        // Background and border of the log box
+        canvas.set_draw_color(self.theme.background);
+        canvas.fill_rect(area).map_err(UiError::sdl2)?;
+        canvas.set_draw_color(self.theme.lines);
+        canvas.draw_rect(area).map_err(UiError::sdl2)?;
+
+        // Write as many events as fit in the box
+        let mut y = area.y() + LOG_PADDING;
+        for event in self.game.as_ref().unwrap().events.iter().rev() {
+            let remaining = area.bottom() - LOG_PADDING - y;
+            if remaining <= 0 {
+                break;
+            }
+
+            let line = Rect::new(
+                area.x() + LOG_PADDING,
+                y,
+                (area.width() as i32 - 2 * LOG_PADDING).max(1) as u32,
+                remaining as u32,
+            );
+            y += self.draw_text(canvas, &event.to_string(), self.theme.text, line)? as i32;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Returns the notes cleared by pressing `keycode` along with Shift: a digit clears all its
+     * notes, R, C and B clear the notes of the selected cell's row, column and box, and I clears
+     * the notes invalidated by the placed values.
+     */
+    fn notes_clear_for_key(&self, keycode: Keycode) -> Option<NotesClear> {
+        if let Some(digit) = digit_from_keycode(keycode) {
+            return Some(NotesClear::Digit(digit));
+        }
+
+        let game = self.game.as_ref().unwrap();
+        let selected = game.selected_index.map(|i| game.coordinates(i));
+        match (keycode, selected) {
+            (Keycode::I, _) => Some(NotesClear::Invalid),
+            (Keycode::R, Some((r, _))) => Some(NotesClear::Row(r)),
+            (Keycode::C, Some((_, c))) => Some(NotesClear::Column(c)),
+            (Keycode::B, Some((r, c))) => Some(NotesClear::Group(game.regions()[game.index(r, c)])),
+            _ => None,
+        }
+    }
+
+    /**
+     * Returns the value typed with the letter key `keycode`, when a cell is selected on a grid
+     * bigger than 9x9, see `letter_value_from_keycode`.
+     */
+    fn letter_value(&self, keycode: Keycode) -> Option<u8> {
+        let game = self.game.as_ref()?;
+        game.selected_index?;
+        letter_value_from_keycode(keycode, game.side_size)
+    }
+
+    /**
+     * Places `value` in the selected cell, unless the game was compared to its solution. When
+     * several cells are selected, the note of `value` is toggled in all of them instead.
+     */
+    fn enter_value(&mut self, value: u8) -> ScreenOutcome {
+        if self.selection.len() > 1 {
+            return self.toggle_selection_note(value);
+        }
+
+        let game = self.game.as_mut().unwrap();
+        let (Some(index), None) = (game.selected_index, self.diff.as_ref()) else {
+            return ScreenOutcome::Unchanged;
+        };
+
+        let (r, c) = game.coordinates(index);
+        match game.do_move(r, c, value, self.validation) {
+            Ok(_) => {
+                game.selected_value = Some(value);
+                self.check_ended();
+            }
+            Err(e) => self.refuse_move(index, value, e),
+        }
+
+        ScreenOutcome::Updated
+    }
+
+    /**
+     * Adds the note of `value` to the empty selected cells, or removes it if they all have it.
+     */
+    fn toggle_selection_note(&mut self, value: u8) -> ScreenOutcome {
+        if self.diff.is_some() {
+            return ScreenOutcome::Unchanged;
+        }
+        let game = self.game.as_mut().unwrap();
+        if let Err(e) = game.toggle_notes(&self.selection, value) {
+            self.show_message(e.to_string(), false);
+        }
+
+        ScreenOutcome::Updated
+    }
+
+    /**
+     * Places `value` in every selected cell, the first refused move being reported.
+     */
+    fn enter_selection_value(&mut self, value: u8) -> ScreenOutcome {
+        if self.diff.is_some() {
+            return ScreenOutcome::Unchanged;
+        }
+        let game = self.game.as_mut().unwrap();
+        let mut refused = None;
+        for index in self.selection.iter().copied() {
+            let (r, c) = game.coordinates(index);
+            if let Err(e) = game.do_move(r, c, value, self.validation) {
+                refused.get_or_insert((index, e));
+            }
+        }
+        game.selected_value = Some(value);
+        match refused {
+            Some((index, e)) => self.refuse_move(index, value, e),
+            None => self.check_ended(),
+        }
+
+        ScreenOutcome::Updated
+    }
+
+    /**
+     * Adds the cell at `index` to the selection, or removes it if it is selected already, the
+     * selected cell of the game joining the selection first.
+     */
+    fn toggle_selected(&mut self, index: usize) {
+        let game = self.game.as_mut().unwrap();
+        if self.selection.is_empty() {
+            self.selection.extend(game.selected_index);
+        }
+        match self.selection.iter().position(|i| *i == index) {
+            Some(position) => {
+                self.selection.remove(position);
+                game.selected_index = self.selection.last().copied();
+            }
+            None => {
+                self.selection.push(index);
+                game.selected_index = Some(index);
+            }
+        }
+        if self.selection.len() < 2 {
+            self.selection.clear();
+        }
+    }
+
+    /**
+     * Shows `message` under the grid for `MESSAGE_DURATION`, in green when it is `good` news and
+     * in red otherwise.
+     */
+    fn show_message(&mut self, message: String, good: bool) {
+        self.message = Some(message);
+        self.message_good = good;
+        self.message_shown = Some(Instant::now());
+    }
+
+    /**
+     * Tells whether the puzzle was `toggled` into the favorites or out of them, or why the
+     * favorites could not be saved.
+     */
+    pub fn show_favorite(&mut self, toggled: Result<bool, UiError>) {
+        match toggled {
+            Ok(true) => self.show_message(String::from("Added to the favorites."), true),
+            Ok(false) => self.show_message(String::from("Removed from the favorites."), true),
+            Err(e) => self.show_message(e.to_string(), false),
+        }
+    }
+
+    /**
+     * Shows `message` like `show_message`, until it is replaced.
+     */
+    fn show_lasting_message(&mut self, message: String, good: bool) {
+        self.show_message(message, good);
+        self.message_shown = None;
+    }
+
+    /**
+     * Draws the progress of the other player of a network versus game as a bar above the grid,
+     * when the margin leaves room for it.
+     */
+    fn draw_opponent(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        let Some((filled, cells)) = self.opponent else {
+            return Ok(());
+        };
+        let top = self.layout.grid_y - 2 * OPPONENT_BAR_HEIGHT as i32;
+        if top < 0 || cells == 0 {
+            return Ok(());
+        }
+
+        let width = self.layout.grid_side() as u32;
+        canvas.set_draw_color(COLOR_OPPONENT_LEFT);
+        canvas
+            .fill_rect(Rect::new(
+                self.layout.grid_x,
+                top,
+                width,
+                OPPONENT_BAR_HEIGHT,
+            ))
+            .map_err(UiError::sdl2)?;
+        let done = (width as usize * filled / cells) as u32;
+        if done > 0 {
+            canvas.set_draw_color(COLOR_OPPONENT);
+            canvas
+                .fill_rect(Rect::new(
+                    self.layout.grid_x,
+                    top,
+                    done,
+                    OPPONENT_BAR_HEIGHT,
+                ))
+                .map_err(UiError::sdl2)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Shows `message` under the grid until it is replaced, such as the progress of a time
+     * attack.
+     */
+    pub fn announce(&mut self, message: String) {
+        self.show_lasting_message(message, true);
+    }
+
+    /**
+     * Returns the opacity of the message, fading out at the end of `MESSAGE_DURATION`.
+     */
+    fn message_alpha(&self) -> u8 {
+        let left = self.message_shown.map_or(MESSAGE_FADE, |shown| {
+            MESSAGE_DURATION.saturating_sub(shown.elapsed())
+        });
+        (255.0 * (left.as_secs_f32() / MESSAGE_FADE.as_secs_f32()).min(1.0)) as u8
+    }
+
+    /**
+     * Shows why placing `value` in the cell at `index` was refused, flashing the cells it
+     * conflicts with, or the cell itself when it is wrong for another reason.
+     */
+    fn refuse_move(&mut self, index: usize, value: u8, error: GameError) {
+        self.show_message(error.to_string(), false);
+
+        let mut cells = self.game.as_ref().unwrap().conflicting_cells(index, value);
+        if cells.is_empty() {
+            cells.push(index);
+        }
+        self.flash = Some((cells, Instant::now()));
+    }
+
+    /**
+     * Returns whether refused cells are flashing or the message is fading out, the screen having
+     * to be drawn again until they are over.
+     */
+    pub fn is_animating(&self) -> bool {
+        let fading = self
+            .message_shown
+            .is_some_and(|shown| shown.elapsed() + MESSAGE_FADE >= MESSAGE_DURATION);
+        self.flash.is_some() || (self.message.is_some() && fading)
+    }
+
+    /**
+     * Returns how long until the screen changes by itself, if it does: right away while it
+     * animates, otherwise when the next message starts fading out, the clock ticks or the
+     * watched solver plays its next step.
+     */
+    pub fn next_redraw(&self) -> Option<Duration> {
+        if self.is_animating() {
+            return Some(Duration::ZERO);
+        }
+
+        let fade = self
+            .message
+            .as_ref()
+            .and(self.message_shown)
+            .map(|shown| (MESSAGE_DURATION - MESSAGE_FADE).saturating_sub(shown.elapsed()));
+        let tick = self
+            .game
+            .as_ref()
+            .filter(|game| game.timer.is_running())
+            .map(|game| {
+                Duration::from_secs(1)
+                    - Duration::from_nanos(game.timer.elapsed().subsec_nanos() as u64)
+            });
+
+        let step = self
+            .watch
+            .as_ref()
+            .filter(|watch| watch.running && !self.paused)
+            .map(|watch| self.watch_delay.saturating_sub(watch.shown.elapsed()));
+        let replayed = self.replay_delay().map(|delay| {
+            let view = self.replay.as_ref().unwrap();
+            delay.saturating_sub(view.shown.elapsed())
+        });
+
+        [fade, tick, step, replayed].into_iter().flatten().min()
+    }
+
+    /**
+     * Recomputes the grid geometry for the current game and canvas.
+     */
+    fn update_layout(&mut self) {
+        if let (Some(game), Some(viewport)) = (self.game.as_ref(), self.viewport) {
+            self.layout = Layout::new(&self.layout_config, viewport, game.size);
+        }
+    }
+
+    /**
+     * Cycles the value of the cell at `index` through its valid candidates and keeps the
+     * highlighting in sync if that cell is the selected one.
+     */
+    fn cycle_cell(&mut self, index: usize, forward: bool) -> ScreenOutcome {
+        if self.diff.is_some() {
+            return ScreenOutcome::Unchanged;
+        }
+        let game = self.game.as_mut().unwrap();
+        let (r, c) = game.coordinates(index);
+
+        match game.cycle_value(r, c, forward) {
+            Ok(value) => {
+                if game.selected_index == Some(index) {
+                    game.selected_value = Some(value);
+                }
+                self.check_ended();
+            }
+            Err(e) => self.show_message(e.to_string(), false),
+        }
+
+        ScreenOutcome::Updated
     }
 }
 
@@ -49,39 +1761,179 @@ impl<'a> Displayable for GameScreen<'a> {
      */
     fn new() -> Self {
         GameScreen {
+            notes_glyphs: GlyphCache::blended(),
             ..Default::default()
         }
     }
 
     /**
-     * If the configuration file contains the path of a game to load and resume.
+     * Applies the settings of the configuration file, the game is attached later with `set_game`.
      */
     fn init(
         &mut self,
-        _canvas: &mut Canvas<sdl2::video::Window>,
+        canvas: &mut Canvas<sdl2::video::Window>,
         config: &GUIConfig,
     ) -> Result<(), UiError> {
-        if !config.game_resume_path.is_empty() {
-            self.game = Some(Game::from_file(&config.game_resume_path)?);
-        }
+        self.layout_config = config.layout.clone();
+        self.validation = config.validation_mode;
+        self.input_mode = config.input_mode;
+        self.mouse = config.mouse;
+        self.watch_delay = Duration::from_millis(config.watch_step_ms);
+        self.theme = Theme::from_config(&config.theme)?;
+        self.other_theme = Theme::preset(match config.theme.preset {
+            ThemePreset::Dark => ThemePreset::Light,
+            ThemePreset::Light => ThemePreset::Dark,
+        });
+        self.viewport = Some(canvas.viewport());
+        self.clipboard = Some(canvas.window().subsystem().clipboard());
+        self.keyboard = Some(canvas.window().subsystem().sdl().keyboard());
 
         Ok(())
     }
 
     fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
         // Reset screen with background color
-        canvas.set_draw_color(COLOR_BCK);
+        canvas.set_draw_color(self.theme.background);
         canvas.clear();
 
+        // Hide the grid while the game is paused
+        if self.paused {
+            self.draw_buttons(canvas)?;
+            let grid = Rect::new(
+                self.layout.grid_x,
+                self.layout.grid_y,
+                self.layout.grid_side() as u32,
+                self.layout.grid_side() as u32,
+            );
+            self.draw_text(canvas, "Paused, press P to resume.", self.theme.text, grid)?;
+            return Ok(());
+        }
+
+        // The symbols are rendered once per font and color, then reused by every draw
+        let side_size = self.game.as_ref().unwrap().side_size;
+        for color in [self.theme.initial, self.theme.entered] {
+            self.glyphs
+                .prepare(canvas, self.font.as_ref().unwrap(), side_size, color)?;
+        }
+        for color in [
+            self.theme.text,
+            COLOR_DIGIT_DONE,
+            COLOR_WATCH_PLACED,
+            COLOR_WATCH_REMOVED,
+        ] {
+            self.notes_glyphs.prepare(
+                canvas,
+                self.notes_font.as_ref().unwrap(),
+                side_size,
+                color,
+            )?;
+        }
+
+        self.draw_region_colors(canvas)?;
+        self.draw_windows(canvas)?;
+        self.draw_cell_colors(canvas)?;
+        self.draw_markers(canvas)?;
+
+        // Shading the selected cells when there are several, otherwise the row, column and box
+        // of the selected cell
+        let game = self.game.as_ref().unwrap();
+        if self.selection.len() > 1 {
+            canvas.set_blend_mode(BlendMode::Blend);
+            canvas.set_draw_color(COLOR_SELECTION);
+            for index in self.selection.iter() {
+                let (r, c) = game.coordinates(*index);
+                canvas
+                    .fill_rect(self.layout.cell_rect(r, c))
+                    .map_err(UiError::sdl2)?;
+            }
+            canvas.set_blend_mode(BlendMode::None);
+        } else if let Some(index) = game.selected_index {
+            let (r, c) = game.coordinates(index);
+            canvas.set_blend_mode(BlendMode::Blend);
+            canvas.set_draw_color(COLOR_UNITS);
+            let mut units: Vec<usize> = game.neighbors(r, c).collect();
+            units.sort();
+            units.dedup();
+            for unit in units {
+                let (r, c) = game.coordinates(unit);
+                canvas
+                    .fill_rect(self.layout.cell_rect(r, c))
+                    .map_err(UiError::sdl2)?;
+            }
+            canvas.set_blend_mode(BlendMode::None);
+        }
+
+        // Shading the cells of the shown hint, the ones it acts on more strongly
+        if let Some(hint) = self.hint.as_ref() {
+            let side = self.game.as_ref().unwrap().side_size;
+            canvas.set_blend_mode(BlendMode::Blend);
+            let targets = hint.targets();
+            for (cells, color) in [
+                (&hint.cells, COLOR_HINT_CELLS),
+                (&targets, COLOR_HINT_TARGETS),
+            ] {
+                canvas.set_draw_color(color);
+                for index in cells.iter() {
+                    canvas
+                        .fill_rect(self.layout.cell_rect(index / side, index % side))
+                        .map_err(UiError::sdl2)?;
+                }
+            }
+            canvas.set_blend_mode(BlendMode::None);
+        }
+
+        // Shading the cells of the watched solver step, the one it places a value in more strongly
+        if let Some(step) = self.watch.as_ref().map(|watch| &watch.steps[0]) {
+            let side = self.game.as_ref().unwrap().side_size;
+            canvas.set_blend_mode(BlendMode::Blend);
+            let cells = step
+                .eliminated
+                .iter()
+                .map(|(index, _)| (*index, COLOR_HINT_CELLS));
+            let placed = step.placed.map(|(index, _)| (index, COLOR_HINT_TARGETS));
+            for (index, color) in cells.chain(placed) {
+                canvas.set_draw_color(color);
+                canvas
+                    .fill_rect(self.layout.cell_rect(index / side, index % side))
+                    .map_err(UiError::sdl2)?;
+            }
+            canvas.set_blend_mode(BlendMode::None);
+        }
+
+        // The cells where the chosen digit can still go
+        let spots = self
+            .where_digit
+            .map(|digit| self.game.as_ref().unwrap().cells_for(digit));
+
+        // The wrong entries accepted in lenient mode
+        let conflicts = match self.validation == Validation::Lenient {
+            true => self.game.as_ref().unwrap().conflicts(),
+            false => vec![],
+        };
+
         // Drawing numbers
         for r in 0..self.game.as_ref().unwrap().side_size {
             for c in 0..self.game.as_ref().unwrap().side_size {
-                // Getting the box's value only if it is not zero
+                // Getting the box's value only if it is not zero, drawing its notes otherwise
                 let number = match self.game.as_ref().unwrap().grid
                     [self.game.as_ref().unwrap().index(r, c)]
-                .value
+                .value()
                 {
-                    0 => continue,
+                    0 => {
+                        // Shade the empty cells depending on whether the chosen digit can go there
+                        if let Some(spots) = spots.as_ref() {
+                            let index = self.game.as_ref().unwrap().index(r, c);
+                            canvas.set_draw_color(match spots.contains(&index) {
+                                true => COLOR_CANDIDATE,
+                                false => COLOR_ELIMINATED,
+                            });
+                            canvas
+                                .fill_rect(self.layout.cell_rect(r, c))
+                                .map_err(UiError::sdl2)?;
+                        }
+                        self.draw_notes(canvas, r, c)?;
+                        continue;
+                    }
                     _ => &self.game.as_ref().unwrap().grid[self.game.as_ref().unwrap().index(r, c)],
                 };
 
@@ -90,184 +1942,787 @@ impl<'a> Displayable for GameScreen<'a> {
                 if self.game.as_ref().unwrap().selected_value.is_some()
                     && *number == self.game.as_ref().unwrap().selected_value.unwrap()
                 {
-                    canvas.set_draw_color(COLOR_HIGHLIGHT);
+                    canvas.set_draw_color(self.theme.highlight);
+                    canvas
+                        .fill_rect(self.layout.cell_rect(r, c))
+                        .map_err(UiError::sdl2)?;
+                } else if conflicts.contains(&self.game.as_ref().unwrap().index(r, c)) {
+                    canvas.set_draw_color(self.theme.error);
                     canvas
-                        .fill_rect(Rect::new(
-                            OFFSET_X + (c as i32) * BOX_SIZE,
-                            OFFSET_Y + (r as i32) * BOX_SIZE,
-                            BOX_SIZE as u32,
-                            BOX_SIZE as u32,
-                        ))
-                        .map_err(|_| UiError::SDL2Error)?;
-                } else if number.value != 0 && !number.initial {
-                    canvas.set_draw_color(COLOR_NOT_INIT);
+                        .fill_rect(self.layout.cell_rect(r, c))
+                        .map_err(UiError::sdl2)?;
+                } else if number.value() != 0 && !number.initial() {
+                    canvas.set_draw_color(self.theme.entered_cell);
                     canvas
-                        .fill_rect(Rect::new(
-                            OFFSET_X + (c as i32) * BOX_SIZE,
-                            OFFSET_Y + (r as i32) * BOX_SIZE,
-                            BOX_SIZE as u32,
-                            BOX_SIZE as u32,
-                        ))
-                        .map_err(|_| UiError::SDL2Error)?;
-                }
-
-                let texture_creator = canvas.texture_creator();
-
-                // Generating the number text
-                let number_text = self
-                    .font
-                    .as_ref()
-                    .unwrap()
-                    .render(&number.value.to_string())
-                    .solid(COLOR_FONT)
-                    .map_err(|_| UiError::SDL2Error)?;
-
-                let tex_number = texture_creator
-                    .create_texture_from_surface(number_text)
-                    .map_err(|_| UiError::SDL2Error)?;
-
-                // Centering the number text in the box
-                let offset_x = OFFSET_X + (BOX_SIZE - tex_number.query().width as i32) / 2 + 1;
-                let offset_y = OFFSET_Y + (BOX_SIZE - tex_number.query().height as i32) / 2 + 1;
+                        .fill_rect(self.layout.cell_rect(r, c))
+                        .map_err(UiError::sdl2)?;
+                }
+
+                let tex_number = self.glyphs.get(
+                    number.value(),
+                    match number.initial() {
+                        true => self.theme.initial,
+                        false => self.theme.entered,
+                    },
+                )?;
 
+                // Centering the number text in the box, shrinking it when the boxes are smaller
+                // than the font
+                let cell = self.layout.cell_rect(r, c);
+                let query = tex_number.query();
                 canvas
                     .copy(
-                        &tex_number,
+                        tex_number,
                         None,
-                        Rect::new(
-                            offset_x + BOX_SIZE * c as i32,
-                            offset_y + BOX_SIZE * r as i32,
-                            tex_number.query().width,
-                            tex_number.query().height,
-                        ),
+                        fit_centered(query.width, query.height, cell),
                     )
-                    .map_err(|_| UiError::SDL2Error)?;
+                    .map_err(UiError::sdl2)?;
             }
         }
 
+        self.draw_diff(canvas)?;
+        self.draw_flash(canvas)?;
+
         // Drawing lines
-        canvas.set_draw_color(COLOR_LINES);
-        for n in 0..=self.game.as_ref().unwrap().side_size {
-            // Line is thicker if modulo game size
-            let thickness = match n % self.game.as_ref().unwrap().size {
-                0 => 3,
-                _ => 1,
+        canvas.set_draw_color(self.theme.lines);
+        let (side, jigsaw) = {
+            let game = self.game.as_ref().unwrap();
+            (game.side_size, game.is_jigsaw())
+        };
+        for n in 0..=side {
+            // Line is thicker if modulo game size, the borders of jigsaw regions being drawn next
+            let thickness = match jigsaw && n % side != 0 {
+                true => self.layout.thin_line,
+                false => self.layout.line_thickness(n),
             };
 
             // Horizontal line
             let line = Rect::new(
-                OFFSET_X,
-                OFFSET_Y + n as i32 * BOX_SIZE,
-                self.game.as_ref().unwrap().side_size as u32 * BOX_SIZE as u32 + 3,
+                self.layout.grid_x,
+                self.layout.grid_y + n as i32 * self.layout.box_size,
+                self.layout.grid_side() as u32 + self.layout.thick_line,
                 thickness,
             );
-            canvas.fill_rect(line).map_err(|_| UiError::SDL2Error)?;
+            canvas.fill_rect(line).map_err(UiError::sdl2)?;
 
             // Vertical line
             let line = Rect::new(
-                OFFSET_X + n as i32 * BOX_SIZE,
-                OFFSET_Y,
+                self.layout.grid_x + n as i32 * self.layout.box_size,
+                self.layout.grid_y,
                 thickness,
-                self.game.as_ref().unwrap().side_size as u32 * BOX_SIZE as u32,
+                self.layout.grid_side() as u32,
             );
-            canvas.fill_rect(line).map_err(|_| UiError::SDL2Error)?;
-        }
-
-        /*
-        if self.game.as_ref().unwrap().is_done() {
-            let mut msg_text = Text::new("You won ! Congratulations !");
-            // If possible, apply the loaded font to the error message
-            if self.font_grid.is_some() {
-                msg_text.set_font(*self.font_grid.as_ref().unwrap(), PxScale::from(30.0));
-            }
-            // Drawing the message
-            graphics::draw(
-                ctx,
-                &msg_text,
-                DrawParam::default()
-                    .dest(mint::Point2 {
-                        x: OFFSET_X,
-                        y: OFFSET_Y * 2.0
-                            + BOX_SIZE * (self.game.as_ref().unwrap().side_size as f32),
-                    })
-                    .color(*COLOR_GOOD_MSG),
-            )?;
+            canvas.fill_rect(line).map_err(UiError::sdl2)?;
         }
-        // If there is an error message to display
-        else if self.message.is_some() {
-            let mut msg_text = Text::new(self.message.as_ref().unwrap().as_str());
-            // If possible, apply the loaded font to the error message
-            if self.font_grid.is_some() {
-                msg_text.set_font(*self.font_grid.as_ref().unwrap(), PxScale::from(30.0));
-            }
-            // Drawing the number
-            graphics::draw(
-                ctx,
-                &msg_text,
-                DrawParam::default()
-                    .dest(mint::Point2 {
-                        x: OFFSET_X,
-                        y: OFFSET_Y * 2.0
-                            + BOX_SIZE * (self.game.as_ref().unwrap().side_size as f32),
-                    })
-                    .color(*COLOR_BAD_MSG),
-            )?;
+        if jigsaw {
+            self.draw_regions(canvas)?;
+        }
+        self.draw_diagonals(canvas)?;
+        self.draw_cages(canvas)?;
+        self.draw_annotations(canvas)?;
+        self.draw_opponent(canvas)?;
+
+        // Outlining the selected cell, moved around with the arrow keys
+        if let Some(index) = self.game.as_ref().unwrap().selected_index {
+            let (r, c) = self.game.as_ref().unwrap().coordinates(index);
+            let cell = self.layout.cell_rect(r, c);
+            canvas.set_draw_color(self.theme.highlight);
+            for inset in 0..2 {
+                let outline = Rect::new(
+                    cell.x() + inset,
+                    cell.y() + inset,
+                    cell.width().saturating_sub(2 * inset as u32),
+                    cell.height().saturating_sub(2 * inset as u32),
+                );
+                canvas.draw_rect(outline).map_err(UiError::sdl2)?;
+            }
+        }
+
+        // Drawing the buttons and the clock at the top of the companion panel
+        self.draw_buttons(canvas)?;
+        self.draw_digits(canvas)?;
+        self.draw_message(canvas)?;
+
+        // Drawing the events log over everything else
+        if self.show_log {
+            self.draw_log(canvas)?;
         }
-        */
+        if let Some(dialog) = self.dialog.as_mut() {
+            dialog.draw(canvas, self.font.as_ref().unwrap())?;
+        }
+
+        Ok(())
+    }
 
-        canvas.present();
+    fn resize(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        self.viewport = Some(canvas.viewport());
+        self.update_layout();
 
         Ok(())
     }
 
     fn update(&mut self, event: &sdl2::event::Event) -> Result<ScreenOutcome, UiError> {
+        // The grid waits for the open question to be answered
+        if let Some(dialog) = self.dialog.as_mut() {
+            return Ok(match dialog.update(event) {
+                DialogOutcome::Unchanged => ScreenOutcome::Unchanged,
+                DialogOutcome::Updated => ScreenOutcome::Updated,
+                DialogOutcome::Confirmed(()) => {
+                    self.dialog = None;
+                    if let Some(path) = self.export_path() {
+                        self.write_export(&path);
+                    }
+                    ScreenOutcome::Updated
+                }
+                DialogOutcome::Cancelled => {
+                    self.dialog = None;
+                    ScreenOutcome::Updated
+                }
+            });
+        }
+
+        // While the solved game is replayed, the grid only changes with its moves
+        if self.replay.is_some() {
+            return Ok(match event {
+                Event::KeyDown {
+                    keycode: Some(Keycode::Space),
+                    ..
+                } => {
+                    // Play the moves back by themselves, or stop
+                    let view = self.replay.as_mut().unwrap();
+                    view.running = !view.running && !view.replay.is_over();
+                    view.shown = Instant::now();
+                    ScreenOutcome::Updated
+                }
+                Event::KeyDown {
+                    keycode: Some(keycode @ (Keycode::Left | Keycode::Right)),
+                    ..
+                } => {
+                    // Step through the moves rather than letting them play back
+                    self.replay.as_mut().unwrap().running = false;
+                    self.step_replay(*keycode == Keycode::Right);
+                    ScreenOutcome::Updated
+                }
+                Event::KeyDown {
+                    keycode:
+                        Some(
+                            keycode @ (Keycode::Plus
+                            | Keycode::KpPlus
+                            | Keycode::Equals
+                            | Keycode::Minus
+                            | Keycode::KpMinus),
+                        ),
+                    ..
+                } => {
+                    let view = self.replay.as_mut().unwrap();
+                    view.speed = match keycode {
+                        Keycode::Minus | Keycode::KpMinus => view.speed / 2,
+                        _ => view.speed * 2,
+                    }
+                    .clamp(1, REPLAY_MAX_SPEED);
+                    let speed = view.speed;
+                    self.show_message(format!("Replaying {} times faster.", speed), true);
+                    ScreenOutcome::Updated
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::M),
+                    ..
+                } => {
+                    self.stop_replay();
+                    ScreenOutcome::Menu
+                }
+                Event::MouseButtonUp {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    ..
+                } if self.menu_button().contains_point((*x, *y)) => {
+                    self.stop_replay();
+                    ScreenOutcome::Menu
+                }
+                _ => ScreenOutcome::Unchanged,
+            });
+        }
+
+        // While paused, the grid is hidden and the game can only be resumed
+        if self.paused {
+            return Ok(match event {
+                Event::KeyDown {
+                    keycode: Some(Keycode::P),
+                    ..
+                } => {
+                    self.toggle_pause();
+                    ScreenOutcome::Updated
+                }
+                Event::MouseButtonUp {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    ..
+                } if self.pause_button().contains_point((*x, *y)) => {
+                    self.toggle_pause();
+                    ScreenOutcome::Updated
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::M),
+                    ..
+                } => ScreenOutcome::BackToMenu,
+                Event::MouseButtonUp {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    ..
+                } if self.menu_button().contains_point((*x, *y)) => ScreenOutcome::BackToMenu,
+                _ => ScreenOutcome::Unchanged,
+            });
+        }
+
+        // While the solver is watched, the grid only changes with its steps
+        if self.watch.is_some() {
+            return Ok(match event {
+                Event::KeyDown {
+                    keycode: Some(Keycode::W),
+                    ..
+                } => {
+                    self.toggle_watch();
+                    ScreenOutcome::Updated
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Space),
+                    ..
+                } => {
+                    // Step through the solver rather than letting it play
+                    self.watch.as_mut().unwrap().running = false;
+                    self.play_watched();
+                    ScreenOutcome::Updated
+                }
+                Event::KeyDown {
+                    keycode:
+                        Some(
+                            keycode @ (Keycode::Plus
+                            | Keycode::KpPlus
+                            | Keycode::Equals
+                            | Keycode::Minus
+                            | Keycode::KpMinus),
+                        ),
+                    ..
+                } => {
+                    let faster = !matches!(keycode, Keycode::Minus | Keycode::KpMinus);
+                    self.adjust_watch_speed(faster);
+                    ScreenOutcome::Updated
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::P),
+                    ..
+                } => {
+                    self.toggle_pause();
+                    ScreenOutcome::Updated
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::M),
+                    ..
+                } => {
+                    self.watch = None;
+                    ScreenOutcome::BackToMenu
+                }
+                _ => ScreenOutcome::Unchanged,
+            });
+        }
+
         match event {
             Event::KeyDown {
-                keycode: Some(Keycode::Num0 | Keycode::Num1),
+                keycode: Some(keycode),
+                keymod,
                 ..
-            } => {}
-            Event::MouseButtonUp {
+            } if !keymod
+                .intersects(Mod::LCTRLMOD | Mod::RCTRLMOD | Mod::LSHIFTMOD | Mod::RSHIFTMOD)
+                && self.letter_value(*keycode).is_some() =>
+            {
+                // On the grids bigger than 9x9, the letters place the values above 9 in the
+                // selected cell rather than triggering their shortcuts
+                let value = self.letter_value(*keycode).unwrap();
+                return Ok(self.enter_value(value));
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::P),
+                ..
+            } if !self.is_ended() => {
+                self.toggle_pause();
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Tab),
+                ..
+            } => {
+                // Switch to the next open game
+                self.switch_game();
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::H),
+                ..
+            } if !self.is_ended() => {
+                self.show_hint();
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::W),
+                ..
+            } if !self.is_ended() => {
+                // Watch the solver play the human techniques
+                self.toggle_watch();
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::E),
+                ..
+            } => {
+                self.export();
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::M),
+                ..
+            } => return Ok(ScreenOutcome::BackToMenu),
+            Event::KeyDown {
+                keycode: Some(Keycode::L),
+                ..
+            } => {
+                // Toggle the events log
+                self.show_log = !self.show_log;
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::T),
+                ..
+            } => {
+                // Switch between the configured theme and the other preset
+                std::mem::swap(&mut self.theme, &mut self.other_theme);
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(keycode),
+                keymod,
+                ..
+            } if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD)
+                && self.notes_clear_for_key(*keycode).is_some() =>
+            {
+                // Clear the notes designated by the key
+                let which = self.notes_clear_for_key(*keycode).unwrap();
+                if let Err(e) = self.game.as_mut().unwrap().clear_notes(which) {
+                    self.show_message(e.to_string(), false);
+                }
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::C),
+                keymod,
+                ..
+            } if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) => {
+                self.copy_share_code();
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(keycode),
+                keymod,
+                ..
+            } if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD)
+                && digit_from_keycode(*keycode).is_some() =>
+            {
+                // Show where the digit can go, or stop showing it
+                let digit = digit_from_keycode(*keycode).unwrap();
+                self.where_digit = match self.where_digit == Some(digit) {
+                    true => None,
+                    false => Some(digit),
+                };
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(keycode @ (Keycode::Z | Keycode::Y)),
+                keymod,
+                ..
+            } if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) && self.diff.is_none() => {
+                // Undo the last move, or play the last undone one again
+                let game = self.game.as_mut().unwrap();
+                let outcome = match keycode {
+                    Keycode::Z => game.undo(),
+                    _ => game.redo(),
+                };
+                match outcome {
+                    Ok(_) => self.check_ended(),
+                    Err(e) => self.show_message(e.to_string(), false),
+                }
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Backspace),
+                keymod,
+                ..
+            } if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) => {
+                // Revert the last moves until the grid contains no mistake
+                match self.game.as_mut().unwrap().rollback() {
+                    Ok(reverted) => {
+                        self.show_message(format!("Reverted {} moves.", reverted), true);
+                    }
+                    Err(e) => self.show_message(e.to_string(), false),
+                }
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::G),
+                keymod,
+                ..
+            } => {
+                // Start a guess, discard it with Shift or keep it with Ctrl
+                let game = self.game.as_mut().unwrap();
+                let outcome = if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+                    game.discard_guess()
+                        .map(|reverted| format!("Reverted {} moves.", reverted))
+                } else if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) {
+                    game.commit_guess()
+                        .map(|_| String::from("Kept the current guess."))
+                } else {
+                    game.start_guess();
+                    Ok(format!("Guessing (depth {}).", game.guesses.len()))
+                };
+
+                let good = outcome.is_ok();
+                self.show_message(outcome.unwrap_or_else(|e| e.to_string()), good);
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::N),
+                keymod,
+                ..
+            } if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) && !self.is_ended() => {
+                // Give up, showing how the grid compares to the solution
+                self.compare_to_solution();
+                if self.diff.is_some() {
+                    self.show_lasting_message(self.completion_message(), false);
+                }
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::N),
+                ..
+            } if self.is_ended() => {
+                // Go on with a new puzzle of the same settings
+                self.message = None;
+                return Ok(ScreenOutcome::NewGame);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::A),
+                keymod,
+                ..
+            } if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) => {
+                // Keep the notes equal to the candidates after every move, or stop
+                let game = self.game.as_mut().unwrap();
+                let on = !game.auto_notes;
+                match game.set_auto_notes(on) {
+                    Ok(()) if on => self.show_message(String::from("Automatic notes on."), true),
+                    Ok(()) => self.show_message(String::from("Automatic notes off."), true),
+                    Err(e) => self.show_message(e.to_string(), false),
+                }
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::A),
+                ..
+            } => {
+                // Fill the notes of every empty cell with its candidates
+                if let Err(e) = self.game.as_mut().unwrap().fill_notes() {
+                    self.show_message(e.to_string(), false);
+                }
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::K),
+                keymod,
+                ..
+            } => {
+                // Paint the selected cells with the next marker color, or remove every color
+                // with Shift
+                let game = self.game.as_mut().unwrap();
+                let outcome = if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+                    let cells: Vec<usize> = (0..game.grid.len()).collect();
+                    game.paint(&cells, 0)
+                } else if self.selection.len() > 1 {
+                    game.cycle_color(&self.selection).map(|_| ())
+                } else {
+                    match game.selected_index {
+                        Some(index) => game.cycle_color(&[index]).map(|_| ()),
+                        None => return Ok(ScreenOutcome::Unchanged),
+                    }
+                };
+                if let Err(e) = outcome {
+                    self.show_message(e.to_string(), false);
+                }
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::F),
+                ..
+            } => return Ok(ScreenOutcome::Favorite),
+            Event::KeyDown {
+                keycode: Some(keycode),
+                keymod,
+                ..
+            } if digit_from_keycode(*keycode).is_some() => {
+                let game = self.game.as_mut().unwrap();
+                let digit = digit_from_keycode(*keycode).unwrap();
+
+                // With several selected cells, Alt places the digit in all of them rather than
+                // toggling its note
+                if self.selection.len() > 1 && keymod.intersects(Mod::LALTMOD | Mod::RALTMOD) {
+                    return Ok(self.enter_selection_value(digit));
+                }
+
+                // Without a selected cell, pressing a digit highlights all its placed instances and
+                // notes
+                if game.selected_index.is_none() && digit as usize <= game.side_size {
+                    game.selected_value = match game.selected_value == Some(digit) {
+                        true => None,
+                        false => Some(digit),
+                    };
+                    return Ok(ScreenOutcome::Updated);
+                }
+
+                // Otherwise the digit is placed in the selected cell
+                return Ok(self.enter_value(digit));
+            }
+            Event::KeyDown {
+                keycode:
+                    Some(keycode @ (Keycode::Up | Keycode::Down | Keycode::Left | Keycode::Right)),
+                ..
+            } => {
+                // Move the selection, starting from the top left cell
+                let game = self.game.as_mut().unwrap();
+                let last = game.side_size - 1;
+                let (r, c) = match game.selected_index {
+                    Some(index) => {
+                        let (r, c) = game.coordinates(index);
+                        match keycode {
+                            Keycode::Up => (r.saturating_sub(1), c),
+                            Keycode::Down => ((r + 1).min(last), c),
+                            Keycode::Left => (r, c.saturating_sub(1)),
+                            _ => (r, (c + 1).min(last)),
+                        }
+                    }
+                    None => (0, 0),
+                };
+                game.selected_index = Some(game.index(r, c));
+                self.selection.clear();
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Delete | Keycode::Backspace),
+                ..
+            } if self.diff.is_none() => {
+                // Empty the selected cells, skipping the ones that cannot be emptied
+                let game = self.game.as_mut().unwrap();
+                if self.selection.len() > 1 {
+                    for index in self.selection.iter().copied() {
+                        let (r, c) = game.coordinates(index);
+                        let _ = game.clear_cell(r, c);
+                    }
+                    self.message = None;
+                    return Ok(ScreenOutcome::Updated);
+                }
+                if let Some(index) = game.selected_index {
+                    let (r, c) = game.coordinates(index);
+                    match game.clear_cell(r, c) {
+                        Ok(_) => self.message = None,
+                        Err(e) => self.show_message(e.to_string(), false),
+                    }
+                    return Ok(ScreenOutcome::Updated);
+                }
+            }
+            Event::KeyDown {
+                keycode:
+                    Some(
+                        keycode @ (Keycode::Plus
+                        | Keycode::KpPlus
+                        | Keycode::Equals
+                        | Keycode::Minus
+                        | Keycode::KpMinus),
+                    ),
+                ..
+            } => {
+                // Cycle the selected cell's value, '=' being the unshifted '+' key
+                if let Some(index) = self.game.as_ref().unwrap().selected_index {
+                    let forward = !matches!(keycode, Keycode::Minus | Keycode::KpMinus);
+                    return Ok(self.cycle_cell(index, forward));
+                }
+            }
+            Event::MouseWheel { y, .. } if self.mouse.wheel != Wheel::Off => {
+                // Cycle the value of the cell under the cursor, rolling upwards going to the next
+                // candidate unless the wheel is reversed
+                if let Some(index) = self.hovered_index {
+                    if *y != 0 {
+                        let forward = (*y > 0) == (self.mouse.wheel == Wheel::Cycle);
+                        return Ok(self.cycle_cell(index, forward));
+                    }
+                }
+            }
+            Event::MouseMotion {
+                mousestate, x, y, ..
+            } => {
+                self.hovered_index = self
+                    .cell_at(*x, *y)
+                    .map(|(r, c)| self.game.as_ref().unwrap().index(r, c));
+
+                // Dragging with the left button selects the cells passed over
+                if let (Some(start), Some(index)) = (self.drag_start, self.hovered_index) {
+                    if mousestate.left() && (self.dragged || index != start) {
+                        if self.selection.is_empty() {
+                            self.selection.push(start);
+                        }
+                        if !self.selection.contains(&index) {
+                            self.selection.push(index);
+                        }
+                        self.dragged = true;
+                        self.game.as_mut().unwrap().selected_index = Some(index);
+                        return Ok(ScreenOutcome::Updated);
+                    }
+                }
+            }
+            Event::MouseButtonDown {
                 mouse_btn: MouseButton::Left,
                 x,
                 y,
                 ..
             } => {
-                let x = *x;
-                let y = *y;
-                // If we're outside the grid, do nothing
-                if x < OFFSET_X
-                    || x >= OFFSET_X + (self.game.as_ref().unwrap().side_size as i32) * BOX_SIZE
-                    || y < OFFSET_Y
-                    || y >= OFFSET_Y + (self.game.as_ref().unwrap().side_size as i32) * BOX_SIZE
-                {
-                    if self.game.as_ref().unwrap().selected_index.is_some() {
-                        self.game.as_mut().unwrap().selected_index = None;
-                        self.game.as_mut().unwrap().selected_value = None;
-
+                self.drag_start = self
+                    .cell_at(*x, *y)
+                    .map(|(r, c)| self.game.as_ref().unwrap().index(r, c));
+                self.dragged = false;
+            }
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Right,
+                x,
+                y,
+                ..
+            } if self.diff.is_none() && self.mouse.right_click != RightClick::Off => {
+                // Empty the clicked cell if the player placed a value there
+                if let Some((r, c)) = self.cell_at(*x, *y) {
+                    let game = self.game.as_mut().unwrap();
+                    let cell = game.grid[game.index(r, c)];
+                    if cell.value() != 0 && !cell.initial() {
+                        match game.clear_cell(r, c) {
+                            Ok(_) => self.message = None,
+                            Err(e) => self.show_message(e.to_string(), false),
+                        }
                         return Ok(ScreenOutcome::Updated);
                     }
+                }
+
+                // Toggle the note of the highlighted value in the clicked cell
+                if self.mouse.right_click != RightClick::Note {
                     return Ok(ScreenOutcome::Unchanged);
                 }
+                let ((r, c), value) = match (
+                    self.cell_at(*x, *y),
+                    self.game.as_ref().unwrap().selected_value,
+                ) {
+                    (Some(position), Some(value)) if value != 0 => (position, value),
+                    _ => return Ok(ScreenOutcome::Unchanged),
+                };
+                if let Err(e) = self.game.as_mut().unwrap().toggle_note(r, c, value) {
+                    self.show_message(e.to_string(), false);
+                }
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => {
+                // Releasing the button ends a drag over the grid
+                self.drag_start = None;
+                if std::mem::take(&mut self.dragged) {
+                    return Ok(ScreenOutcome::Updated);
+                }
+
+                if self.hint_button().contains_point((*x, *y)) && !self.is_ended() {
+                    self.show_hint();
+                    return Ok(ScreenOutcome::Updated);
+                }
+                if self.pause_button().contains_point((*x, *y)) && !self.is_ended() {
+                    self.toggle_pause();
+                    return Ok(ScreenOutcome::Updated);
+                }
+                if self.export_button().contains_point((*x, *y)) {
+                    self.export();
+                    return Ok(ScreenOutcome::Updated);
+                }
+                if self.menu_button().contains_point((*x, *y)) {
+                    return Ok(ScreenOutcome::BackToMenu);
+                }
+
+                // Clicking a digit fills the selected cell in cell-first mode, otherwise it
+                // selects the digit for placement in the empty cells clicked next
+                if let Some(value) = self.digit_at(*x, *y) {
+                    let game = self.game.as_mut().unwrap();
+                    if self.input_mode == InputMode::CellFirst && game.selected_index.is_some() {
+                        return Ok(self.enter_value(value));
+                    }
+                    game.selected_index = None;
+                    self.selection.clear();
+                    game.selected_value = match game.selected_value == Some(value) {
+                        true => None,
+                        false => Some(value),
+                    };
+                    return Ok(ScreenOutcome::Updated);
+                }
+
+                // If we're outside the grid, do nothing
+                let (row_index, col_index) = match self.cell_at(*x, *y) {
+                    Some(position) => position,
+                    None => {
+                        self.selection.clear();
+                        if self.game.as_ref().unwrap().selected_index.is_some() {
+                            self.game.as_mut().unwrap().selected_index = None;
+                            self.game.as_mut().unwrap().selected_value = None;
+
+                            return Ok(ScreenOutcome::Updated);
+                        }
+                        return Ok(ScreenOutcome::Unchanged);
+                    }
+                };
 
                 // Calculate on which value the user clicked
-                let row_index = ((y - OFFSET_Y) / BOX_SIZE) as usize;
-                let col_index = ((x - OFFSET_X) / BOX_SIZE) as usize;
                 let click_index = self.game.as_ref().unwrap().index(row_index, col_index);
-                let click_value = self.game.as_ref().unwrap().grid[click_index].value;
 
-                // If the game contains a number, highlight them, otherwise reset any highlighting
-                if click_value == 0 && self.game.as_ref().unwrap().selected_value.is_some() {
+                // Ctrl+click adds the cell to the selection, or takes it out
+                let ctrl = Mod::LCTRLMOD | Mod::RCTRLMOD;
+                if self.keyboard.as_ref().unwrap().mod_state().intersects(ctrl) {
+                    self.toggle_selected(click_index);
+                    return Ok(ScreenOutcome::Updated);
+                }
+                self.selection.clear();
+                let mut click_value = self.game.as_ref().unwrap().grid[click_index].value();
+
+                // If the game contains a number, highlight them, otherwise reset any highlighting.
+                // In digit-first mode, the selected digit is placed in the empty cell clicked.
+                if click_value == 0
+                    && self.input_mode == InputMode::DigitFirst
+                    && self.game.as_ref().unwrap().selected_value.is_some()
+                    && self.diff.is_none()
+                {
                     let value = self.game.as_ref().unwrap().selected_value.unwrap();
-                    match self
-                        .game
-                        .as_mut()
-                        .unwrap()
-                        .do_move(row_index, col_index, value)
-                    {
-                        Ok(_) => (),
-                        Err(e) => {
-                            self.message = Some(format!("{}", e));
+                    match self.game.as_mut().unwrap().do_move(
+                        row_index,
+                        col_index,
+                        value,
+                        self.validation,
+                    ) {
+                        Ok(_) => {
+                            // The placed value stays selected to place it again
+                            click_value = value;
+                            self.check_ended();
                         }
+                        Err(e) => self.refuse_move(click_index, value, e),
                     }
                 }
 
@@ -284,13 +2739,107 @@ impl<'a> Displayable for GameScreen<'a> {
 }
 
 impl<'a> GameScreen<'a> {
+    /**
+     * Makes `game` the one being played. The previous game, if it is not over, is parked so
+     * that the player can switch back to it.
+     */
     pub fn set_game(&mut self, game: Game) {
+        self.park();
+        self.play(game);
+    }
+
+    /**
+     * Parks the current game, if it is not over, after the other open games.
+     */
+    fn park(&mut self) {
+        let ongoing = !self.is_ended();
+        if let Some(game) = self.game.take() {
+            if ongoing {
+                self.parked.push(Session::park(game));
+            }
+        }
+    }
+
+    /**
+     * Plays `game`, with its play time so far.
+     */
+    fn play(&mut self, game: Game) {
         self.game = Some(game);
+        self.paused = false;
+        self.where_digit = None;
+        self.diff = None;
+        self.hint = None;
+        self.flash = None;
+        self.watch = None;
+        self.replay = None;
+        self.dialog = None;
+        self.message = None;
+        self.selection.clear();
+        self.drag_start = None;
+        self.dragged = false;
+        self.opponent = None;
+        self.update_layout();
+    }
+
+    /**
+     * Returns whether a question is waiting for an answer over the grid.
+     */
+    pub fn has_dialog(&self) -> bool {
+        self.dialog.is_some()
+    }
+
+    /**
+     * Saves the current game and the parked ones, e.g. before the window closes.
+     */
+    pub fn save_games(&mut self) -> Result<(), GameError> {
+        // The grid of a replay is not the one of the game
+        self.stop_replay();
+        let parked = self.parked.iter_mut().map(|session| &mut session.game);
+        for game in self.game.iter_mut().chain(parked) {
+            Autosave::save(game)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Parks the current game and plays the next open one, if any.
+     */
+    fn switch_game(&mut self) {
+        if self.parked.is_empty() {
+            self.show_message(String::from("No other game is open."), false);
+            return;
+        }
+
+        self.park();
+        let game = self.parked.remove(0).resume();
+        self.play(game);
     }
     pub fn set_font(&mut self, new_font: Rc<Font<'a, 'a>>) {
         self.font = Some(new_font);
+        self.glyphs.clear();
+    }
+    pub fn set_notes_font(&mut self, new_font: Rc<Font<'a, 'a>>) {
+        self.notes_font = Some(new_font);
+        self.notes_glyphs.clear();
     }
     pub fn has_game(&self) -> bool {
         self.game.is_some()
     }
+
+    /**
+     * Returns the play time of the current game.
+     */
+    pub fn elapsed(&self) -> std::time::Duration {
+        self.game
+            .as_ref()
+            .map_or(std::time::Duration::ZERO, |g| g.timer.elapsed())
+    }
+
+    /**
+     * Returns whether the shown play time is behind, the screen having to be drawn again.
+     */
+    pub fn clock_changed(&self) -> bool {
+        self.game.is_some() && self.elapsed().as_secs() != self.drawn_seconds
+    }
 }
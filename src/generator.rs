@@ -0,0 +1,540 @@
+use crate::errors::UiError;
+use crate::game::{self, value_symbol, Game, Variant};
+use crate::grader;
+use crate::solver::{self, SolverKind};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+
+/// Number of puzzles generated, across all threads, before settling for the closest one.
+const MAX_GENERATION_ATTEMPTS: usize = 50;
+/// Swaps tried per cell when drawing jigsaw regions, see `jigsaw_regions`.
+const JIGSAW_SWAPS: usize = 4;
+/// Restarts of the filling of a grid on jigsaw regions before they are drawn again, see
+/// `Game::fill_within`.
+const JIGSAW_FILL_RESTARTS: usize = 4;
+/// Sizes of the boards that puzzles are generated for, the 25x25 grids taking minutes to unfill.
+pub const SIZES: RangeInclusive<usize> = 2..=4;
+/// How the puzzles of each size in `SIZES` are generated by default, see `Calibration`.
+const CALIBRATIONS: [Calibration; 3] = [
+    Calibration {
+        min_clues_percent: 21,
+        easy_clues_percent: 45,
+        medium_clues_percent: 37,
+        unfill_attempts: [1, 2, 8, 8],
+    },
+    Calibration {
+        min_clues_percent: 21,
+        easy_clues_percent: 45,
+        medium_clues_percent: 37,
+        unfill_attempts: [3, 5, 40, 40],
+    },
+    Calibration {
+        min_clues_percent: 21,
+        easy_clues_percent: 49,
+        medium_clues_percent: 44,
+        unfill_attempts: [3, 5, 30, 30],
+    },
+];
+
+/// How hard a generated puzzle should be, based on its number of clues, expert puzzles also
+/// needing more than singles, pairs and triples, see `grader::Grade::difficulty`.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+    Expert,
+}
+
+/// The numbers of clues and the unfill attempts of the puzzles of a size, measured for every
+/// difficulty to be reached within a few attempts: the more attempts at emptying cells, the fewer
+/// clues are left, and the longer each attempt takes on the bigger boards.
+struct Calibration {
+    min_clues_percent: usize,
+    easy_clues_percent: usize,
+    medium_clues_percent: usize,
+    /// Failed attempts at emptying a cell before the unfilling stops, for each difficulty.
+    unfill_attempts: [usize; 4],
+}
+
+/**
+ * Returns the calibration of the puzzles of the given size, the closest one outside `SIZES`.
+ */
+fn calibration(size: usize) -> &'static Calibration {
+    &CALIBRATIONS[size.clamp(*SIZES.start(), *SIZES.end()) - SIZES.start()]
+}
+
+/// How puzzles are generated, the numbers of clues being percentages of the cells of the board
+/// so that they scale with its size. The settings left absent are calibrated for each size, see
+/// `Calibration`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct GenerationConfig {
+    /// Failed attempts at emptying a cell before the unfilling stops.
+    pub max_unfill_attempts: Option<usize>,
+    /// Minimum percentage of the cells left as clues.
+    pub min_clues_percent: Option<usize>,
+    /// Minimum percentage of the cells given as clues in easy puzzles.
+    pub easy_clues_percent: Option<usize>,
+    /// Minimum percentage of the cells given as clues in medium puzzles, below is hard or expert.
+    pub medium_clues_percent: Option<usize>,
+    /// Solver checking that the medium, hard and expert puzzles keep a single solution while
+    /// they are unfilled, easy ones being always checked with singles only.
+    pub solver: SolverKind,
+    /// The rules of the generated puzzles, see `Variant`.
+    pub variant: Variant,
+    /// Whether the boxes of the generated puzzles are replaced by irregular regions, see
+    /// `jigsaw_regions`.
+    pub jigsaw: bool,
+}
+
+impl Default for GenerationConfig {
+    fn default() -> Self {
+        GenerationConfig {
+            max_unfill_attempts: None,
+            min_clues_percent: None,
+            easy_clues_percent: None,
+            medium_clues_percent: None,
+            solver: SolverKind::Dlx,
+            variant: Variant::Classic,
+            jigsaw: false,
+        }
+    }
+}
+
+/**
+ * Checks that the size of the games of the configuration file is one of `SIZES`, before any
+ * game of that size is created or generated.
+ */
+pub fn validate_size(size: usize) -> Result<(), UiError> {
+    match SIZES.contains(&size) {
+        true => Ok(()),
+        false => Err(UiError::InvalidGameSize),
+    }
+}
+
+impl GenerationConfig {
+    /**
+     * Checks that the percentages are ordered for every size and the attempts non zero.
+     */
+    pub fn validate(&self) -> Result<(), UiError> {
+        let ordered = SIZES.into_iter().all(|size| {
+            let (min, easy, medium) = self.percents(size);
+            min <= medium && medium <= easy && easy <= 100
+        });
+        match ordered && self.max_unfill_attempts != Some(0) {
+            true => Ok(()),
+            false => Err(UiError::InvalidGenerationConfig),
+        }
+    }
+
+    /**
+     * Returns the minimum, easy and medium percentages of clues of the puzzles of the given size.
+     */
+    fn percents(&self, size: usize) -> (usize, usize, usize) {
+        let calibration = calibration(size);
+        (
+            self.min_clues_percent
+                .unwrap_or(calibration.min_clues_percent),
+            self.easy_clues_percent
+                .unwrap_or(calibration.easy_clues_percent),
+            self.medium_clues_percent
+                .unwrap_or(calibration.medium_clues_percent),
+        )
+    }
+
+    /**
+     * Returns the number of failed attempts at emptying a cell before the unfilling stops, for
+     * puzzles of the given size and difficulty.
+     */
+    pub fn max_unfill_attempts(&self, size: usize, difficulty: Difficulty) -> usize {
+        self.max_unfill_attempts
+            .unwrap_or(calibration(size).unfill_attempts[difficulty as usize])
+    }
+
+    /**
+     * Returns the minimum number of clues of the puzzles of the given size.
+     */
+    pub fn min_clues(&self, size: usize) -> usize {
+        size.pow(4) * self.percents(size).0 / 100
+    }
+
+    /**
+     * Returns the range of the number of clues of a puzzle of the given difficulty and size.
+     */
+    pub fn clues(&self, difficulty: Difficulty, size: usize) -> RangeInclusive<usize> {
+        let (_, easy, medium) = self.percents(size);
+        let cells = size.pow(4);
+        let (easy, medium) = (cells * easy / 100, cells * medium / 100);
+        match difficulty {
+            Difficulty::Easy => easy..=cells,
+            Difficulty::Medium => medium..=easy.saturating_sub(1),
+            Difficulty::Hard | Difficulty::Expert => 0..=medium.saturating_sub(1),
+        }
+    }
+}
+
+/**
+ * Returns how far `clues` is from `range`.
+ */
+fn distance(range: &RangeInclusive<usize>, clues: usize) -> usize {
+    match clues < *range.start() {
+        true => range.start() - clues,
+        false => clues.saturating_sub(*range.end()),
+    }
+}
+
+/**
+ * Returns how far the puzzle of `game` is from the given difficulty: how far its clues are from
+ * `range`, one more for an expert puzzle that the grader does not rate as such. The 4x4 grids are
+ * too small for the grader to ever rate them expert, their expert puzzles are only the hard ones.
+ */
+fn mismatch(game: &Game, difficulty: Difficulty, range: &RangeInclusive<usize>) -> usize {
+    let distance = distance(range, game.nb_clues());
+    match difficulty {
+        Difficulty::Expert
+            if game.size > 2 && grader::grade(game).difficulty() != Difficulty::Expert =>
+        {
+            distance + 1
+        }
+        _ => distance,
+    }
+}
+
+/**
+ * Returns the regions of a jigsaw grid of boxes of `size` by `size` cells, see `Game::regions`:
+ * starting from the boxes, cells of neighboring regions are swapped at random as long as both
+ * regions stay in one piece.
+ */
+pub fn jigsaw_regions<R: Rng>(size: usize, rng: &mut R) -> Vec<usize> {
+    let side_size = size * size;
+    let cells = side_size * side_size;
+    let mut regions = game::boxes(size);
+    let sides = |index: usize| {
+        let (r, c) = (index / side_size, index % side_size);
+        [
+            (r > 0).then(|| index - side_size),
+            (r + 1 < side_size).then(|| index + side_size),
+            (c > 0).then(|| index - 1),
+            (c + 1 < side_size).then(|| index + 1),
+        ]
+        .into_iter()
+        .flatten()
+    };
+    // Whether the cells of `region` are all reached from one of them, through their sides
+    let connected = |regions: &[usize], region: usize| {
+        let first = (0..cells).find(|i| regions[*i] == region).unwrap();
+        let (mut seen, mut stack) = (vec![first], vec![first]);
+        while let Some(index) = stack.pop() {
+            for next in sides(index) {
+                if regions[next] == region && !seen.contains(&next) {
+                    seen.push(next);
+                    stack.push(next);
+                }
+            }
+        }
+        seen.len() == side_size
+    };
+
+    for _ in 0..JIGSAW_SWAPS * cells {
+        // A cell of the border of a region, and a cell of the neighboring region that also
+        // borders the first one, swapping regions; the first one is drawn as a u32 like the
+        // cells emptied by `Game::unfill_with`
+        let a = rng.gen_range(0..cells as u32) as usize;
+        let (from, to) = match sides(a)
+            .filter(|n| regions[*n] != regions[a])
+            .collect::<Vec<_>>()
+            .choose(rng)
+        {
+            Some(n) => (regions[a], regions[*n]),
+            None => continue,
+        };
+        let borders: Vec<usize> = (0..cells)
+            .filter(|i| regions[*i] == to && sides(*i).any(|n| n != a && regions[n] == from))
+            .collect();
+        let Some(&b) = borders.choose(rng) else {
+            continue;
+        };
+
+        regions[a] = to;
+        regions[b] = from;
+        if !connected(&regions, from) || !connected(&regions, to) {
+            regions[a] = from;
+            regions[b] = to;
+        }
+    }
+
+    regions
+}
+
+/**
+ * Fills the grid of `game` from scratch, on new jigsaw regions if `jigsaw` is set, which are
+ * drawn again when they are not filled within `JIGSAW_FILL_RESTARTS` restarts, some of them
+ * never being. The values and the regions come from `rng`.
+ */
+fn fill_new<R: Rng>(game: &mut Game, jigsaw: bool, rng: &mut R) {
+    loop {
+        game.clear();
+        let restarts = match jigsaw {
+            true => JIGSAW_FILL_RESTARTS,
+            false => usize::MAX,
+        };
+        if jigsaw {
+            game.set_regions(jigsaw_regions(game.size, rng));
+        }
+        if game.fill_within(0, rng, restarts) {
+            return;
+        }
+    }
+}
+
+/**
+ * Generates a puzzle of the given size and difficulty, as defined by `config`, not attached to
+ * any save file.
+ *
+ * Independent attempts are run concurrently on every available core, the first puzzle matching
+ * the difficulty is returned and the other threads stop before their next attempt. If none
+ * matches after `MAX_GENERATION_ATTEMPTS` attempts, the closest one is returned. In
+ * WebAssembly, the attempts are run one after the other like in `generate_seeded`.
+ */
+pub fn generate(size: usize, difficulty: Difficulty, config: &GenerationConfig) -> Game {
+    // WebAssembly in the browsers has no threads
+    if cfg!(target_arch = "wasm32") {
+        return generate_seeded(size, difficulty, config, rand::random());
+    }
+
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let range = config.clues(difficulty, size);
+    let min_clues = config.min_clues(size);
+    let max_attempts = config.max_unfill_attempts(size, difficulty);
+    let done = AtomicBool::new(false);
+    let attempts = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            let (done, attempts, range, sender) = (&done, &attempts, &range, sender.clone());
+            scope.spawn(move || {
+                let mut game = Game::new(size, None).unwrap();
+                game.variant = config.variant;
+                while !done.load(Ordering::Relaxed)
+                    && attempts.fetch_add(1, Ordering::Relaxed) < MAX_GENERATION_ATTEMPTS
+                {
+                    fill_new(&mut game, config.jigsaw, &mut rand::thread_rng());
+                    // Easy puzzles must be solvable with singles only
+                    match difficulty {
+                        Difficulty::Easy => game.unfill(solver::Obvious, min_clues, max_attempts),
+                        _ => game.unfill(config.solver, min_clues, max_attempts),
+                    }
+
+                    let distance = mismatch(&game, difficulty, range);
+                    if distance == 0 {
+                        done.store(true, Ordering::Relaxed);
+                    }
+                    // The receiver only goes away once every thread is over
+                    let puzzle = (
+                        game.grid.clone(),
+                        game.solution.take(),
+                        game.regions().to_vec(),
+                    );
+                    sender.send((distance, puzzle)).unwrap();
+                }
+            });
+        }
+    });
+    drop(sender);
+
+    // Keep the first matching puzzle, or the closest one
+    let (_, (grid, solution, regions)) = receiver
+        .into_iter()
+        .min_by_key(|(distance, _)| *distance)
+        .unwrap();
+    let mut game = Game::new(size, None).unwrap();
+    game.variant = config.variant;
+    game.grid = grid;
+    game.solution = solution;
+    game.set_regions(regions);
+    game.difficulty = Some(difficulty);
+
+    game
+}
+
+/**
+ * Generates a puzzle like `generate`, always the same one for a given `seed`: the attempts are
+ * run one after the other, from a random number generator seeded with `seed`.
+ */
+pub fn generate_seeded(
+    size: usize,
+    difficulty: Difficulty,
+    config: &GenerationConfig,
+    seed: u64,
+) -> Game {
+    let range = config.clues(difficulty, size);
+    let min_clues = config.min_clues(size);
+    let max_attempts = config.max_unfill_attempts(size, difficulty);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut game = Game::new(size, None).unwrap();
+    game.variant = config.variant;
+    let mut closest: Option<(usize, Game)> = None;
+    for _ in 0..MAX_GENERATION_ATTEMPTS {
+        fill_new(&mut game, config.jigsaw, &mut rng);
+        match difficulty {
+            Difficulty::Easy => {
+                game.unfill_with(solver::Obvious, min_clues, max_attempts, &mut rng)
+            }
+            _ => game.unfill_with(config.solver, min_clues, max_attempts, &mut rng),
+        }
+
+        let distance = mismatch(&game, difficulty, &range);
+        if closest.as_ref().is_none_or(|(d, _)| distance < *d) {
+            let mut puzzle = Game::new(size, None).unwrap();
+            puzzle.variant = config.variant;
+            puzzle.set_regions(game.regions().to_vec());
+            puzzle.grid = game.grid.clone();
+            puzzle.solution = game.solution.take();
+            closest = Some((distance, puzzle));
+        }
+        if distance == 0 {
+            break;
+        }
+    }
+
+    let (_, mut game) = closest.unwrap();
+    game.difficulty = Some(difficulty);
+
+    game
+}
+
+/**
+ * Generates `count` puzzles of the given settings, spread over every available core: each thread
+ * generates whole puzzles one after the other, like `generate_seeded` with random seeds.
+ */
+pub fn generate_batch(
+    size: usize,
+    difficulty: Difficulty,
+    config: &GenerationConfig,
+    count: usize,
+) -> Vec<Game> {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let started = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.min(count))
+            .map(|_| {
+                let started = &started;
+                scope.spawn(move || {
+                    let mut puzzles = Vec::new();
+                    while started.fetch_add(1, Ordering::Relaxed) < count {
+                        puzzles.push(generate_seeded(size, difficulty, config, rand::random()));
+                    }
+                    puzzles
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect()
+    })
+}
+
+/**
+ * A generated puzzle as written in the JSON batches, with what is known about it.
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PuzzleRecord {
+    /// The puzzle, see `Game::puzzle_id`.
+    pub puzzle: String,
+    /// Its solution, written like the puzzle.
+    pub solution: String,
+    pub size: usize,
+    /// The difficulty it was generated for.
+    pub difficulty: Option<Difficulty>,
+    /// Its grade, see `grader::Grade`.
+    pub rating: String,
+    pub score: usize,
+    pub clues: usize,
+}
+
+impl PuzzleRecord {
+    /**
+     * Describes the puzzle of `game`.
+     */
+    pub fn new(game: &Game) -> Self {
+        PuzzleRecord {
+            puzzle: game.puzzle_id(),
+            solution: game
+                .solution()
+                .map(|values| values.into_iter().map(value_symbol).collect())
+                .unwrap_or_default(),
+            size: game.size,
+            difficulty: game.difficulty,
+            rating: game.rating().to_string(),
+            score: game.rating().score(),
+            clues: game.nb_clues(),
+        }
+    }
+}
+
+/**
+ * Generates puzzles of given settings in the background, so that the next one is ready by the
+ * time the player asks for it.
+ */
+pub struct Pool {
+    size: usize,
+    difficulty: Difficulty,
+    config: GenerationConfig,
+    /// The puzzle being generated, or already generated, in the background.
+    next: Option<JoinHandle<Game>>,
+}
+
+impl Pool {
+    /**
+     * Creates a pool of puzzles of the given size and difficulty, starting the generation of the
+     * first one.
+     */
+    pub fn new(size: usize, difficulty: Difficulty, config: &GenerationConfig) -> Self {
+        let mut pool = Pool {
+            size,
+            difficulty,
+            config: config.clone(),
+            next: None,
+        };
+        pool.refill();
+
+        pool
+    }
+
+    /**
+     * Starts generating the next puzzle in the background.
+     */
+    fn refill(&mut self) {
+        let (size, difficulty, config) = (self.size, self.difficulty, self.config.clone());
+        self.next = Some(thread::spawn(move || generate(size, difficulty, &config)));
+    }
+
+    /**
+     * Returns the pre-generated puzzle, waiting for it if it is not ready yet, and starts
+     * generating the following one.
+     */
+    pub fn take(&mut self) -> Game {
+        let game = match self.next.take().map(|next| next.join()) {
+            Some(Ok(game)) => game,
+            _ => generate(self.size, self.difficulty, &self.config),
+        };
+        self.refill();
+
+        game
+    }
+}
@@ -0,0 +1,85 @@
+use sdl2::pixels::Color;
+use sdl2::render::{Canvas, Texture};
+use sdl2::ttf::Font;
+use sdl2::video::Window;
+
+use std::collections::HashMap;
+
+use crate::errors::UiError;
+use crate::game::value_symbol;
+
+/**
+ * The textures of the value symbols drawn with a font, rendered once for each color they are
+ * drawn in and reused by every draw, until the font changes.
+ */
+#[derive(Default)]
+pub struct GlyphCache {
+    /// The texture of each (value, color) rendered.
+    textures: HashMap<(u8, Color), Texture>,
+    /// Whether the symbols are rendered anti-aliased.
+    blended: bool,
+}
+
+impl GlyphCache {
+    /**
+     * Creates a cache rendering its symbols anti-aliased, suiting the small fonts.
+     */
+    pub fn blended() -> Self {
+        GlyphCache {
+            blended: true,
+            ..Default::default()
+        }
+    }
+
+    /**
+     * Renders the symbols of the values 1 to `side_size` in `color` with `font`, the ones already
+     * rendered being kept.
+     */
+    pub fn prepare(
+        &mut self,
+        canvas: &Canvas<Window>,
+        font: &Font,
+        side_size: usize,
+        color: Color,
+    ) -> Result<(), UiError> {
+        let texture_creator = canvas.texture_creator();
+        for value in 1..=side_size as u8 {
+            if self.textures.contains_key(&(value, color)) {
+                continue;
+            }
+
+            let symbol = value_symbol(value).to_string();
+            let text = font.render(&symbol);
+            let surface = match self.blended {
+                true => text.blended(color),
+                false => text.solid(color),
+            }
+            .map_err(UiError::sdl2)?;
+            let texture = texture_creator
+                .create_texture_from_surface(surface)
+                .map_err(UiError::sdl2)?;
+            self.textures.insert((value, color), texture);
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Returns the texture of the symbol of `value` in `color`, which must have been prepared.
+     */
+    pub fn get(&self, value: u8, color: Color) -> Result<&Texture, UiError> {
+        self.textures
+            .get(&(value, color))
+            .ok_or(UiError::MissingLoadedTexture)
+    }
+
+    /**
+     * Destroys the textures, for the symbols to be rendered again with another font.
+     */
+    pub fn clear(&mut self) {
+        for (_, texture) in self.textures.drain() {
+            // The textures are only used through the cache, and their canvas outlives the screens
+            unsafe { texture.destroy() };
+        }
+    }
+}
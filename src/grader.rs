@@ -0,0 +1,694 @@
+use crate::game::{Cell, Game, Variant};
+use crate::generator::Difficulty;
+use crate::solver;
+
+use std::fmt;
+
+/**
+ * A human solving technique of the grading ladder. They are ordered from the simplest to the
+ * hardest, the hardest one a puzzle requires giving its grade.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Technique {
+    /// A cell has a single candidate left.
+    NakedSingle,
+    /// A value has a single cell left in a row, column or box.
+    HiddenSingle,
+    /// The candidates of a value in a box are all in the same row or column, so the value
+    /// cannot go anywhere else in that row or column.
+    PointingPair,
+    /// The candidates of a value in a row or column are all in the same box, so the value
+    /// cannot go anywhere else in that box.
+    BoxLineReduction,
+    /// Two cells of a unit share the same two candidates, which cannot go anywhere else in it.
+    NakedPair,
+    /// Two values of a unit can only go in the same two cells, which cannot take other values.
+    HiddenPair,
+    /// Like `NakedPair`, with three cells and three values.
+    NakedTriple,
+    /// Like `HiddenPair`, with three values and three cells.
+    HiddenTriple,
+    /// A value can only go in the same two columns of two rows, or the same two rows of two
+    /// columns, so it cannot go anywhere else in those columns, or rows.
+    XWing,
+    /// A cell can only take two values `x` and `y`, and two cells it sees can only take `x` and
+    /// `z`, and `y` and `z`: whichever value it takes, one of them holds `z`, so the cells seeing
+    /// both of them cannot.
+    XYWing,
+    /// Like `XWing`, with three rows and three columns.
+    Swordfish,
+}
+
+/// The techniques tried at each step, the simplest first.
+pub const LADDER: [Technique; 11] = [
+    Technique::NakedSingle,
+    Technique::HiddenSingle,
+    Technique::PointingPair,
+    Technique::BoxLineReduction,
+    Technique::NakedPair,
+    Technique::HiddenPair,
+    Technique::NakedTriple,
+    Technique::HiddenTriple,
+    Technique::XWing,
+    Technique::XYWing,
+    Technique::Swordfish,
+];
+
+impl fmt::Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Technique::NakedSingle => write!(f, "Naked single"),
+            Technique::HiddenSingle => write!(f, "Hidden single"),
+            Technique::PointingPair => write!(f, "Pointing pair"),
+            Technique::BoxLineReduction => write!(f, "Box/line reduction"),
+            Technique::NakedPair => write!(f, "Naked pair"),
+            Technique::HiddenPair => write!(f, "Hidden pair"),
+            Technique::NakedTriple => write!(f, "Naked triple"),
+            Technique::HiddenTriple => write!(f, "Hidden triple"),
+            Technique::XWing => write!(f, "X-wing"),
+            Technique::XYWing => write!(f, "XY-wing"),
+            Technique::Swordfish => write!(f, "Swordfish"),
+        }
+    }
+}
+
+/**
+ * How hard a puzzle is for a human, from the techniques needed to solve it.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Grade {
+    /// The hardest technique the puzzle required, `None` if it required none.
+    pub hardest: Option<Technique>,
+    /// Whether the techniques of the ladder were enough to solve the puzzle.
+    pub solved: bool,
+}
+
+impl Grade {
+    /**
+     * Returns the score of the puzzle: the rank of its hardest technique in the ladder, starting
+     * at 1, or one more than the last technique if the ladder was not enough.
+     */
+    pub fn score(&self) -> usize {
+        match (self.solved, self.hardest) {
+            (false, _) => LADDER.len() + 1,
+            (true, Some(technique)) => technique as usize + 1,
+            (true, None) => 0,
+        }
+    }
+
+    /**
+     * Returns the difficulty the puzzle is labelled with: easy if singles are enough, medium up
+     * to pairs, hard up to triples and expert beyond.
+     */
+    pub fn difficulty(&self) -> Difficulty {
+        match (self.solved, self.hardest) {
+            (true, None | Some(Technique::NakedSingle | Technique::HiddenSingle)) => {
+                Difficulty::Easy
+            }
+            (true, Some(technique)) if technique <= Technique::HiddenPair => Difficulty::Medium,
+            (true, Some(technique)) if technique <= Technique::HiddenTriple => Difficulty::Hard,
+            _ => Difficulty::Expert,
+        }
+    }
+}
+
+impl fmt::Display for Grade {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.solved, self.hardest) {
+            (false, _) => write!(f, "{:?} (beyond the known techniques)", self.difficulty()),
+            (true, Some(technique)) => write!(f, "{:?} ({})", self.difficulty(), technique),
+            (true, None) => write!(f, "{:?}", self.difficulty()),
+        }
+    }
+}
+
+/**
+ * Returns the bit of `value` in a candidates mask.
+ */
+fn bit(value: u8) -> u32 {
+    1 << value
+}
+
+/**
+ * Returns every combination of `k` items of `items`, in order.
+ */
+fn combinations(items: &[usize], k: usize) -> Vec<Vec<usize>> {
+    match (k, items) {
+        (0, _) => vec![vec![]],
+        (_, []) => vec![],
+        (_, [first, rest @ ..]) => {
+            let mut combinations_of = combinations(rest, k - 1);
+            for combination in combinations_of.iter_mut() {
+                combination.insert(0, *first);
+            }
+            combinations_of.extend(combinations(rest, k));
+            combinations_of
+        }
+    }
+}
+
+/**
+ * The grid being solved by the ladder, with the candidates of its empty cells. The hints are
+ * found on it too, see `hint::next_hint`.
+ */
+#[derive(Clone)]
+pub(crate) struct Board {
+    side_size: usize,
+    /// The box, or jigsaw region, of each cell.
+    regions: Vec<usize>,
+    pub(crate) values: Vec<u8>,
+    /// The candidates of each empty cell, as a mask of `bit(value)`.
+    pub(crate) candidates: Vec<u32>,
+    /// The cells of the rows, then of the columns, then of the boxes, then of the diagonals and
+    /// windows if they are units.
+    units: Vec<Vec<usize>>,
+    /// Whether the two diagonals are units, after the boxes.
+    diagonals: bool,
+    /// The cells the last technique applied looked at, such as the two cells of a naked pair.
+    pub(crate) pattern: Vec<usize>,
+    /// The unit of `units` the last technique applied worked in, if it works in one.
+    pub(crate) pattern_unit: Option<usize>,
+}
+
+impl Board {
+    /**
+     * Creates the board of the values of `game`, only its initial ones if `initial_only` is set,
+     * the values placed by the player being ignored.
+     */
+    fn new(game: &Game, initial_only: bool) -> Self {
+        let side_size = game.side_size;
+        let mut units: Vec<Vec<usize>> = (0..side_size)
+            .map(|r| game.row(r).collect())
+            .chain((0..side_size).map(|c| game.column(c).collect()))
+            .chain((0..side_size).map(|g| game.region(g).collect()))
+            .collect();
+        if game.variant == Variant::Diagonal {
+            units.push(game.diagonals(0, 0).collect());
+            units.push(game.diagonals(0, side_size - 1).collect());
+        }
+        units.extend((0..game.windows_count()).map(|w| game.window(w).collect()));
+        let all = (1..=side_size as u8).fold(0, |mask, v| mask | bit(v));
+        let mut board = Board {
+            side_size,
+            regions: game.regions().to_vec(),
+            values: vec![0; game.grid.len()],
+            candidates: vec![all; game.grid.len()],
+            units,
+            diagonals: game.variant == Variant::Diagonal,
+            pattern: Vec::new(),
+            pattern_unit: None,
+        };
+        for (index, cell) in game.grid.iter().enumerate() {
+            if (cell.initial() || !initial_only) && cell.value() != 0 {
+                board.place(index, cell.value());
+            }
+        }
+
+        board
+    }
+
+    /**
+     * Creates the board of every value of `game`, the candidates of the cells the player noted
+     * being restricted to their notes, as the hints are.
+     */
+    pub(crate) fn with_notes(game: &Game) -> Self {
+        let mut board = Board::new(game, false);
+        for (index, cell) in game.grid.iter().enumerate() {
+            if cell.notes() != 0 {
+                board.candidates[index] &= cell.notes();
+            }
+        }
+
+        board
+    }
+
+    /// Returns the name of `unit` in a sentence, such as `row 1`, the units being one based.
+    pub(crate) fn unit_name(&self, unit: usize) -> String {
+        let side_size = self.side_size;
+        let diagonals = match self.diagonals {
+            true => 2,
+            false => 0,
+        };
+        match unit / side_size {
+            0 => format!("row {}", unit + 1),
+            1 => format!("column {}", unit - side_size + 1),
+            2 => format!("box {}", unit - 2 * side_size + 1),
+            _ if unit == 3 * side_size && self.diagonals => String::from("main diagonal"),
+            _ if unit == 3 * side_size + 1 && self.diagonals => String::from("anti-diagonal"),
+            _ => format!("window {}", unit - 3 * side_size - diagonals + 1),
+        }
+    }
+
+    /// Keeps the cells the technique being applied looked at and its unit, see `pattern`,
+    /// returning that it made progress.
+    fn found(&mut self, pattern: Vec<usize>, unit: Option<usize>) -> bool {
+        self.pattern = pattern;
+        self.pattern_unit = unit;
+        true
+    }
+
+    /// Returns the units of the cell at `index`: its row, column and box, or jigsaw region.
+    fn units_of(&self, index: usize) -> [usize; 3] {
+        let (r, c) = (index / self.side_size, index % self.side_size);
+        [
+            r,
+            self.side_size + c,
+            2 * self.side_size + self.regions[index],
+        ]
+    }
+
+    /// Places `value` at `index`, removing it from the candidates of the cell's units, its
+    /// diagonal and window ones included.
+    fn place(&mut self, index: usize, value: u8) {
+        self.values[index] = value;
+        self.candidates[index] = 0;
+        let extra =
+            (3 * self.side_size..self.units.len()).filter(|u| self.units[*u].contains(&index));
+        let units: Vec<usize> = self.units_of(index).into_iter().chain(extra).collect();
+        for unit in units {
+            for cell in self.units[unit].iter() {
+                self.candidates[*cell] &= !bit(value);
+            }
+        }
+    }
+
+    /// Returns whether the empty cell at `index` can take `value`.
+    fn has(&self, index: usize, value: u8) -> bool {
+        self.values[index] == 0 && self.candidates[index] & bit(value) != 0
+    }
+
+    /// Returns the cells of `unit` that can take `value`.
+    pub(crate) fn spots(&self, unit: usize, value: u8) -> Vec<usize> {
+        self.units[unit]
+            .iter()
+            .copied()
+            .filter(|i| self.has(*i, value))
+            .collect()
+    }
+
+    /// Removes the candidates of `mask` from `cells`, returning whether any was removed.
+    fn remove(&mut self, cells: &[usize], mask: u32) -> bool {
+        let mut removed = false;
+        for cell in cells {
+            if self.values[*cell] == 0 && self.candidates[*cell] & mask != 0 {
+                self.candidates[*cell] &= !mask;
+                removed = true;
+            }
+        }
+
+        removed
+    }
+
+    /// Returns whether every cell holds a value.
+    fn is_solved(&self) -> bool {
+        self.values.iter().all(|v| *v != 0)
+    }
+
+    /// Returns whether an empty cell has no candidate left, the puzzle being invalid.
+    fn is_broken(&self) -> bool {
+        (0..self.values.len()).any(|i| self.values[i] == 0 && self.candidates[i] == 0)
+    }
+
+    /// Applies `technique` once, returning whether it made any progress.
+    pub(crate) fn apply(&mut self, technique: Technique) -> bool {
+        match technique {
+            Technique::NakedSingle => self.naked_single(),
+            Technique::HiddenSingle => self.hidden_single(),
+            Technique::PointingPair => self.pointing_pair(),
+            Technique::BoxLineReduction => self.box_line_reduction(),
+            Technique::NakedPair => self.naked_subset(2),
+            Technique::HiddenPair => self.hidden_subset(2),
+            Technique::NakedTriple => self.naked_subset(3),
+            Technique::HiddenTriple => self.hidden_subset(3),
+            Technique::XWing => self.fish(2),
+            Technique::XYWing => self.xy_wing(),
+            Technique::Swordfish => self.fish(3),
+        }
+    }
+
+    fn naked_single(&mut self) -> bool {
+        let single = (0..self.values.len())
+            .find(|i| self.values[*i] == 0 && self.candidates[*i].count_ones() == 1);
+        match single {
+            Some(index) => {
+                let value = self.candidates[index].trailing_zeros() as u8;
+                self.place(index, value);
+                let neighbors = (0..self.values.len())
+                    .filter(|i| self.sees(index, *i))
+                    .collect();
+                self.found(neighbors, None)
+            }
+            None => false,
+        }
+    }
+
+    fn hidden_single(&mut self) -> bool {
+        for unit in 0..self.units.len() {
+            for value in 1..=self.side_size as u8 {
+                if let [index] = self.spots(unit, value).as_slice() {
+                    self.place(*index, value);
+                    return self.found(self.units[unit].clone(), Some(unit));
+                }
+            }
+        }
+
+        false
+    }
+
+    fn pointing_pair(&mut self) -> bool {
+        for unit in 2 * self.side_size..3 * self.side_size {
+            for value in 1..=self.side_size as u8 {
+                let spots = self.spots(unit, value);
+                if spots.len() < 2 {
+                    continue;
+                }
+                // The row or column shared by every spot, if any
+                let [row, column, _] = self.units_of(spots[0]);
+                for line in [row, column] {
+                    if spots.iter().all(|i| self.units_of(*i).contains(&line)) {
+                        let others: Vec<usize> = self.units[line]
+                            .iter()
+                            .copied()
+                            .filter(|i| !self.units[unit].contains(i))
+                            .collect();
+                        if self.remove(&others, bit(value)) {
+                            return self.found(spots, Some(unit));
+                        }
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    fn box_line_reduction(&mut self) -> bool {
+        for unit in 0..2 * self.side_size {
+            for value in 1..=self.side_size as u8 {
+                let spots = self.spots(unit, value);
+                if spots.len() < 2 {
+                    continue;
+                }
+                let group = self.units_of(spots[0])[2];
+                if spots.iter().all(|i| self.units_of(*i)[2] == group) {
+                    let others: Vec<usize> = self.units[group]
+                        .iter()
+                        .copied()
+                        .filter(|i| !self.units[unit].contains(i))
+                        .collect();
+                    if self.remove(&others, bit(value)) {
+                        return self.found(spots, Some(unit));
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    /**
+     * Looks for `n` cells of a unit sharing `n` candidates, removing those from the other cells
+     * of the unit.
+     */
+    fn naked_subset(&mut self, n: usize) -> bool {
+        for unit in 0..self.units.len() {
+            let cells: Vec<usize> = self.units[unit]
+                .iter()
+                .copied()
+                .filter(|i| self.values[*i] == 0)
+                .filter(|i| (2..=n as u32).contains(&self.candidates[*i].count_ones()))
+                .collect();
+            for subset in combinations(&cells, n) {
+                let mask = subset.iter().fold(0, |mask, i| mask | self.candidates[*i]);
+                if mask.count_ones() as usize != n {
+                    continue;
+                }
+                let others: Vec<usize> = self.units[unit]
+                    .iter()
+                    .copied()
+                    .filter(|i| !subset.contains(i))
+                    .collect();
+                if self.remove(&others, mask) {
+                    return self.found(subset, Some(unit));
+                }
+            }
+        }
+
+        false
+    }
+
+    /**
+     * Looks for `n` values of a unit that can only go in the same `n` cells, removing the other
+     * candidates of those cells.
+     */
+    fn hidden_subset(&mut self, n: usize) -> bool {
+        for unit in 0..self.units.len() {
+            let values: Vec<usize> = (1..=self.side_size)
+                .filter(|v| (2..=n).contains(&self.spots(unit, *v as u8).len()))
+                .collect();
+            for subset in combinations(&values, n) {
+                let mut cells: Vec<usize> = subset
+                    .iter()
+                    .flat_map(|v| self.spots(unit, *v as u8))
+                    .collect();
+                cells.sort();
+                cells.dedup();
+                if cells.len() != n {
+                    continue;
+                }
+                let mask = subset.iter().fold(0, |mask, v| mask | bit(*v as u8));
+                if self.remove(&cells, !mask) {
+                    return self.found(cells, Some(unit));
+                }
+            }
+        }
+
+        false
+    }
+
+    /**
+     * Looks for a value that can only go in the same `n` columns of `n` rows, or the same `n`
+     * rows of `n` columns, removing it from the other cells of those columns, or rows.
+     */
+    fn fish(&mut self, n: usize) -> bool {
+        let side_size = self.side_size;
+        for value in 1..=side_size as u8 {
+            // Rows crossed by columns, then columns crossed by rows
+            for (lines, crosses) in [(0, side_size), (side_size, 0)] {
+                let positions: Vec<Vec<usize>> = (0..side_size)
+                    .map(|line| {
+                        (0..side_size)
+                            .filter(|p| self.has(self.units[lines + line][*p], value))
+                            .collect()
+                    })
+                    .collect();
+                let candidates: Vec<usize> = (0..side_size)
+                    .filter(|line| (2..=n).contains(&positions[*line].len()))
+                    .collect();
+                for fish in combinations(&candidates, n) {
+                    let mut covered: Vec<usize> = fish
+                        .iter()
+                        .flat_map(|line| positions[*line].iter().copied())
+                        .collect();
+                    covered.sort();
+                    covered.dedup();
+                    if covered.len() != n {
+                        continue;
+                    }
+                    let others: Vec<usize> = covered
+                        .iter()
+                        .flat_map(|p| self.units[crosses + p].iter().copied())
+                        .filter(|i| !fish.iter().any(|line| self.units[lines + line].contains(i)))
+                        .collect();
+                    let units = &self.units;
+                    let pattern = fish
+                        .iter()
+                        .flat_map(|line| {
+                            positions[*line]
+                                .iter()
+                                .map(move |p| units[lines + line][*p])
+                        })
+                        .collect();
+                    if self.remove(&others, bit(value)) {
+                        return self.found(pattern, None);
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Returns whether the cells at `a` and `b` are different and share a unit.
+    fn sees(&self, a: usize, b: usize) -> bool {
+        a != b
+            && self
+                .units
+                .iter()
+                .any(|unit| unit.contains(&a) && unit.contains(&b))
+    }
+
+    fn xy_wing(&mut self) -> bool {
+        let pairs: Vec<usize> = (0..self.values.len())
+            .filter(|i| self.values[*i] == 0 && self.candidates[*i].count_ones() == 2)
+            .collect();
+        for pivot in pairs.iter().copied() {
+            let wings: Vec<usize> = pairs
+                .iter()
+                .copied()
+                .filter(|i| self.sees(pivot, *i))
+                // Each wing shares a single value with the pivot
+                .filter(|i| (self.candidates[*i] & self.candidates[pivot]).count_ones() == 1)
+                .collect();
+            for (a, first) in wings.iter().enumerate() {
+                for second in wings[a + 1..].iter() {
+                    let (x, y) = (self.candidates[*first], self.candidates[*second]);
+                    // The wings hold both values of the pivot, along with the same other value
+                    let z = x & y & !self.candidates[pivot];
+                    if z.count_ones() != 1
+                        || (x | y) & self.candidates[pivot] != self.candidates[pivot]
+                    {
+                        continue;
+                    }
+                    let others: Vec<usize> = (0..self.values.len())
+                        .filter(|i| self.sees(*first, *i) && self.sees(*second, *i))
+                        .collect();
+                    if self.remove(&others, z) {
+                        return self.found(vec![pivot, *first, *second], None);
+                    }
+                }
+            }
+        }
+
+        false
+    }
+}
+
+/**
+ * A step of the solution of a grid with the techniques of the ladder.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolveStep {
+    pub technique: Technique,
+    /// The index of the cell where the step placed a value, and that value, if it placed one.
+    pub placed: Option<(usize, u8)>,
+    /// The candidates the step removed, as the index of their cell and their value, the ones
+    /// removed from the neighbors of a placed value being left out.
+    pub eliminated: Vec<(usize, u8)>,
+}
+
+impl SolveStep {
+    /**
+     * Explains the step in a sentence, the rows and columns being one based.
+     */
+    pub fn describe(&self, game: &Game) -> String {
+        let position = |index: usize| {
+            let (r, c) = game.coordinates(index);
+            format!("row {}, column {}", r + 1, c + 1)
+        };
+
+        match self.placed {
+            Some((index, value)) => format!(
+                "{}: place {} at {}.",
+                self.technique,
+                value,
+                position(index)
+            ),
+            None => {
+                let mut values: Vec<u8> = self.eliminated.iter().map(|(_, v)| *v).collect();
+                values.sort();
+                values.dedup();
+                let removals: Vec<String> = values
+                    .iter()
+                    .map(|value| {
+                        let cells: Vec<String> = self
+                            .eliminated
+                            .iter()
+                            .filter(|(_, v)| v == value)
+                            .map(|(i, _)| position(*i))
+                            .collect();
+                        format!("{} from {}", value, cells.join("; "))
+                    })
+                    .collect();
+                format!("{}: remove {}.", self.technique, removals.join(", and "))
+            }
+        }
+    }
+}
+
+/**
+ * Solves `board` with `techniques`, always trying the first one of them that makes progress.
+ * Returns the steps taken, and whether the board got solved.
+ */
+fn run(board: &mut Board, techniques: &[Technique]) -> (Vec<SolveStep>, bool) {
+    let mut steps = Vec::new();
+    while !board.is_solved() {
+        if board.is_broken() {
+            return (steps, false);
+        }
+        let (values, candidates) = (board.values.clone(), board.candidates.clone());
+        let Some(technique) = techniques.iter().find(|technique| board.apply(**technique)) else {
+            return (steps, false);
+        };
+
+        // Tell the step from what changed on the board
+        let placed = (0..values.len())
+            .find(|i| values[*i] != board.values[*i])
+            .map(|i| (i, board.values[i]));
+        let eliminated = match placed {
+            Some(_) => Vec::new(),
+            None => (0..values.len())
+                .flat_map(|i| {
+                    let removed = candidates[i] & !board.candidates[i];
+                    solver::values_of(removed).into_iter().map(move |v| (i, v))
+                })
+                .collect(),
+        };
+        steps.push(SolveStep {
+            technique: *technique,
+            placed,
+            eliminated,
+        });
+    }
+
+    (steps, true)
+}
+
+/**
+ * Returns the steps solving `game` with `techniques` only, starting from all of its values, along
+ * with whether they were enough. The grid is left as it was.
+ */
+pub fn trace(game: &Game, techniques: &[Technique]) -> (Vec<SolveStep>, bool) {
+    run(&mut Board::new(game, false), techniques)
+}
+
+/**
+ * Solves `game` with `techniques` only, starting from all of its values, and returns the steps
+ * taken along with whether they were enough. The grid is only filled when they were.
+ */
+pub fn solve_with(game: &mut Game, techniques: &[Technique]) -> (Vec<SolveStep>, bool) {
+    let mut board = Board::new(game, false);
+    let (steps, solved) = run(&mut board, techniques);
+    if solved {
+        for (cell, value) in game.grid.iter_mut().zip(board.values) {
+            if *cell == 0 {
+                *cell = Cell::new(value, false);
+            }
+        }
+    }
+
+    (steps, solved)
+}
+
+/**
+ * Grades the puzzle of `game`, its initial values, by solving it with the techniques of the
+ * ladder, always trying the simplest one first.
+ */
+pub fn grade(game: &Game) -> Grade {
+    let (steps, solved) = run(&mut Board::new(game, true), &LADDER);
+
+    Grade {
+        hardest: steps.into_iter().map(|step| step.technique).max(),
+        solved,
+    }
+}
@@ -1,33 +1,87 @@
-use sdl2::event::Event;
+use sdl2::event::{Event, WindowEvent};
+use sdl2::image::{LoadSurface, SaveSurface};
 use sdl2::keyboard::Keycode;
 use sdl2::mouse::MouseButton;
+use sdl2::pixels::PixelFormatEnum;
 use sdl2::render::Canvas;
 use sdl2::render::WindowCanvas;
+use sdl2::surface::Surface;
 use sdl2::ttf::FontStyle;
 use sdl2::ttf::{Font, Sdl2TtfContext};
-use sdl2::video::Window;
+use sdl2::video::{FullscreenType, Window};
 use sdl2::EventPump;
 use sdl2::Sdl;
 
 use core::time::Duration;
 use std::fs;
-use std::fs::File;
-use std::io::prelude::Write;
-use std::path::PathBuf;
 use std::rc::Rc;
 
-use crate::errors::UiError;
-use crate::game::Game;
+use crate::annotations;
+#[cfg(feature = "audio")]
+use crate::audio::{Audio, Sound};
+use crate::autosave::Autosave;
+use crate::config;
+use crate::editor_screen::EditorScreen;
+#[cfg(feature = "audio")]
+use crate::errors;
+use crate::errors::{GameError, UiError};
+#[cfg(feature = "fetch")]
+use crate::fetch;
+use crate::game::{Game, Variant};
 use crate::game_screen::GameScreen;
+use crate::generator;
+use crate::hotseat::HotSeat;
+use crate::layout::scaled_font_size;
+use crate::load_screen::LoadScreen;
+#[cfg(feature = "net")]
+use crate::lobby_screen::LobbyScreen;
+use crate::logging;
 use crate::main_screen::MainScreen;
-use crate::solver;
+#[cfg(feature = "net")]
+use crate::net::{Link, Message, NetEvent, NetGame, NetMode, PROTOCOL_VERSION};
+use crate::new_game_screen::{NewGameOptions, NewGameScreen};
+use crate::overlay::{self, DebugOverlay};
+use crate::pack_screen::PackScreen;
+use crate::profile::Profiles;
+use crate::race::Race;
+use crate::race_screen::{RaceScreen, RACE_SIZE};
+use crate::samurai::SamuraiGame;
+use crate::samurai_screen::SamuraiScreen;
+use crate::settings_screen::{Settings, SettingsScreen};
+use crate::time_attack::TimeAttack;
 use crate::traits::{Displayable, GUIConfig, ScreenOutcome, Ui};
+use crate::victory_screen::VictoryScreen;
+
+const WINDOW_TITLE: &str = "Sudoku (Rust)";
+/// Shortest wait between two draws, while the screen animates.
+const FRAME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+/// Longest wait for an event, the screen being checked for changes at least that often.
+const IDLE_WAIT: Duration = Duration::from_secs(1);
+/// Point size of the font at the configured window size.
+const FONT_SIZE: u16 = 30;
+/// Point size of the font used for the notes, at the configured window size.
+const NOTES_FONT_SIZE: u16 = 12;
+/// Number of the best time attacks listed on the victory screen once one is finished.
+const LEADERBOARD_SHOWN: usize = 3;
+/// Longest wait for an event while a network game runs, its connection being polled that often.
+#[cfg(feature = "net")]
+const NET_POLL: Duration = Duration::from_millis(100);
 
 #[derive(Default, PartialEq, Eq)]
 pub enum Screen {
     #[default]
     Main,
     Game,
+    Packs,
+    Saves,
+    NewGame,
+    Victory,
+    Samurai,
+    Race,
+    Editor,
+    Settings,
+    #[cfg(feature = "net")]
+    Lobby,
 }
 
 pub struct Gui<'a> {
@@ -37,105 +91,304 @@ pub struct Gui<'a> {
     event_pump: EventPump,
     /// Loaded SDL2 font pointer
     font: Rc<Font<'a, 'a>>,
+    /// Smaller version of the font, used for the notes
+    notes_font: Rc<Font<'a, 'a>>,
+    /// SDL2 TTF context, to load the fonts again at another size
+    ttf_context: &'a Sdl2TtfContext,
+    /// Point size the font was loaded at, following the size of the canvas
+    font_size: u16,
 
-    /// Looaded configuration file path
-    config_path: PathBuf,
     /// Loaded config file
     config: GUIConfig,
+    /// Where the config file was loaded from, to save the settings back to it
+    config_path: String,
 
     /// Currently displayed screen
     current_screen: Screen,
 
     /// Main screen instance
-    main_screen: Option<MainScreen>,
+    main_screen: Option<MainScreen<'a>>,
     /// Game screen instance
     game_screen: Option<GameScreen<'a>>,
+    /// Puzzle pack browser instance
+    pack_screen: Option<PackScreen<'a>>,
+    /// Saved games browser instance
+    load_screen: Option<LoadScreen<'a>>,
+    /// New game options screen instance
+    new_game_screen: Option<NewGameScreen<'a>>,
+    /// Completion screen instance
+    victory_screen: Option<VictoryScreen<'a>>,
+    /// Samurai puzzle screen instance
+    samurai_screen: Option<SamuraiScreen<'a>>,
+    /// Split-screen race screen instance
+    race_screen: Option<RaceScreen<'a>>,
+    /// Puzzle editor instance
+    editor_screen: Option<EditorScreen<'a>>,
+    /// Settings screen instance
+    settings_screen: Option<SettingsScreen<'a>>,
+    /// Network game lobby instance
+    #[cfg(feature = "net")]
+    lobby_screen: Option<LobbyScreen<'a>>,
+
+    /// Puzzles generated in the background with the configured settings
+    pool: generator::Pool,
+    /// The running session, to recover its game if it crashes
+    autosave: Autosave,
+    /// The players and their statistics, one of them playing
+    profiles: Profiles,
+    /// The time attack being played, its puzzles following one another on the game screen
+    time_attack: Option<TimeAttack>,
+    /// The puzzle shared with another instance over the network, once hosted or joined
+    #[cfg(feature = "net")]
+    network: Option<NetGame>,
+    /// The sound effects and the music, if the audio device could be opened
+    #[cfg(feature = "audio")]
+    audio: Option<Audio>,
+    /// The frame rate, last events and timings shown over the screens with F3
+    overlay: DebugOverlay,
+}
+
+/**
+ * Loads the font at `path` in bold at `size` points, and the smaller one used for the notes.
+ */
+fn load_fonts<'a>(
+    ttf_context: &'a Sdl2TtfContext,
+    path: &str,
+    size: u16,
+) -> Result<(Font<'a, 'a>, Font<'a, 'a>), UiError> {
+    let mut font = match ttf_context.load_font(path, size) {
+        Err(e) => return Err(UiError::LoadFontError(e.into())),
+        Ok(font) => font,
+    };
+    font.set_style(FontStyle::BOLD);
+
+    let notes_size = (size as u32 * NOTES_FONT_SIZE as u32 / FONT_SIZE as u32).max(6) as u16;
+    let notes_font = match ttf_context.load_font(path, notes_size) {
+        Err(e) => return Err(UiError::LoadFontError(e.into())),
+        Ok(font) => font,
+    };
+
+    Ok((font, notes_font))
+}
+
+/**
+ * Converts the position of a mouse event from window coordinates to canvas pixels, `ratio`
+ * being the number of pixels per window coordinate, above 1 on high DPI displays.
+ */
+fn to_pixels(mut event: Event, ratio: f32) -> Event {
+    match &mut event {
+        Event::MouseButtonDown { x, y, .. }
+        | Event::MouseButtonUp { x, y, .. }
+        | Event::MouseMotion { x, y, .. } => {
+            *x = (*x as f32 * ratio) as i32;
+            *y = (*y as f32 * ratio) as i32;
+        }
+        _ => {}
+    }
+
+    event
 }
 
 impl<'a> Gui<'a> {
     pub fn new(
         sdl_context: &'a Sdl,
         ttf_context: &'a Sdl2TtfContext,
-        config_path: &String,
+        config_path: &str,
     ) -> Result<Self, UiError> {
         // Config loader.
-        let config_txt = match fs::read_to_string(config_path) {
+        let config_txt = match config::read_config(config_path) {
             Ok(config) => config,
             Err(e) => {
-                eprintln!(
+                log::error!(
                     "Error while loading the configuration file {}: {}",
-                    config_path, e
+                    config_path,
+                    e
                 );
 
-                return Err(UiError::LoadConfigError);
+                return Err(UiError::LoadConfigError(e));
             }
         };
 
         let config: GUIConfig = match serde_json::from_str(&config_txt) {
             Ok(config) => config,
             Err(e) => {
-                eprintln!(
+                log::error!(
                     "Error while loading the configuration file {}: {}",
-                    config_path, e
+                    config_path,
+                    e
                 );
 
-                return Err(UiError::ConfigSyntaxError);
+                return Err(UiError::ConfigSyntaxError(e));
             }
         };
+        config.generation.validate()?;
+        generator::validate_size(config.game_size)?;
 
         let video_subsystem = sdl_context.video().unwrap();
-        let window = video_subsystem
-            .window("Sudoku (Rust)", config.res_x as u32, config.res_y as u32)
+        let mut window = video_subsystem
+            .window(WINDOW_TITLE, config.res_x as u32, config.res_y as u32)
             .position_centered()
+            .resizable()
+            .allow_highdpi()
             .build()
             .unwrap();
+
+        // Set the window icon if one is configured
+        if !config.window_icon_path.is_empty() {
+            match Surface::from_file(&config.window_icon_path) {
+                Ok(icon) => window.set_icon(icon),
+                Err(e) => return Err(UiError::LoadSpriteError(e.into())),
+            }
+        }
         let canvas = window.into_canvas().build().unwrap();
 
         let event_pump = sdl_context.event_pump().unwrap();
 
-        let mut font = match ttf_context.load_font(&config.font_path, 30) {
+        // On high DPI displays, the canvas has more pixels than the window
+        let (_, height) = canvas.output_size().map_err(UiError::sdl2)?;
+        let font_size = scaled_font_size(FONT_SIZE, height, config.res_y as u32);
+        let (font, notes_font) = load_fonts(ttf_context, &config.font_path, font_size)?;
+
+        // Start generating puzzles right away
+        let pool = generator::Pool::new(config.game_size, config.difficulty, &config.generation);
+        let autosave = Autosave::start(&config.save_folder_path)?;
+        let profiles = Profiles::load(&config.save_folder_path)?;
+
+        // The game goes on silently without an audio device
+        #[cfg(feature = "audio")]
+        let audio = match Audio::new(&config.audio) {
+            Ok(audio) => Some(audio),
             Err(e) => {
-                eprintln!("{}", e);
-                return Err(UiError::LoadFontError);
+                log::warn!("{}", errors::report(&e));
+                None
             }
-            Ok(font) => font,
         };
-        font.set_style(FontStyle::BOLD);
 
         Ok(Gui {
             canvas,
             event_pump,
             font: Rc::new(font),
+            notes_font: Rc::new(notes_font),
+            ttf_context,
+            font_size,
 
-            config_path: PathBuf::from(config_path),
             config,
+            config_path: config_path.to_string(),
 
             current_screen: Screen::Main,
             main_screen: None,
             game_screen: None,
+            pack_screen: None,
+            load_screen: None,
+            new_game_screen: None,
+            victory_screen: None,
+            samurai_screen: None,
+            race_screen: None,
+            editor_screen: None,
+            settings_screen: None,
+            #[cfg(feature = "net")]
+            lobby_screen: None,
+
+            pool,
+            autosave,
+            profiles,
+            time_attack: None,
+            #[cfg(feature = "net")]
+            network: None,
+            #[cfg(feature = "audio")]
+            audio,
+            overlay: DebugOverlay::default(),
         })
     }
 
     pub fn init(&mut self) -> Result<(), UiError> {
         self.main_screen = Some(MainScreen::new());
-        self.main_screen
-            .as_mut()
-            .unwrap()
-            .init(&mut self.canvas, &self.config)?;
+        let main_screen = self.main_screen.as_mut().unwrap();
+        main_screen.set_font(self.font.clone());
+        main_screen.init(&mut self.canvas, &self.config)?;
         self.game_screen = Some(GameScreen::new());
         self.game_screen
             .as_mut()
             .unwrap()
             .init(&mut self.canvas, &self.config)?;
 
-        self.game_screen
+        self.pack_screen = Some(PackScreen::new());
+        self.pack_screen
             .as_mut()
             .unwrap()
-            .set_font(self.font.clone());
+            .init(&mut self.canvas, &self.config)?;
+        self.load_screen = Some(LoadScreen::new());
+        self.load_screen
+            .as_mut()
+            .unwrap()
+            .init(&mut self.canvas, &self.config)?;
+        self.new_game_screen = Some(NewGameScreen::new());
+        self.new_game_screen
+            .as_mut()
+            .unwrap()
+            .init(&mut self.canvas, &self.config)?;
+        self.victory_screen = Some(VictoryScreen::new());
+        self.victory_screen
+            .as_mut()
+            .unwrap()
+            .init(&mut self.canvas, &self.config)?;
+        self.samurai_screen = Some(SamuraiScreen::new());
+        self.samurai_screen
+            .as_mut()
+            .unwrap()
+            .init(&mut self.canvas, &self.config)?;
+        self.race_screen = Some(RaceScreen::new());
+        self.race_screen
+            .as_mut()
+            .unwrap()
+            .init(&mut self.canvas, &self.config)?;
+        self.editor_screen = Some(EditorScreen::new());
+        self.editor_screen
+            .as_mut()
+            .unwrap()
+            .init(&mut self.canvas, &self.config)?;
+        self.settings_screen = Some(SettingsScreen::new());
+        self.settings_screen
+            .as_mut()
+            .unwrap()
+            .init(&mut self.canvas, &self.config)?;
+        #[cfg(feature = "net")]
+        {
+            self.lobby_screen = Some(LobbyScreen::new());
+            self.lobby_screen
+                .as_mut()
+                .unwrap()
+                .init(&mut self.canvas, &self.config)?;
+        }
 
-        // If a game was loaded, set the boolean
-        if self.game_screen.as_ref().unwrap().has_game() {
-            self.main_screen.as_mut().unwrap().has_current_game = true;
+        self.set_fonts();
+
+        // The daily puzzle is offered once its endpoint is configured
+        #[cfg(feature = "fetch")]
+        if !self.config.daily_puzzle_url.is_empty() {
+            self.main_screen
+                .as_mut()
+                .unwrap()
+                .add_daily_button(&mut self.canvas, &self.font)?;
+        }
+
+        // The profile playing is shown on the main screen
+        self.main_screen.as_mut().unwrap().set_profile(
+            &mut self.canvas,
+            &self.font,
+            &self.profiles.current().name,
+        )?;
+
+        // The saved games can be resumed from the main screen
+        self.main_screen.as_mut().unwrap().has_saves =
+            self.load_screen.as_ref().unwrap().has_saves();
+
+        // Go back to the game of the previous session if it crashed
+        if let Some(path) = self.autosave.recovered() {
+            match self.resume_game(&path) {
+                Ok(()) => self.current_screen = Screen::Game,
+                Err(e) => log::error!("Unable to recover the game {}: {}", path, e),
+            }
         }
 
         Ok(())
@@ -144,98 +397,416 @@ impl<'a> Gui<'a> {
     pub fn run(&mut self) -> Result<(), UiError> {
         // TODO: This does not solve the first black screen
         let mut outcome;
-        self.main_screen.as_mut().unwrap().draw(&mut self.canvas)?;
+        self.draw_current()?;
+
+        // An unknown or empty key name disables the screenshots
+        let screenshot_key = Keycode::from_name(&self.config.screenshot_key);
 
         'running: loop {
-            //if let Some(event) = self.event_pump.poll_event() {
-            for event in self.event_pump.poll_iter() {
+            let ratio = self.pixel_ratio();
+
+            // Sleep until the next event, or until the screen changes by itself, then handle the
+            // pending events before drawing the screen once
+            let wait = self.next_redraw();
+            let mut first = self.event_pump.wait_event_timeout(wait.as_millis() as u32);
+            #[cfg(feature = "audio")]
+            let logged = self.logged_events();
+            let mut dirty = false;
+            while let Some(event) = first.take().or_else(|| self.event_pump.poll_event()) {
+                let event = to_pixels(event, ratio);
+                self.overlay.record_event(&event);
                 match event {
-                    Event::Quit { .. }
-                    | Event::KeyDown {
+                    Event::Quit { .. } => break 'running,
+                    // The open questions, the unsaved samurai puzzles, the races, the editor and
+                    // the screens going back to the main menu take the escape key
+                    Event::KeyDown {
                         keycode: Some(Keycode::Escape),
                         ..
+                    } if !matches!(
+                        self.current_screen,
+                        Screen::Samurai
+                            | Screen::Race
+                            | Screen::Editor
+                            | Screen::Packs
+                            | Screen::Saves
+                            | Screen::NewGame
+                            | Screen::Settings
+                    ) && !self.main_screen.as_ref().unwrap().has_dialog()
+                        && !self.game_screen.as_ref().unwrap().has_dialog() =>
+                    {
+                        break 'running
+                    }
+                    Event::KeyDown {
+                        keycode: Some(keycode),
+                        ..
+                    } if Some(keycode) == screenshot_key => {
+                        match self.screenshot() {
+                            Ok(path) => log::info!("Screenshot saved to {}", path),
+                            Err(e) => log::error!("{}", e),
+                        }
+                        continue 'running;
+                    }
+                    Event::Window {
+                        win_event: WindowEvent::SizeChanged(..),
+                        ..
                     } => {
-                        // If there is an ongoing game that isn't over, write its path in the
-                        // configuration file as the game to resume in the next launch.
-                        if self.current_screen == Screen::Game
-                            && !self.game_screen.as_ref().unwrap().is_over()
-                        {
-                            self.config.game_resume_path = String::from(
-                                self.game_screen
-                                    .as_ref()
-                                    .unwrap()
-                                    .game
-                                    .as_ref()
-                                    .unwrap()
-                                    .save_path
-                                    .as_ref()
-                                    .unwrap()
-                                    .as_path()
-                                    .to_str()
-                                    .unwrap(),
-                            );
-
-                            if let Ok(mut file) = File::create(&self.config_path) {
-                                if let Ok(config_txt) = serde_json::to_string_pretty(&self.config) {
-                                    if file.write_all(config_txt.as_bytes()).is_err() {
-                                        return Err(UiError::WriteConfigError);
-                                    }
-                                } else {
-                                    return Err(UiError::WriteConfigError);
-                                }
-                            } else {
-                                return Err(UiError::WriteConfigError);
-                            }
+                        self.resize()?;
+                        continue 'running;
+                    }
+                    Event::KeyDown {
+                        keycode: Some(Keycode::F3),
+                        ..
+                    } => {
+                        self.overlay.toggle();
+                        self.draw_current()?;
+                        continue 'running;
+                    }
+                    Event::KeyDown {
+                        keycode: Some(Keycode::F11),
+                        ..
+                    } => {
+                        self.toggle_fullscreen()?;
+                        continue 'running;
+                    }
+                    #[cfg(feature = "audio")]
+                    Event::KeyDown {
+                        keycode: Some(Keycode::F9),
+                        ..
+                    } => {
+                        if let Some(audio) = self.audio.as_mut() {
+                            audio.toggle_mute();
                         }
-
-                        break 'running;
+                        continue 'running;
                     }
                     Event::MouseButtonUp {
                         mouse_btn: MouseButton::Left,
                         ..
+                    }
+                    | Event::MouseMotion { .. }
+                    | Event::KeyDown { .. }
+                    | Event::MouseWheel { .. } => {
+                        outcome = self.current_screen_mut().update(&event)?;
+                    }
+                    Event::MouseButtonDown {
+                        mouse_btn: MouseButton::Left,
+                        ..
                     } => {
-                        match self.current_screen {
-                            Screen::Main => {
-                                outcome = self.main_screen.as_mut().unwrap().update(&event)?;
-                            }
-                            Screen::Game => {
-                                outcome = self.game_screen.as_mut().unwrap().update(&event)?;
-                            }
+                        // Only the grid can be dragged over, to select several cells
+                        outcome = match self.current_screen {
+                            Screen::Game => self.game_screen.as_mut().unwrap().update(&event)?,
+                            _ => ScreenOutcome::Unchanged,
                         };
                     }
-                    Event::MouseMotion { .. } => match self.current_screen {
-                        Screen::Main => {
-                            outcome = self.main_screen.as_mut().unwrap().update(&event)?;
-                        }
-                        Screen::Game => {
-                            outcome = ScreenOutcome::Unchanged;
-                        }
-                    },
                     _ => {
                         outcome = ScreenOutcome::Unchanged;
                     }
                 }
 
-                // println!("Event: [{:?}] -> Outcome: [{:?}]", event, outcome);
+                log::trace!("Event: [{:?}] -> Outcome: [{:?}]", event, outcome);
 
                 match outcome {
-                    ScreenOutcome::Updated => match self.current_screen {
-                        Screen::Main => {
-                            self.main_screen.as_mut().unwrap().draw(&mut self.canvas)?;
-                        }
-                        Screen::Game => {
-                            self.game_screen.as_mut().unwrap().draw(&mut self.canvas)?;
-                        }
-                    },
+                    // Drawn once the pending events are handled
+                    ScreenOutcome::Updated => dirty = true,
                     ScreenOutcome::Resume => {
                         self.current_screen = Screen::Game;
-                        self.game_screen.as_mut().unwrap().draw(&mut self.canvas)?;
+                        self.draw_current()?;
                         continue 'running;
                     }
                     ScreenOutcome::NewGame => {
-                        self.new_random_game()?;
-                        self.current_screen = Screen::Game;
-                        self.game_screen.as_mut().unwrap().draw(&mut self.canvas)?;
+                        match self.new_random_game() {
+                            Ok(()) => {
+                                self.current_screen = Screen::Game;
+                                self.draw_current()?;
+                            }
+                            Err(e) => log::error!("Unable to start a new game: {}", e),
+                        }
+                        continue 'running;
+                    }
+                    ScreenOutcome::Packs => {
+                        let favorites = self.profiles.current().favorites.clone();
+                        self.pack_screen.as_mut().unwrap().set_favorites(favorites);
+                        self.current_screen = Screen::Packs;
+                        self.draw_current()?;
+                        continue 'running;
+                    }
+                    ScreenOutcome::Saves => {
+                        self.current_screen = Screen::Saves;
+                        let favorites = self.profiles.current().favorites.clone();
+                        let load_screen = self.load_screen.as_mut().unwrap();
+                        load_screen.set_favorites(favorites);
+                        load_screen.reload();
+                        self.draw_current()?;
+                        continue 'running;
+                    }
+                    ScreenOutcome::Load => {
+                        let path = self.load_screen.as_mut().unwrap().chosen.take().unwrap();
+                        match self.resume_game(&path) {
+                            Ok(()) => {
+                                self.current_screen = Screen::Game;
+                                self.draw_current()?;
+                            }
+                            Err(e) => {
+                                let load_screen = self.load_screen.as_mut().unwrap();
+                                load_screen.set_message(e.to_string());
+                                self.draw_current()?;
+                            }
+                        }
+                        continue 'running;
+                    }
+                    ScreenOutcome::ChooseNewGame => {
+                        self.current_screen = Screen::NewGame;
+                        self.draw_current()?;
+                        continue 'running;
+                    }
+                    ScreenOutcome::Settings => {
+                        // Start from the saved settings, the unsaved ones being forgotten
+                        self.current_screen = Screen::Settings;
+                        let settings_screen = self.settings_screen.as_mut().unwrap();
+                        settings_screen.init(&mut self.canvas, &self.config)?;
+                        self.draw_current()?;
+                        continue 'running;
+                    }
+                    ScreenOutcome::SaveSettings => {
+                        match self.apply_settings() {
+                            Ok(()) => {
+                                self.current_screen = Screen::Main;
+                                self.draw_current()?;
+                            }
+                            Err(e) => {
+                                let settings_screen = self.settings_screen.as_mut().unwrap();
+                                settings_screen.set_message(e.to_string());
+                                self.draw_current()?;
+                            }
+                        }
+                        continue 'running;
+                    }
+                    ScreenOutcome::Generate => {
+                        let options = self.new_game_screen.as_mut().unwrap().chosen.take();
+                        match self.new_game(options.unwrap()) {
+                            Ok(()) => {
+                                self.current_screen = Screen::Game;
+                                self.draw_current()?;
+                            }
+                            Err(e) => {
+                                let new_game_screen = self.new_game_screen.as_mut().unwrap();
+                                new_game_screen.set_message(e.to_string());
+                                self.draw_current()?;
+                            }
+                        }
+                        continue 'running;
+                    }
+                    ScreenOutcome::Play => {
+                        let saving_path = self.new_saving_path();
+                        let pack_screen = self.pack_screen.as_mut().unwrap();
+                        // The favorites keep their variant, regions and cages
+                        let game = match pack_screen.chosen_favorite.take() {
+                            Some(favorite) => favorite.to_game(Some(&saving_path)),
+                            None => {
+                                let puzzle = pack_screen.chosen.take().unwrap();
+                                Game::from_puzzle(&puzzle, Some(&saving_path))
+                            }
+                        };
+                        let started = game
+                            .map_err(UiError::from)
+                            .and_then(|game| self.start_game(game));
+                        match started {
+                            Ok(()) => {
+                                self.current_screen = Screen::Game;
+                                self.draw_current()?;
+                            }
+                            Err(e) => {
+                                let pack_screen = self.pack_screen.as_mut().unwrap();
+                                pack_screen.set_message(e.to_string());
+                                self.draw_current()?;
+                            }
+                        }
+                        continue 'running;
+                    }
+                    ScreenOutcome::Menu => {
+                        self.show_menu()?;
+                        continue 'running;
+                    }
+                    ScreenOutcome::BackToMenu => {
+                        // Keep the play time of the open games, listed then in the saves
+                        if let Err(e) = self.game_screen.as_mut().unwrap().save_games() {
+                            log::error!("{}", e);
+                        }
+                        self.show_menu()?;
+                        continue 'running;
+                    }
+                    #[cfg(feature = "fetch")]
+                    ScreenOutcome::Daily => {
+                        match fetch::daily_puzzle(
+                            &self.config.daily_puzzle_url,
+                            &self.config.save_folder_path,
+                            Some(&self.new_saving_path()),
+                        ) {
+                            Ok(game) => match self.start_game(game) {
+                                Ok(()) => {
+                                    self.current_screen = Screen::Game;
+                                    self.draw_current()?;
+                                }
+                                Err(e) => log::error!("Unable to start the daily puzzle: {}", e),
+                            },
+                            Err(e) => log::error!("Unable to get the daily puzzle: {}", e),
+                        }
+                        continue 'running;
+                    }
+                    ScreenOutcome::Paste => {
+                        match self.paste_game() {
+                            Ok(()) => {
+                                self.current_screen = Screen::Game;
+                                self.draw_current()?;
+                            }
+                            // The new game screen tells why, the main screen has no message
+                            Err(e) if self.current_screen == Screen::NewGame => {
+                                let new_game_screen = self.new_game_screen.as_mut().unwrap();
+                                new_game_screen.set_message(e.to_string());
+                                self.draw_current()?;
+                            }
+                            Err(e) => log::error!("Unable to play the pasted puzzle: {}", e),
+                        }
+                        continue 'running;
+                    }
+                    ScreenOutcome::SwitchProfile | ScreenOutcome::AddProfile => {
+                        let profile = match outcome {
+                            ScreenOutcome::AddProfile => self.profiles.add()?,
+                            _ => self.profiles.switch()?,
+                        };
+                        self.main_screen.as_mut().unwrap().set_profile(
+                            &mut self.canvas,
+                            &self.font,
+                            &profile.name,
+                        )?;
+                        dirty = true;
+                    }
+                    ScreenOutcome::Replay => {
+                        match self.game_screen.as_mut().unwrap().start_replay() {
+                            Ok(()) => {
+                                self.current_screen = Screen::Game;
+                                self.draw_current()?;
+                            }
+                            Err(e) => log::error!("Unable to replay the game: {}", e),
+                        }
+                        continue 'running;
+                    }
+                    ScreenOutcome::Favorite => {
+                        let game_screen = self.game_screen.as_mut().unwrap();
+                        let Some(game) = game_screen.game.as_ref() else {
+                            continue 'running;
+                        };
+                        // A failed write of the profiles is told rather than leaving the game
+                        let toggled = self.profiles.toggle_favorite(game);
+                        match self.current_screen {
+                            Screen::Victory => {
+                                let victory_screen = self.victory_screen.as_mut().unwrap();
+                                match toggled {
+                                    Ok(favorite) => victory_screen.set_favorite(favorite),
+                                    Err(e) => victory_screen.set_message(e.to_string()),
+                                }
+                            }
+                            _ => game_screen.show_favorite(toggled),
+                        }
+                        dirty = true;
+                    }
+                    ScreenOutcome::TimeAttack => {
+                        let attack = logging::timed("generation", || {
+                            TimeAttack::new(
+                                self.config.game_size,
+                                self.config.difficulty,
+                                &self.config.generation,
+                            )
+                        });
+                        self.time_attack = Some(attack);
+                        match self.next_time_attack_puzzle() {
+                            Ok(()) => {
+                                self.current_screen = Screen::Game;
+                                self.draw_current()?;
+                            }
+                            Err(e) => log::error!("Unable to start the time attack: {}", e),
+                        }
+                        continue 'running;
+                    }
+                    ScreenOutcome::Race => {
+                        // Both players race on a classic grid, whatever the configured variant
+                        let config = generator::GenerationConfig {
+                            variant: Variant::Classic,
+                            jigsaw: false,
+                            ..self.config.generation.clone()
+                        };
+                        let puzzle = logging::timed("generation", || {
+                            generator::generate(RACE_SIZE, self.config.difficulty, &config)
+                        });
+                        match Race::new(&puzzle) {
+                            Ok(race) => {
+                                self.race_screen.as_mut().unwrap().set_race(race);
+                                self.current_screen = Screen::Race;
+                                self.draw_current()?;
+                            }
+                            Err(e) => log::error!("Unable to start the race: {}", e),
+                        }
+                        continue 'running;
+                    }
+                    ScreenOutcome::Editor => {
+                        // A new puzzle of the configured size, classic until changed
+                        let editor_screen = self.editor_screen.as_mut().unwrap();
+                        editor_screen.reset(self.config.game_size)?;
+                        editor_screen.resize(&mut self.canvas)?;
+                        self.current_screen = Screen::Editor;
+                        self.draw_current()?;
+                        continue 'running;
+                    }
+                    ScreenOutcome::PlayEdited => {
+                        let path = self.new_saving_path();
+                        let game = self
+                            .editor_screen
+                            .as_ref()
+                            .unwrap()
+                            .editor
+                            .as_ref()
+                            .map_or(Err(GameError::InvalidPuzzle), |editor| {
+                                editor.to_game(Some(&path))
+                            });
+                        match game
+                            .map_err(UiError::from)
+                            .and_then(|game| self.start_game(game))
+                        {
+                            Ok(()) => {
+                                self.current_screen = Screen::Game;
+                                self.draw_current()?;
+                            }
+                            Err(e) => log::error!("Unable to play the edited puzzle: {}", e),
+                        }
+                        continue 'running;
+                    }
+                    #[cfg(feature = "net")]
+                    ScreenOutcome::Lobby => {
+                        self.current_screen = Screen::Lobby;
+                        self.draw_current()?;
+                        continue 'running;
+                    }
+                    #[cfg(feature = "net")]
+                    ScreenOutcome::Host | ScreenOutcome::Join => {
+                        let lobby_screen = self.lobby_screen.as_mut().unwrap();
+                        let address = lobby_screen.address.clone();
+                        let link = match outcome {
+                            ScreenOutcome::Host => Link::host(&address),
+                            _ => Link::join(&address),
+                        };
+                        match link {
+                            Ok(link) => {
+                                let message = match link.local_address() {
+                                    Some(local) => {
+                                        format!("Waiting for the other player on {}", local)
+                                    }
+                                    None => String::from("Joined, waiting for the puzzle"),
+                                };
+                                self.network = Some(NetGame::new(link, lobby_screen.mode));
+                                lobby_screen.set_message(message, true);
+                            }
+                            Err(e) => lobby_screen.set_message(e.to_string(), false),
+                        }
+                        self.draw_current()?;
                         continue 'running;
                     }
                     ScreenOutcome::Exit => break 'running,
@@ -244,8 +815,774 @@ impl<'a> Gui<'a> {
                 }
             }
 
-            // TODO: Why was this here ? How to count FPS ?
-            ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
+            // Play the next step of the watched solver, or the next move of the replay, once it
+            // is due
+            let game_screen = self.game_screen.as_mut().unwrap();
+            if self.current_screen == Screen::Game
+                && (game_screen.watch_tick() || game_screen.replay_tick())
+            {
+                dirty = true;
+            }
+
+            // Exchange the moves of the network game with the other player
+            #[cfg(feature = "net")]
+            if self.network_tick()? {
+                dirty = true;
+            }
+
+            // Play the sounds of the moves played meanwhile
+            #[cfg(feature = "audio")]
+            self.play_sounds(logged);
+
+            // Keep track of the solved puzzles for the packs progress
+            if self.current_screen == Screen::Game && self.game_screen.as_ref().unwrap().is_over() {
+                let id = self
+                    .game_screen
+                    .as_ref()
+                    .unwrap()
+                    .game
+                    .as_ref()
+                    .unwrap()
+                    .puzzle_id();
+                let seconds = self.game_screen.as_ref().unwrap().elapsed().as_secs();
+                self.pack_screen
+                    .as_mut()
+                    .unwrap()
+                    .mark_solved(&id, seconds)?;
+
+                // Count it in the statistics of the profile playing
+                let game_screen = self.game_screen.as_ref().unwrap();
+                let game = game_screen.game.as_ref().unwrap();
+                let difficulty = game
+                    .difficulty
+                    .unwrap_or_else(|| game.rating().difficulty());
+                self.profiles.record(difficulty, game_screen.elapsed())?;
+
+                // A time attack goes on with its next puzzle, until the last one is solved
+                let timed = self
+                    .time_attack
+                    .as_mut()
+                    .is_some_and(|attack| attack.finish_puzzle(game));
+                if timed && !self.time_attack.as_ref().unwrap().is_over() {
+                    self.next_time_attack_puzzle()?;
+                    self.draw_current()?;
+                    continue 'running;
+                }
+
+                // Then congratulate the player
+                #[cfg(feature = "audio")]
+                if let Some(audio) = self.audio.as_ref() {
+                    audio.play(Sound::Victory);
+                }
+                // The other player of a versus game learns the time, and who was faster is told
+                #[cfg(feature = "net")]
+                let versus = self
+                    .network
+                    .as_mut()
+                    .filter(|network| network.mode == NetMode::Versus && network.is_playing(game))
+                    .map(|network| {
+                        let secs = game_screen.elapsed().as_secs();
+                        network.link.send(&Message::Finished { secs });
+                        match network.opponent_finished {
+                            Some(theirs) => format!(
+                                "The other player was faster, in {:02}:{:02}",
+                                theirs / 60,
+                                theirs % 60
+                            ),
+                            None => String::from("You beat the other player!"),
+                        }
+                    });
+                let victory_screen = self.victory_screen.as_mut().unwrap();
+                match self.time_attack.take().filter(|_| timed) {
+                    Some(attack) => {
+                        let rank = self.profiles.record_time_attack(&attack)?;
+                        let leaderboard = self
+                            .profiles
+                            .current()
+                            .leaderboard(attack.difficulty, LEADERBOARD_SHOWN);
+                        victory_screen.set_time_attack_results(&attack, rank, &leaderboard);
+                    }
+                    None => victory_screen.set_results(
+                        game,
+                        game_screen.elapsed(),
+                        &self.profiles.current().summary(difficulty),
+                    ),
+                }
+                #[cfg(feature = "net")]
+                if let Some(line) = versus {
+                    victory_screen.add_result(line);
+                }
+                let favorites = &self.profiles.current().favorites;
+                victory_screen.set_favorite(favorites.contains(&game.to_share_code()));
+                self.current_screen = Screen::Victory;
+                self.draw_current()?;
+            }
+
+            // Keep the clock of the game screen running, and the refused cells flashing
+            let game_screen = self.game_screen.as_ref().unwrap();
+            if self.current_screen == Screen::Game
+                && (game_screen.clock_changed() || game_screen.is_animating())
+            {
+                dirty = true;
+            }
+            if self.current_screen == Screen::Race
+                && self.race_screen.as_ref().unwrap().clock_changed()
+            {
+                dirty = true;
+            }
+            // Keep the numbers of the debug overlay current
+            if dirty || self.overlay.visible {
+                self.draw_current()?;
+            }
+
+            self.update_title()?;
+        }
+
+        // Keep the play time of the open games, then end the session cleanly
+        if let Err(e) = self.game_screen.as_mut().unwrap().save_games() {
+            log::error!("{}", e);
+        }
+        self.autosave.finish();
+
+        Ok(())
+    }
+
+    /**
+     * Returns how many events the current game logged, to play the sounds of the next ones.
+     */
+    #[cfg(feature = "audio")]
+    fn logged_events(&self) -> usize {
+        let game = self.game_screen.as_ref().unwrap().game.as_ref();
+        game.map_or(0, |game| game.events.len())
+    }
+
+    /**
+     * Plays the sound of the events the current game logged after the first `from` ones, a
+     * refused move being heard over the placed values.
+     */
+    #[cfg(feature = "audio")]
+    fn play_sounds(&self, from: usize) {
+        let (Some(audio), Some(game)) = (
+            self.audio.as_ref(),
+            self.game_screen.as_ref().unwrap().game.as_ref(),
+        ) else {
+            return;
+        };
+
+        let sounds: Vec<Sound> = game
+            .events
+            .iter()
+            .skip(from)
+            .filter_map(|logged| Sound::of(&logged.event))
+            .collect();
+        let sound = match sounds.contains(&Sound::Error) {
+            true => Some(Sound::Error),
+            false => sounds.first().copied(),
+        };
+        if let Some(sound) = sound {
+            audio.play(sound);
+        }
+    }
+
+    /**
+     * Returns the saving path of a game started now.
+     */
+    fn new_saving_path(&self) -> String {
+        let current_utc = chrono::offset::Utc::now();
+        format!("{}{}.game", self.config.save_folder_path, current_utc).replace(' ', " ")
+    }
+
+    /**
+     * Starts a game with the options picked on the new game screen. They become the settings
+     * of the next random games, the pool being started again when they change.
+     */
+    fn new_game(&mut self, options: NewGameOptions) -> Result<(), UiError> {
+        if options.size != self.config.game_size
+            || options.difficulty != self.config.difficulty
+            || options.variant != self.config.generation.variant
+        {
+            self.config.game_size = options.size;
+            self.config.difficulty = options.difficulty;
+            self.config.generation.variant = options.variant;
+            self.pool =
+                generator::Pool::new(options.size, options.difficulty, &self.config.generation);
+        }
+
+        match options.seed {
+            Some(seed) => {
+                let puzzle = logging::timed("generation", || {
+                    generator::generate_seeded(
+                        options.size,
+                        options.difficulty,
+                        &self.config.generation,
+                        seed,
+                    )
+                });
+                let mut new_game = Game::new(options.size, Some(&self.new_saving_path()))?;
+                new_game.set_regions(puzzle.regions().to_vec());
+                new_game.grid = puzzle.grid;
+                new_game.solution = puzzle.solution;
+                new_game.difficulty = puzzle.difficulty;
+                new_game.variant = puzzle.variant;
+                self.start_game(new_game)
+            }
+            None => self.new_random_game(),
+        }
+    }
+
+    /**
+     * Starts a game from the puzzle in the system clipboard, see `Game::from_text`.
+     */
+    fn paste_game(&mut self) -> Result<(), UiError> {
+        let clipboard = self.canvas.window().subsystem().clipboard();
+        let text = clipboard.clipboard_text().map_err(UiError::sdl2)?;
+        let new_game = Game::from_text(&text, Some(&self.new_saving_path()))?;
+        self.start_game(new_game)
+    }
+
+    /**
+     * Switches to the main screen, offering to resume the current game if it is not solved.
+     */
+    fn show_menu(&mut self) -> Result<(), UiError> {
+        // A solved game can't be resumed
+        let game_screen = self.game_screen.as_ref().unwrap();
+        self.main_screen.as_mut().unwrap().has_current_game =
+            game_screen.has_game() && !game_screen.is_over();
+        self.load_screen.as_mut().unwrap().reload();
+        self.main_screen.as_mut().unwrap().has_saves =
+            self.load_screen.as_ref().unwrap().has_saves();
+        self.current_screen = Screen::Main;
+        self.draw_current()
+    }
+
+    /**
+     * Applies the settings picked on the settings screen to the screens, the pool and the audio,
+     * then saves them to the config file.
+     */
+    fn apply_settings(&mut self) -> Result<(), UiError> {
+        let settings = match self.settings_screen.as_mut().unwrap().chosen.take() {
+            Some(settings) => settings,
+            None => return Ok(()),
+        };
+        let previous = Settings::from_config(&self.config);
+        settings.apply(&mut self.config);
+
+        self.game_screen
+            .as_mut()
+            .unwrap()
+            .init(&mut self.canvas, &self.config)?;
+        self.new_game_screen
+            .as_mut()
+            .unwrap()
+            .init(&mut self.canvas, &self.config)?;
+        if settings.size != previous.size || settings.difficulty != previous.difficulty {
+            self.pool = generator::Pool::new(
+                self.config.game_size,
+                self.config.difficulty,
+                &self.config.generation,
+            );
+        }
+        #[cfg(feature = "audio")]
+        if let Some(audio) = self.audio.as_mut() {
+            audio.set_volume(settings.volume);
+        }
+
+        config::write_config(&self.config_path, &self.config).map_err(UiError::WriteConfigError)
+    }
+
+    /**
+     * Resumes the game saved at `path` with the configured game options, unless it is the one
+     * being played already.
+     */
+    fn resume_game(&mut self, path: &str) -> Result<(), GameError> {
+        let game_screen = self.game_screen.as_mut().unwrap();
+        let current = game_screen
+            .game
+            .as_ref()
+            .and_then(|game| game.save_path.as_ref());
+        if current.is_some_and(|current| current.to_str() == Some(path)) {
+            return Ok(());
+        }
+
+        let mut game = Game::from_file(path)?;
+        // The turns of a resumed hot-seat game are kept
+        game.hot_seat = game
+            .hot_seat
+            .take()
+            .or_else(|| self.config.hot_seat.then(HotSeat::default));
+        game.remove_notes = self.config.remove_notes;
+        game_screen.set_game(game);
+
+        Ok(())
+    }
+
+    /**
+     * Applies the configured game options to `new_game`, saves it and attaches it to the game
+     * screen.
+     */
+    fn start_game(&mut self, mut new_game: Game) -> Result<(), UiError> {
+        new_game.hot_seat = self.config.hot_seat.then(HotSeat::default);
+        new_game.remove_notes = self.config.remove_notes;
+        if self.config.auto_notes {
+            new_game.auto_notes = true;
+            new_game.fill_notes()?;
+        }
+        new_game.save()?;
+        // Attach the new game to the game screen
+        self.game_screen.as_mut().unwrap().set_game(new_game);
+
+        Ok(())
+    }
+
+    /**
+     * Starts the puzzle of the time attack to solve now, telling which one it is.
+     */
+    fn next_time_attack_puzzle(&mut self) -> Result<(), UiError> {
+        let attack = self.time_attack.as_ref().unwrap();
+        let mut game = Game::from_puzzle(attack.current().unwrap(), Some(&self.new_saving_path()))?;
+        game.difficulty = Some(attack.difficulty);
+        let announce = format!(
+            "Time attack: puzzle {}/{}, score so far {:02}:{:02}",
+            attack.splits.len() + 1,
+            attack.len(),
+            attack.score() / 60,
+            attack.score() % 60
+        );
+        self.start_game(game)?;
+        self.game_screen.as_mut().unwrap().announce(announce);
+
+        Ok(())
+    }
+
+    /**
+     * Sends the moves of the network game the other player does not know yet, then handles what
+     * happened on the connection. Returns whether the current screen has to be drawn again.
+     */
+    #[cfg(feature = "net")]
+    fn network_tick(&mut self) -> Result<bool, UiError> {
+        let Some(network) = self.network.as_mut() else {
+            return Ok(false);
+        };
+
+        // The local moves are sent before the ones of the other player are applied
+        let game_screen = self.game_screen.as_mut().unwrap();
+        if let Some(game) = game_screen.game.as_ref() {
+            if network.is_playing(game) {
+                network.share(game);
+            }
+        }
+        let events = network.link.poll();
+        let changed = !events.is_empty();
+        for event in events {
+            match event {
+                NetEvent::Connected => self.network_connected()?,
+                NetEvent::Disconnected => {
+                    let message = match self.network.as_ref().unwrap().link.is_host() {
+                        true => "Connection lost, waiting for the other player to come back",
+                        false => "Connection lost, joining the host again",
+                    };
+                    self.network_notice(message.to_string(), false);
+                }
+                NetEvent::Received(message) => self.network_received(message)?,
+            }
+        }
+
+        // The progress of the other player of a versus game is shown above the grid
+        if let Some(network) = self.network.as_ref() {
+            let game_screen = self.game_screen.as_mut().unwrap();
+            let playing = game_screen
+                .game
+                .as_ref()
+                .is_some_and(|game| network.is_playing(game));
+            game_screen.opponent = network.opponent.filter(|_| playing);
+        }
+
+        Ok(changed)
+    }
+
+    /**
+     * Tells how the network game goes on the game screen while it is played, on the lobby
+     * otherwise.
+     */
+    #[cfg(feature = "net")]
+    fn network_notice(&mut self, message: String, good: bool) {
+        let game_screen = self.game_screen.as_mut().unwrap();
+        let playing = match (self.network.as_ref(), game_screen.game.as_ref()) {
+            (Some(network), Some(game)) => network.is_playing(game),
+            _ => false,
+        };
+        match playing {
+            true => game_screen.announce(message),
+            false => self
+                .lobby_screen
+                .as_mut()
+                .unwrap()
+                .set_message(message, good),
+        }
+    }
+
+    /**
+     * Starts the network game once the other player joins the host, on a classic grid any
+     * instance can play, or sends them the grid again when they come back.
+     */
+    #[cfg(feature = "net")]
+    fn network_connected(&mut self) -> Result<(), UiError> {
+        let network = self.network.as_mut().unwrap();
+        if !network.link.is_host() {
+            network.reconnected();
+            self.network_notice(String::from("Joined the host again"), true);
+            return Ok(());
+        }
+
+        let game_screen = self.game_screen.as_ref().unwrap();
+        let playing = game_screen
+            .game
+            .as_ref()
+            .is_some_and(|game| network.is_playing(game));
+        if !playing {
+            let config = generator::GenerationConfig {
+                variant: Variant::Classic,
+                jigsaw: false,
+                ..self.config.generation.clone()
+            };
+            let puzzle = logging::timed("generation", || {
+                generator::generate(self.config.game_size, self.config.difficulty, &config)
+            });
+            let mut game = Game::from_puzzle(&puzzle.puzzle_id(), Some(&self.new_saving_path()))?;
+            game.solution = puzzle.solution;
+            game.difficulty = puzzle.difficulty;
+            self.start_game(game)?;
+            self.current_screen = Screen::Game;
+        }
+
+        let game = self.game_screen.as_ref().unwrap().game.as_ref().unwrap();
+        let network = self.network.as_mut().unwrap();
+        if !playing {
+            network.begin(game);
+        }
+        network.reconnected();
+        let start = network.start_message(game);
+        network.link.send(&start);
+        self.network_notice(String::from("The other player joined"), true);
+
+        Ok(())
+    }
+
+    /**
+     * Applies what the other player sent: the guest starts the puzzle of the host, or catches up
+     * with its grid, and the moves and the progress of the other player follow.
+     */
+    #[cfg(feature = "net")]
+    fn network_received(&mut self, message: Message) -> Result<(), UiError> {
+        let network = self.network.as_ref().unwrap();
+        match &message {
+            // Only the host starts puzzles
+            Message::Start { .. } if network.link.is_host() => return Ok(()),
+            Message::Start { version, .. } if *version != PROTOCOL_VERSION => {
+                self.network = None;
+                let lobby_screen = self.lobby_screen.as_mut().unwrap();
+                lobby_screen.set_message(UiError::ProtocolMismatch.to_string(), false);
+                self.current_screen = Screen::Lobby;
+                return Ok(());
+            }
+            Message::Start { puzzle, .. } => {
+                let game_screen = self.game_screen.as_ref().unwrap();
+                let playing = game_screen
+                    .game
+                    .as_ref()
+                    .is_some_and(|game| game.puzzle_id() == *puzzle);
+                if !playing {
+                    let game = Game::from_puzzle(puzzle, Some(&self.new_saving_path()))?;
+                    self.start_game(game)?;
+                    self.current_screen = Screen::Game;
+                }
+            }
+            _ => {}
+        }
+
+        // The moves of another game than the shared one are not applied
+        let network = self.network.as_mut().unwrap();
+        let game_screen = self.game_screen.as_mut().unwrap();
+        if let Some(game) = game_screen.game.as_mut() {
+            if network.is_playing(game) || matches!(message, Message::Start { .. }) {
+                if let Err(e) = network.apply(game, &message) {
+                    log::warn!("Ignoring a move of the other player: {}", e);
+                }
+            }
+        }
+        if let Message::Finished { secs } = message {
+            self.network_notice(
+                format!(
+                    "The other player completed their grid in {:02}:{:02}",
+                    secs / 60,
+                    secs % 60
+                ),
+                true,
+            );
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Hands the current fonts to the screens drawing texts.
+     */
+    fn set_fonts(&mut self) {
+        self.main_screen
+            .as_mut()
+            .unwrap()
+            .set_font(self.font.clone());
+        let game_screen = self.game_screen.as_mut().unwrap();
+        game_screen.set_font(self.font.clone());
+        game_screen.set_notes_font(self.notes_font.clone());
+        self.pack_screen
+            .as_mut()
+            .unwrap()
+            .set_font(self.font.clone());
+        self.load_screen
+            .as_mut()
+            .unwrap()
+            .set_font(self.font.clone());
+        self.new_game_screen
+            .as_mut()
+            .unwrap()
+            .set_font(self.font.clone());
+        self.victory_screen
+            .as_mut()
+            .unwrap()
+            .set_font(self.font.clone());
+        self.samurai_screen
+            .as_mut()
+            .unwrap()
+            .set_font(self.font.clone());
+        self.race_screen
+            .as_mut()
+            .unwrap()
+            .set_font(self.font.clone());
+        self.editor_screen
+            .as_mut()
+            .unwrap()
+            .set_font(self.font.clone());
+        self.settings_screen
+            .as_mut()
+            .unwrap()
+            .set_font(self.font.clone());
+        #[cfg(feature = "net")]
+        self.lobby_screen
+            .as_mut()
+            .unwrap()
+            .set_font(self.font.clone());
+    }
+
+    /**
+     * Returns the number of canvas pixels per window coordinate, above 1 on high DPI displays.
+     */
+    fn pixel_ratio(&self) -> f32 {
+        let (width, _) = self.canvas.window().size();
+        match self.canvas.output_size() {
+            Ok((pixels, _)) if width > 0 => pixels as f32 / width as f32,
+            _ => 1.0,
+        }
+    }
+
+    /**
+     * Loads the fonts again at the size suiting the canvas, and recomputes the geometry of every
+     * screen before drawing the current one again.
+     */
+    fn resize(&mut self) -> Result<(), UiError> {
+        let (_, height) = self.canvas.output_size().map_err(UiError::sdl2)?;
+        let font_size = scaled_font_size(FONT_SIZE, height, self.config.res_y as u32);
+        if font_size != self.font_size {
+            let (font, notes_font) =
+                load_fonts(self.ttf_context, &self.config.font_path, font_size)?;
+            self.font = Rc::new(font);
+            self.notes_font = Rc::new(notes_font);
+            self.font_size = font_size;
+            self.set_fonts();
+        }
+
+        let canvas = &mut self.canvas;
+        self.main_screen.as_mut().unwrap().resize(canvas)?;
+        self.game_screen.as_mut().unwrap().resize(canvas)?;
+        self.pack_screen.as_mut().unwrap().resize(canvas)?;
+        self.load_screen.as_mut().unwrap().resize(canvas)?;
+        self.new_game_screen.as_mut().unwrap().resize(canvas)?;
+        self.victory_screen.as_mut().unwrap().resize(canvas)?;
+        self.samurai_screen.as_mut().unwrap().resize(canvas)?;
+        self.race_screen.as_mut().unwrap().resize(canvas)?;
+        self.editor_screen.as_mut().unwrap().resize(canvas)?;
+        self.settings_screen.as_mut().unwrap().resize(canvas)?;
+        #[cfg(feature = "net")]
+        self.lobby_screen.as_mut().unwrap().resize(canvas)?;
+
+        self.draw_current()
+    }
+
+    /**
+     * Returns the screen shown, which the events go to.
+     */
+    fn current_screen_mut(&mut self) -> &mut dyn Displayable {
+        match self.current_screen {
+            Screen::Main => self.main_screen.as_mut().unwrap(),
+            Screen::Game => self.game_screen.as_mut().unwrap(),
+            Screen::Packs => self.pack_screen.as_mut().unwrap(),
+            Screen::Saves => self.load_screen.as_mut().unwrap(),
+            Screen::NewGame => self.new_game_screen.as_mut().unwrap(),
+            Screen::Victory => self.victory_screen.as_mut().unwrap(),
+            Screen::Samurai => self.samurai_screen.as_mut().unwrap(),
+            Screen::Race => self.race_screen.as_mut().unwrap(),
+            Screen::Editor => self.editor_screen.as_mut().unwrap(),
+            Screen::Settings => self.settings_screen.as_mut().unwrap(),
+            #[cfg(feature = "net")]
+            Screen::Lobby => self.lobby_screen.as_mut().unwrap(),
+        }
+    }
+
+    /**
+     * Draws the current screen on the window, under the debug overlay if it is shown.
+     */
+    fn draw_current(&mut self) -> Result<(), UiError> {
+        let canvas = &mut self.canvas;
+        let drawn = match self.current_screen {
+            Screen::Main => self.main_screen.as_mut().unwrap().draw(canvas),
+            Screen::Game => self.game_screen.as_mut().unwrap().draw(canvas),
+            Screen::Packs => self.pack_screen.as_mut().unwrap().draw(canvas),
+            Screen::Saves => self.load_screen.as_mut().unwrap().draw(canvas),
+            Screen::NewGame => self.new_game_screen.as_mut().unwrap().draw(canvas),
+            Screen::Victory => self.victory_screen.as_mut().unwrap().draw(canvas),
+            Screen::Samurai => self.samurai_screen.as_mut().unwrap().draw(canvas),
+            Screen::Race => self.race_screen.as_mut().unwrap().draw(canvas),
+            Screen::Editor => self.editor_screen.as_mut().unwrap().draw(canvas),
+            Screen::Settings => self.settings_screen.as_mut().unwrap().draw(canvas),
+            #[cfg(feature = "net")]
+            Screen::Lobby => self.lobby_screen.as_mut().unwrap().draw(canvas),
+        };
+        drawn?;
+        if self.overlay.visible {
+            self.overlay.draw(canvas, &self.font)?;
+        }
+        canvas.present();
+
+        Ok(())
+    }
+
+    /**
+     * Returns how long to wait for events before the current screen has to be drawn again by
+     * itself: a frame while the game screen animates, until its clock ticks while it runs.
+     */
+    fn next_redraw(&self) -> Duration {
+        let next = match self.current_screen {
+            Screen::Game => self.game_screen.as_ref().unwrap().next_redraw(),
+            _ => None,
+        };
+
+        let wait = next.map_or(IDLE_WAIT, |wait| wait.clamp(FRAME, IDLE_WAIT));
+        #[cfg(feature = "net")]
+        let wait = match self.network.is_some() {
+            true => wait.min(NET_POLL),
+            false => wait,
+        };
+        match self.overlay.visible {
+            true => wait.min(overlay::REFRESH),
+            false => wait,
+        }
+    }
+
+    /**
+     * Switches the window between fullscreen, at the resolution of the desktop, and windowed.
+     */
+    fn toggle_fullscreen(&mut self) -> Result<(), UiError> {
+        let window = self.canvas.window_mut();
+        let state = match window.fullscreen_state() {
+            FullscreenType::Off => FullscreenType::Desktop,
+            _ => FullscreenType::Off,
+        };
+
+        window.set_fullscreen(state).map_err(UiError::sdl2)
+    }
+
+    /**
+     * Draws the current screen again on a texture, and saves its pixels as a PNG image named
+     * after the current time in the save folder. Returns the path of the image.
+     */
+    fn screenshot(&mut self) -> Result<String, UiError> {
+        let (width, height) = self.canvas.output_size().map_err(UiError::sdl2)?;
+        let texture_creator = self.canvas.texture_creator();
+        let mut texture = texture_creator
+            .create_texture_target(PixelFormatEnum::ARGB8888, width, height)
+            .map_err(|e| UiError::ScreenshotError(e.into()))?;
+
+        let mut drawn = Ok(());
+        let mut pixels = Ok(Vec::new());
+        self.canvas
+            .with_texture_canvas(&mut texture, |canvas| {
+                drawn = match self.current_screen {
+                    Screen::Main => self.main_screen.as_mut().unwrap().draw(canvas),
+                    Screen::Game => self.game_screen.as_mut().unwrap().draw(canvas),
+                    Screen::Packs => self.pack_screen.as_mut().unwrap().draw(canvas),
+                    Screen::Saves => self.load_screen.as_mut().unwrap().draw(canvas),
+                    Screen::NewGame => self.new_game_screen.as_mut().unwrap().draw(canvas),
+                    Screen::Victory => self.victory_screen.as_mut().unwrap().draw(canvas),
+                    Screen::Samurai => self.samurai_screen.as_mut().unwrap().draw(canvas),
+                    Screen::Race => self.race_screen.as_mut().unwrap().draw(canvas),
+                    Screen::Editor => self.editor_screen.as_mut().unwrap().draw(canvas),
+                    Screen::Settings => self.settings_screen.as_mut().unwrap().draw(canvas),
+                    #[cfg(feature = "net")]
+                    Screen::Lobby => self.lobby_screen.as_mut().unwrap().draw(canvas),
+                };
+                pixels = canvas.read_pixels(None, PixelFormatEnum::ARGB8888);
+            })
+            .map_err(|e| UiError::ScreenshotError(e.into()))?;
+        drawn?;
+        let mut pixels = pixels.map_err(|e| UiError::ScreenshotError(e.into()))?;
+
+        let surface = Surface::from_data(
+            &mut pixels,
+            width,
+            height,
+            width * 4,
+            PixelFormatEnum::ARGB8888,
+        )
+        .map_err(|e| UiError::ScreenshotError(e.into()))?;
+        let path = format!(
+            "{}screenshot {}.png",
+            self.config.save_folder_path,
+            chrono::offset::Local::now().format("%Y-%m-%d %H-%M-%S")
+        );
+        surface
+            .save(&path)
+            .map_err(|e| UiError::ScreenshotError(e.into()))?;
+
+        Ok(path)
+    }
+
+    /**
+     * Updates the window title with the status of the game being played, if any.
+     */
+    fn update_title(&mut self) -> Result<(), UiError> {
+        let title = match self.current_screen {
+            Screen::Main
+            | Screen::Packs
+            | Screen::Saves
+            | Screen::NewGame
+            | Screen::Victory
+            | Screen::Settings => None,
+            Screen::Samurai => Some(String::from("Samurai")),
+            Screen::Race => Some(String::from("Race")),
+            Screen::Editor => Some(String::from("Puzzle editor")),
+            #[cfg(feature = "net")]
+            Screen::Lobby => Some(String::from("Network game")),
+            Screen::Game => self.game_screen.as_ref().unwrap().status(),
+        }
+        .map(|status| format!("{} - {}", WINDOW_TITLE, status))
+        .unwrap_or_else(|| WINDOW_TITLE.to_string());
+
+        // Only bother the window manager when the title actually changes
+        if self.canvas.window().title() != title {
+            self.canvas
+                .window_mut()
+                .set_title(&title)
+                .map_err(UiError::sdl2)?;
         }
 
         Ok(())
@@ -262,6 +1599,9 @@ impl<'a> Displayable for Gui<'a> {
     fn draw(&mut self, _canvas: &mut Canvas<Window>) -> Result<(), UiError> {
         todo!()
     }
+    fn resize(&mut self, _canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        todo!()
+    }
 
     fn update(&mut self, _event: &Event) -> Result<ScreenOutcome, UiError> {
         todo!()
@@ -270,19 +1610,31 @@ impl<'a> Displayable for Gui<'a> {
 
 impl Ui for Gui<'_> {
     fn new_random_game(&mut self) -> Result<(), UiError> {
-        // Generate the game's saving path
-        let current_utc = chrono::offset::Utc::now();
-        let saving_path =
-            format!("{}{}.game", self.config.save_folder_path, current_utc).replace(' ', " ");
         // Instanciate a new game with its saving path
-        let mut new_game = Game::new(self.config.game_size, Some(&saving_path))?;
-        new_game.clear();
-        new_game.fill_rng(0);
-        let solver = solver::Obvious;
-        new_game.unfill(solver);
-        new_game.save()?;
-        // Attach the new game to the game screen
-        self.game_screen.as_mut().unwrap().set_game(new_game);
+        let mut new_game = Game::new(self.config.game_size, Some(&self.new_saving_path()))?;
+        let puzzle = logging::timed("generation", || self.pool.take());
+        new_game.set_regions(puzzle.regions().to_vec());
+        new_game.grid = puzzle.grid;
+        new_game.solution = puzzle.solution;
+        new_game.difficulty = puzzle.difficulty;
+        new_game.variant = puzzle.variant;
+
+        self.start_game(new_game)
+    }
+
+    fn load_puzzle(&mut self, path: &str) -> Result<(), UiError> {
+        // Samurai puzzles are played on their own screen, without a save
+        if path.ends_with(".samurai") {
+            let samurai = SamuraiGame::from_file(path).map_err(|_| UiError::ImportPuzzleError)?;
+            self.samurai_screen.as_mut().unwrap().set_samurai(samurai);
+            self.current_screen = Screen::Samurai;
+            return Ok(());
+        }
+
+        let json = fs::read_to_string(path).map_err(|_| UiError::ImportPuzzleError)?;
+        let new_game = annotations::import_fpuzzles(&json, Some(&self.new_saving_path()))?;
+        self.start_game(new_game)?;
+        self.current_screen = Screen::Game;
 
         Ok(())
     }
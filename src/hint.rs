@@ -0,0 +1,247 @@
+use crate::game::Game;
+use crate::grader::{self, Board, Technique};
+use crate::solver;
+
+/**
+ * What a hint suggests to do.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// Place the value in the cell at the given index.
+    Place { index: usize, value: u8 },
+    /// Remove the note of the value from the cells at the given indexes.
+    Eliminate { cells: Vec<usize>, value: u8 },
+}
+
+/**
+ * The next logical move on a grid, along with the technique of the grading ladder and the cells
+ * justifying it.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hint {
+    pub technique: Technique,
+    pub action: Action,
+    /// The cells the technique looks at, such as the row where a hidden single is.
+    pub cells: Vec<usize>,
+    /// The unit the technique works in, such as `row 1`, if it works in one.
+    unit: Option<String>,
+    /// The values the cells of a naked subset can take, or the values of a hidden subset.
+    values: Vec<u8>,
+}
+
+/**
+ * Lists `values` in a sentence, as "1, 2 and 3".
+ */
+fn enumerate(values: &[u8]) -> String {
+    match values {
+        [] => String::new(),
+        [value] => value.to_string(),
+        [rest @ .., last] => format!(
+            "{} and {}",
+            rest.iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<String>>()
+                .join(", "),
+            last
+        ),
+    }
+}
+
+/**
+ * Returns the hint of `technique` having turned `before` into `after`: the value it placed, or
+ * the first value it removed from the notes of the player, `None` if it only removed candidates
+ * the player did not note.
+ */
+fn hint_of(game: &Game, technique: Technique, before: &Board, after: &Board) -> Option<Hint> {
+    let cells = 0..game.grid.len();
+    let action = match cells
+        .clone()
+        .find(|i| before.values[*i] != after.values[*i])
+    {
+        Some(index) => Action::Place {
+            index,
+            value: after.values[index],
+        },
+        None => {
+            let eliminated: Vec<(usize, u8)> = cells
+                .flat_map(|i| {
+                    let removed =
+                        before.candidates[i] & !after.candidates[i] & game.grid[i].notes();
+                    solver::values_of(removed).into_iter().map(move |v| (i, v))
+                })
+                .collect();
+            let value = eliminated.iter().map(|(_, v)| *v).min()?;
+            Action::Eliminate {
+                cells: eliminated
+                    .iter()
+                    .filter(|(_, v)| *v == value)
+                    .map(|(i, _)| *i)
+                    .collect(),
+                value,
+            }
+        }
+    };
+
+    let pattern = after.pattern.clone();
+    let values = match (technique, after.pattern_unit) {
+        (Technique::NakedPair | Technique::NakedTriple, _) => solver::values_of(
+            pattern
+                .iter()
+                .fold(0, |mask, i| mask | before.candidates[*i]),
+        ),
+        (Technique::HiddenPair | Technique::HiddenTriple, Some(unit)) => (1..=game.side_size as u8)
+            .filter(|v| {
+                let spots = before.spots(unit, *v);
+                !spots.is_empty() && spots.iter().all(|i| pattern.contains(i))
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    Some(Hint {
+        technique,
+        action,
+        cells: pattern,
+        unit: after.pattern_unit.map(|unit| after.unit_name(unit)),
+        values,
+    })
+}
+
+/**
+ * Returns the next logical move on `game`, trying the simplest techniques of the grading ladder
+ * first on the candidates of its cells, restricted to their notes, or `None` if none of them
+ * applies. The candidates a technique removes from cells without notes are looked past, the
+ * player having nothing to remove there.
+ */
+pub fn next_hint(game: &Game) -> Option<Hint> {
+    let board = Board::with_notes(game);
+
+    for technique in grader::LADDER {
+        let mut before = board.clone();
+        let mut after = board.clone();
+        while after.apply(technique) {
+            if let Some(hint) = hint_of(game, technique, &before, &after) {
+                return Some(hint);
+            }
+            before = after.clone();
+        }
+    }
+
+    None
+}
+
+/**
+ * Solves `game` with the known techniques only, starting from the candidates of its cells, and
+ * returns the hints applied in order. The grid is left partly filled when the techniques are not
+ * enough.
+ */
+pub fn solve_path(game: &mut Game) -> Vec<Hint> {
+    game.notes_from_candidates();
+
+    let mut path = Vec::new();
+    while let Some(hint) = next_hint(game) {
+        match &hint.action {
+            Action::Place { index, value } => {
+                game.grid[*index].set_value(*value);
+                game.grid[*index].set_notes(0);
+            }
+            Action::Eliminate { cells, value } => {
+                for index in cells {
+                    let notes = game.grid[*index].notes();
+                    game.grid[*index].set_notes(notes & !(1 << value));
+                }
+            }
+        }
+        path.push(hint);
+    }
+
+    path
+}
+
+impl Hint {
+    /**
+     * Explains the hint in a sentence, the rows, columns and boxes being one based.
+     */
+    pub fn describe(&self, game: &Game) -> String {
+        let position = |index: usize| {
+            let (r, c) = game.coordinates(index);
+            format!("row {}, column {}", r + 1, c + 1)
+        };
+        let positions = |cells: &[usize]| {
+            cells
+                .iter()
+                .map(|i| position(*i))
+                .collect::<Vec<String>>()
+                .join("; ")
+        };
+        let within = match self.unit.as_ref() {
+            Some(unit) => format!("in {}, ", unit),
+            None => String::new(),
+        };
+
+        match (&self.technique, &self.action) {
+            (Technique::NakedSingle, Action::Place { index, value }) => format!(
+                "{}: {} is the only value left for {}.",
+                self.technique,
+                value,
+                position(*index)
+            ),
+            (_, Action::Place { index, value }) => format!(
+                "{}: {}{} can only go at {}.",
+                self.technique,
+                within,
+                value,
+                position(*index)
+            ),
+            (Technique::NakedPair | Technique::NakedTriple, Action::Eliminate { cells, value }) => {
+                format!(
+                    "{}: {}{} can only take {}, so {} can be removed from the notes of {}.",
+                    self.technique,
+                    within,
+                    positions(&self.cells),
+                    enumerate(&self.values),
+                    value,
+                    positions(cells)
+                )
+            }
+            (Technique::HiddenPair | Technique::HiddenTriple, Action::Eliminate { value, .. }) => {
+                format!(
+                    "{}: {}{} can only go in {}, so {} can be removed from their notes.",
+                    self.technique,
+                    within,
+                    enumerate(&self.values),
+                    positions(&self.cells),
+                    value
+                )
+            }
+            (Technique::XYWing, Action::Eliminate { cells, value }) => format!(
+                "{}: whichever value {} takes, {} or {} holds {}, so it can be removed from the \
+                 notes of {}.",
+                self.technique,
+                position(self.cells[0]),
+                position(self.cells[1]),
+                position(self.cells[2]),
+                value,
+                positions(cells)
+            ),
+            (_, Action::Eliminate { cells, value }) => format!(
+                "{}: {}{} can only go in {}, so it can be removed from the notes of {}.",
+                self.technique,
+                within,
+                value,
+                positions(&self.cells),
+                positions(cells)
+            ),
+        }
+    }
+
+    /**
+     * Returns the cells the hint acts on.
+     */
+    pub fn targets(&self) -> Vec<usize> {
+        match &self.action {
+            Action::Place { index, .. } => vec![*index],
+            Action::Eliminate { cells, .. } => cells.clone(),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use crate::game::Cell;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/**
+ * A change of a single cell of the grid, keeping its previous state so that it can be reverted.
+ */
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Move {
+    /// Index of the changed cell.
+    pub index: usize,
+    /// The cell before the change.
+    pub before: Cell,
+    /// The cell after the change.
+    pub after: Cell,
+    /// Play time of the game when the change was made, in milliseconds, 0 for the moves of
+    /// the saves written before it was kept.
+    #[serde(default)]
+    pub at: u64,
+}
+
+impl Move {
+    /**
+     * Returns the move written `index:before:after` by `to_string`, the cells being written as
+     * their packed bits.
+     */
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.split(':');
+        let index = parts.next()?.parse::<usize>().ok()?;
+        let before = Cell::from_bits(parts.next()?.parse::<u64>().ok()?);
+        let after = Cell::from_bits(parts.next()?.parse::<u64>().ok()?);
+        match parts.next() {
+            Some(_) => None,
+            None => Some(Move {
+                index,
+                before,
+                after,
+                at: 0,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            self.index,
+            self.before.bits(),
+            self.after.bits()
+        )
+    }
+}
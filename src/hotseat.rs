@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// Number of players taking turns in hot-seat mode.
+pub const HOT_SEAT_PLAYERS: usize = 2;
+
+/**
+ * Keeps track of the turns and of the per-player statistics of a hot-seat game, where players
+ * take turns on the same board.
+ */
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct HotSeat {
+    /// Index of the player whose turn it is.
+    pub current: usize,
+    /// Number of accepted moves of each player.
+    pub moves: [usize; HOT_SEAT_PLAYERS],
+    /// Number of wrong values of each player.
+    pub mistakes: [usize; HOT_SEAT_PLAYERS],
+}
+
+impl HotSeat {
+    /**
+     * Records the outcome of the current player's move and gives the turn to the next player.
+     */
+    pub fn end_turn(&mut self, accepted: bool) {
+        match accepted {
+            true => self.moves[self.current] += 1,
+            false => self.mistakes[self.current] += 1,
+        }
+        self.current = (self.current + 1) % HOT_SEAT_PLAYERS;
+    }
+
+    /**
+     * Returns a line per player summarizing their contribution to the grid.
+     */
+    pub fn summary(&self) -> String {
+        (0..HOT_SEAT_PLAYERS)
+            .map(|p| {
+                format!(
+                    "Player {}: {} moves, {} mistakes.",
+                    p + 1,
+                    self.moves[p],
+                    self.mistakes[p]
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
@@ -0,0 +1,84 @@
+use crate::annotations::Region;
+use crate::game::Cell;
+
+use serde::{Deserialize, Serialize};
+
+/**
+ * A cage of a killer sudoku: its cells hold distinct values adding up to its sum.
+ */
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Cage {
+    /// Indexes of the cells of the cage.
+    pub cells: Vec<usize>,
+    pub sum: usize,
+}
+
+impl Cage {
+    /**
+     * Returns the cage of the region `region` if its label is a sum, and its cells are in a grid
+     * of `side_size` rows.
+     */
+    pub fn from_region(region: &Region, side_size: usize) -> Option<Self> {
+        let sum = region.label.trim().parse::<usize>().ok()?;
+        let valid =
+            !region.cells.is_empty() && region.cells.iter().all(|i| *i < side_size * side_size);
+
+        valid.then(|| Cage {
+            cells: region.cells.clone(),
+            sum,
+        })
+    }
+
+    /**
+     * Returns whether `value` can be placed at `index`, given the values of the other cells of
+     * the cage in `grid`: it must not be in the cage already, and the cells left empty must still
+     * be able to make up the rest of the sum with distinct values up to `side_size`. Cells out of
+     * the cage allow any value.
+     */
+    pub fn allows(&self, grid: &[Cell], index: usize, value: u8, side_size: usize) -> bool {
+        if !self.cells.contains(&index) {
+            return true;
+        }
+
+        let others: Vec<u8> = self
+            .cells
+            .iter()
+            .filter(|i| **i != index)
+            .map(|i| grid[*i].value())
+            .collect();
+        if others.contains(&value) {
+            return false;
+        }
+
+        let total = others.iter().map(|v| *v as usize).sum::<usize>() + value as usize;
+        if total > self.sum {
+            return false;
+        }
+
+        // The smallest and largest sums the empty cells can still make with the unused values
+        let empties = others.iter().filter(|v| **v == 0).count();
+        let unused: Vec<usize> = (1..=side_size)
+            .filter(|v| *v != value as usize && !others.contains(&(*v as u8)))
+            .collect();
+        if unused.len() < empties {
+            return false;
+        }
+        let smallest: usize = unused.iter().take(empties).sum();
+        let largest: usize = unused.iter().rev().take(empties).sum();
+
+        (smallest..=largest).contains(&(self.sum - total))
+    }
+
+    /**
+     * Returns whether the cells of the cage in `grid` are all filled, with distinct values adding
+     * up to its sum.
+     */
+    pub fn is_satisfied(&self, grid: &[Cell]) -> bool {
+        let mut values: Vec<u8> = self.cells.iter().map(|i| grid[*i].value()).collect();
+        let sum: usize = values.iter().map(|v| *v as usize).sum();
+        values.sort();
+        values.dedup();
+
+        !values.contains(&0) && values.len() == self.cells.len() && sum == self.sum
+    }
+}
@@ -0,0 +1,192 @@
+use sdl2::rect::Rect;
+
+use crate::traits::{GridPlacement, LayoutConfig};
+
+/**
+ * Geometry of the game screen, computed from the layout configuration, the size of the canvas
+ * and the size of the grid to draw.
+ */
+#[derive(Clone, Copy)]
+pub struct Layout {
+    /// Horizontal position of the grid's top left corner.
+    pub grid_x: i32,
+    /// Vertical position of the grid's top left corner.
+    pub grid_y: i32,
+    /// Side size of a single cell, in pixels.
+    pub box_size: i32,
+    /// Number of cells on a side of the grid.
+    pub side_size: usize,
+    /// Number of cells on a side of a group.
+    pub group_size: usize,
+    /// Thickness of the lines between cells.
+    pub thin_line: u32,
+    /// Thickness of the lines between groups.
+    pub thick_line: u32,
+    /// Area left to the companion widgets (number pad, status, ...).
+    pub panel: Rect,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout {
+            grid_x: 0,
+            grid_y: 0,
+            box_size: 1,
+            side_size: 0,
+            group_size: 0,
+            thin_line: 0,
+            thick_line: 0,
+            panel: Rect::new(0, 0, 1, 1),
+        }
+    }
+}
+
+/**
+ * Returns the area where a `width` x `height` texture is drawn centered in `area`, scaled down
+ * to fit in it when it is bigger, keeping its proportions.
+ */
+pub fn fit_centered(width: u32, height: u32, area: Rect) -> Rect {
+    let scale = (area.width() as f32 / width.max(1) as f32)
+        .min(area.height() as f32 / height.max(1) as f32)
+        .min(1.0);
+    let (width, height) = (
+        ((width as f32 * scale) as u32).max(1),
+        ((height as f32 * scale) as u32).max(1),
+    );
+
+    Rect::new(
+        area.x() + (area.width() as i32 - width as i32) / 2,
+        area.y() + (area.height() as i32 - height as i32) / 2,
+        width,
+        height,
+    )
+}
+
+/**
+ * Returns the point size of a font of `size` points at the configured window height `reference`,
+ * scaled for a canvas `height` pixels high, which also accounts for high DPI displays.
+ */
+pub fn scaled_font_size(size: u16, height: u32, reference: u32) -> u16 {
+    let scaled = size as f32 * height as f32 / reference.max(1) as f32;
+    (scaled.round() as u16).max(6)
+}
+
+impl Layout {
+    /**
+     * Computes the largest grid that fits in `viewport` with the configured margins, keeping its
+     * cells square, and places it according to the configured placement.
+     */
+    pub fn new(config: &LayoutConfig, viewport: Rect, size: usize) -> Self {
+        Layout::with_side(config, viewport, size * size, size)
+    }
+
+    /**
+     * Computes the layout of a board of `side_size` cells per side, its groups having
+     * `group_size` cells per side, like `new`.
+     */
+    pub fn with_side(
+        config: &LayoutConfig,
+        viewport: Rect,
+        side_size: usize,
+        group_size: usize,
+    ) -> Self {
+        let margin = config.margin as i32;
+        let panel_width = match config.placement {
+            GridPlacement::Centered => 0,
+            GridPlacement::Anchored => config.side_panel_width as i32,
+        };
+
+        // Square cells, as big as the most constrained dimension allows
+        let available_x = viewport.width() as i32 - 2 * margin - panel_width;
+        let available_y = viewport.height() as i32 - 2 * margin;
+        let box_size = (available_x.min(available_y) / side_size.max(1) as i32).max(1);
+        let grid_side = box_size * side_size as i32;
+
+        let (grid_x, grid_y, panel) = match config.placement {
+            GridPlacement::Centered => {
+                let grid_x = (viewport.width() as i32 - grid_side) / 2;
+                let grid_y = (viewport.height() as i32 - grid_side) / 2;
+                // Without a side panel, widgets go below the grid
+                let panel_y = grid_y + grid_side + config.thick_line as i32;
+                (
+                    grid_x,
+                    grid_y,
+                    Rect::new(
+                        grid_x,
+                        panel_y,
+                        grid_side as u32,
+                        (viewport.height() as i32 - panel_y).max(1) as u32,
+                    ),
+                )
+            }
+            GridPlacement::Anchored => (
+                margin,
+                margin,
+                Rect::new(
+                    2 * margin + grid_side,
+                    margin,
+                    (viewport.width() as i32 - 3 * margin - grid_side).max(1) as u32,
+                    grid_side as u32,
+                ),
+            ),
+        };
+
+        Layout {
+            grid_x,
+            grid_y,
+            box_size,
+            side_size,
+            group_size,
+            thin_line: config.thin_line,
+            thick_line: config.thick_line,
+            panel,
+        }
+    }
+
+    /**
+     * Returns the side size of the whole grid, in pixels.
+     */
+    pub fn grid_side(&self) -> i32 {
+        self.box_size * self.side_size as i32
+    }
+
+    /**
+     * Returns the area covered by the cell at row `r` and column `c`.
+     */
+    pub fn cell_rect(&self, r: usize, c: usize) -> Rect {
+        Rect::new(
+            self.grid_x + c as i32 * self.box_size,
+            self.grid_y + r as i32 * self.box_size,
+            self.box_size as u32,
+            self.box_size as u32,
+        )
+    }
+
+    /**
+     * Returns the (row, column) of the cell located at the given window position, if any.
+     */
+    pub fn cell_at(&self, x: i32, y: i32) -> Option<(usize, usize)> {
+        if x < self.grid_x
+            || x >= self.grid_x + self.grid_side()
+            || y < self.grid_y
+            || y >= self.grid_y + self.grid_side()
+        {
+            return None;
+        }
+
+        Some((
+            ((y - self.grid_y) / self.box_size) as usize,
+            ((x - self.grid_x) / self.box_size) as usize,
+        ))
+    }
+
+    /**
+     * Returns the thickness of the `n`-th line of the grid, thicker between groups.
+     */
+    pub fn line_thickness(&self, n: usize) -> u32 {
+        match n % self.group_size.max(1) {
+            0 => self.thick_line,
+            _ => self.thin_line,
+        }
+    }
+}
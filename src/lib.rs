@@ -0,0 +1,49 @@
+//! Sudoku engine: grids and their save files, a puzzle editor, solvers, puzzle generation and
+//! grading, hints, packs, saved games, replays, time attacks, races, player profiles, share codes,
+//! printing and exporting, an HTTP/JSON game server, WebAssembly bindings and a C API. It does not
+//! depend on SDL2, the interfaces living in the `rs-sudoku` binary.
+//!
+//! ```
+//! use rs_sudoku::game::Game;
+//! use rs_sudoku::generator::{self, Difficulty, GenerationConfig};
+//!
+//! let puzzle = generator::generate(2, Difficulty::Easy, &GenerationConfig::default());
+//! let game = Game::from_puzzle(&puzzle.puzzle_id(), None).unwrap();
+//! assert_eq!(game.solution().unwrap(), puzzle.solution.unwrap());
+//! ```
+
+pub mod annotations;
+pub mod autosave;
+pub mod editor;
+pub mod errors;
+pub mod events;
+pub mod export;
+pub mod favorites;
+#[cfg(feature = "fetch")]
+pub mod fetch;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod game;
+pub mod generator;
+pub mod grader;
+pub mod hint;
+pub mod history;
+pub mod hotseat;
+pub mod killer;
+pub mod lock;
+#[cfg(feature = "net")]
+pub mod net;
+pub mod packs;
+pub mod print;
+pub mod profile;
+pub mod race;
+pub mod replay;
+pub mod samurai;
+pub mod saves;
+pub mod server;
+pub mod session;
+pub mod share;
+pub mod solver;
+pub mod time_attack;
+#[cfg(feature = "wasm")]
+pub mod wasm;
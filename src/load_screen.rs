@@ -0,0 +1,330 @@
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::Canvas;
+use sdl2::ttf::Font;
+use sdl2::video::Window;
+
+use std::rc::Rc;
+
+use crate::errors::UiError;
+use crate::favorites::Favorites;
+use crate::saves::SavedGame;
+use crate::traits::{Displayable, GUIConfig, ScreenOutcome};
+
+static COLOR_BCK: Color = Color::BLACK;
+static COLOR_HOVER: Color = Color::RGBA(75, 75, 75, 255);
+static COLOR_FONT: Color = Color::WHITE;
+static COLOR_BAD_MSG: Color = Color::RED;
+
+/// Space between the border of the window and the list.
+const MARGIN: i32 = 40;
+/// Height of a row of the list.
+const ROW_HEIGHT: i32 = 40;
+
+/**
+ * Lists the games of the save folder with their size, progress and last save date, scrolled with
+ * the mouse wheel or the arrow keys, and lets the player pick the one to resume. The `F` key
+ * shows only the games of the favorite puzzles of the profile playing.
+ */
+#[derive(Default)]
+pub struct LoadScreen<'a> {
+    font: Option<Rc<Font<'a, 'a>>>,
+
+    /// Folder the games are saved in.
+    save_folder: String,
+    /// The saved games, the most recent first.
+    saves: Vec<SavedGame>,
+    /// The favorite puzzles of the profile playing.
+    favorites: Favorites,
+    /// Whether only the games of favorite puzzles are listed.
+    only_favorites: bool,
+    /// Indices in `saves` of the listed games.
+    listed: Vec<usize>,
+    /// Index of the first listed save shown.
+    first: usize,
+
+    /// The row under the mouse cursor, the last row being the 'Back' one.
+    hovered_row: Option<usize>,
+    message: Option<String>,
+    viewport: Option<Rect>,
+
+    /// The save picked by the player, to be resumed by the caller.
+    pub chosen: Option<String>,
+}
+
+impl<'a> LoadScreen<'a> {
+    pub fn set_font(&mut self, new_font: Rc<Font<'a, 'a>>) {
+        self.font = Some(new_font);
+    }
+
+    /**
+     * Lists the saves again, as games were saved since the screen was last shown.
+     */
+    pub fn reload(&mut self) {
+        self.saves = SavedGame::list(&self.save_folder);
+        self.filter();
+        self.message = None;
+    }
+
+    /**
+     * Lists the games of the `favorites` of the profile playing only when they are filtered.
+     */
+    pub fn set_favorites(&mut self, favorites: Favorites) {
+        self.favorites = favorites;
+        self.filter();
+    }
+
+    /**
+     * Lists the saves again, only the favorite ones if they are filtered, from the first one.
+     */
+    fn filter(&mut self) {
+        self.listed = (0..self.saves.len())
+            .filter(|i| !self.only_favorites || self.favorites.contains(&self.saves[*i].code))
+            .collect();
+        self.first = 0;
+        self.hovered_row = None;
+    }
+
+    /**
+     * Returns whether any game can be resumed.
+     */
+    pub fn has_saves(&self) -> bool {
+        !self.saves.is_empty()
+    }
+
+    /**
+     * Shows `message` below the list, e.g. why the picked save could not be resumed.
+     */
+    pub fn set_message(&mut self, message: String) {
+        self.message = Some(message);
+    }
+
+    /**
+     * Returns the number of saves shown at once, leaving room for the title, the 'Back' row and
+     * the message.
+     */
+    fn visible_rows(&self) -> usize {
+        let height = self.viewport.map_or(0, |v| v.height() as i32);
+        ((height - 2 * MARGIN) / ROW_HEIGHT - 3).max(1) as usize
+    }
+
+    /**
+     * Returns the area of the row `row` of the list, counted from the first save shown.
+     */
+    fn row_rect(&self, row: usize) -> Rect {
+        let width = self.viewport.map_or(0, |v| v.width() as i32);
+        Rect::new(
+            MARGIN,
+            MARGIN + (row as i32 + 1) * ROW_HEIGHT,
+            (width - 2 * MARGIN).max(1) as u32,
+            ROW_HEIGHT as u32,
+        )
+    }
+
+    /**
+     * Returns the number of rows shown, the 'Back' one excluded.
+     */
+    fn shown_rows(&self) -> usize {
+        self.visible_rows().min(self.listed.len() - self.first)
+    }
+
+    /**
+     * Returns the row of the list at the given window position, if any.
+     */
+    fn row_at(&self, x: i32, y: i32) -> Option<usize> {
+        (0..=self.shown_rows()).find(|row| self.row_rect(*row).contains_point((x, y)))
+    }
+
+    /**
+     * Scrolls the list by `rows` rows, down when positive, returning whether it moved.
+     */
+    fn scroll(&mut self, rows: i32) -> bool {
+        let last = self.listed.len().saturating_sub(self.visible_rows());
+        let first = (self.first as i32 + rows).clamp(0, last as i32) as usize;
+        let moved = first != self.first;
+        self.first = first;
+        self.hovered_row = None;
+
+        moved
+    }
+
+    /**
+     * Draws `text` with the screen's font, vertically centered in `area`.
+     */
+    fn draw_text(
+        &self,
+        canvas: &mut Canvas<Window>,
+        text: &str,
+        color: Color,
+        area: Rect,
+    ) -> Result<(), UiError> {
+        let texture_creator = canvas.texture_creator();
+        let surface = self
+            .font
+            .as_ref()
+            .unwrap()
+            .render(text)
+            .blended(color)
+            .map_err(UiError::sdl2)?;
+        let texture = texture_creator
+            .create_texture_from_surface(surface)
+            .map_err(UiError::sdl2)?;
+
+        let width = texture.query().width.min(area.width());
+        let height = texture.query().height.min(area.height());
+        canvas
+            .copy(
+                &texture,
+                Rect::new(0, 0, width, height),
+                Rect::new(
+                    area.x(),
+                    area.y() + (area.height() - height) as i32 / 2,
+                    width,
+                    height,
+                ),
+            )
+            .map_err(UiError::sdl2)
+    }
+}
+
+impl<'a> Displayable for LoadScreen<'a> {
+    fn new() -> Self {
+        LoadScreen {
+            ..Default::default()
+        }
+    }
+
+    fn init(&mut self, canvas: &mut Canvas<Window>, config: &GUIConfig) -> Result<(), UiError> {
+        self.save_folder = config.save_folder_path.clone();
+        self.viewport = Some(canvas.viewport());
+        self.reload();
+
+        Ok(())
+    }
+
+    fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        canvas.set_draw_color(COLOR_BCK);
+        canvas.clear();
+
+        // Title, with the saves shown when they do not all fit
+        let title_area = Rect::new(MARGIN, MARGIN, self.row_rect(0).width(), ROW_HEIGHT as u32);
+        let shown = self.shown_rows();
+        let name = match self.only_favorites {
+            true => "Favorite games (F: all)",
+            false => "Load Game (F: favorites)",
+        };
+        let title = match self.listed.len() {
+            0 if self.only_favorites => String::from("No saved game of a favorite puzzle (F: all)"),
+            0 => format!("No saved game in {}", self.save_folder),
+            n if n > shown => format!(
+                "{} - {} to {} of {}",
+                name,
+                self.first + 1,
+                self.first + shown,
+                n
+            ),
+            _ => String::from(name),
+        };
+        self.draw_text(canvas, &title, COLOR_FONT, title_area)?;
+
+        // One row per save shown, then the 'Back' row
+        for row in 0..=shown {
+            let area = self.row_rect(row);
+            if self.hovered_row == Some(row) {
+                canvas.set_draw_color(COLOR_HOVER);
+                canvas.fill_rect(area).map_err(UiError::sdl2)?;
+            }
+
+            let text = match row < shown {
+                true => self.saves[self.listed[self.first + row]].to_string(),
+                false => String::from("Back"),
+            };
+            self.draw_text(canvas, &text, COLOR_FONT, area)?;
+        }
+
+        if let Some(message) = self.message.as_ref() {
+            let area = self.row_rect(shown + 1);
+            self.draw_text(canvas, message, COLOR_BAD_MSG, area)?;
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        self.viewport = Some(canvas.viewport());
+        // Showing more rows may leave fewer saves below the first one shown
+        self.scroll(0);
+
+        Ok(())
+    }
+
+    fn update(&mut self, event: &Event) -> Result<ScreenOutcome, UiError> {
+        match event {
+            Event::MouseMotion { x, y, .. } => {
+                let hovered_row = self.row_at(*x, *y);
+                if hovered_row != self.hovered_row {
+                    self.hovered_row = hovered_row;
+                    return Ok(ScreenOutcome::Updated);
+                }
+            }
+            Event::MouseWheel { y, .. } => {
+                return match self.scroll(-*y) {
+                    true => Ok(ScreenOutcome::Updated),
+                    false => Ok(ScreenOutcome::Unchanged),
+                };
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Escape),
+                ..
+            } => {
+                self.message = None;
+                return Ok(ScreenOutcome::Menu);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::F),
+                ..
+            } => {
+                self.only_favorites = !self.only_favorites;
+                self.filter();
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(keycode @ (Keycode::Up | Keycode::Down)),
+                ..
+            } => {
+                let rows = match keycode {
+                    Keycode::Up => -1,
+                    _ => 1,
+                };
+                return match self.scroll(rows) {
+                    true => Ok(ScreenOutcome::Updated),
+                    false => Ok(ScreenOutcome::Unchanged),
+                };
+            }
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => match self.row_at(*x, *y) {
+                Some(row) if row == self.shown_rows() => {
+                    self.message = None;
+                    return Ok(ScreenOutcome::Menu);
+                }
+                Some(row) => {
+                    let save = &self.saves[self.listed[self.first + row]];
+                    self.chosen = Some(save.path.clone());
+                    self.message = None;
+                    return Ok(ScreenOutcome::Load);
+                }
+                None => {}
+            },
+            _ => {}
+        }
+
+        Ok(ScreenOutcome::Unchanged)
+    }
+}
@@ -0,0 +1,289 @@
+use sdl2::event::Event;
+use sdl2::keyboard::{Keycode, Mod};
+use sdl2::mouse::MouseButton;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::Canvas;
+use sdl2::ttf::Font;
+use sdl2::video::Window;
+
+use std::rc::Rc;
+
+use crate::errors::UiError;
+use crate::game_screen::digit_from_keycode;
+use crate::net::{NetMode, DEFAULT_ADDRESS};
+use crate::traits::{Displayable, GUIConfig, ScreenOutcome};
+
+static COLOR_BCK: Color = Color::BLACK;
+static COLOR_HOVER: Color = Color::RGBA(75, 75, 75, 255);
+static COLOR_FONT: Color = Color::WHITE;
+static COLOR_TITLE: Color = Color::GREEN;
+static COLOR_GOOD_MSG: Color = Color::GREEN;
+static COLOR_BAD_MSG: Color = Color::RED;
+
+/// Space between the border of the window and the text.
+const MARGIN: i32 = 40;
+/// Height of a line of text or of a button.
+const ROW_HEIGHT: i32 = 40;
+/// Longest address that can be typed.
+const MAX_ADDRESS_LEN: usize = 64;
+
+/// Buttons of the screen, below the settings of the game.
+const BUTTON_HOST: usize = 0;
+const BUTTON_JOIN: usize = 1;
+const BUTTON_MENU: usize = 2;
+
+/**
+ * Returns the character of an address typed with the given key: a letter, a digit, or one of
+ * `.`, `:` and `-`.
+ */
+fn address_char(keycode: Keycode) -> Option<char> {
+    match keycode {
+        Keycode::Num0 | Keycode::Kp0 => Some('0'),
+        Keycode::Period | Keycode::KpPeriod => Some('.'),
+        Keycode::Colon | Keycode::Semicolon => Some(':'),
+        Keycode::Minus | Keycode::KpMinus => Some('-'),
+        _ => match digit_from_keycode(keycode) {
+            Some(digit) => Some((b'0' + digit) as char),
+            None => {
+                let name = keycode.name();
+                let mut chars = name.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if c.is_ascii_alphabetic() => Some(c.to_ascii_lowercase()),
+                    _ => None,
+                }
+            }
+        },
+    }
+}
+
+/**
+ * Sets up a game shared with another instance: the mode, the address to host on or to join,
+ * and how the connection is going.
+ */
+#[derive(Default)]
+pub struct LobbyScreen<'a> {
+    font: Option<Rc<Font<'a, 'a>>>,
+
+    /// Whether the players share a single grid or race on copies of it.
+    pub mode: NetMode,
+    /// The address to wait for the other player on, or to join them at.
+    pub address: String,
+    /// How the connection is going, or why it failed, in green when it is good news.
+    message: Option<(String, bool)>,
+
+    /// The button under the mouse cursor.
+    hovered_button: Option<usize>,
+    viewport: Option<Rect>,
+}
+
+impl<'a> LobbyScreen<'a> {
+    pub fn set_font(&mut self, new_font: Rc<Font<'a, 'a>>) {
+        self.font = Some(new_font);
+    }
+
+    /**
+     * Shows how the connection is going below the settings, in green when it is `good` news and
+     * in red otherwise.
+     */
+    pub fn set_message(&mut self, message: String, good: bool) {
+        self.message = Some((message, good));
+    }
+
+    /**
+     * Returns the area of the `row`-th line of the screen, the title being the first one.
+     */
+    fn row_rect(&self, row: usize) -> Rect {
+        let width = self.viewport.map_or(0, |v| v.width() as i32);
+        Rect::new(
+            MARGIN,
+            MARGIN + row as i32 * ROW_HEIGHT,
+            (width - 2 * MARGIN).max(1) as u32,
+            ROW_HEIGHT as u32,
+        )
+    }
+
+    /**
+     * Returns the area of the button `button`, below the settings and the message.
+     */
+    fn button_rect(&self, button: usize) -> Rect {
+        self.row_rect(5 + button)
+    }
+
+    /**
+     * Returns the button at the given window position, if any.
+     */
+    fn button_at(&self, x: i32, y: i32) -> Option<usize> {
+        [BUTTON_HOST, BUTTON_JOIN, BUTTON_MENU]
+            .into_iter()
+            .find(|button| self.button_rect(*button).contains_point((x, y)))
+    }
+
+    /**
+     * Draws `text` with the screen's font, vertically centered in `area`.
+     */
+    fn draw_text(
+        &self,
+        canvas: &mut Canvas<Window>,
+        text: &str,
+        color: Color,
+        area: Rect,
+    ) -> Result<(), UiError> {
+        let texture_creator = canvas.texture_creator();
+        let surface = self
+            .font
+            .as_ref()
+            .unwrap()
+            .render(text)
+            .blended(color)
+            .map_err(UiError::sdl2)?;
+        let texture = texture_creator
+            .create_texture_from_surface(surface)
+            .map_err(UiError::sdl2)?;
+
+        let width = texture.query().width.min(area.width());
+        let height = texture.query().height.min(area.height());
+        canvas
+            .copy(
+                &texture,
+                Rect::new(0, 0, width, height),
+                Rect::new(
+                    area.x(),
+                    area.y() + (area.height() - height) as i32 / 2,
+                    width,
+                    height,
+                ),
+            )
+            .map_err(UiError::sdl2)
+    }
+}
+
+impl<'a> Displayable for LobbyScreen<'a> {
+    fn new() -> Self {
+        LobbyScreen {
+            address: DEFAULT_ADDRESS.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn init(&mut self, canvas: &mut Canvas<Window>, _config: &GUIConfig) -> Result<(), UiError> {
+        self.viewport = Some(canvas.viewport());
+
+        Ok(())
+    }
+
+    fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        canvas.set_draw_color(COLOR_BCK);
+        canvas.clear();
+
+        let mode = match self.mode {
+            NetMode::Coop => "Co-op, one shared grid",
+            NetMode::Versus => "Versus, a race on copies of the grid",
+        };
+        self.draw_text(canvas, "Network game", COLOR_TITLE, self.row_rect(0))?;
+        self.draw_text(
+            canvas,
+            &format!("Mode: {} (Tab to change)", mode),
+            COLOR_FONT,
+            self.row_rect(1),
+        )?;
+        self.draw_text(
+            canvas,
+            &format!("Address: {}_", self.address),
+            COLOR_FONT,
+            self.row_rect(2),
+        )?;
+        if let Some((message, good)) = self.message.as_ref() {
+            let color = match good {
+                true => COLOR_GOOD_MSG,
+                false => COLOR_BAD_MSG,
+            };
+            self.draw_text(canvas, message, color, self.row_rect(3))?;
+        }
+
+        for (button, text) in [
+            (BUTTON_HOST, "Host (Shift+Enter)"),
+            (BUTTON_JOIN, "Join (Enter)"),
+            (BUTTON_MENU, "Main Menu"),
+        ] {
+            let area = self.button_rect(button);
+            if self.hovered_button == Some(button) {
+                canvas.set_draw_color(COLOR_HOVER);
+                canvas.fill_rect(area).map_err(UiError::sdl2)?;
+            }
+            self.draw_text(canvas, text, COLOR_FONT, area)?;
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        self.viewport = Some(canvas.viewport());
+
+        Ok(())
+    }
+
+    fn update(&mut self, event: &Event) -> Result<ScreenOutcome, UiError> {
+        match event {
+            Event::MouseMotion { x, y, .. } => {
+                let hovered_button = self.button_at(*x, *y);
+                if hovered_button != self.hovered_button {
+                    self.hovered_button = hovered_button;
+                    return Ok(ScreenOutcome::Updated);
+                }
+            }
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => match self.button_at(*x, *y) {
+                Some(BUTTON_HOST) => return Ok(ScreenOutcome::Host),
+                Some(BUTTON_JOIN) => return Ok(ScreenOutcome::Join),
+                Some(_) => return Ok(ScreenOutcome::Menu),
+                None => {}
+            },
+            Event::KeyDown {
+                keycode: Some(Keycode::Tab),
+                ..
+            } => {
+                self.mode = match self.mode {
+                    NetMode::Coop => NetMode::Versus,
+                    NetMode::Versus => NetMode::Coop,
+                };
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Return | Keycode::KpEnter),
+                keymod,
+                ..
+            } => {
+                return Ok(match keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+                    true => ScreenOutcome::Host,
+                    false => ScreenOutcome::Join,
+                })
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Backspace),
+                ..
+            } => {
+                self.address.pop();
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } => {
+                if let Some(c) = address_char(*keycode) {
+                    if self.address.len() < MAX_ADDRESS_LEN {
+                        self.address.push(c);
+                        return Ok(ScreenOutcome::Updated);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(ScreenOutcome::Unchanged)
+    }
+}
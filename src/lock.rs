@@ -0,0 +1,67 @@
+use crate::errors::GameError;
+
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/**
+ * Returns whether the file at `path` is locked by a running process, see `SaveLock`: the locks
+ * are released by the system when their process ends, even when it crashed.
+ */
+pub(crate) fn is_held(path: &Path) -> bool {
+    match File::open(path) {
+        Ok(file) => matches!(file.try_lock(), Err(TryLockError::WouldBlock)),
+        Err(_) => false,
+    }
+}
+
+/**
+ * An exclusive hold on a save file, so that two running instances never write the same save.
+ *
+ * It is materialized by a `<save>.lock` file containing the id of the holding process, on which
+ * an advisory lock of the system is held, and which is removed when the lock is dropped. Lock
+ * files left behind by processes that are not running anymore are not locked anymore, and are
+ * taken over.
+ */
+#[derive(Debug)]
+pub struct SaveLock {
+    path: PathBuf,
+    /// The lock file, locked as long as it is open.
+    file: File,
+}
+
+impl SaveLock {
+    /**
+     * Takes the lock of the save file at `save_path`, failing with `GameError::SaveLocked` if
+     * another running instance holds it.
+     */
+    pub fn acquire(save_path: &str) -> Result<Self, GameError> {
+        let path = PathBuf::from(format!("{}.lock", save_path));
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(GameError::CreateSaveFileError)?;
+        match file.try_lock() {
+            Ok(()) => (),
+            Err(TryLockError::WouldBlock) => return Err(GameError::SaveLocked),
+            Err(TryLockError::Error(e)) => return Err(GameError::CreateSaveFileError(e)),
+        }
+
+        // The id of the holder is only there for the players wondering who holds the save
+        file.set_len(0)
+            .and_then(|_| write!(file, "{}", std::process::id()))
+            .map_err(GameError::CreateSaveFileError)?;
+
+        Ok(SaveLock { path, file })
+    }
+}
+
+impl Drop for SaveLock {
+    fn drop(&mut self) {
+        // Removed while still locked, the lock is released when the file is closed
+        let _ = fs::remove_file(&self.path);
+        let _ = self.file.unlock();
+    }
+}
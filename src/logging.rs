@@ -0,0 +1,46 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Messages shown when the `RUST_LOG` environment variable does not pick any, such as
+/// `RUST_LOG=debug` or `RUST_LOG=rs_sudoku=trace`.
+const DEFAULT_FILTER: &str = "warn";
+/// Number of solver and generator timings kept for the debug overlay.
+const KEPT_TIMINGS: usize = 6;
+
+/// The last timings measured by `timed`, the latest one at the end.
+static TIMINGS: Mutex<VecDeque<(&str, Duration)>> = Mutex::new(VecDeque::new());
+
+/**
+ * Writes the messages of the program and of the engine to the standard error, filtered by the
+ * `RUST_LOG` environment variable.
+ */
+pub fn init() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(DEFAULT_FILTER))
+        .init();
+}
+
+/**
+ * Runs `task`, logging how long it took under `label` and keeping it for the debug overlay.
+ */
+pub fn timed<T>(label: &'static str, task: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = task();
+    let elapsed = start.elapsed();
+    log::debug!("{} took {:?}", label, elapsed);
+
+    let mut timings = TIMINGS.lock().unwrap();
+    if timings.len() == KEPT_TIMINGS {
+        timings.pop_front();
+    }
+    timings.push_back((label, elapsed));
+
+    result
+}
+
+/**
+ * Returns the last timings measured by `timed`, the latest one at the end.
+ */
+pub fn timings() -> Vec<(&'static str, Duration)> {
+    TIMINGS.lock().unwrap().iter().copied().collect()
+}
@@ -1,76 +1,412 @@
 extern crate sdl2;
 use sdl2::image::InitFlag;
 
+#[cfg(any(test, feature = "audio"))]
+use rs_sudoku::events;
+#[cfg(feature = "fetch")]
+use rs_sudoku::fetch;
+#[cfg(all(test, feature = "ffi"))]
+use rs_sudoku::ffi;
+#[cfg(feature = "net")]
+use rs_sudoku::net;
+#[cfg(all(test, feature = "wasm"))]
+use rs_sudoku::wasm;
+use rs_sudoku::{
+    annotations, autosave, editor, errors, export, favorites, game, generator, grader, hint,
+    hotseat, packs, print, profile, race, replay, samurai, saves, server, session, solver,
+    time_attack,
+};
+#[cfg(test)]
+use rs_sudoku::{history, killer};
+
+mod args;
+#[cfg(feature = "audio")]
+mod audio;
 mod cli;
-mod errors;
-mod game;
+mod config;
+mod dialog;
+mod editor_screen;
 mod game_screen;
+mod glyphs;
 mod gui;
+mod layout;
+mod load_screen;
+#[cfg(feature = "net")]
+mod lobby_screen;
+mod logging;
 mod main_screen;
-mod solver;
+mod new_game_screen;
+mod overlay;
+mod pack_screen;
+mod race_screen;
+mod samurai_screen;
+mod settings_screen;
 mod tests;
+mod theme;
 mod traits;
+#[cfg(unix)]
+mod tui;
 mod utils;
+mod victory_screen;
+mod widget;
 
 use std::env;
+use std::fs;
+use std::time::Instant;
+use traits::Ui;
+
+use args::{BatchFormat, Command, Mode};
+use game::Game;
+use generator::{GenerationConfig, PuzzleRecord};
+use replay::Replay;
+use server::Server;
+use solver::{PuzzleCheck, Solver, SolverKind};
 
 pub fn main() {
-    // Get command line arguments and check there are 3
-    let args: Vec<String> = env::args().collect();
-    if args.is_empty() {
-        eprintln!(
-            "This program should be launched as './sudocurs <CONFIGURATION_PATH> [CLI/GUI]'."
-        );
-        return;
+    logging::init();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = match args::parse(&args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, args::USAGE);
+            return;
+        }
+    };
+
+    match command {
+        Command::Play {
+            config,
+            mode,
+            puzzle,
+        } => {
+            if let Some(path) = config_path(config) {
+                play(&path, mode, puzzle.as_deref())
+            }
+        }
+        // Packs are played at the prompt, which goes on with their next puzzle
+        Command::PackPlay { config, pack } => {
+            if let Some(path) = config_path(config) {
+                play(&path, Mode::Cli, Some(&pack))
+            }
+        }
+        Command::Serve { address } => match server::bind(&address) {
+            Ok(listener) => {
+                println!("Serving the games on http://{}", address);
+                Server::new(GenerationConfig::default()).serve(listener);
+            }
+            Err(e) => eprintln!("{}", e),
+        },
+        Command::InitConfig => match config::init_config() {
+            Ok(path) => println!("Configuration written to {}.", path.display()),
+            Err(e) => eprintln!("{}", e),
+        },
+        Command::Generate {
+            size,
+            difficulty,
+            count,
+            out,
+            format,
+        } => {
+            let config = GenerationConfig::default();
+            let games = generator::generate_batch(size, difficulty, &config, count);
+            let puzzles = match format {
+                BatchFormat::Lines => games.iter().map(|g| g.puzzle_id() + "\n").collect(),
+                BatchFormat::Json => {
+                    let records: Vec<PuzzleRecord> = games.iter().map(PuzzleRecord::new).collect();
+                    serde_json::to_string_pretty(&records).unwrap() + "\n"
+                }
+            };
+            match out {
+                Some(path) => {
+                    if let Err(e) = fs::write(&path, puzzles) {
+                        eprintln!("Unable to write the puzzles to {}: {}", path, e);
+                    }
+                }
+                None => print!("{}", puzzles),
+            }
+        }
+        // Samurai puzzles have their own solver, across their five grids
+        Command::Solve { source, .. } if source.ends_with(".samurai") => {
+            if !solve_samurai(&source) {
+                std::process::exit(1);
+            }
+        }
+        Command::Solve {
+            source,
+            solver,
+            trace,
+            explain,
+        } => match print::load_games(&source) {
+            Ok(games) => {
+                if !solve(games, solver, trace, explain) {
+                    std::process::exit(1);
+                }
+            }
+            Err(e) => {
+                eprintln!("Unable to read the collection {}: {}", source, e);
+                std::process::exit(1);
+            }
+        },
+        Command::Check { source, json } => match print::load_games(&source) {
+            Ok(games) => {
+                let checks: Vec<PuzzleCheck> = games
+                    .into_iter()
+                    .map(|game| match game {
+                        Ok(mut game) => PuzzleCheck::new(&mut game),
+                        Err(_) => PuzzleCheck::invalid(),
+                    })
+                    .collect();
+                match json {
+                    true => println!("{}", serde_json::to_string_pretty(&checks).unwrap()),
+                    false => {
+                        for (i, check) in checks.iter().enumerate() {
+                            println!("Puzzle {}: {}.", i + 1, check);
+                        }
+                    }
+                }
+            }
+            Err(e) => eprintln!("Unable to read the collection {}: {}", source, e),
+        },
+        Command::Export {
+            source,
+            out,
+            options,
+        } => {
+            // The values placed in a save or a CSV grid are drawn too
+            let game = match source.ends_with(".game") || source.ends_with(".csv") {
+                true => Game::read(&source).map_err(|e| e.to_string()),
+                false => print::load_games(&source)
+                    .map_err(|e| e.to_string())
+                    .and_then(|games| {
+                        let game = games.into_iter().next();
+                        game.ok_or_else(|| String::from("There is no puzzle to export."))
+                    })
+                    .and_then(|game| game.map_err(|e| e.to_string())),
+            };
+            match game {
+                Ok(game) => match export::export(&game, &out, &options) {
+                    Ok(()) => println!("Exported to {}.", out),
+                    Err(e) => eprintln!("Unable to write {}: {}", out, e),
+                },
+                Err(e) => eprintln!("Unable to read {}: {}", source, e),
+            }
+        }
+        Command::ImportCode { code, out } => match Game::from_share_code(&code, out.as_deref()) {
+            Ok(mut game) => match out {
+                Some(path) => match game.save() {
+                    Ok(()) => println!("Puzzle saved to {}.", path),
+                    Err(e) => eprintln!("{}", errors::report(&e)),
+                },
+                None => {
+                    let clues: Vec<u8> = game.grid.iter().map(|c| c.value()).collect();
+                    println!("{}\n", game.puzzle_id());
+                    print!("{}", print::render_grid(game.size, &clues, false));
+                }
+            },
+            Err(e) => eprintln!("Unable to read the share code: {}", e),
+        },
+        Command::Replay { source } => match Game::read(&source)
+            .and_then(|game| Replay::new(&game).map(|replay| (game.size, replay)))
+        {
+            Ok((size, mut replay)) => {
+                // The grid before the first move, then after each of them
+                loop {
+                    let values: Vec<u8> = replay.grid.iter().map(|c| c.value()).collect();
+                    println!("{}\n", replay.describe());
+                    println!("{}", print::render_grid(size, &values, false));
+                    if replay.step().is_none() {
+                        break;
+                    }
+                }
+            }
+            Err(e) => eprintln!("Unable to replay {}: {}", source, e),
+        },
+        // Render puzzles as text for paper handouts
+        Command::Print {
+            source,
+            with_solutions,
+            ascii,
+        } => match print::load_puzzles(&source) {
+            Ok(puzzles) => print!("{}", print::render_puzzles(&puzzles, with_solutions, ascii)),
+            Err(e) => eprintln!("Unable to read the collection {}: {}", source, e),
+        },
     }
-    if args.len() != 3 {
-        eprintln!(
-            "This program should be launched as '{} <CONFIGURATION_PATH> [CLI/GUI]'.",
-            args[0]
-        );
-        return;
+}
+
+/**
+ * Prints the solutions of `games` found by `solver` and the time taken, preceded by the
+ * techniques solving them step by step if `trace` is set, and by the steps of `solver` with the
+ * candidates they place or remove if `explain` is set. Returns whether every game was solved.
+ */
+fn solve(
+    games: Vec<Result<Game, errors::GameError>>,
+    solver: SolverKind,
+    trace: bool,
+    explain: bool,
+) -> bool {
+    let mut solved = true;
+    for (i, game) in games.into_iter().enumerate() {
+        println!("Puzzle {}\n", i + 1);
+        let Ok(mut game) = game else {
+            println!("Invalid puzzle.\n");
+            solved = false;
+            continue;
+        };
+
+        if trace {
+            let mut steps = Game::from_puzzle(&game.puzzle_id(), None).unwrap();
+            let path = hint::solve_path(&mut steps);
+            for (step, hint) in path.iter().enumerate() {
+                println!("{:>4}. {}", step + 1, hint.describe(&steps));
+            }
+            if !steps.is_filled() {
+                println!("The known techniques are not enough to go further.");
+            }
+            println!();
+        }
+
+        if explain {
+            let steps = solver.solve_with_trace(&game);
+            for (step, solve_step) in steps.iter().enumerate() {
+                println!("{:>4}. {}", step + 1, solve_step.describe(&game));
+            }
+            if steps.iter().filter(|step| step.placed.is_some()).count() < game.nb_empty() {
+                println!("The human techniques are not enough to go further.");
+            }
+            println!();
+        }
+
+        let start = Instant::now();
+        match solver.solve(&mut game) {
+            Ok(()) => {
+                let values: Vec<u8> = game.grid.iter().map(|cell| cell.value()).collect();
+                println!("{}", print::render_grid(game.size, &values, false));
+                println!(
+                    "Solved in {:.3} ms.\n",
+                    start.elapsed().as_secs_f64() * 1000.0
+                );
+            }
+            Err(e) => {
+                println!("{}\n", e);
+                solved = false;
+            }
+        }
     }
 
-    // Get the executable name
-    let self_name = &args[0];
-    println!("Launched [{}].", self_name);
+    solved
+}
+
+/**
+ * Prints the solution of the samurai puzzle at `path` and the time taken. Returns whether it
+ * was solved.
+ */
+fn solve_samurai(path: &str) -> bool {
+    let mut samurai = match samurai::SamuraiGame::from_file(path) {
+        Ok(samurai) => samurai,
+        Err(e) => {
+            eprintln!("Unable to read the samurai puzzle {}: {}", path, e);
+            return false;
+        }
+    };
 
-    // The first argument should be the path to the configuration file
-    let config_path = &args[1];
-    println!("Loading configuration file [{}].", config_path);
+    let start = Instant::now();
+    match samurai.solve() {
+        Ok(()) => {
+            println!("{}", samurai);
+            println!(
+                "Solved in {:.3} ms.",
+                start.elapsed().as_secs_f64() * 1000.0
+            );
+            true
+        }
+        Err(e) => {
+            println!("{}", e);
+            false
+        }
+    }
+}
 
-    // The diplay mode, CLI for CLI and GUI for ggez graphics
-    let mode: &str = match args[2].as_str() {
-        "CLI" => "CLI",
-        "GUI" => "GUI",
-        _ => {
+/**
+ * Returns the path of the configuration file `config`, or of the one of the platform
+ * configuration folder without a path, telling why when there is none.
+ */
+fn config_path(config: Option<String>) -> Option<String> {
+    let config =
+        config.or_else(|| config::default_config_path().map(|p| p.to_string_lossy().to_string()));
+    match config {
+        Some(path) if std::path::Path::new(&path).exists() => Some(path),
+        Some(path) => {
             eprintln!(
-                "This program should be launched as '{} <CONFIGURATION_PATH> [CLI/GUI]'.\n
-                Argument 2 should be one of 'CLI' or 'GUI', not '{}'.",
-                self_name, args[2]
+                "No configuration file at {}, run 'sudocurs --init-config' to write one.",
+                path
             );
-            return;
+            None
         }
-    };
+        None => {
+            eprintln!("No configuration folder was found, give a configuration file.");
+            None
+        }
+    }
+}
+
+/**
+ * Plays with the configuration file at `config_path`, at a prompt, in a full-screen terminal
+ * interface or in a window, starting
+ * with the f-puzzles JSON puzzle at `puzzle_path` if given, or the pack at the prompt.
+ */
+fn play(config_path: &str, mode: Mode, puzzle_path: Option<&str>) {
+    log::info!("Loading configuration file [{}].", config_path);
 
     // Launch the game either in CLI or GUI mode
     match mode {
-        "CLI" => {
+        Mode::Cli => {
             // Create the CLI using the configuration file
             let mut cli = match cli::Cli::new(config_path) {
                 Ok(cli) => cli,
                 Err(e) => {
-                    println!("{}", e);
+                    println!("{}", errors::report(&e));
                     return;
                 }
             };
 
+            // Import the given puzzle, if any
+            if let Some(path) = puzzle_path {
+                if let Err(e) = cli.load_puzzle(path) {
+                    eprintln!("{}", errors::report(&e));
+                    return;
+                }
+            }
+
             // Play
             if let Err(e) = cli.run() {
-                eprintln!("{}", e);
+                eprintln!("{}", errors::report(&e));
+            }
+        }
+        #[cfg(unix)]
+        Mode::Tui => {
+            let mut tui = match tui::Tui::new(config_path) {
+                Ok(tui) => tui,
+                Err(e) => {
+                    println!("{}", errors::report(&e));
+                    return;
+                }
+            };
+
+            // Import the given puzzle, if any
+            if let Some(path) = puzzle_path {
+                if let Err(e) = tui.load_puzzle(path) {
+                    eprintln!("{}", errors::report(&e));
+                    return;
+                }
+            }
+
+            if let Err(e) = tui.run() {
+                eprintln!("{}", errors::report(&e));
             }
         }
-        "GUI" => {
+        #[cfg(not(unix))]
+        Mode::Tui => eprintln!("The TUI is only available on Unix terminals."),
+        Mode::Gui => {
             // Init SDL Context
             let sdl_context = sdl2::init().unwrap();
             // Init TTF Context
@@ -91,22 +427,29 @@ pub fn main() {
             let mut gui = match gui::Gui::new(&sdl_context, &ttf_context, config_path) {
                 Ok(gui) => gui,
                 Err(e) => {
-                    eprintln!("{}", e);
+                    eprintln!("{}", errors::report(&e));
                     return;
                 }
             };
 
             // Try initiating the GUI
             if let Err(e) = gui.init() {
-                eprintln!("{}", e);
+                eprintln!("{}", errors::report(&e));
                 return;
             }
 
+            // Import the given puzzle, if any
+            if let Some(path) = puzzle_path {
+                if let Err(e) = gui.load_puzzle(path) {
+                    eprintln!("{}", errors::report(&e));
+                    return;
+                }
+            }
+
             // Launch the GUI
             if let Err(e) = gui.run() {
-                eprintln!("{}", e);
+                eprintln!("{}", errors::report(&e));
             }
         }
-        _ => panic!("WTF"),
     }
 }
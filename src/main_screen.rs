@@ -1,291 +1,229 @@
 use sdl2::event::Event;
-use sdl2::image::LoadSurface;
+use sdl2::keyboard::{Keycode, Mod};
 use sdl2::mouse::MouseButton;
 use sdl2::pixels::Color;
 use sdl2::rect::{Point, Rect};
 use sdl2::render::{Canvas, Texture, TextureCreator};
-use sdl2::surface::Surface;
+use sdl2::ttf::Font;
 use sdl2::video::Window;
-use std::collections::HashMap;
+use std::rc::Rc;
 
+use crate::dialog::{Dialog, DialogOutcome};
 use crate::errors::UiError;
 use crate::traits::{Displayable, GUIConfig, ScreenOutcome};
+use crate::widget::{Button, Column};
 
-pub struct MainScreen {
-    // Textures
-    textures: HashMap<String, (Texture, Rect)>,
+/// Colors of the text of the 'Daily Puzzle' button, and of it hovered.
+#[cfg(feature = "fetch")]
+static COLOR_DAILY: Color = Color::RGBA(0, 90, 90, 255);
+#[cfg(feature = "fetch")]
+static COLOR_DAILY_HOVER: Color = Color::RGBA(255, 110, 50, 255);
+/// Color of the name of the profile playing, above the buttons.
+static COLOR_PROFILE: Color = Color::RGBA(0, 60, 60, 255);
+/// Space between two buttons, and between the buttons and the profile.
+const SPACING: u32 = 10;
 
-    // Current Textures
-    current_btn_resume: String,
-    current_btn_new_game: String,
-    current_btn_exit: String,
+pub struct MainScreen<'a> {
+    font: Option<Rc<Font<'a, 'a>>>,
+
+    /// The entries of the menu, from 'Resume' to 'Exit'.
+    buttons: Column,
+    /// The name of the profile playing, drawn above the buttons.
+    profile: Option<Texture>,
+    viewport: Rect,
 
     // Outside vars
     pub has_current_game: bool,
+    /// Whether games were saved, the 'Resume' button then opens the saved games browser when
+    /// there is no current game.
+    pub has_saves: bool,
+
+    /// The question asked before the current game is left for another one, while it is open.
+    dialog: Option<Dialog<ScreenOutcome>>,
 }
 
-impl Displayable for MainScreen {
+impl<'a> Displayable for MainScreen<'a> {
     fn new() -> Self {
-        // Texture creator associated to the current canvas
-
         MainScreen {
-            textures: HashMap::new(),
-
-            current_btn_resume: "btn_resume".to_string(),
-            current_btn_new_game: "btn_new_game".to_string(),
-            current_btn_exit: "btn_exit".to_string(),
+            font: None,
+            buttons: Column::new(SPACING),
+            profile: None,
+            viewport: Rect::new(0, 0, 1, 1),
 
             has_current_game: false,
+            has_saves: false,
+
+            dialog: None,
         }
     }
 
     fn init(&mut self, canvas: &mut Canvas<Window>, config: &GUIConfig) -> Result<(), UiError> {
-        let texture_creator: TextureCreator<_> = canvas.texture_creator();
-
-        // 'RESUME' button
-        let btn_resume = match Surface::from_file(&config.btn_resume_path) {
-            Err(e) => {
-                eprintln!("{}", e);
-                return Err(UiError::LoadSpriteError);
-            }
-            Ok(surface) => surface,
-        };
-        let btn_resume_tex = texture_creator
-            .create_texture_from_surface(&btn_resume)
-            .unwrap();
-
-        // 'RESUME HOVER' button
-        let btn_resume_hover = match Surface::from_file(&config.btn_resume_hover_path) {
-            Err(e) => {
-                eprintln!("{}", e);
-                return Err(UiError::LoadSpriteError);
-            }
-            Ok(surface) => surface,
-        };
-        let btn_resume_hover_tex = texture_creator
-            .create_texture_from_surface(&btn_resume_hover)
-            .unwrap();
-
-        // 'NEW GAME' button
-        let btn_new_game = match Surface::from_file(&config.btn_new_game_path) {
-            Err(e) => {
-                eprintln!("{}", e);
-                return Err(UiError::LoadSpriteError);
-            }
-            Ok(surface) => surface,
-        };
-        let btn_new_game_tex = texture_creator
-            .create_texture_from_surface(&btn_new_game)
-            .unwrap();
-
-        // 'NEW GAME HOVER' button
-        let btn_new_game_hover = match Surface::from_file(&config.btn_new_game_hover_path) {
-            Err(e) => {
-                eprintln!("{}", e);
-                return Err(UiError::LoadSpriteError);
-            }
-            Ok(surface) => surface,
-        };
-        let btn_new_game_hover_tex = texture_creator
-            .create_texture_from_surface(&btn_new_game_hover)
-            .unwrap();
-
-        // 'EXIT' button
-        let btn_exit = match Surface::from_file(&config.btn_exit_path) {
-            Err(e) => {
-                eprintln!("{}", e);
-                return Err(UiError::LoadSpriteError);
-            }
-            Ok(surface) => surface,
-        };
-        let btn_exit_tex = texture_creator
-            .create_texture_from_surface(&btn_exit)
-            .unwrap();
-
-        // 'EXIT HOVER' button
-        let btn_exit_hover = match Surface::from_file(&config.btn_exit_hover_path) {
-            Err(e) => {
-                eprintln!("{}", e);
-                return Err(UiError::LoadSpriteError);
-            }
-            Ok(surface) => surface,
-        };
-        let btn_exit_hover_tex = texture_creator
-            .create_texture_from_surface(&btn_exit_hover)
-            .unwrap();
-
-        let btn_resume_pos = Rect::from_center(
-            Point::new(
-                (canvas.viewport().width() / 2).try_into().unwrap(),
-                (canvas.viewport().height() / 2
-                    - btn_new_game_tex.query().height / 2
-                    - 10
-                    - btn_resume_tex.query().height / 2)
-                    .try_into()
-                    .unwrap(),
+        // The buttons without images are drawn as text, with the font set before
+        let font = self.font.clone().unwrap();
+        self.buttons = Column::new(SPACING);
+        let no_image = String::new();
+        for ((path, hover_path), label, on_click) in [
+            (
+                (&config.btn_resume_path, &config.btn_resume_hover_path),
+                "Resume",
+                ScreenOutcome::Resume,
             ),
-            btn_resume_tex.query().width,
-            btn_resume_tex.query().height,
-        );
-
-        let btn_new_game_pos = Rect::from_center(
-            Point::new(
-                (canvas.viewport().width() / 2).try_into().unwrap(),
-                (canvas.viewport().height() / 2).try_into().unwrap(),
+            (
+                (&config.btn_new_game_path, &config.btn_new_game_hover_path),
+                "New Game",
+                ScreenOutcome::ChooseNewGame,
             ),
-            btn_new_game_tex.query().width,
-            btn_new_game_tex.query().height,
-        );
-        let btn_exit_pos = Rect::from_center(
-            Point::new(
-                (canvas.viewport().width() / 2).try_into().unwrap(),
-                (canvas.viewport().height() / 2
-                    + btn_new_game_tex.query().height / 2
-                    + 10
-                    + btn_exit_tex.query().height / 2)
-                    .try_into()
-                    .unwrap(),
+            // No image is configured for the time attack, the race, the network game and the
+            // editor
+            (
+                (&no_image, &no_image),
+                "Time Attack",
+                ScreenOutcome::TimeAttack,
             ),
-            btn_exit_tex.query().width,
-            btn_exit_tex.query().height,
-        );
-
-        self.textures
-            .insert(String::from("btn_resume"), (btn_resume_tex, btn_resume_pos));
-        self.textures.insert(
-            String::from("btn_resume_hover"),
-            (btn_resume_hover_tex, btn_resume_pos),
-        );
-
-        self.textures.insert(
-            String::from("btn_new_game"),
-            (btn_new_game_tex, btn_new_game_pos),
-        );
-        self.textures.insert(
-            String::from("btn_new_game_hover"),
-            (btn_new_game_hover_tex, btn_new_game_pos),
-        );
-
-        self.textures
-            .insert(String::from("btn_exit"), (btn_exit_tex, btn_exit_pos));
-        self.textures.insert(
-            String::from("btn_exit_hover"),
-            (btn_exit_hover_tex, btn_exit_pos),
-        );
-
-        Ok(())
+            ((&no_image, &no_image), "Race", ScreenOutcome::Race),
+            ((&no_image, &no_image), "Network Game", ScreenOutcome::Lobby),
+            (
+                (&no_image, &no_image),
+                "Puzzle Editor",
+                ScreenOutcome::Editor,
+            ),
+            (
+                (&config.btn_exit_path, &config.btn_exit_hover_path),
+                "Exit",
+                ScreenOutcome::Exit,
+            ),
+        ]
+        .into_iter()
+        // The network game needs the `net` feature
+        .filter(|(_, _, on_click)| cfg!(feature = "net") || *on_click != ScreenOutcome::Lobby)
+        {
+            self.buttons.push(Button::from_files_or_label(
+                canvas,
+                (path.as_str(), hover_path.as_str()),
+                &font,
+                label,
+                on_click,
+            )?);
+        }
+        self.resize(canvas)
     }
 
     fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
         canvas.set_draw_color(Color::RGB(0, 255, 255));
         canvas.clear();
 
-        if self.has_current_game {
-            if let Some((texture, position)) = self.textures.get(&self.current_btn_resume) {
-                canvas
-                    .copy(texture, None, *position)
-                    .map_err(|_| UiError::SDL2Error)?;
-            } else {
-                return Err(UiError::MissingLoadedTexture);
-            }
-        }
-        if let Some((texture, position)) = self.textures.get(&self.current_btn_new_game) {
+        self.show_resume();
+        self.buttons.draw(canvas)?;
+        if let Some(texture) = self.profile.as_ref() {
             canvas
-                .copy(texture, None, *position)
-                .map_err(|_| UiError::SDL2Error)?;
-        } else {
-            return Err(UiError::MissingLoadedTexture);
+                .copy(texture, None, self.profile_rect(texture))
+                .map_err(UiError::sdl2)?;
         }
-        if let Some((texture, position)) = self.textures.get(&self.current_btn_exit) {
-            canvas
-                .copy(texture, None, *position)
-                .map_err(|_| UiError::SDL2Error)?;
-        } else {
-            return Err(UiError::MissingLoadedTexture);
+        if let Some(dialog) = self.dialog.as_mut() {
+            dialog.draw(canvas, self.font.as_ref().unwrap())?;
         }
 
-        canvas.present();
+        Ok(())
+    }
+
+    fn resize(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        // The buttons keep their places around the center of the canvas
+        self.viewport = canvas.viewport();
+        self.buttons.layout(self.viewport);
 
         Ok(())
     }
 
     fn update(&mut self, event: &Event) -> Result<ScreenOutcome, UiError> {
-        match event {
-            Event::MouseMotion { x, y, .. } => {
-                // Draw buttons
-                if self.has_current_game {
-                    if self
-                        .textures
-                        .get("btn_resume")
-                        .unwrap()
-                        .1
-                        .contains_point(Point::new(*x, *y))
-                    {
-                        self.current_btn_resume = String::from("btn_resume_hover");
-                    } else {
-                        self.current_btn_resume = String::from("btn_resume");
-                    }
+        // The buttons wait for the open question to be answered
+        if let Some(dialog) = self.dialog.as_mut() {
+            return Ok(match dialog.update(event) {
+                DialogOutcome::Unchanged => ScreenOutcome::Unchanged,
+                DialogOutcome::Updated => ScreenOutcome::Updated,
+                DialogOutcome::Confirmed(outcome) => {
+                    self.dialog = None;
+                    outcome
                 }
-                if self
-                    .textures
-                    .get("btn_new_game")
-                    .unwrap()
-                    .1
-                    .contains_point(Point::new(*x, *y))
-                {
-                    self.current_btn_new_game = String::from("btn_new_game_hover");
-                } else {
-                    self.current_btn_new_game = String::from("btn_new_game");
+                DialogOutcome::Cancelled => {
+                    self.dialog = None;
+                    ScreenOutcome::Updated
                 }
-                if self
-                    .textures
-                    .get("btn_exit")
-                    .unwrap()
-                    .1
-                    .contains_point(Point::new(*x, *y))
-                {
-                    self.current_btn_exit = String::from("btn_exit_hover");
-                } else {
-                    self.current_btn_exit = String::from("btn_exit");
-                }
-                return Ok(ScreenOutcome::Updated);
-            }
+            });
+        }
 
+        self.show_resume();
+        match event {
+            Event::MouseMotion { x, y, .. } if self.buttons.hover(*x, *y) => {
+                return Ok(ScreenOutcome::Updated)
+            }
             Event::MouseButtonUp {
                 mouse_btn: MouseButton::Left,
                 x,
                 y,
                 ..
-            } => {
-                // Draw buttons
-                if self.has_current_game
-                    && self
-                        .textures
-                        .get("btn_resume")
-                        .unwrap()
-                        .1
-                        .contains_point(Point::new(*x, *y))
-                {
-                    return Ok(ScreenOutcome::Resume);
-                }
-                if self
-                    .textures
-                    .get("btn_new_game")
-                    .unwrap()
-                    .1
-                    .contains_point(Point::new(*x, *y))
-                {
-                    return Ok(ScreenOutcome::NewGame);
-                } else if self
-                    .textures
-                    .get("btn_exit")
-                    .unwrap()
-                    .1
-                    .contains_point(Point::new(*x, *y))
-                {
-                    return Ok(ScreenOutcome::Exit);
+            } => match self.buttons.clicked(*x, *y) {
+                // Without a current game, the saved ones are offered
+                Some(ScreenOutcome::Resume) if !self.has_current_game => {
+                    return Ok(ScreenOutcome::Saves)
                 }
+                Some(
+                    outcome @ (ScreenOutcome::ChooseNewGame
+                    | ScreenOutcome::Daily
+                    | ScreenOutcome::TimeAttack),
+                ) => return Ok(self.abandon(outcome)),
+                Some(outcome) => return Ok(outcome),
+                None => {}
+            },
+            Event::KeyDown {
+                keycode: Some(Keycode::P),
+                ..
+            } => return Ok(ScreenOutcome::Packs),
+            Event::KeyDown {
+                keycode: Some(Keycode::L),
+                ..
+            } => return Ok(ScreenOutcome::Saves),
+            Event::KeyDown {
+                keycode: Some(Keycode::D),
+                ..
+            } if self.buttons.button_mut(ScreenOutcome::Daily).is_some() => {
+                return Ok(self.abandon(ScreenOutcome::Daily))
             }
+            Event::KeyDown {
+                keycode: Some(Keycode::V),
+                keymod,
+                ..
+            } if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) => {
+                return Ok(self.abandon(ScreenOutcome::Paste))
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::T),
+                ..
+            } => return Ok(self.abandon(ScreenOutcome::TimeAttack)),
+            Event::KeyDown {
+                keycode: Some(Keycode::R),
+                ..
+            } => return Ok(ScreenOutcome::Race),
+            Event::KeyDown {
+                keycode: Some(Keycode::E),
+                ..
+            } => return Ok(ScreenOutcome::Editor),
+            #[cfg(feature = "net")]
+            Event::KeyDown {
+                keycode: Some(Keycode::N),
+                ..
+            } => return Ok(ScreenOutcome::Lobby),
+            Event::KeyDown {
+                keycode: Some(Keycode::S),
+                ..
+            } => return Ok(ScreenOutcome::Settings),
+            Event::KeyDown {
+                keycode: Some(Keycode::Tab),
+                ..
+            } => return Ok(ScreenOutcome::SwitchProfile),
+            Event::KeyDown {
+                keycode: Some(Keycode::Plus | Keycode::KpPlus | Keycode::Equals),
+                ..
+            } => return Ok(ScreenOutcome::AddProfile),
             _ => {}
         }
 
@@ -293,4 +231,98 @@ impl Displayable for MainScreen {
     }
 }
 
-impl MainScreen {}
+impl<'a> MainScreen<'a> {
+    pub fn set_font(&mut self, new_font: Rc<Font<'a, 'a>>) {
+        self.font = Some(new_font);
+    }
+
+    /**
+     * Returns `outcome`, which starts another game, once the player confirmed the current one
+     * can be left when there is one.
+     */
+    fn abandon(&mut self, outcome: ScreenOutcome) -> ScreenOutcome {
+        match self.has_current_game {
+            true => {
+                self.dialog = Some(Dialog::new("Abandon current game?", outcome));
+                ScreenOutcome::Updated
+            }
+            false => outcome,
+        }
+    }
+
+    /**
+     * Returns whether a question is waiting for an answer over the buttons.
+     */
+    pub fn has_dialog(&self) -> bool {
+        self.dialog.is_some()
+    }
+
+    /**
+     * Shows the 'Resume' button when there is a game to resume, current or saved.
+     */
+    fn show_resume(&mut self) {
+        let visible = self.has_current_game || self.has_saves;
+        if let Some(button) = self.buttons.button_mut(ScreenOutcome::Resume) {
+            button.visible = visible;
+        }
+    }
+
+    /**
+     * Returns where the name of the profile playing is drawn, centered above the buttons.
+     */
+    fn profile_rect(&self, texture: &Texture) -> Rect {
+        let query = texture.query();
+        let above = self.buttons.rect().unwrap_or(self.viewport);
+        Rect::from_center(
+            Point::new(
+                above.center().x(),
+                above.top() - SPACING as i32 - query.height as i32 / 2,
+            ),
+            query.width,
+            query.height,
+        )
+    }
+
+    /**
+     * Shows the name of the profile playing above the buttons, its text drawn with `font`.
+     */
+    pub fn set_profile(
+        &mut self,
+        canvas: &mut Canvas<Window>,
+        font: &Font,
+        name: &str,
+    ) -> Result<(), UiError> {
+        let texture_creator: TextureCreator<_> = canvas.texture_creator();
+        let surface = font
+            .render(&format!("Profile: {} (Tab to switch, + to add)", name))
+            .blended(COLOR_PROFILE)
+            .map_err(UiError::sdl2)?;
+        let texture = texture_creator
+            .create_texture_from_surface(&surface)
+            .map_err(UiError::sdl2)?;
+        self.profile = Some(texture);
+
+        Ok(())
+    }
+
+    /**
+     * Adds the 'Daily Puzzle' button below the others, its text drawn with `font`.
+     */
+    #[cfg(feature = "fetch")]
+    pub fn add_daily_button(
+        &mut self,
+        canvas: &mut Canvas<Window>,
+        font: &Font,
+    ) -> Result<(), UiError> {
+        self.buttons.push(Button::from_text(
+            canvas,
+            font,
+            "Daily Puzzle",
+            (COLOR_DAILY, COLOR_DAILY_HOVER),
+            ScreenOutcome::Daily,
+        )?);
+        self.buttons.layout(self.viewport);
+
+        Ok(())
+    }
+}
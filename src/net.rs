@@ -0,0 +1,435 @@
+use crate::errors::{GameError, UiError};
+use crate::game::{Cell, Game};
+
+use serde::{Deserialize, Serialize};
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+/// Address the lobby offers to host or to join at first.
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+/// Version of the messages, both instances having to speak the same one.
+pub const PROTOCOL_VERSION: u32 = 1;
+/// How long joining a host may take.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+/// How long an attempt at joining the host again may take, the interface waiting for it.
+const RECONNECT_TIMEOUT: Duration = Duration::from_millis(300);
+/// Wait between two attempts at joining the host again once the connection is lost.
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+/// Longest line of JSON accepted, well above the start of a 25x25 grid: the connection is
+/// dropped past it rather than keeping every byte of a line that never ends.
+const MAX_LINE: usize = 64 * 1024;
+
+/**
+ * How two players share a puzzle.
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum NetMode {
+    /// Both players fill a single grid, each move showing on both screens.
+    #[default]
+    Coop,
+    /// Each player races on their own copy of the grid, seeing how far the other one is.
+    Versus,
+}
+
+/**
+ * What the instances tell each other, written as a line of JSON.
+ */
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    /// The puzzle to play, sent by the host once a player joins and again when they come back,
+    /// with the cells of its grid.
+    Start {
+        version: u32,
+        mode: NetMode,
+        puzzle: String,
+        cells: Vec<u64>,
+    },
+    /// A cell of the shared grid changed, in co-op mode.
+    Cell { index: usize, cell: u64 },
+    /// How many cells of their grid the sender filled, in versus mode.
+    Progress { filled: usize, total: usize },
+    /// The sender completed their grid in `secs` of play, in versus mode.
+    Finished { secs: u64 },
+}
+
+/**
+ * What happened on the connection since it was last polled.
+ */
+#[derive(PartialEq, Eq, Debug)]
+pub enum NetEvent {
+    /// The other player joined, or came back after the connection was lost.
+    Connected,
+    /// The connection was lost, the host waiting for the other player to come back and the
+    /// guest trying to join it again.
+    Disconnected,
+    Received(Message),
+}
+
+/**
+ * Which side of the connection this instance is.
+ */
+enum Role {
+    /// Waits for the other player on its address.
+    Host(TcpListener),
+    /// Joined the host at this address.
+    Guest(String),
+}
+
+/**
+ * The connection between two instances, which never blocks the interface: the messages are
+ * read as they arrive, and the connection is set up again once it is lost.
+ */
+pub struct Link {
+    role: Role,
+    stream: Option<TcpStream>,
+    /// The bytes received after the last complete message.
+    pending: Vec<u8>,
+    /// When joining the host again was last tried.
+    last_attempt: Option<Instant>,
+    /// Whether the connection was lost while sending, which the next poll tells.
+    dropped: bool,
+}
+
+/**
+ * Returns a connection to `address`, set up within `timeout`, which does not block reading.
+ */
+fn connect(address: &str, timeout: Duration) -> Result<TcpStream, UiError> {
+    let socket = address
+        .to_socket_addrs()
+        .map_err(UiError::NetworkError)?
+        .next()
+        .ok_or_else(|| UiError::NetworkError(io::Error::from(ErrorKind::InvalidInput)))?;
+    let stream = TcpStream::connect_timeout(&socket, timeout).map_err(UiError::NetworkError)?;
+    prepare(&stream).map_err(UiError::NetworkError)?;
+
+    Ok(stream)
+}
+
+/**
+ * Sets `stream` up for the small messages of the players, which are sent at once and read
+ * without blocking.
+ */
+fn prepare(stream: &TcpStream) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    stream.set_nonblocking(true)
+}
+
+/**
+ * Moves the complete lines of `pending` to `events` as the messages they hold, the malformed
+ * ones being ignored.
+ */
+fn take_messages(pending: &mut Vec<u8>, events: &mut Vec<NetEvent>) {
+    while let Some(end) = pending.iter().position(|byte| *byte == b'\n') {
+        let line: Vec<u8> = pending.drain(..=end).collect();
+        match serde_json::from_slice(&line[..end]) {
+            Ok(message) => events.push(NetEvent::Received(message)),
+            Err(e) => log::warn!("Ignoring a malformed message: {}", e),
+        }
+    }
+}
+
+impl Link {
+    /**
+     * Waits for the other player on `address`, such as `0.0.0.0:7878` to be joined from the
+     * other machines of the network.
+     */
+    pub fn host(address: &str) -> Result<Self, UiError> {
+        let listener = TcpListener::bind(address).map_err(UiError::NetworkError)?;
+        listener
+            .set_nonblocking(true)
+            .map_err(UiError::NetworkError)?;
+
+        Ok(Link {
+            role: Role::Host(listener),
+            stream: None,
+            pending: Vec::new(),
+            last_attempt: None,
+            dropped: false,
+        })
+    }
+
+    /**
+     * Joins the host waiting on `address`.
+     */
+    pub fn join(address: &str) -> Result<Self, UiError> {
+        let stream = connect(address, CONNECT_TIMEOUT)?;
+
+        Ok(Link {
+            role: Role::Guest(address.to_string()),
+            stream: Some(stream),
+            pending: Vec::new(),
+            last_attempt: None,
+            dropped: false,
+        })
+    }
+
+    /**
+     * Returns whether this instance waits for the other player rather than joined them.
+     */
+    pub fn is_host(&self) -> bool {
+        matches!(self.role, Role::Host(_))
+    }
+
+    /**
+     * Returns whether the other player is connected.
+     */
+    pub fn is_connected(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /**
+     * Returns the address the host waits on, its port being picked by the system when it is 0.
+     */
+    pub fn local_address(&self) -> Option<String> {
+        match &self.role {
+            Role::Host(listener) => listener.local_addr().ok().map(|a| a.to_string()),
+            Role::Guest(_) => None,
+        }
+    }
+
+    /**
+     * Sends `message` to the other player, returning whether it was sent. The connection is
+     * dropped if it fails, the next poll telling it was lost.
+     */
+    pub fn send(&mut self, message: &Message) -> bool {
+        let Some(stream) = self.stream.as_mut() else {
+            return false;
+        };
+        let mut line = serde_json::to_vec(message).unwrap();
+        line.push(b'\n');
+
+        match stream.write_all(&line) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("Connection lost while sending: {}", e);
+                self.stream = None;
+                self.dropped = true;
+                false
+            }
+        }
+    }
+
+    /**
+     * Returns what happened since the last poll: the other player joining or coming back, the
+     * messages they sent and the connection being lost.
+     */
+    pub fn poll(&mut self) -> Vec<NetEvent> {
+        let mut events = Vec::new();
+        if std::mem::take(&mut self.dropped) {
+            events.push(NetEvent::Disconnected);
+        }
+        if self.stream.is_none() {
+            match self.reconnect() {
+                Some(stream) => {
+                    self.stream = Some(stream);
+                    self.pending.clear();
+                    events.push(NetEvent::Connected);
+                }
+                None => return events,
+            }
+        }
+
+        // Everything available is read, the connection being lost at its end or on an error
+        let mut lost = false;
+        let mut buffer = [0; 4096];
+        let stream = self.stream.as_mut().unwrap();
+        loop {
+            match stream.read(&mut buffer) {
+                Ok(0) => {
+                    lost = true;
+                    break;
+                }
+                Ok(read) => {
+                    self.pending.extend_from_slice(&buffer[..read]);
+                    take_messages(&mut self.pending, &mut events);
+                    if self.pending.len() > MAX_LINE {
+                        log::warn!(
+                            "Dropping a connection sending a line of over {} bytes",
+                            MAX_LINE
+                        );
+                        lost = true;
+                        break;
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    log::warn!("Connection lost while receiving: {}", e);
+                    lost = true;
+                    break;
+                }
+            }
+        }
+
+        if lost {
+            self.stream = None;
+            events.push(NetEvent::Disconnected);
+        }
+
+        events
+    }
+
+    /**
+     * Returns a new connection to the other player, if they joined the host again or if the
+     * host could be joined again.
+     */
+    fn reconnect(&mut self) -> Option<TcpStream> {
+        match &self.role {
+            Role::Host(listener) => {
+                let (stream, _) = listener.accept().ok()?;
+                prepare(&stream).ok()?;
+                Some(stream)
+            }
+            Role::Guest(address) => {
+                if self
+                    .last_attempt
+                    .is_some_and(|attempt| attempt.elapsed() < RECONNECT_DELAY)
+                {
+                    return None;
+                }
+                self.last_attempt = Some(Instant::now());
+                connect(address, RECONNECT_TIMEOUT).ok()
+            }
+        }
+    }
+}
+
+/**
+ * A puzzle shared with another instance: the changes of the grid are sent to the other player
+ * in co-op mode, and the progress of each player in versus mode.
+ */
+pub struct NetGame {
+    pub link: Link,
+    /// Picked by the host, known by the guest once the puzzle is received.
+    pub mode: NetMode,
+    /// The puzzle being played, once it is started.
+    pub puzzle: Option<String>,
+    /// Number of entries of the timeline of the game already shared, in co-op mode.
+    shared: usize,
+    /// The number of filled cells last sent, in versus mode.
+    sent_filled: Option<usize>,
+    /// The filled cells of the grid of the other player and their number, in versus mode.
+    pub opponent: Option<(usize, usize)>,
+    /// The play time in which the other player completed their grid, in versus mode.
+    pub opponent_finished: Option<u64>,
+}
+
+impl NetGame {
+    /**
+     * Returns a shared game over `link`, its puzzle being started later.
+     */
+    pub fn new(link: Link, mode: NetMode) -> Self {
+        NetGame {
+            link,
+            mode,
+            puzzle: None,
+            shared: 0,
+            sent_filled: None,
+            opponent: None,
+            opponent_finished: None,
+        }
+    }
+
+    /**
+     * Returns whether `game` is the shared one, rather than another game open meanwhile.
+     */
+    pub fn is_playing(&self, game: &Game) -> bool {
+        self.puzzle.as_deref() == Some(game.puzzle_id().as_str())
+    }
+
+    /**
+     * Shares `game` from now on, its changes so far being known by the other player.
+     */
+    pub fn begin(&mut self, game: &Game) {
+        self.puzzle = Some(game.puzzle_id());
+        self.shared = game.timeline.len();
+        self.sent_filled = None;
+    }
+
+    /**
+     * Returns the message starting `game` on the other instance, or bringing it up to date with
+     * the grid of the host when the guest comes back.
+     */
+    pub fn start_message(&self, game: &Game) -> Message {
+        Message::Start {
+            version: PROTOCOL_VERSION,
+            mode: self.mode,
+            puzzle: game.puzzle_id(),
+            cells: game.grid.iter().map(Cell::bits).collect(),
+        }
+    }
+
+    /**
+     * Sends the changes of `game` the other player does not know yet: the changed cells in co-op
+     * mode, or the number of filled cells in versus mode. Nothing is sent while the connection
+     * is lost, the host sending its whole grid once the guest comes back.
+     */
+    pub fn share(&mut self, game: &Game) {
+        if !self.link.is_connected() {
+            return;
+        }
+
+        match self.mode {
+            NetMode::Coop => {
+                for played in game.timeline[self.shared.min(game.timeline.len())..].iter() {
+                    let cell = game.grid[played.index].bits();
+                    self.link.send(&Message::Cell {
+                        index: played.index,
+                        cell,
+                    });
+                }
+                self.shared = game.timeline.len();
+            }
+            NetMode::Versus => {
+                let filled = game.grid.iter().filter(|cell| cell.value() != 0).count();
+                if self.sent_filled != Some(filled) {
+                    let total = game.grid.len();
+                    if self.link.send(&Message::Progress { filled, total }) {
+                        self.sent_filled = Some(filled);
+                    }
+                }
+            }
+        }
+    }
+
+    /**
+     * Applies the changes of the grid of the other player to `game`: their cells in co-op mode,
+     * their progress in versus mode. In co-op mode, the grid of the host replaces the one of the
+     * guest when it starts or comes back.
+     */
+    pub fn apply(&mut self, game: &mut Game, message: &Message) -> Result<(), GameError> {
+        match message {
+            Message::Start { mode, cells, .. } => {
+                self.mode = *mode;
+                if self.mode == NetMode::Coop {
+                    // The clues are the same on both grids
+                    for (index, bits) in cells.iter().enumerate() {
+                        if game.grid.get(index).is_some_and(|cell| !cell.initial()) {
+                            game.apply_shared_cell(index, Cell::from_bits(*bits))?;
+                        }
+                    }
+                }
+                self.begin(game);
+            }
+            Message::Cell { index, cell } => {
+                game.apply_shared_cell(*index, Cell::from_bits(*cell))?;
+                self.shared = game.timeline.len();
+            }
+            Message::Progress { filled, total } => self.opponent = Some((*filled, *total)),
+            Message::Finished { secs } => self.opponent_finished = Some(*secs),
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Forgets what was sent to the other player, for everything to be sent again once they
+     * come back.
+     */
+    pub fn reconnected(&mut self) {
+        self.sent_filled = None;
+    }
+}
@@ -0,0 +1,330 @@
+use sdl2::event::Event;
+use sdl2::keyboard::{Keycode, Mod};
+use sdl2::mouse::MouseButton;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::Canvas;
+use sdl2::ttf::Font;
+use sdl2::video::Window;
+
+use std::rc::Rc;
+
+use crate::errors::UiError;
+use crate::game::Variant;
+use crate::game_screen::digit_from_keycode;
+use crate::generator::Difficulty;
+use crate::traits::{Displayable, GUIConfig, ScreenOutcome};
+
+static COLOR_BCK: Color = Color::BLACK;
+static COLOR_HOVER: Color = Color::RGBA(75, 75, 75, 255);
+static COLOR_FONT: Color = Color::WHITE;
+static COLOR_BAD_MSG: Color = Color::RED;
+
+/// Space between the border of the window and the options.
+const MARGIN: i32 = 40;
+/// Height of a row of options.
+const ROW_HEIGHT: i32 = 40;
+/// Sizes of the boxes of the boards offered, for 4x4, 9x9 and 16x16 boards.
+const SIZES: [usize; 3] = [2, 3, 4];
+/// Difficulties offered, in the order they are cycled through.
+const DIFFICULTIES: [Difficulty; 4] = [
+    Difficulty::Easy,
+    Difficulty::Medium,
+    Difficulty::Hard,
+    Difficulty::Expert,
+];
+/// Variants offered, in the order they are cycled through.
+const VARIANTS: [Variant; 3] = [Variant::Classic, Variant::Diagonal, Variant::Hyper];
+/// Most digits of a seed, so that it always fits in a `u64`.
+const MAX_SEED_DIGITS: usize = 18;
+
+/// Rows of the screen, below its title.
+const ROW_SIZE: usize = 0;
+const ROW_DIFFICULTY: usize = 1;
+const ROW_VARIANT: usize = 2;
+const ROW_SEED: usize = 3;
+const ROW_START: usize = 4;
+const ROW_BACK: usize = 5;
+
+/**
+ * The options of a game to generate, picked on the new game screen.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NewGameOptions {
+    pub size: usize,
+    pub difficulty: Difficulty,
+    pub variant: Variant,
+    /// Seed of the generation, for a random puzzle when absent.
+    pub seed: Option<u64>,
+}
+
+/**
+ * Lets the player pick the size, difficulty and, optionally, the seed of a new game before it
+ * is generated.
+ */
+#[derive(Default)]
+pub struct NewGameScreen<'a> {
+    font: Option<Rc<Font<'a, 'a>>>,
+
+    size: usize,
+    difficulty: Difficulty,
+    variant: Variant,
+    /// The digits of the seed typed by the player, empty for a random puzzle.
+    seed: String,
+
+    /// The row under the mouse cursor.
+    hovered_row: Option<usize>,
+    viewport: Option<Rect>,
+    /// Why the last game could not be started, if it could not.
+    message: Option<String>,
+
+    /// The options picked by the player, to be started by the caller.
+    pub chosen: Option<NewGameOptions>,
+}
+
+impl<'a> NewGameScreen<'a> {
+    pub fn set_font(&mut self, new_font: Rc<Font<'a, 'a>>) {
+        self.font = Some(new_font);
+    }
+
+    /**
+     * Shows `message` below the options, e.g. why the game could not be started.
+     */
+    pub fn set_message(&mut self, message: String) {
+        self.message = Some(message);
+    }
+
+    /**
+     * Returns the area of the row `row`.
+     */
+    fn row_rect(&self, row: usize) -> Rect {
+        let width = self.viewport.map_or(0, |v| v.width() as i32);
+        Rect::new(
+            MARGIN,
+            MARGIN + (row as i32 + 1) * ROW_HEIGHT,
+            (width - 2 * MARGIN).max(1) as u32,
+            ROW_HEIGHT as u32,
+        )
+    }
+
+    /**
+     * Returns the row at the given window position, if any.
+     */
+    fn row_at(&self, x: i32, y: i32) -> Option<usize> {
+        (ROW_SIZE..=ROW_BACK).find(|row| self.row_rect(*row).contains_point((x, y)))
+    }
+
+    /**
+     * Returns the text of the row `row`.
+     */
+    fn row_text(&self, row: usize) -> String {
+        match row {
+            ROW_SIZE => format!("Size: {0}x{0}", self.size * self.size),
+            ROW_DIFFICULTY => format!("Difficulty: {:?}", self.difficulty),
+            ROW_VARIANT => format!("Variant: {:?}", self.variant),
+            ROW_SEED => match self.seed.is_empty() {
+                true => String::from("Seed: random (type digits to pick one)"),
+                false => format!("Seed: {}", self.seed),
+            },
+            ROW_START => String::from("Start"),
+            _ => String::from("Back"),
+        }
+    }
+
+    /**
+     * Keeps the picked options for the caller to start the game.
+     */
+    fn start(&mut self) -> ScreenOutcome {
+        self.message = None;
+        self.chosen = Some(NewGameOptions {
+            size: self.size,
+            difficulty: self.difficulty,
+            variant: self.variant,
+            seed: self.seed.parse::<u64>().ok(),
+        });
+
+        ScreenOutcome::Generate
+    }
+
+    /**
+     * Draws `text` with the screen's font, vertically centered in `area`.
+     */
+    fn draw_text(
+        &self,
+        canvas: &mut Canvas<Window>,
+        text: &str,
+        color: Color,
+        area: Rect,
+    ) -> Result<(), UiError> {
+        let texture_creator = canvas.texture_creator();
+        let surface = self
+            .font
+            .as_ref()
+            .unwrap()
+            .render(text)
+            .blended(color)
+            .map_err(UiError::sdl2)?;
+        let texture = texture_creator
+            .create_texture_from_surface(surface)
+            .map_err(UiError::sdl2)?;
+
+        let width = texture.query().width.min(area.width());
+        let height = texture.query().height.min(area.height());
+        canvas
+            .copy(
+                &texture,
+                Rect::new(0, 0, width, height),
+                Rect::new(
+                    area.x(),
+                    area.y() + (area.height() - height) as i32 / 2,
+                    width,
+                    height,
+                ),
+            )
+            .map_err(UiError::sdl2)
+    }
+}
+
+impl<'a> Displayable for NewGameScreen<'a> {
+    fn new() -> Self {
+        NewGameScreen {
+            ..Default::default()
+        }
+    }
+
+    fn init(&mut self, canvas: &mut Canvas<Window>, config: &GUIConfig) -> Result<(), UiError> {
+        // Start from the configured settings
+        self.size = match SIZES.contains(&config.game_size) {
+            true => config.game_size,
+            false => 3,
+        };
+        self.difficulty = config.difficulty;
+        self.variant = config.generation.variant;
+        self.viewport = Some(canvas.viewport());
+
+        Ok(())
+    }
+
+    fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        canvas.set_draw_color(COLOR_BCK);
+        canvas.clear();
+
+        let title_area = Rect::new(MARGIN, MARGIN, self.row_rect(0).width(), ROW_HEIGHT as u32);
+        self.draw_text(canvas, "New game", COLOR_FONT, title_area)?;
+
+        for row in ROW_SIZE..=ROW_BACK {
+            let area = self.row_rect(row);
+            if self.hovered_row == Some(row) {
+                canvas.set_draw_color(COLOR_HOVER);
+                canvas.fill_rect(area).map_err(UiError::sdl2)?;
+            }
+            self.draw_text(canvas, &self.row_text(row), COLOR_FONT, area)?;
+        }
+
+        if let Some(message) = self.message.as_ref() {
+            let area = self.row_rect(ROW_BACK + 1);
+            self.draw_text(canvas, message, COLOR_BAD_MSG, area)?;
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        self.viewport = Some(canvas.viewport());
+
+        Ok(())
+    }
+
+    fn update(&mut self, event: &Event) -> Result<ScreenOutcome, UiError> {
+        match event {
+            Event::MouseMotion { x, y, .. } => {
+                let hovered_row = self.row_at(*x, *y);
+                if hovered_row != self.hovered_row {
+                    self.hovered_row = hovered_row;
+                    return Ok(ScreenOutcome::Updated);
+                }
+            }
+            // Clicking an option cycles through its values
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => match self.row_at(*x, *y) {
+                Some(ROW_SIZE) => {
+                    let next = SIZES
+                        .iter()
+                        .position(|s| *s == self.size)
+                        .map_or(0, |i| i + 1);
+                    self.size = SIZES[next % SIZES.len()];
+                    return Ok(ScreenOutcome::Updated);
+                }
+                Some(ROW_DIFFICULTY) => {
+                    let next = DIFFICULTIES
+                        .iter()
+                        .position(|d| *d == self.difficulty)
+                        .map_or(0, |i| i + 1);
+                    self.difficulty = DIFFICULTIES[next % DIFFICULTIES.len()];
+                    return Ok(ScreenOutcome::Updated);
+                }
+                Some(ROW_VARIANT) => {
+                    let next = VARIANTS
+                        .iter()
+                        .position(|v| *v == self.variant)
+                        .map_or(0, |i| i + 1);
+                    self.variant = VARIANTS[next % VARIANTS.len()];
+                    return Ok(ScreenOutcome::Updated);
+                }
+                Some(ROW_SEED) => {
+                    self.seed.clear();
+                    return Ok(ScreenOutcome::Updated);
+                }
+                Some(ROW_START) => return Ok(self.start()),
+                Some(_) => return Ok(ScreenOutcome::Menu),
+                None => {}
+            },
+            Event::KeyDown {
+                keycode: Some(Keycode::Return | Keycode::KpEnter),
+                ..
+            } => return Ok(self.start()),
+            Event::KeyDown {
+                keycode: Some(Keycode::Escape),
+                ..
+            } => return Ok(ScreenOutcome::Menu),
+            Event::KeyDown {
+                keycode: Some(Keycode::V),
+                keymod,
+                ..
+            } if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) => {
+                // Play the puzzle in the clipboard rather than a generated one
+                return Ok(ScreenOutcome::Paste);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Backspace),
+                ..
+            } => {
+                self.seed.pop();
+                return Ok(ScreenOutcome::Updated);
+            }
+            // The seed is typed with the digit keys
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } => {
+                let digit = match keycode {
+                    Keycode::Num0 | Keycode::Kp0 => Some(0),
+                    _ => digit_from_keycode(*keycode),
+                };
+                if let Some(digit) = digit {
+                    if self.seed.len() < MAX_SEED_DIGITS {
+                        self.seed.push_str(&digit.to_string());
+                        return Ok(ScreenOutcome::Updated);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(ScreenOutcome::Unchanged)
+    }
+}
@@ -0,0 +1,157 @@
+use sdl2::event::Event;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, Canvas};
+use sdl2::ttf::Font;
+use sdl2::video::Window;
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::errors::UiError;
+use crate::logging;
+
+static COLOR_BCK: Color = Color::RGBA(0, 0, 0, 190);
+static COLOR_FONT: Color = Color::RGBA(120, 255, 120, 255);
+
+/// Height of a line of text, whatever the size of the font.
+const LINE_HEIGHT: u32 = 18;
+/// Space between the border of the overlay and its text.
+const PADDING: i32 = 8;
+/// Number of events listed.
+const KEPT_EVENTS: usize = 6;
+/// How often the overlay is drawn again while it is shown, for its numbers to stay current.
+pub const REFRESH: Duration = Duration::from_millis(250);
+
+/**
+ * Debugging information drawn over the top left corner of the window, toggled with `F3`: the
+ * frames drawn per second, the last events and the last solver and generator timings.
+ */
+#[derive(Default)]
+pub struct DebugOverlay {
+    /// Whether the overlay is drawn.
+    pub visible: bool,
+    /// When the frames of the last second were drawn.
+    frames: VecDeque<Instant>,
+    /// The last events, the latest one at the end.
+    events: VecDeque<String>,
+}
+
+/**
+ * Returns a short description of `event`, none for the ones not worth listing such as the
+ * mouse motions.
+ */
+fn describe(event: &Event) -> Option<String> {
+    match event {
+        Event::KeyDown {
+            keycode: Some(keycode),
+            keymod,
+            ..
+        } => Some(format!("key {} {:?}", keycode.name(), keymod)),
+        Event::MouseButtonUp {
+            mouse_btn, x, y, ..
+        } => Some(format!("click {:?} at {}, {}", mouse_btn, x, y)),
+        Event::MouseWheel { y, .. } => Some(format!("wheel {}", y)),
+        Event::Window { win_event, .. } => Some(format!("window {:?}", win_event)),
+        _ => None,
+    }
+}
+
+impl DebugOverlay {
+    /**
+     * Shows the overlay, or hides it.
+     */
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+        self.frames.clear();
+    }
+
+    /**
+     * Lists `event` among the last ones.
+     */
+    pub fn record_event(&mut self, event: &Event) {
+        if let Some(description) = describe(event) {
+            if self.events.len() == KEPT_EVENTS {
+                self.events.pop_front();
+            }
+            self.events.push_back(description);
+        }
+    }
+
+    /**
+     * Counts a frame being drawn, and returns the frames drawn during the last second.
+     */
+    fn count_frame(&mut self) -> usize {
+        let now = Instant::now();
+        self.frames.push_back(now);
+        while self
+            .frames
+            .front()
+            .is_some_and(|drawn| now.duration_since(*drawn) > Duration::from_secs(1))
+        {
+            self.frames.pop_front();
+        }
+
+        self.frames.len()
+    }
+
+    /**
+     * Draws the overlay over the screen drawn on `canvas` with `font`, leaving it to the caller
+     * to present the canvas.
+     */
+    pub fn draw(&mut self, canvas: &mut Canvas<Window>, font: &Font) -> Result<(), UiError> {
+        let mut lines = vec![
+            format!("FPS: {}", self.count_frame()),
+            String::from("Events:"),
+        ];
+        lines.extend(self.events.iter().map(|event| format!("  {}", event)));
+        lines.push(String::from("Timings:"));
+        lines.extend(
+            logging::timings()
+                .iter()
+                .map(|(label, took)| format!("  {}: {:.2} ms", label, took.as_secs_f64() * 1e3)),
+        );
+
+        // Each line is scaled to the same height, whatever the size of the font
+        let texture_creator = canvas.texture_creator();
+        let mut textures = Vec::new();
+        for line in lines.iter() {
+            let surface = font
+                .render(line)
+                .blended(COLOR_FONT)
+                .map_err(UiError::sdl2)?;
+            let texture = texture_creator
+                .create_texture_from_surface(surface)
+                .map_err(UiError::sdl2)?;
+            let query = texture.query();
+            let width = (query.width * LINE_HEIGHT / query.height.max(1)).max(1);
+            textures.push((texture, width));
+        }
+
+        let width = textures.iter().map(|(_, width)| *width).max().unwrap_or(1);
+        let height = LINE_HEIGHT * textures.len() as u32;
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(COLOR_BCK);
+        canvas
+            .fill_rect(Rect::new(
+                0,
+                0,
+                width + 2 * PADDING as u32,
+                height + 2 * PADDING as u32,
+            ))
+            .map_err(UiError::sdl2)?;
+        canvas.set_blend_mode(BlendMode::None);
+
+        let mut copied = Ok(());
+        for (i, (texture, width)) in textures.into_iter().enumerate() {
+            let y = PADDING + (i as u32 * LINE_HEIGHT) as i32;
+            if copied.is_ok() {
+                copied = canvas.copy(&texture, None, Rect::new(PADDING, y, width, LINE_HEIGHT));
+            }
+            // The overlay is drawn several times a second, its textures must not pile up
+            unsafe { texture.destroy() };
+        }
+
+        copied.map_err(UiError::sdl2)
+    }
+}
@@ -0,0 +1,376 @@
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::Canvas;
+use sdl2::ttf::Font;
+use sdl2::video::Window;
+
+use std::rc::Rc;
+
+use crate::errors::UiError;
+use crate::favorites::{Favorite, Favorites};
+use crate::packs::{Pack, Progress};
+use crate::traits::{Displayable, GUIConfig, ScreenOutcome};
+
+static COLOR_BCK: Color = Color::BLACK;
+static COLOR_HOVER: Color = Color::RGBA(75, 75, 75, 255);
+static COLOR_FONT: Color = Color::WHITE;
+static COLOR_DONE: Color = Color::GREEN;
+static COLOR_DESCRIPTION: Color = Color::RGBA(180, 180, 180, 255);
+static COLOR_BAD_MSG: Color = Color::RED;
+
+/// Space between the border of the window and the list.
+const MARGIN: i32 = 40;
+/// Height of a row of the list.
+const ROW_HEIGHT: i32 = 40;
+
+/**
+ * Lists the installed puzzle packs with the progress of the player in each of them, and lets
+ * the player start the next unsolved puzzle of a pack. The favorite puzzles of the profile
+ * playing are listed under their own row, to be played again.
+ */
+#[derive(Default)]
+pub struct PackScreen<'a> {
+    font: Option<Rc<Font<'a, 'a>>>,
+
+    /// Folder the packs are installed in.
+    packs_folder: String,
+    /// The installed packs.
+    packs: Vec<Pack>,
+    /// The puzzles solved by the player.
+    progress: Progress,
+    /// The favorite puzzles of the profile playing.
+    favorites: Favorites,
+    /// Whether the favorites are listed rather than the packs.
+    showing_favorites: bool,
+
+    /// The row under the mouse cursor, the last row being the 'Back' one.
+    hovered_row: Option<usize>,
+    message: Option<String>,
+    viewport: Option<Rect>,
+
+    /// The puzzle picked by the player, to be started by the caller.
+    pub chosen: Option<String>,
+    /// The favorite puzzle picked by the player, to be started by the caller with its variant.
+    pub chosen_favorite: Option<Favorite>,
+    /// The pack and the position in it of the puzzle last picked, for its play time to be
+    /// recorded once it is solved.
+    playing: Option<(usize, usize)>,
+}
+
+impl<'a> PackScreen<'a> {
+    pub fn set_font(&mut self, new_font: Rc<Font<'a, 'a>>) {
+        self.font = Some(new_font);
+    }
+
+    /**
+     * Shows `message` below the packs, e.g. why the picked puzzle could not be started.
+     */
+    pub fn set_message(&mut self, message: String) {
+        self.message = Some(message);
+    }
+
+    /**
+     * Lists the `favorites` of the profile playing, the packs being shown first.
+     */
+    pub fn set_favorites(&mut self, favorites: Favorites) {
+        self.favorites = favorites;
+        self.showing_favorites = false;
+        self.hovered_row = None;
+    }
+
+    /**
+     * Returns the number of rows of the list, the 'Back' one excluded: the packs and the
+     * 'Favorites' row, or the favorites.
+     */
+    fn nb_rows(&self) -> usize {
+        match self.showing_favorites {
+            true => self.favorites.puzzles.len(),
+            false => self.packs.len() + 1,
+        }
+    }
+
+    /**
+     * Lists the favorites rather than the packs, or the other way around.
+     */
+    fn show_favorites(&mut self, shown: bool) -> ScreenOutcome {
+        self.showing_favorites = shown;
+        self.hovered_row = None;
+        self.message = None;
+        ScreenOutcome::Updated
+    }
+
+    /**
+     * Records the puzzle with the given id as solved in `seconds`, along with its play time in
+     * its pack when it is the puzzle picked last.
+     */
+    pub fn mark_solved(&mut self, id: &str, seconds: u64) -> Result<(), UiError> {
+        match self.playing.take() {
+            Some((pack, position)) if self.packs[pack].puzzles[position] == id => self
+                .progress
+                .mark_solved_in(&self.packs[pack], position, seconds),
+            playing => {
+                self.playing = playing;
+                self.progress.mark_solved(id)
+            }
+        }
+    }
+
+    /**
+     * Returns the area of the row `row` of the list.
+     */
+    fn row_rect(&self, row: usize) -> Rect {
+        let width = self.viewport.map_or(0, |v| v.width() as i32);
+        Rect::new(
+            MARGIN,
+            MARGIN + (row as i32 + 1) * ROW_HEIGHT,
+            (width - 2 * MARGIN).max(1) as u32,
+            ROW_HEIGHT as u32,
+        )
+    }
+
+    /**
+     * Returns the row of the list at the given window position, if any.
+     */
+    fn row_at(&self, x: i32, y: i32) -> Option<usize> {
+        (0..=self.nb_rows()).find(|row| self.row_rect(*row).contains_point((x, y)))
+    }
+
+    /**
+     * Draws `text` with the screen's font, vertically centered in `area`.
+     */
+    fn draw_text(
+        &self,
+        canvas: &mut Canvas<Window>,
+        text: &str,
+        color: Color,
+        area: Rect,
+    ) -> Result<(), UiError> {
+        let texture_creator = canvas.texture_creator();
+        let surface = self
+            .font
+            .as_ref()
+            .unwrap()
+            .render(text)
+            .blended(color)
+            .map_err(UiError::sdl2)?;
+        let texture = texture_creator
+            .create_texture_from_surface(surface)
+            .map_err(UiError::sdl2)?;
+
+        let width = texture.query().width.min(area.width());
+        let height = texture.query().height.min(area.height());
+        canvas
+            .copy(
+                &texture,
+                Rect::new(0, 0, width, height),
+                Rect::new(
+                    area.x(),
+                    area.y() + (area.height() - height) as i32 / 2,
+                    width,
+                    height,
+                ),
+            )
+            .map_err(UiError::sdl2)
+    }
+
+    /**
+     * Draws a row per favorite puzzle, then the 'Back' row going back to the packs.
+     */
+    fn draw_favorites(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        for row in 0..=self.nb_rows() {
+            let area = self.row_rect(row);
+            if self.hovered_row == Some(row) {
+                canvas.set_draw_color(COLOR_HOVER);
+                canvas.fill_rect(area).map_err(UiError::sdl2)?;
+            }
+
+            let (text, color) = match self.favorites.puzzles.get(row) {
+                Some(favorite) => match self.progress.is_solved(&favorite.id) {
+                    true => (format!("{} - solved", favorite), COLOR_DONE),
+                    false => (favorite.to_string(), COLOR_FONT),
+                },
+                None => (String::from("Back"), COLOR_FONT),
+            };
+            self.draw_text(canvas, &text, color, area)?;
+        }
+
+        if let Some(message) = self.message.as_ref() {
+            let area = self.row_rect(self.nb_rows() + 1);
+            self.draw_text(canvas, message, COLOR_BAD_MSG, area)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Displayable for PackScreen<'a> {
+    fn new() -> Self {
+        PackScreen {
+            ..Default::default()
+        }
+    }
+
+    fn init(&mut self, canvas: &mut Canvas<Window>, config: &GUIConfig) -> Result<(), UiError> {
+        self.packs_folder = format!("{}packs", config.save_folder_path);
+        self.packs = Pack::load_all(&config.save_folder_path)?;
+        self.progress = Progress::load(&config.save_folder_path)?;
+        self.viewport = Some(canvas.viewport());
+
+        Ok(())
+    }
+
+    fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        canvas.set_draw_color(COLOR_BCK);
+        canvas.clear();
+
+        // Title, or where to install packs if there is none
+        let title_area = Rect::new(MARGIN, MARGIN, self.row_rect(0).width(), ROW_HEIGHT as u32);
+        let title = match (self.showing_favorites, self.packs.is_empty()) {
+            (true, _) if self.favorites.puzzles.is_empty() => String::from(
+                "No favorite puzzle yet, press F while playing or once solved to add one",
+            ),
+            (true, _) => String::from("Favorite puzzles"),
+            (false, true) => format!(
+                "No pack installed, add .pack or .sdm files to {}",
+                self.packs_folder
+            ),
+            (false, false) => String::from("Puzzle packs"),
+        };
+        self.draw_text(canvas, &title, COLOR_FONT, title_area)?;
+
+        if self.showing_favorites {
+            return self.draw_favorites(canvas);
+        }
+
+        // One row per pack, then the 'Favorites' and 'Back' rows
+        for row in 0..=self.nb_rows() {
+            let area = self.row_rect(row);
+            if self.hovered_row == Some(row) {
+                canvas.set_draw_color(COLOR_HOVER);
+                canvas.fill_rect(area).map_err(UiError::sdl2)?;
+            }
+
+            let (text, color) = match self.packs.get(row) {
+                Some(pack) => {
+                    let solved = pack.nb_solved(&self.progress);
+                    let color = match solved == pack.puzzles.len() {
+                        true => COLOR_DONE,
+                        false => COLOR_FONT,
+                    };
+                    let author = pack
+                        .author
+                        .as_ref()
+                        .map(|author| format!(" by {}", author))
+                        .unwrap_or_default();
+                    let seconds = pack.play_time(&self.progress);
+                    (
+                        format!(
+                            "{}{} - {}/{} solved in {:02}:{:02}",
+                            pack.name,
+                            author,
+                            solved,
+                            pack.puzzles.len(),
+                            seconds / 60,
+                            seconds % 60
+                        ),
+                        color,
+                    )
+                }
+                None if row == self.packs.len() => (
+                    format!("Favorites - {} puzzles", self.favorites.puzzles.len()),
+                    COLOR_FONT,
+                ),
+                None => (String::from("Back"), COLOR_FONT),
+            };
+            self.draw_text(canvas, &text, color, area)?;
+        }
+
+        // The message, or else the description of the hovered pack
+        let area = self.row_rect(self.nb_rows() + 1);
+        if let Some(message) = self.message.as_ref() {
+            self.draw_text(canvas, message, COLOR_BAD_MSG, area)?;
+        } else if let Some(description) = self
+            .hovered_row
+            .and_then(|row| self.packs.get(row))
+            .and_then(|pack| pack.description.as_ref())
+        {
+            self.draw_text(canvas, description, COLOR_DESCRIPTION, area)?;
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        self.viewport = Some(canvas.viewport());
+
+        Ok(())
+    }
+
+    fn update(&mut self, event: &Event) -> Result<ScreenOutcome, UiError> {
+        match event {
+            Event::MouseMotion { x, y, .. } => {
+                let hovered_row = self.row_at(*x, *y);
+                if hovered_row != self.hovered_row {
+                    self.hovered_row = hovered_row;
+                    return Ok(ScreenOutcome::Updated);
+                }
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Escape),
+                ..
+            } if self.showing_favorites => return Ok(self.show_favorites(false)),
+            Event::KeyDown {
+                keycode: Some(Keycode::Escape),
+                ..
+            } => {
+                self.message = None;
+                return Ok(ScreenOutcome::Menu);
+            }
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } if self.showing_favorites => match self.row_at(*x, *y) {
+                Some(row) if row == self.nb_rows() => return Ok(self.show_favorites(false)),
+                Some(row) => {
+                    self.chosen_favorite = Some(self.favorites.puzzles[row].clone());
+                    self.message = None;
+                    return Ok(ScreenOutcome::Play);
+                }
+                None => {}
+            },
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => match self.row_at(*x, *y) {
+                Some(row) if row == self.packs.len() => return Ok(self.show_favorites(true)),
+                Some(row) if row == self.nb_rows() => {
+                    self.message = None;
+                    return Ok(ScreenOutcome::Menu);
+                }
+                Some(row) => match self.packs[row].next_unsolved_from(&self.progress, 0) {
+                    Some(position) => {
+                        self.chosen = Some(self.packs[row].puzzles[position].clone());
+                        self.playing = Some((row, position));
+                        self.message = None;
+                        return Ok(ScreenOutcome::Play);
+                    }
+                    None => {
+                        self.message = Some(String::from("Every puzzle of this pack is solved."));
+                        return Ok(ScreenOutcome::Updated);
+                    }
+                },
+                None => {}
+            },
+            _ => {}
+        }
+
+        Ok(ScreenOutcome::Unchanged)
+    }
+}
@@ -0,0 +1,254 @@
+use crate::errors::UiError;
+use crate::generator::Difficulty;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Name of the folder holding the installed packs, inside the save folder.
+const PACKS_FOLDER: &str = "packs";
+/// Extension of the pack files holding one puzzle per line.
+const PACK_EXTENSION: &str = "sdm";
+/// Extension of the JSON pack files, with a name and metadata along with the puzzles.
+const PACK_FILE_EXTENSION: &str = "pack";
+/// Name of the file keeping track of the solved puzzles, stored in the save folder.
+const PROGRESS_FILE: &str = "progress.json";
+
+/**
+ * Returns the canonical form of the puzzle written on `line` (see `Game::puzzle_id`), if it is
+ * one. Empty cells can be written either `.` or `0`.
+ */
+pub fn normalize_puzzle(line: &str) -> Option<String> {
+    let line = line.trim();
+    if !(2..=5usize).any(|size| size.pow(4) == line.len()) {
+        return None;
+    }
+
+    line.chars()
+        .map(|c| match c {
+            '.' | '0' => Some('.'),
+            _ if c.is_ascii_alphanumeric() => Some(c.to_ascii_uppercase()),
+            _ => None,
+        })
+        .collect()
+}
+
+/**
+ * A named and ordered collection of puzzles, installed in the packs folder or played with
+ * `sudocurs pack play`.
+ *
+ * The `.pack` files describe it in JSON, only `puzzles` being required:
+ * `{"name": "First steps", "author": "...", "description": "...", "difficulty": "easy",
+ * "puzzles": ["1.3.4...2..1.3.4", ...]}`. The `.sdm` files only hold the puzzles, one per line.
+ */
+#[derive(Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct Pack {
+    /// Name of the pack, the file name when it is not given.
+    pub name: String,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    /// The difficulty the puzzles were chosen for.
+    pub difficulty: Option<Difficulty>,
+    /// The puzzles of the pack in the order they are played, in their canonical form.
+    pub puzzles: Vec<String>,
+}
+
+impl Pack {
+    /**
+     * Loads the pack at `path`: a `.pack` file, or the puzzles of a `.sdm` file, whose lines
+     * that are not puzzles are ignored. The puzzles of a `.pack` file must all be valid.
+     */
+    pub fn from_file(path: &Path) -> Result<Pack, UiError> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            log::warn!("Error while loading the pack {:?}: {}", path, e);
+            UiError::LoadPacksError(e)
+        })?;
+
+        let mut pack = match path.extension().and_then(|e| e.to_str()) {
+            Some(PACK_FILE_EXTENSION) => {
+                let mut pack = serde_json::from_str::<Pack>(&content).map_err(|e| {
+                    log::warn!("Error while loading the pack {:?}: {}", path, e);
+                    UiError::LoadPacksError(e.into())
+                })?;
+                pack.puzzles = pack
+                    .puzzles
+                    .iter()
+                    .map(|p| {
+                        normalize_puzzle(p).ok_or_else(|| {
+                            log::warn!("Error while loading the pack {:?}: invalid {}", path, p);
+                            UiError::LoadPacksError(io::ErrorKind::InvalidData.into())
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+                pack
+            }
+            _ => Pack {
+                puzzles: content.lines().filter_map(normalize_puzzle).collect(),
+                ..Default::default()
+            },
+        };
+        if pack.name.is_empty() {
+            pack.name = path.file_stem().unwrap().to_string_lossy().to_string();
+        }
+
+        Ok(pack)
+    }
+
+    /**
+     * Loads every pack of the packs folder of `save_folder`, sorted by name. A missing folder
+     * means no pack is installed.
+     */
+    pub fn load_all(save_folder: &str) -> Result<Vec<Pack>, UiError> {
+        let entries = match fs::read_dir(Path::new(save_folder).join(PACKS_FOLDER)) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(Vec::new()),
+        };
+
+        let mut packs = Vec::new();
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            let extension = path.extension().and_then(|e| e.to_str());
+            if [Some(PACK_EXTENSION), Some(PACK_FILE_EXTENSION)].contains(&extension) {
+                packs.push(Pack::from_file(&path)?);
+            }
+        }
+        packs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(packs)
+    }
+
+    /**
+     * Returns the number of puzzles of the pack that were solved.
+     */
+    pub fn nb_solved(&self, progress: &Progress) -> usize {
+        self.puzzles
+            .iter()
+            .filter(|p| progress.is_solved(p))
+            .count()
+    }
+
+    /**
+     * Returns the first puzzle of the pack that was not solved yet, if any.
+     */
+    pub fn next_unsolved(&self, progress: &Progress) -> Option<&String> {
+        self.next_unsolved_from(progress, 0)
+            .map(|position| &self.puzzles[position])
+    }
+
+    /**
+     * Returns the position of the first puzzle not solved yet from the position `start`, going
+     * back to the first puzzle after the last one, if any.
+     */
+    pub fn next_unsolved_from(&self, progress: &Progress, start: usize) -> Option<usize> {
+        let count = self.puzzles.len();
+        (start..start + count)
+            .map(|position| position % count)
+            .find(|position| !progress.is_solved(&self.puzzles[*position]))
+    }
+
+    /**
+     * Returns the play time, in seconds, of the puzzles solved in this pack.
+     */
+    pub fn play_time(&self, progress: &Progress) -> u64 {
+        progress
+            .packs
+            .get(&self.name)
+            .map_or(0, |times| times.values().sum())
+    }
+}
+
+/**
+ * The puzzles the player solved, persisted as JSON in the save folder.
+ */
+#[derive(Serialize, Deserialize, Default)]
+pub struct Progress {
+    /// Canonical forms of the solved puzzles, see `Game::puzzle_id`.
+    pub solved: Vec<String>,
+    /// The best play time, in seconds, of the puzzles solved in each pack, by pack name then
+    /// position of the puzzle.
+    #[serde(default)]
+    pub packs: BTreeMap<String, BTreeMap<usize, u64>>,
+
+    /// Where the progress is saved.
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl Progress {
+    /**
+     * Loads the progress stored in `save_folder`, an absent file meaning nothing was solved yet.
+     */
+    pub fn load(save_folder: &str) -> Result<Self, UiError> {
+        let path = Path::new(save_folder).join(PROGRESS_FILE);
+
+        let mut progress = match fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str::<Progress>(&content) {
+                Ok(progress) => progress,
+                Err(e) => {
+                    log::warn!("Error while loading the progress {:?}: {}", path, e);
+                    return Err(UiError::LoadPacksError(e.into()));
+                }
+            },
+            Err(_) => Progress::default(),
+        };
+        progress.path = path;
+
+        Ok(progress)
+    }
+
+    /**
+     * Returns whether the puzzle with the given id was solved.
+     */
+    pub fn is_solved(&self, id: &str) -> bool {
+        self.solved.iter().any(|s| s == id)
+    }
+
+    /**
+     * Records the puzzle with the given id as solved, writing the progress file if it was not
+     * solved before.
+     */
+    pub fn mark_solved(&mut self, id: &str) -> Result<(), UiError> {
+        if self.is_solved(id) {
+            return Ok(());
+        }
+        self.solved.push(id.to_string());
+
+        self.save()
+    }
+
+    /**
+     * Records the puzzle at `position` in `pack` as solved in `seconds`, keeping its best time,
+     * and writes the progress file.
+     */
+    pub fn mark_solved_in(
+        &mut self,
+        pack: &Pack,
+        position: usize,
+        seconds: u64,
+    ) -> Result<(), UiError> {
+        let id = &pack.puzzles[position];
+        if !self.is_solved(id) {
+            self.solved.push(id.to_string());
+        }
+        let best = self
+            .packs
+            .entry(pack.name.clone())
+            .or_default()
+            .entry(position)
+            .or_insert(seconds);
+        *best = seconds.min(*best);
+
+        self.save()
+    }
+
+    /**
+     * Writes the progress file.
+     */
+    fn save(&self) -> Result<(), UiError> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| UiError::WriteProgressError(e.into()))?;
+        fs::write(&self.path, content).map_err(UiError::WriteProgressError)
+    }
+}
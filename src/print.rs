@@ -0,0 +1,166 @@
+use crate::errors::GameError;
+use crate::game::{value_symbol, Game};
+use crate::packs;
+
+use std::path::Path;
+
+/// Number of puzzles printed on a page, pages being separated by a form feed.
+const PUZZLES_PER_PAGE: usize = 2;
+
+/**
+ * Characters used to draw the borders of a grid: the horizontal and vertical lines, then the
+ * corners and crossings from top left to bottom right.
+ */
+struct Borders {
+    horizontal: char,
+    vertical: char,
+    corners: [[char; 3]; 3],
+}
+
+const UNICODE: Borders = Borders {
+    horizontal: '─',
+    vertical: '│',
+    corners: [['┌', '┬', '┐'], ['├', '┼', '┤'], ['└', '┴', '┘']],
+};
+
+const ASCII: Borders = Borders {
+    horizontal: '-',
+    vertical: '|',
+    corners: [['+', '+', '+'], ['+', '+', '+'], ['+', '+', '+']],
+};
+
+/**
+ * Renders the `values` of a grid of the given size as text, row by row, 0 being an empty cell.
+ */
+pub fn render_grid(size: usize, values: &[u8], ascii: bool) -> String {
+    let borders = match ascii {
+        true => ASCII,
+        false => UNICODE,
+    };
+    let side_size = size * size;
+
+    // Border line between groups: 0 for the top one, 1 inside, 2 for the bottom one
+    let line = |kind: usize| {
+        let segment = borders.horizontal.to_string().repeat(size * 2 + 1);
+        let mut line = borders.corners[kind][0].to_string();
+        line.push_str(&vec![segment; size].join(&borders.corners[kind][1].to_string()));
+        line.push(borders.corners[kind][2]);
+        line.push('\n');
+        line
+    };
+
+    let mut text = line(0);
+    for r in 0..side_size {
+        if r != 0 && r % size == 0 {
+            text.push_str(&line(1));
+        }
+        for c in 0..side_size {
+            if c % size == 0 {
+                text.push(borders.vertical);
+                text.push(' ');
+            }
+            text.push(match values[r * side_size + c] {
+                0 if ascii => '.',
+                0 => ' ',
+                value => value_symbol(value),
+            });
+            text.push(' ');
+        }
+        text.push(borders.vertical);
+        text.push('\n');
+    }
+    text.push_str(&line(2));
+
+    text
+}
+
+/**
+ * Renders the given puzzles (see `Game::puzzle_id`) for printing, a few per page, followed by
+ * their solutions if `with_solutions` is set.
+ */
+pub fn render_puzzles(puzzles: &[String], with_solutions: bool, ascii: bool) -> String {
+    let games: Vec<Option<Game>> = puzzles
+        .iter()
+        .map(|p| Game::from_puzzle(p, None).ok())
+        .collect();
+
+    let mut pages: Vec<String> = games
+        .chunks(PUZZLES_PER_PAGE)
+        .enumerate()
+        .map(|(page, chunk)| {
+            chunk
+                .iter()
+                .enumerate()
+                .map(|(i, game)| {
+                    let number = page * PUZZLES_PER_PAGE + i + 1;
+                    match game {
+                        Some(game) => {
+                            let values: Vec<u8> = game.grid.iter().map(|x| x.value()).collect();
+                            format!(
+                                "Puzzle {}\n\n{}",
+                                number,
+                                render_grid(game.size, &values, ascii)
+                            )
+                        }
+                        None => format!("Puzzle {}: invalid puzzle.\n", number),
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join("\n")
+        })
+        .collect();
+
+    if with_solutions {
+        let mut answers = String::from("Answers\n");
+        for (i, game) in games.iter().enumerate() {
+            answers.push_str(&format!("\nPuzzle {}\n\n", i + 1));
+            match game.as_ref().map(|g| (g.size, g.solution())) {
+                Some((size, Ok(solution))) => {
+                    answers.push_str(&render_grid(size, &solution, ascii))
+                }
+                _ => answers.push_str("No solution found.\n"),
+            }
+        }
+        pages.push(answers);
+    }
+
+    pages.join("\x0c")
+}
+
+/**
+ * Returns the puzzles designated by `source`: either a single puzzle or the path of a
+ * collection holding one puzzle per line.
+ */
+pub fn load_puzzles(source: &str) -> Result<Vec<String>, std::io::Error> {
+    match packs::normalize_puzzle(source) {
+        Some(puzzle) => Ok(vec![puzzle]),
+        None => Ok(std::fs::read_to_string(source)?
+            .lines()
+            .filter_map(packs::normalize_puzzle)
+            .collect()),
+    }
+}
+
+/**
+ * Returns the games designated by `source`: the puzzle of a SadMan Sudoku `.sdk` file, the
+ * puzzle of a `.game` save without its moves or of a `.csv` grid without its placed values, or
+ * the puzzles of `load_puzzles`. Each game is an
+ * error when its puzzle does not describe a valid grid.
+ */
+pub fn load_games(source: &str) -> Result<Vec<Result<Game, GameError>>, std::io::Error> {
+    match Path::new(source).extension().and_then(|e| e.to_str()) {
+        Some("sdk") => Ok(vec![Game::from_sdk(
+            &std::fs::read_to_string(source)?,
+            None,
+        )]),
+        Some("game" | "csv") => {
+            Ok(vec![Game::read(source).and_then(|game| {
+                Game::from_puzzle(&game.puzzle_id(), None)
+            })])
+        }
+        _ => Ok(load_puzzles(source)?
+            .iter()
+            .map(|puzzle| Game::from_puzzle(puzzle, None))
+            .collect()),
+    }
+}
@@ -0,0 +1,304 @@
+use crate::errors::UiError;
+use crate::favorites::Favorites;
+use crate::game::Game;
+use crate::generator::Difficulty;
+use crate::time_attack::{Run, TimeAttack};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Name of the profiles file, stored in the save folder.
+const PROFILES_FILE: &str = "profiles.json";
+/// Name of the profile created when there is none yet.
+const DEFAULT_PROFILE: &str = "Player";
+/// Number of time attacks kept on the leaderboard of each difficulty.
+pub const LEADERBOARD_SIZE: usize = 10;
+/// How the days of the solved puzzles are written in the profiles file.
+pub(crate) const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/**
+ * Formats `seconds` as minutes and seconds, `MM:SS`.
+ */
+pub(crate) fn clock(seconds: u64) -> String {
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/**
+ * The puzzles of a difficulty solved by a player, and how long they took.
+ */
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, Debug)]
+pub struct DifficultyStats {
+    /// How many puzzles were solved.
+    pub solved: u32,
+    /// The shortest play time of a solved puzzle, in seconds.
+    pub best_secs: Option<u64>,
+    /// The play time of all the solved puzzles, in seconds.
+    pub total_secs: u64,
+}
+
+impl DifficultyStats {
+    /**
+     * Returns the average play time of the solved puzzles, in seconds, if there are any.
+     */
+    pub fn average_secs(&self) -> Option<u64> {
+        (self.solved > 0).then(|| self.total_secs / self.solved as u64)
+    }
+}
+
+/**
+ * A player and their statistics.
+ */
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, Debug)]
+pub struct Profile {
+    pub name: String,
+    /// The puzzles solved, by difficulty.
+    #[serde(default)]
+    pub stats: BTreeMap<Difficulty, DifficultyStats>,
+    /// Days in a row a puzzle was solved, up to `last_solved`.
+    #[serde(default)]
+    pub streak: u32,
+    /// The longest streak so far.
+    #[serde(default)]
+    pub best_streak: u32,
+    /// The day the last puzzle was solved, written `YYYY-MM-DD`.
+    #[serde(default)]
+    pub last_solved: Option<String>,
+    /// The best time attacks by difficulty, the best one first.
+    #[serde(default)]
+    pub time_attacks: BTreeMap<Difficulty, Vec<Run>>,
+    /// The puzzles the player marked as favorite, to replay or share them later.
+    #[serde(default)]
+    pub favorites: Favorites,
+}
+
+impl Profile {
+    /**
+     * Returns a new profile named `name`, without any solved puzzle.
+     */
+    pub fn new(name: &str) -> Self {
+        Profile {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /**
+     * Returns how many puzzles were solved, whatever their difficulty.
+     */
+    pub fn solved(&self) -> u32 {
+        self.stats.values().map(|stats| stats.solved).sum()
+    }
+
+    /**
+     * Counts a puzzle of `difficulty` solved on `today` in `elapsed`, the streak going on if the
+     * last one was solved the day before.
+     */
+    pub fn record(&mut self, difficulty: Difficulty, elapsed: Duration, today: NaiveDate) {
+        let seconds = elapsed.as_secs();
+        let stats = self.stats.entry(difficulty).or_default();
+        stats.solved += 1;
+        stats.total_secs += seconds;
+        stats.best_secs = Some(stats.best_secs.map_or(seconds, |best| best.min(seconds)));
+
+        let last = self
+            .last_solved
+            .as_deref()
+            .and_then(|day| NaiveDate::parse_from_str(day, DATE_FORMAT).ok());
+        self.streak = match last {
+            Some(day) if day == today => self.streak.max(1),
+            Some(day) if today.pred_opt() == Some(day) => self.streak + 1,
+            _ => 1,
+        };
+        self.best_streak = self.best_streak.max(self.streak);
+        self.last_solved = Some(today.format(DATE_FORMAT).to_string());
+    }
+
+    /**
+     * Keeps `run`, a time attack of `difficulty`, on the leaderboard if it is among the
+     * `LEADERBOARD_SIZE` best ones, returning its rank from 0. A run ties with the earlier
+     * ones of the same score below them.
+     */
+    pub fn record_time_attack(&mut self, difficulty: Difficulty, run: Run) -> Option<usize> {
+        let board = self.time_attacks.entry(difficulty).or_default();
+        let rank = board
+            .iter()
+            .position(|kept| kept.score > run.score)
+            .unwrap_or(board.len());
+        if rank >= LEADERBOARD_SIZE {
+            return None;
+        }
+
+        board.insert(rank, run);
+        board.truncate(LEADERBOARD_SIZE);
+        Some(rank)
+    }
+
+    /**
+     * Describes the `count` best time attacks of `difficulty`, one per line.
+     */
+    pub fn leaderboard(&self, difficulty: Difficulty, count: usize) -> Vec<String> {
+        let board = self
+            .time_attacks
+            .get(&difficulty)
+            .map_or(&[][..], Vec::as_slice);
+        board
+            .iter()
+            .take(count)
+            .enumerate()
+            .map(|(i, run)| format!("{}. {} on {}", i + 1, clock(run.score), run.date))
+            .collect()
+    }
+
+    /**
+     * Describes the statistics of the profile in a few lines, the ones of `difficulty` in
+     * detail.
+     */
+    pub fn summary(&self, difficulty: Difficulty) -> Vec<String> {
+        let stats = self.stats.get(&difficulty).cloned().unwrap_or_default();
+        let mut lines = vec![format!("{}: {} solved", self.name, self.solved())];
+        lines.push(match (stats.best_secs, stats.average_secs()) {
+            (Some(best), Some(average)) => format!(
+                "{:?}: {} solved, best {}, average {}",
+                difficulty,
+                stats.solved,
+                clock(best),
+                clock(average)
+            ),
+            _ => format!("{:?}: none solved", difficulty),
+        });
+        lines.push(format!(
+            "Streak: {} {} (best {})",
+            self.streak,
+            match self.streak {
+                1 => "day",
+                _ => "days",
+            },
+            self.best_streak
+        ));
+
+        lines
+    }
+}
+
+/**
+ * The players of this save folder, one of them playing, persisted as JSON in the save folder.
+ */
+#[derive(Serialize, Deserialize, Default)]
+pub struct Profiles {
+    pub profiles: Vec<Profile>,
+    /// Index of the profile playing.
+    #[serde(default)]
+    pub current: usize,
+
+    /// Where the profiles are saved.
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl Profiles {
+    /**
+     * Loads the profiles stored in `save_folder`, an absent file meaning a single default
+     * profile.
+     */
+    pub fn load(save_folder: &str) -> Result<Self, UiError> {
+        let path = Path::new(save_folder).join(PROFILES_FILE);
+
+        let mut profiles = match fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str::<Profiles>(&content) {
+                Ok(profiles) => profiles,
+                Err(e) => {
+                    log::warn!("Error while loading the profiles {:?}: {}", path, e);
+                    return Err(UiError::LoadProfilesError(e));
+                }
+            },
+            Err(_) => Profiles::default(),
+        };
+        if profiles.profiles.is_empty() {
+            profiles.profiles.push(Profile::new(DEFAULT_PROFILE));
+        }
+        profiles.current = profiles.current.min(profiles.profiles.len() - 1);
+        profiles.path = path;
+
+        Ok(profiles)
+    }
+
+    /**
+     * Writes the profiles back to their file.
+     */
+    pub fn save(&self) -> Result<(), UiError> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| UiError::WriteProfilesError(e.into()))?;
+        fs::write(&self.path, content).map_err(UiError::WriteProfilesError)
+    }
+
+    /**
+     * Returns the profile playing.
+     */
+    pub fn current(&self) -> &Profile {
+        &self.profiles[self.current]
+    }
+
+    /**
+     * Makes the next profile the playing one, then saves the profiles.
+     */
+    pub fn switch(&mut self) -> Result<&Profile, UiError> {
+        self.current = (self.current + 1) % self.profiles.len();
+        self.save()?;
+
+        Ok(self.current())
+    }
+
+    /**
+     * Adds a profile named after the number of players, renamed in the profiles file, makes it
+     * the playing one, then saves the profiles.
+     */
+    pub fn add(&mut self) -> Result<&Profile, UiError> {
+        let name = (self.profiles.len() + 1..)
+            .map(|n| format!("{} {}", DEFAULT_PROFILE, n))
+            .find(|name| self.profiles.iter().all(|p| p.name != *name))
+            .unwrap();
+        self.profiles.push(Profile::new(&name));
+        self.current = self.profiles.len() - 1;
+        self.save()?;
+
+        Ok(self.current())
+    }
+
+    /**
+     * Counts a puzzle of `difficulty` solved today in `elapsed` for the profile playing, then
+     * saves the profiles.
+     */
+    pub fn record(&mut self, difficulty: Difficulty, elapsed: Duration) -> Result<(), UiError> {
+        let today = chrono::offset::Local::now().naive_local().date();
+        self.profiles[self.current].record(difficulty, elapsed, today);
+        self.save()
+    }
+
+    /**
+     * Adds the puzzle of `game` to the favorites of the profile playing, or removes it, then
+     * saves the profiles. Returns whether the puzzle is now a favorite.
+     */
+    pub fn toggle_favorite(&mut self, game: &Game) -> Result<bool, UiError> {
+        let added = self.profiles[self.current].favorites.toggle(game);
+        self.save()?;
+
+        Ok(added)
+    }
+
+    /**
+     * Keeps the finished `attack` on the leaderboard of the profile playing, then saves the
+     * profiles, returning its rank from 0 if it is among the best ones.
+     */
+    pub fn record_time_attack(&mut self, attack: &TimeAttack) -> Result<Option<usize>, UiError> {
+        let today = chrono::offset::Local::now().naive_local().date();
+        let rank =
+            self.profiles[self.current].record_time_attack(attack.difficulty, attack.run(today));
+        self.save()?;
+
+        Ok(rank)
+    }
+}
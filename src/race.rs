@@ -0,0 +1,121 @@
+use crate::errors::GameError;
+use crate::game::{Game, Validation};
+use crate::profile::clock;
+use crate::session::Timer;
+
+use std::time::Duration;
+
+/// Number of players racing.
+pub const RACE_PLAYERS: usize = 2;
+
+/**
+ * Two players racing to complete their own copy of the same puzzle, against a clock they share.
+ * The first one to complete their grid wins.
+ */
+pub struct Race {
+    /// The grid of each player.
+    pub games: [Game; RACE_PLAYERS],
+    /// The time of the race, running from its start.
+    timer: Timer,
+    /// When each player completed their grid, in race time.
+    pub finished: [Option<Duration>; RACE_PLAYERS],
+}
+
+impl Race {
+    /**
+     * Returns a race on copies of the grid of `puzzle`, started now. The solution, against which
+     * every move is checked, is found once for both copies.
+     */
+    pub fn new(puzzle: &Game) -> Result<Self, GameError> {
+        let solution = puzzle.solution()?;
+        let copy = || -> Result<Game, GameError> {
+            let mut game = Game::from_puzzle(&puzzle.puzzle_id(), None)?;
+            game.solution = Some(solution.clone());
+            game.difficulty = puzzle.difficulty;
+            Ok(game)
+        };
+
+        Ok(Race {
+            games: [copy()?, copy()?],
+            timer: Timer::start(),
+            finished: [None; RACE_PLAYERS],
+        })
+    }
+
+    /**
+     * Places `value` in the cell at row `r` and column `c` of the grid of `player`. Only the
+     * values of the solution are accepted, the others counting as mistakes.
+     */
+    pub fn play(&mut self, player: usize, r: usize, c: usize, value: u8) -> Result<(), GameError> {
+        let game = &mut self.games[player];
+        game.do_move(r, c, value, Validation::Solution)?;
+        if game.is_done() && self.finished[player].is_none() {
+            self.finished[player] = Some(self.timer.elapsed());
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Returns the time of the race so far, or the winning time once it is over.
+     */
+    pub fn elapsed(&self) -> Duration {
+        self.winner()
+            .and_then(|winner| self.finished[winner])
+            .unwrap_or_else(|| self.timer.elapsed())
+    }
+
+    /**
+     * Returns the player who completed their grid first, if any.
+     */
+    pub fn winner(&self) -> Option<usize> {
+        (0..RACE_PLAYERS)
+            .filter(|player| self.finished[*player].is_some())
+            .min_by_key(|player| self.finished[*player])
+    }
+
+    /**
+     * Returns whether a player completed their grid, which ends the race.
+     */
+    pub fn is_over(&self) -> bool {
+        self.winner().is_some()
+    }
+
+    /**
+     * Returns how many cells of the grid of `player` are filled, and how many there are.
+     */
+    pub fn progress(&self, player: usize) -> (usize, usize) {
+        let grid = &self.games[player].grid;
+        (
+            grid.iter().filter(|cell| cell.value() != 0).count(),
+            grid.len(),
+        )
+    }
+
+    /**
+     * Describes how the race went: who won, then a line per player with their progress and
+     * their mistakes.
+     */
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = vec![match self.winner() {
+            Some(winner) => format!(
+                "Player {} wins in {}!",
+                winner + 1,
+                clock(self.elapsed().as_secs())
+            ),
+            None => format!("Racing for {}", clock(self.elapsed().as_secs())),
+        }];
+        for player in 0..RACE_PLAYERS {
+            let (filled, cells) = self.progress(player);
+            lines.push(format!(
+                "Player {}: {}/{} cells, {} mistakes",
+                player + 1,
+                filled,
+                cells,
+                self.games[player].mistakes
+            ));
+        }
+
+        lines
+    }
+}
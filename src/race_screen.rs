@@ -0,0 +1,455 @@
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, Canvas};
+use sdl2::ttf::Font;
+use sdl2::video::Window;
+
+use std::rc::Rc;
+
+use crate::dialog::{Dialog, DialogOutcome};
+use crate::errors::UiError;
+use crate::layout::{fit_centered, Layout};
+use crate::race::{Race, RACE_PLAYERS};
+use crate::traits::{Displayable, GUIConfig, LayoutConfig, ScreenOutcome};
+
+static COLOR_BCK: Color = Color::BLACK;
+static COLOR_NOT_INIT: Color = Color::RGBA(75, 75, 75, 255);
+static COLOR_LINES: Color = Color::RGBA(255, 220, 0, 255);
+static COLOR_FONT: Color = Color::WHITE;
+static COLOR_BAD_MSG: Color = Color::RED;
+/// Color of the selected cell and of the name of each player.
+static COLOR_PLAYERS: [Color; RACE_PLAYERS] = [
+    Color::RGBA(255, 110, 50, 255),
+    Color::RGBA(50, 170, 255, 255),
+];
+static COLOR_WINNER_BCK: Color = Color::RGBA(0, 0, 0, 220);
+static COLOR_WINNER: Color = Color::GREEN;
+
+/// Size of the raced grids.
+pub const RACE_SIZE: usize = 3;
+/// Height of a line of text of the panels and of the winner screen.
+const ROW_HEIGHT: i32 = 40;
+
+/**
+ * The keys of a player: moving their selection up, down, left and right, and the digits they
+ * type.
+ */
+struct Bindings {
+    moves: [Keycode; 4],
+    digits: [Keycode; 9],
+}
+
+/// Player 1 moves with W, A, S and D and types with the main row of digits, player 2 moves with
+/// the arrows and types with the keypad.
+const BINDINGS: [Bindings; RACE_PLAYERS] = [
+    Bindings {
+        moves: [Keycode::W, Keycode::S, Keycode::A, Keycode::D],
+        digits: [
+            Keycode::Num1,
+            Keycode::Num2,
+            Keycode::Num3,
+            Keycode::Num4,
+            Keycode::Num5,
+            Keycode::Num6,
+            Keycode::Num7,
+            Keycode::Num8,
+            Keycode::Num9,
+        ],
+    },
+    Bindings {
+        moves: [Keycode::Up, Keycode::Down, Keycode::Left, Keycode::Right],
+        digits: [
+            Keycode::Kp1,
+            Keycode::Kp2,
+            Keycode::Kp3,
+            Keycode::Kp4,
+            Keycode::Kp5,
+            Keycode::Kp6,
+            Keycode::Kp7,
+            Keycode::Kp8,
+            Keycode::Kp9,
+        ],
+    },
+];
+
+/**
+ * Two players race on their own copy of the same puzzle, side by side on a split screen, each
+ * with their own selection and keys. The winner is announced over the grids once one of them
+ * completes theirs.
+ */
+#[derive(Default)]
+pub struct RaceScreen<'a> {
+    font: Option<Rc<Font<'a, 'a>>>,
+
+    /// The race being played.
+    pub race: Option<Race>,
+    /// The (row, column) of the selected cell of each player.
+    selected: [(usize, usize); RACE_PLAYERS],
+    /// Why the last move of each player was refused, if it was.
+    messages: [Option<String>; RACE_PLAYERS],
+    /// The race time last drawn, in seconds.
+    drawn_seconds: u64,
+
+    /// Configured margins and line thicknesses of the grids.
+    layout_config: LayoutConfig,
+    /// The grid of each player, in their half of the canvas.
+    layouts: [Layout; RACE_PLAYERS],
+
+    /// The question asked before leaving a race that is not over, while it is open.
+    dialog: Option<Dialog<ScreenOutcome>>,
+}
+
+impl<'a> RaceScreen<'a> {
+    pub fn set_font(&mut self, new_font: Rc<Font<'a, 'a>>) {
+        self.font = Some(new_font);
+    }
+
+    /**
+     * Starts playing `race`, both selections on the top left cell.
+     */
+    pub fn set_race(&mut self, race: Race) {
+        self.race = Some(race);
+        self.selected = [(0, 0); RACE_PLAYERS];
+        self.messages = Default::default();
+        self.dialog = None;
+    }
+
+    /**
+     * Returns whether the shown race time is behind, the screen having to be drawn again.
+     */
+    pub fn clock_changed(&self) -> bool {
+        self.race
+            .as_ref()
+            .is_some_and(|race| race.elapsed().as_secs() != self.drawn_seconds)
+    }
+
+    /**
+     * Splits the canvas in two halves, a grid being laid out in each of them.
+     */
+    fn compute_layouts(&mut self, viewport: Rect) {
+        let half = Rect::new(0, 0, (viewport.width() / 2).max(1), viewport.height());
+        let layout = Layout::new(&self.layout_config, half, RACE_SIZE);
+        self.layouts = [layout; RACE_PLAYERS];
+        let right = &mut self.layouts[1];
+        right.grid_x += half.width() as i32;
+        right.panel.offset(half.width() as i32, 0);
+    }
+
+    /**
+     * Returns `outcome` if the race is over, otherwise asks `question` first.
+     */
+    fn leave(&mut self, question: &str, outcome: ScreenOutcome) -> ScreenOutcome {
+        match self.race.as_ref().is_some_and(|race| !race.is_over()) {
+            true => {
+                self.dialog = Some(Dialog::new(question, outcome));
+                ScreenOutcome::Updated
+            }
+            false => outcome,
+        }
+    }
+
+    /**
+     * Draws `text` with the screen's font, centered in `area` and shrunk to fit in it.
+     */
+    fn draw_text(
+        &self,
+        canvas: &mut Canvas<Window>,
+        text: &str,
+        color: Color,
+        area: Rect,
+    ) -> Result<(), UiError> {
+        let texture_creator = canvas.texture_creator();
+        let surface = self
+            .font
+            .as_ref()
+            .unwrap()
+            .render(text)
+            .blended(color)
+            .map_err(UiError::sdl2)?;
+        let texture = texture_creator
+            .create_texture_from_surface(surface)
+            .map_err(UiError::sdl2)?;
+
+        let query = texture.query();
+        canvas
+            .copy(
+                &texture,
+                None,
+                fit_centered(query.width, query.height, area),
+            )
+            .map_err(UiError::sdl2)
+    }
+
+    /**
+     * Draws the grid of `player` with their selection, then their progress and the reason of
+     * their last refused move below it.
+     */
+    fn draw_player(&self, canvas: &mut Canvas<Window>, player: usize) -> Result<(), UiError> {
+        let race = self.race.as_ref().unwrap();
+        let game = &race.games[player];
+        let layout = &self.layouts[player];
+
+        for r in 0..game.side_size {
+            for c in 0..game.side_size {
+                let cell = game.grid[game.index(r, c)];
+                let area = layout.cell_rect(r, c);
+                if self.selected[player] == (r, c) {
+                    canvas.set_draw_color(COLOR_PLAYERS[player]);
+                    canvas.fill_rect(area).map_err(UiError::sdl2)?;
+                } else if cell.value() != 0 && !cell.initial() {
+                    canvas.set_draw_color(COLOR_NOT_INIT);
+                    canvas.fill_rect(area).map_err(UiError::sdl2)?;
+                }
+                if cell.value() != 0 {
+                    self.draw_text(canvas, &cell.value().to_string(), COLOR_FONT, area)?;
+                }
+            }
+        }
+
+        canvas.set_draw_color(COLOR_LINES);
+        let grid_side = layout.grid_side();
+        for n in 0..=game.side_size {
+            let thickness = layout.line_thickness(n);
+            let offset = n as i32 * layout.box_size;
+            for line in [
+                Rect::new(
+                    layout.grid_x,
+                    layout.grid_y + offset,
+                    grid_side as u32 + thickness,
+                    thickness,
+                ),
+                Rect::new(
+                    layout.grid_x + offset,
+                    layout.grid_y,
+                    thickness,
+                    grid_side as u32,
+                ),
+            ] {
+                canvas.fill_rect(line).map_err(UiError::sdl2)?;
+            }
+        }
+
+        let panel = layout.panel;
+        let row = |i: i32| {
+            Rect::new(
+                panel.x(),
+                panel.y() + i * ROW_HEIGHT,
+                panel.width(),
+                ROW_HEIGHT.min(panel.height() as i32).max(1) as u32,
+            )
+        };
+        let (filled, cells) = race.progress(player);
+        let status = format!(
+            "Player {}: {}/{}, {} mistakes",
+            player + 1,
+            filled,
+            cells,
+            game.mistakes
+        );
+        self.draw_text(canvas, &status, COLOR_PLAYERS[player], row(0))?;
+        if let Some(message) = self.messages[player].as_ref() {
+            self.draw_text(canvas, message, COLOR_BAD_MSG, row(1))?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Draws the winner screen over the grids: the summary of the race and the keys to go on.
+     */
+    fn draw_winner(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        let race = self.race.as_ref().unwrap();
+        let mut lines = race.summary();
+        lines.push(String::from("N: new race, M: main menu"));
+
+        let viewport = canvas.viewport();
+        let height = ROW_HEIGHT * (lines.len() as i32 + 1);
+        let area = Rect::new(
+            0,
+            (viewport.height() as i32 - height) / 2,
+            viewport.width(),
+            height as u32,
+        );
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(COLOR_WINNER_BCK);
+        canvas.fill_rect(area).map_err(UiError::sdl2)?;
+        canvas.set_blend_mode(BlendMode::None);
+
+        for (i, line) in lines.iter().enumerate() {
+            let color = match i {
+                0 => COLOR_WINNER,
+                _ => COLOR_FONT,
+            };
+            let y = area.y() + ROW_HEIGHT / 2 + i as i32 * ROW_HEIGHT;
+            self.draw_text(
+                canvas,
+                line,
+                color,
+                Rect::new(0, y, viewport.width(), ROW_HEIGHT as u32),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Places `value` in the selected cell of `player`, keeping why it was refused.
+     */
+    fn enter_value(&mut self, player: usize, value: u8) -> ScreenOutcome {
+        let Some(race) = self.race.as_mut() else {
+            return ScreenOutcome::Unchanged;
+        };
+        let (r, c) = self.selected[player];
+        self.messages[player] = race.play(player, r, c, value).err().map(|e| e.to_string());
+
+        ScreenOutcome::Updated
+    }
+
+    /**
+     * Moves the selection of `player` by `dr` rows and `dc` columns, unless it would leave the
+     * grid.
+     */
+    fn move_selection(&mut self, player: usize, dr: isize, dc: isize) -> ScreenOutcome {
+        let side = (RACE_SIZE * RACE_SIZE) as isize;
+        let (r, c) = self.selected[player];
+        let (r, c) = (r as isize + dr, c as isize + dc);
+        if !(0..side).contains(&r) || !(0..side).contains(&c) {
+            return ScreenOutcome::Unchanged;
+        }
+
+        self.selected[player] = (r as usize, c as usize);
+        ScreenOutcome::Updated
+    }
+
+    /**
+     * Handles a key of one of the players, if it is bound.
+     */
+    fn player_key(&mut self, keycode: Keycode) -> ScreenOutcome {
+        for (player, bindings) in BINDINGS.iter().enumerate() {
+            if let Some(direction) = bindings.moves.iter().position(|key| *key == keycode) {
+                let (dr, dc) = [(-1, 0), (1, 0), (0, -1), (0, 1)][direction];
+                return self.move_selection(player, dr, dc);
+            }
+            if let Some(digit) = bindings.digits.iter().position(|key| *key == keycode) {
+                return self.enter_value(player, digit as u8 + 1);
+            }
+        }
+
+        ScreenOutcome::Unchanged
+    }
+}
+
+impl<'a> Displayable for RaceScreen<'a> {
+    fn new() -> Self {
+        RaceScreen {
+            ..Default::default()
+        }
+    }
+
+    fn init(&mut self, canvas: &mut Canvas<Window>, config: &GUIConfig) -> Result<(), UiError> {
+        self.layout_config = config.layout.clone();
+        self.compute_layouts(canvas.viewport());
+
+        Ok(())
+    }
+
+    fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        canvas.set_draw_color(COLOR_BCK);
+        canvas.clear();
+
+        let Some(race) = self.race.as_ref() else {
+            return Ok(());
+        };
+        self.drawn_seconds = race.elapsed().as_secs();
+        let over = race.is_over();
+
+        for player in 0..RACE_PLAYERS {
+            self.draw_player(canvas, player)?;
+        }
+        // The clock of the race, centered above the grids
+        let seconds = self.drawn_seconds;
+        let top = self.layouts[0].grid_y.max(ROW_HEIGHT);
+        self.draw_text(
+            canvas,
+            &format!("{:02}:{:02}", seconds / 60, seconds % 60),
+            COLOR_FONT,
+            Rect::new(
+                0,
+                top - ROW_HEIGHT,
+                canvas.viewport().width(),
+                ROW_HEIGHT as u32,
+            ),
+        )?;
+
+        if over {
+            self.draw_winner(canvas)?;
+        }
+        if let Some(dialog) = self.dialog.as_mut() {
+            dialog.draw(canvas, self.font.as_ref().unwrap())?;
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        self.compute_layouts(canvas.viewport());
+
+        Ok(())
+    }
+
+    fn update(&mut self, event: &Event) -> Result<ScreenOutcome, UiError> {
+        // The grids wait for the open question to be answered
+        if let Some(dialog) = self.dialog.as_mut() {
+            return Ok(match dialog.update(event) {
+                DialogOutcome::Unchanged => ScreenOutcome::Unchanged,
+                DialogOutcome::Updated => ScreenOutcome::Updated,
+                DialogOutcome::Confirmed(outcome) => {
+                    self.dialog = None;
+                    outcome
+                }
+                DialogOutcome::Cancelled => {
+                    self.dialog = None;
+                    ScreenOutcome::Updated
+                }
+            });
+        }
+
+        let over = self.race.as_ref().is_none_or(Race::is_over);
+        match event {
+            // Either player can pick a cell of their grid with the mouse
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } if !over => {
+                for player in 0..RACE_PLAYERS {
+                    if let Some(cell) = self.layouts[player].cell_at(*x, *y) {
+                        self.selected[player] = cell;
+                        return Ok(ScreenOutcome::Updated);
+                    }
+                }
+            }
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } => {
+                return Ok(match keycode {
+                    // A new race once this one is over, with another puzzle
+                    Keycode::N if over => ScreenOutcome::Race,
+                    // Back to the main menu, or out of the game
+                    Keycode::M => self.leave("Abandon the race?", ScreenOutcome::Menu),
+                    Keycode::Escape => self.leave("Abandon the race?", ScreenOutcome::Exit),
+                    _ if over => ScreenOutcome::Unchanged,
+                    _ => self.player_key(*keycode),
+                });
+            }
+            _ => {}
+        }
+
+        Ok(ScreenOutcome::Unchanged)
+    }
+}
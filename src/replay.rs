@@ -0,0 +1,140 @@
+use crate::errors::GameError;
+use crate::game::{value_symbol, Cell, Game};
+use crate::history::Move;
+
+use std::time::Duration;
+
+/**
+ * Plays back the moves of a game one at a time, from the grid it started with, with the play
+ * time at which each of them was made.
+ */
+pub struct Replay {
+    /// Number of rows of the grid.
+    side_size: usize,
+    /// The moves played back, in the order they were made.
+    moves: Vec<Move>,
+    /// The grid once the first `position` moves are played back.
+    pub grid: Vec<Cell>,
+    /// Number of moves played back.
+    position: usize,
+}
+
+impl Replay {
+    /**
+     * Returns the replay of `game`, its grid being put back as it was before its first move. The
+     * timeline of the game is played back, or its history for the saves written before the
+     * timeline was kept.
+     */
+    pub fn new(game: &Game) -> Result<Self, GameError> {
+        let moves = match game.timeline.is_empty() {
+            true => game.history.clone(),
+            false => game.timeline.clone(),
+        };
+        if moves.is_empty() {
+            return Err(GameError::NothingToReplay);
+        }
+
+        let mut grid = game.grid.clone();
+        for played in moves.iter().rev() {
+            grid[played.index] = played.before;
+        }
+
+        Ok(Replay {
+            side_size: game.side_size,
+            moves,
+            grid,
+            position: 0,
+        })
+    }
+
+    /**
+     * Returns the number of moves of the replay.
+     */
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /**
+     * Returns whether the replay has no move, which `new` does not allow.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /**
+     * Returns the number of moves played back so far.
+     */
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /**
+     * Returns whether every move was played back.
+     */
+    pub fn is_over(&self) -> bool {
+        self.position == self.moves.len()
+    }
+
+    /**
+     * Plays back the next move, returning it, or `None` once every move was played back.
+     */
+    pub fn step(&mut self) -> Option<&Move> {
+        let played = self.moves.get(self.position)?;
+        self.grid[played.index] = played.after;
+        self.position += 1;
+
+        Some(played)
+    }
+
+    /**
+     * Takes back the last move played back, returning it, or `None` at the start of the game.
+     */
+    pub fn step_back(&mut self) -> Option<&Move> {
+        self.position = self.position.checked_sub(1)?;
+        let played = &self.moves[self.position];
+        self.grid[played.index] = played.before;
+
+        Some(played)
+    }
+
+    /**
+     * Returns how long after the previous move the next one was made, in play time.
+     */
+    pub fn next_delay(&self) -> Option<Duration> {
+        let next = self.moves.get(self.position)?;
+        let previous = match self.position {
+            0 => 0,
+            position => self.moves[position - 1].at,
+        };
+
+        Some(Duration::from_millis(next.at.saturating_sub(previous)))
+    }
+
+    /**
+     * Describes the last move played back, with its number and its play time, such as
+     * `Move 3/42 at 01:25: 7 in row 2, column 5.`
+     */
+    pub fn describe(&self) -> String {
+        let Some(played) = self.position.checked_sub(1).map(|i| &self.moves[i]) else {
+            return format!("Start of the game, {} moves to replay.", self.moves.len());
+        };
+
+        let seconds = played.at / 1000;
+        let (row, column) = (
+            played.index / self.side_size + 1,
+            played.index % self.side_size + 1,
+        );
+        let change = match played.after.value() {
+            0 => format!("emptied row {}, column {}", row, column),
+            value => format!("{} in row {}, column {}", value_symbol(value), row, column),
+        };
+        format!(
+            "Move {}/{} at {:02}:{:02}: {}.",
+            self.position,
+            self.moves.len(),
+            seconds / 60,
+            seconds % 60,
+            change
+        )
+    }
+}
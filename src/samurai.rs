@@ -0,0 +1,250 @@
+use crate::errors::{GameError, SolverError};
+use crate::game::{Cell, Game};
+
+use std::fmt;
+use std::fs;
+
+/// Number of cells on a side of the board holding the five grids.
+pub const SIDE: usize = 21;
+/// Position of the top left cell of each grid on the board: the four corner grids, then the
+/// central one sharing a corner box with each of them.
+pub const OFFSETS: [(usize, usize); 5] = [(0, 0), (0, 12), (12, 0), (12, 12), (6, 6)];
+
+/**
+ * A samurai sudoku: five 9x9 grids, the central one sharing each of its corner boxes with one
+ * of the four others. The values of the shared cells are kept equal in both grids.
+ */
+pub struct SamuraiGame {
+    /// The grids, placed on the board according to `OFFSETS`.
+    pub grids: Vec<Game>,
+}
+
+impl SamuraiGame {
+    /**
+     * Creates a samurai game with five empty grids.
+     */
+    pub fn new() -> Result<Self, GameError> {
+        Ok(SamuraiGame {
+            grids: (0..OFFSETS.len())
+                .map(|_| Game::new(3, None))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /**
+     * Creates a samurai game from its text form: one line per row of the board, with a digit
+     * for each clue and `.` or `0` for the empty cells. The characters out of the grids are
+     * ignored, as are the missing ones at the end of the lines.
+     */
+    pub fn from_text(text: &str) -> Result<Self, GameError> {
+        let lines: Vec<Vec<char>> = text
+            .lines()
+            .map(|l| l.trim_end().chars().collect())
+            .filter(|l: &Vec<char>| !l.is_empty())
+            .collect();
+        if lines.len() != SIDE {
+            return Err(GameError::InvalidPuzzle);
+        }
+
+        let mut samurai = SamuraiGame::new()?;
+        for (r, line) in lines.iter().enumerate() {
+            for c in 0..SIDE {
+                if samurai.cells(r, c).is_empty() {
+                    continue;
+                }
+                let value = match line.get(c) {
+                    None | Some('.' | '0' | ' ') => 0,
+                    Some(c) => match c.to_digit(10) {
+                        Some(value) => value as u8,
+                        None => return Err(GameError::InvalidPuzzle),
+                    },
+                };
+                samurai.set(r, c, Cell::new(value, value != 0));
+            }
+        }
+
+        Ok(samurai)
+    }
+
+    /**
+     * Reads the samurai game written in its text form at `path`, see `from_text`.
+     */
+    pub fn from_file(path: &str) -> Result<Self, GameError> {
+        let text = fs::read_to_string(path).map_err(GameError::OpenFileError)?;
+        SamuraiGame::from_text(&text)
+    }
+
+    /**
+     * Returns the grids holding the cell at row `r` and column `c` of the board, along with its
+     * index in each of them. It is empty for the cells out of the grids.
+     */
+    pub fn cells(&self, r: usize, c: usize) -> Vec<(usize, usize)> {
+        OFFSETS
+            .iter()
+            .enumerate()
+            .filter(|(_, (top, left))| {
+                (*top..top + 9).contains(&r) && (*left..left + 9).contains(&c)
+            })
+            .map(|(g, (top, left))| (g, (r - top) * 9 + c - left))
+            .collect()
+    }
+
+    /**
+     * Returns the cell at row `r` and column `c` of the board, if it is in a grid.
+     */
+    pub fn cell(&self, r: usize, c: usize) -> Option<Cell> {
+        self.cells(r, c)
+            .first()
+            .map(|(g, index)| self.grids[*g].grid[*index])
+    }
+
+    /// Writes `cell` at row `r` and column `c` of the board, in every grid holding it.
+    fn set(&mut self, r: usize, c: usize, cell: Cell) {
+        for (g, index) in self.cells(r, c) {
+            self.grids[g].grid[index] = cell;
+        }
+    }
+
+    /**
+     * Returns the values that can be placed at row `r` and column `c` of the board: the ones
+     * allowed by every grid holding it.
+     */
+    pub fn valids(&self, r: usize, c: usize) -> Vec<u8> {
+        let cells = self.cells(r, c);
+        if cells.is_empty() {
+            return Vec::new();
+        }
+
+        (1..=9)
+            .filter(|value| {
+                cells
+                    .iter()
+                    .all(|(g, index)| self.grids[*g].valids(*index).contains(value))
+            })
+            .collect()
+    }
+
+    /**
+     * Places `value` at row `r` and column `c` of the board, or empties the cell with 0. The
+     * value must be allowed by every grid holding the cell, and the initial values cannot be
+     * changed.
+     */
+    pub fn do_move(&mut self, r: usize, c: usize, value: u8) -> Result<(), GameError> {
+        let cell = self.cell(r, c).ok_or(GameError::IllegalPosition)?;
+        if value > 9 {
+            return Err(GameError::IllegalValue);
+        }
+        if cell.initial() {
+            return Err(GameError::NonEmptyCell);
+        }
+
+        // The current value of the cell does not prevent replacing it
+        self.set(r, c, Cell::new(0, false));
+        if value != 0 && !self.valids(r, c).contains(&value) {
+            self.set(r, c, cell);
+            return Err(GameError::InvalidValue);
+        }
+        self.set(r, c, Cell::new(value, false));
+
+        Ok(())
+    }
+
+    /**
+     * Returns whether every grid is solved.
+     */
+    pub fn is_done(&self) -> bool {
+        self.grids.iter().all(|grid| grid.is_done())
+    }
+
+    /**
+     * Returns the empty cell of the board with the fewest values allowed, along with them.
+     */
+    fn most_constrained(&self) -> Option<((usize, usize), Vec<u8>)> {
+        (0..SIDE * SIDE)
+            .map(|i| (i / SIDE, i % SIDE))
+            .filter(|(r, c)| self.cell(*r, *c).is_some_and(|cell| cell.value() == 0))
+            .map(|(r, c)| ((r, c), self.valids(r, c)))
+            .min_by_key(|(_, values)| values.len())
+    }
+
+    /**
+     * Counts the solutions of the board up to `limit`, by backtracking on the most constrained
+     * cells, each value placed in a shared cell constraining both of its grids. The board is left
+     * as it was.
+     */
+    pub fn count_solutions(&mut self, limit: usize) -> usize {
+        let Some(((r, c), values)) = self.most_constrained() else {
+            return 1;
+        };
+
+        let mut count = 0;
+        for value in values {
+            self.set(r, c, Cell::new(value, false));
+            count += self.count_solutions(limit - count);
+            if count >= limit {
+                break;
+            }
+        }
+        self.set(r, c, Cell::new(0, false));
+
+        count
+    }
+
+    /**
+     * Fills the empty cells of the board with the values of its first solution found, the same
+     * way as `count_solutions`.
+     */
+    pub fn solve(&mut self) -> Result<(), SolverError> {
+        match self.fill() {
+            true => Ok(()),
+            false => Err(SolverError::FailedToSolve),
+        }
+    }
+
+    /// Fills the empty cells of the board, returning whether a solution was found.
+    fn fill(&mut self) -> bool {
+        let Some(((r, c), values)) = self.most_constrained() else {
+            return true;
+        };
+
+        for value in values {
+            self.set(r, c, Cell::new(value, false));
+            if self.fill() {
+                return true;
+            }
+        }
+        self.set(r, c, Cell::new(0, false));
+
+        false
+    }
+}
+
+/**
+ * Draws the board with its five grids in place, the boxes being separated by blank columns and
+ * lines, and the empty cells shown by dots.
+ */
+impl fmt::Display for SamuraiGame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for r in 0..SIDE {
+            if r > 0 && r % 3 == 0 {
+                writeln!(f)?;
+            }
+            let mut line = String::new();
+            for c in 0..SIDE {
+                if c > 0 && c % 3 == 0 {
+                    line.push_str("  ");
+                } else if c > 0 {
+                    line.push(' ');
+                }
+                line.push(match self.cell(r, c) {
+                    None => ' ',
+                    Some(cell) if cell.value() == 0 => '.',
+                    Some(cell) => (b'0' + cell.value()) as char,
+                });
+            }
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,302 @@
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::Canvas;
+use sdl2::ttf::Font;
+use sdl2::video::Window;
+
+use std::rc::Rc;
+
+use crate::dialog::{Dialog, DialogOutcome};
+use crate::errors::UiError;
+use crate::game_screen::digit_from_keycode;
+use crate::layout::{fit_centered, Layout};
+use crate::samurai::{SamuraiGame, OFFSETS, SIDE};
+use crate::traits::{Displayable, GUIConfig, LayoutConfig, ScreenOutcome};
+
+static COLOR_BCK: Color = Color::BLACK;
+static COLOR_NOT_INIT: Color = Color::RGBA(75, 75, 75, 255);
+static COLOR_LINES: Color = Color::RGBA(255, 220, 0, 255);
+static COLOR_HIGHLIGHT: Color = Color::RGBA(255, 110, 50, 255);
+static COLOR_FONT: Color = Color::WHITE;
+static COLOR_GOOD_MSG: Color = Color::GREEN;
+static COLOR_BAD_MSG: Color = Color::RED;
+
+/**
+ * Plays a samurai puzzle: its five grids are drawn in place on a single board, the shared boxes
+ * once, and a value placed in one of them fills both of its grids.
+ */
+#[derive(Default)]
+pub struct SamuraiScreen<'a> {
+    font: Option<Rc<Font<'a, 'a>>>,
+
+    /// The puzzle being played.
+    pub samurai: Option<SamuraiGame>,
+    /// The (row, column) of the selected cell of the board.
+    selected: Option<(usize, usize)>,
+    /// Why the last move was refused, if it was.
+    message: Option<String>,
+
+    /// Configured margins and line thicknesses of the grids.
+    layout_config: LayoutConfig,
+    layout: Layout,
+
+    /// The question asked before leaving a puzzle that is not solved, while it is open.
+    dialog: Option<Dialog<ScreenOutcome>>,
+}
+
+impl<'a> SamuraiScreen<'a> {
+    pub fn set_font(&mut self, new_font: Rc<Font<'a, 'a>>) {
+        self.font = Some(new_font);
+    }
+
+    /**
+     * Starts playing `samurai`.
+     */
+    pub fn set_samurai(&mut self, samurai: SamuraiGame) {
+        self.samurai = Some(samurai);
+        self.selected = None;
+        self.message = None;
+        self.dialog = None;
+    }
+
+    /**
+     * Returns `outcome` if the puzzle is solved, the samurai puzzles having no save, otherwise
+     * asks `question` first.
+     */
+    fn leave(&mut self, question: &str, outcome: ScreenOutcome) -> ScreenOutcome {
+        match self
+            .samurai
+            .as_ref()
+            .is_some_and(|samurai| !samurai.is_done())
+        {
+            true => {
+                self.dialog = Some(Dialog::new(question, outcome));
+                ScreenOutcome::Updated
+            }
+            false => outcome,
+        }
+    }
+
+    /**
+     * Draws `text` with the screen's font, centered in `area` and shrunk to fit in it.
+     */
+    fn draw_text(
+        &self,
+        canvas: &mut Canvas<Window>,
+        text: &str,
+        color: Color,
+        area: Rect,
+    ) -> Result<(), UiError> {
+        let texture_creator = canvas.texture_creator();
+        let surface = self
+            .font
+            .as_ref()
+            .unwrap()
+            .render(text)
+            .blended(color)
+            .map_err(UiError::sdl2)?;
+        let texture = texture_creator
+            .create_texture_from_surface(surface)
+            .map_err(UiError::sdl2)?;
+
+        let query = texture.query();
+        canvas
+            .copy(
+                &texture,
+                None,
+                fit_centered(query.width, query.height, area),
+            )
+            .map_err(UiError::sdl2)
+    }
+
+    /**
+     * Draws the lines of each grid, thicker around its boxes. The lines of the shared boxes are
+     * drawn by both of their grids.
+     */
+    fn draw_lines(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        canvas.set_draw_color(COLOR_LINES);
+        let grid_side = 9 * self.layout.box_size;
+        for (top, left) in OFFSETS {
+            let (x, y) = (
+                self.layout.grid_x + left as i32 * self.layout.box_size,
+                self.layout.grid_y + top as i32 * self.layout.box_size,
+            );
+            for n in 0..=9 {
+                let thickness = self.layout.line_thickness(n);
+                let offset = n as i32 * self.layout.box_size;
+                for line in [
+                    Rect::new(x, y + offset, grid_side as u32 + thickness, thickness),
+                    Rect::new(x + offset, y, thickness, grid_side as u32),
+                ] {
+                    canvas.fill_rect(line).map_err(UiError::sdl2)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Places `value` in the selected cell, or empties it with 0, keeping why it was refused.
+     */
+    fn enter_value(&mut self, value: u8) -> ScreenOutcome {
+        let (Some(samurai), Some((r, c))) = (self.samurai.as_mut(), self.selected) else {
+            return ScreenOutcome::Unchanged;
+        };
+        self.message = samurai.do_move(r, c, value).err().map(|e| e.to_string());
+
+        ScreenOutcome::Updated
+    }
+
+    /**
+     * Moves the selection by `dr` rows and `dc` columns, unless it would leave the grids.
+     */
+    fn move_selection(&mut self, dr: isize, dc: isize) -> ScreenOutcome {
+        let (Some(samurai), Some((r, c))) = (self.samurai.as_ref(), self.selected) else {
+            return ScreenOutcome::Unchanged;
+        };
+        let (r, c) = (r as isize + dr, c as isize + dc);
+        if !(0..SIDE as isize).contains(&r) || !(0..SIDE as isize).contains(&c) {
+            return ScreenOutcome::Unchanged;
+        }
+
+        match samurai.cell(r as usize, c as usize) {
+            Some(_) => {
+                self.selected = Some((r as usize, c as usize));
+                ScreenOutcome::Updated
+            }
+            None => ScreenOutcome::Unchanged,
+        }
+    }
+}
+
+impl<'a> Displayable for SamuraiScreen<'a> {
+    fn new() -> Self {
+        SamuraiScreen {
+            ..Default::default()
+        }
+    }
+
+    fn init(&mut self, canvas: &mut Canvas<Window>, config: &GUIConfig) -> Result<(), UiError> {
+        self.layout_config = config.layout.clone();
+        self.layout = Layout::with_side(&self.layout_config, canvas.viewport(), SIDE, 3);
+
+        Ok(())
+    }
+
+    fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        canvas.set_draw_color(COLOR_BCK);
+        canvas.clear();
+
+        let Some(samurai) = self.samurai.as_ref() else {
+            return Ok(());
+        };
+
+        // Drawing the values, the cells out of the grids staying empty
+        for r in 0..SIDE {
+            for c in 0..SIDE {
+                let Some(cell) = samurai.cell(r, c) else {
+                    continue;
+                };
+                let area = self.layout.cell_rect(r, c);
+                if self.selected == Some((r, c)) {
+                    canvas.set_draw_color(COLOR_HIGHLIGHT);
+                    canvas.fill_rect(area).map_err(UiError::sdl2)?;
+                } else if cell.value() != 0 && !cell.initial() {
+                    canvas.set_draw_color(COLOR_NOT_INIT);
+                    canvas.fill_rect(area).map_err(UiError::sdl2)?;
+                }
+                if cell.value() != 0 {
+                    self.draw_text(canvas, &cell.value().to_string(), COLOR_FONT, area)?;
+                }
+            }
+        }
+
+        self.draw_lines(canvas)?;
+
+        // The verdict or the reason of the last refused move, below the board
+        let panel = self.layout.panel;
+        let message_area = Rect::new(panel.x(), panel.y(), panel.width(), 40.min(panel.height()));
+        match (samurai.is_done(), self.message.as_ref()) {
+            (true, _) => {
+                self.draw_text(canvas, "Grid completed !", COLOR_GOOD_MSG, message_area)?
+            }
+            (false, Some(message)) => {
+                self.draw_text(canvas, message, COLOR_BAD_MSG, message_area)?
+            }
+            (false, None) => {}
+        }
+        if let Some(dialog) = self.dialog.as_mut() {
+            dialog.draw(canvas, self.font.as_ref().unwrap())?;
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        self.layout = Layout::with_side(&self.layout_config, canvas.viewport(), SIDE, 3);
+
+        Ok(())
+    }
+
+    fn update(&mut self, event: &Event) -> Result<ScreenOutcome, UiError> {
+        // The board waits for the open question to be answered
+        if let Some(dialog) = self.dialog.as_mut() {
+            return Ok(match dialog.update(event) {
+                DialogOutcome::Unchanged => ScreenOutcome::Unchanged,
+                DialogOutcome::Updated => ScreenOutcome::Updated,
+                DialogOutcome::Confirmed(outcome) => {
+                    self.dialog = None;
+                    outcome
+                }
+                DialogOutcome::Cancelled => {
+                    self.dialog = None;
+                    ScreenOutcome::Updated
+                }
+            });
+        }
+
+        match event {
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => {
+                let cell = self.layout.cell_at(*x, *y);
+                let samurai = self.samurai.as_ref();
+                if let Some((r, c)) = cell
+                    .filter(|(r, c)| samurai.is_some_and(|samurai| samurai.cell(*r, *c).is_some()))
+                {
+                    self.selected = Some((r, c));
+                    return Ok(ScreenOutcome::Updated);
+                }
+            }
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } => {
+                return Ok(match keycode {
+                    Keycode::Up => self.move_selection(-1, 0),
+                    Keycode::Down => self.move_selection(1, 0),
+                    Keycode::Left => self.move_selection(0, -1),
+                    Keycode::Right => self.move_selection(0, 1),
+                    Keycode::Delete | Keycode::Backspace => self.enter_value(0),
+                    // Back to the main menu, or out of the game
+                    Keycode::M => self.leave("Leave without saving?", ScreenOutcome::Menu),
+                    Keycode::Escape => self.leave("Exit without saving?", ScreenOutcome::Exit),
+                    _ => match digit_from_keycode(*keycode) {
+                        Some(digit) => self.enter_value(digit),
+                        None => ScreenOutcome::Unchanged,
+                    },
+                });
+            }
+            _ => {}
+        }
+
+        Ok(ScreenOutcome::Unchanged)
+    }
+}
@@ -0,0 +1,82 @@
+use crate::game::Game;
+
+use chrono::{DateTime, Local};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Extension of the save files.
+const SAVE_EXTENSION: &str = "game";
+
+/**
+ * A game saved in the save folder, as listed by the saved games browsers.
+ */
+pub struct SavedGame {
+    /// Path of the save file.
+    pub path: String,
+    /// Size of the board.
+    pub size: usize,
+    /// Share code of the puzzle, telling whether it is a favorite, see `Game::to_share_code`.
+    pub code: String,
+    /// Percentage of the cells that are filled, see `Game::completion`.
+    pub completion: usize,
+    /// When the game was last saved.
+    pub modified: DateTime<Local>,
+}
+
+impl SavedGame {
+    /**
+     * Lists the games saved in `save_folder`, the most recent first. The finished games and the
+     * files that cannot be read are left out, and a missing folder means nothing is saved.
+     */
+    pub fn list(save_folder: &str) -> Vec<SavedGame> {
+        let entries = match fs::read_dir(Path::new(save_folder)) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut saves = Vec::new();
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            if path.extension().and_then(|e| e.to_str()) != Some(SAVE_EXTENSION) {
+                continue;
+            }
+            let Some(path_str) = path.to_str() else {
+                continue;
+            };
+            let (Ok(game), Ok(modified)) = (
+                Game::read(path_str),
+                fs::metadata(&path).and_then(|m| m.modified()),
+            ) else {
+                continue;
+            };
+            if game.is_filled() {
+                continue;
+            }
+
+            saves.push(SavedGame {
+                path: path_str.to_string(),
+                size: game.size,
+                code: game.to_share_code(),
+                completion: game.completion(),
+                modified: modified.into(),
+            });
+        }
+        saves.sort_by_key(|save| std::cmp::Reverse(save.modified));
+
+        saves
+    }
+}
+
+impl fmt::Display for SavedGame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let side_size = self.size * self.size;
+        write!(
+            f,
+            "{}x{} - {}% filled - {}",
+            side_size,
+            side_size,
+            self.completion,
+            self.modified.format("%Y-%m-%d %H:%M")
+        )
+    }
+}
@@ -0,0 +1,459 @@
+use crate::errors::{GameError, SolverError, UiError};
+use crate::game::{Game, Validation};
+use crate::generator::{self, Difficulty, GenerationConfig};
+use crate::hint::{self, Action};
+use crate::solver;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+/// Address the games are served on unless another one is given.
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+/// Number of games kept at most, the oldest one being forgotten to start another.
+pub const MAX_GAMES: usize = 1_000;
+/// Longest request body accepted, in bytes.
+const MAX_BODY: usize = 64 * 1024;
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+/// Most cells tried to solve a puzzle before giving up, the requests being answered one after
+/// the other.
+const MAX_SOLVER_NODES: usize = 1_000_000;
+
+/**
+ * The puzzle to start with `POST /new`: the given one, or one generated with the size and the
+ * difficulty.
+ */
+#[derive(Deserialize)]
+struct NewRequest {
+    /// The size of the boxes of the generated grid, 3 for a 9x9 grid.
+    #[serde(default)]
+    size: Option<usize>,
+    #[serde(default)]
+    difficulty: Option<Difficulty>,
+    /// A puzzle written like `Game::puzzle_id`, played rather than a generated one.
+    #[serde(default)]
+    puzzle: Option<String>,
+    /// How the moves of the game are checked.
+    #[serde(default)]
+    validation: Validation,
+}
+
+/**
+ * The move to play with `POST /move`, 0 clearing the cell.
+ */
+#[derive(Deserialize)]
+struct MoveRequest {
+    id: u64,
+    row: usize,
+    column: usize,
+    value: u8,
+}
+
+/**
+ * The grid `POST /hint` and `POST /solve` are about: a served game, or a puzzle written like
+ * `Game::puzzle_id`.
+ */
+#[derive(Deserialize)]
+struct GridRequest {
+    #[serde(default)]
+    id: Option<u64>,
+    #[serde(default)]
+    puzzle: Option<String>,
+}
+
+/**
+ * An answer to a request: its HTTP status and its JSON body.
+ */
+#[derive(Debug, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Response { status: 200, body }
+    }
+
+    fn error(status: u16, message: impl ToString) -> Self {
+        Response {
+            status,
+            body: json!({ "error": message.to_string() }),
+        }
+    }
+
+    /**
+     * Returns the reason phrase of the status, for the status line.
+     */
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            204 => "No Content",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Payload Too Large",
+            _ => "Unprocessable Entity",
+        }
+    }
+}
+
+/**
+ * A game being played through the server.
+ */
+struct ServedGame {
+    game: Game,
+    validation: Validation,
+}
+
+/**
+ * Plays games for clients over a small HTTP/JSON API, such as web front ends or bots, without
+ * any window:
+ * - `POST /new` starts a game, from `{"size", "difficulty", "validation"}` or `{"puzzle"}`;
+ * - `POST /move` plays `{"id", "row", "column", "value"}`, the value 0 clearing the cell;
+ * - `POST /hint` tells the next logical move on `{"id"}` or `{"puzzle"}`;
+ * - `POST /solve` gives the solution of `{"id"}` or `{"puzzle"}`.
+ *
+ * The rows and columns are zero based, and the games are identified by the `id` `/new` answers.
+ */
+pub struct Server {
+    /// How the puzzles asked for are generated.
+    config: GenerationConfig,
+    /// The games being played, by id.
+    games: BTreeMap<u64, ServedGame>,
+    /// The id of the next game started.
+    next_id: u64,
+}
+
+/**
+ * Returns the body a game is described with: its id, its puzzle and its grid, row after row
+ * with 0 for the empty cells, with how it goes.
+ */
+fn describe(id: u64, game: &Game) -> Value {
+    json!({
+        "id": id,
+        "side": game.side_size,
+        "puzzle": game.puzzle_id(),
+        "grid": game.grid.iter().map(|cell| cell.value()).collect::<Vec<u8>>(),
+        "mistakes": game.mistakes,
+        "hints": game.hints,
+        "done": game.is_done(),
+    })
+}
+
+/**
+ * Returns the listener of the requests sent to `address`, such as `0.0.0.0:8080` to serve the
+ * other machines of the network.
+ */
+pub fn bind(address: &str) -> Result<TcpListener, UiError> {
+    TcpListener::bind(address).map_err(UiError::ServeError)
+}
+
+/**
+ * Returns the game of a puzzle written like `Game::puzzle_id`, as large as the generated ones
+ * at most.
+ */
+fn load(puzzle: &str) -> Result<Game, Response> {
+    let game = Game::from_puzzle(puzzle, None).map_err(game_error)?;
+    match generator::SIZES.contains(&game.size) {
+        true => Ok(game),
+        false => Err(Response::error(400, "The puzzles must be 16x16 at most.")),
+    }
+}
+
+/**
+ * Returns the solution of `game`, row after row, giving up on the ones taking too long to solve.
+ */
+fn solution(game: &Game) -> Result<Vec<u8>, Response> {
+    if let Some(solution) = game.solution.as_ref() {
+        return Ok(solution.clone());
+    }
+
+    let mut puzzle = game.copy_puzzle();
+    match solver::solve_within(&mut puzzle, MAX_SOLVER_NODES) {
+        Ok(()) => Ok(puzzle.grid.iter().map(|cell| cell.value()).collect()),
+        Err(SolverError::OutOfNodes) => Err(Response::error(422, SolverError::OutOfNodes)),
+        Err(SolverError::FailedToSolve) => Err(game_error(GameError::NoSolution)),
+    }
+}
+
+/**
+ * Returns the answer to a request failing with `error`: a malformed request, or a move the game
+ * refuses.
+ */
+fn game_error(error: GameError) -> Response {
+    match error {
+        GameError::InvalidPuzzle | GameError::IllegalValue | GameError::IllegalPosition => {
+            Response::error(400, error)
+        }
+        _ => Response::error(422, error),
+    }
+}
+
+impl Server {
+    pub fn new(config: GenerationConfig) -> Self {
+        Server {
+            config,
+            games: BTreeMap::new(),
+            next_id: 1,
+        }
+    }
+
+    /**
+     * Returns the answer to the request `method path` with the JSON `body`.
+     */
+    pub fn handle(&mut self, method: &str, path: &str, body: &str) -> Response {
+        // The query string is not used
+        let path = path.split('?').next().unwrap_or_default();
+        match (method, path) {
+            ("POST", "/new") => parse(body).map_or_else(|e| e, |request| self.new_game(request)),
+            ("POST", "/move") => parse(body).map_or_else(|e| e, |request| self.play(request)),
+            ("POST", "/hint") => parse(body).map_or_else(|e| e, |request| self.hint(request)),
+            ("POST", "/solve") => parse(body).map_or_else(|e| e, |request| self.solve(request)),
+            (_, "/new" | "/move" | "/hint" | "/solve") => {
+                Response::error(405, format!("'{}' expects a POST request.", path))
+            }
+            _ => Response::error(404, format!("Unknown endpoint '{}'.", path)),
+        }
+    }
+
+    /**
+     * Starts a game, forgetting the oldest one when `MAX_GAMES` are played. The moves of a game
+     * checked against the solution need it, so it is found first on a given puzzle.
+     */
+    fn new_game(&mut self, request: NewRequest) -> Response {
+        let game = match request.puzzle {
+            Some(puzzle) => match load(&puzzle) {
+                Ok(mut game) => {
+                    if request.validation == Validation::Solution {
+                        match solution(&game) {
+                            Ok(solution) => game.solution = Some(solution),
+                            Err(response) => return response,
+                        }
+                    }
+                    game
+                }
+                Err(response) => return response,
+            },
+            None => {
+                let size = request.size.unwrap_or(3);
+                if !generator::SIZES.contains(&size) {
+                    return Response::error(400, "The size must be between 2 and 4.");
+                }
+                let difficulty = request.difficulty.unwrap_or_default();
+                generator::generate(size, difficulty, &self.config)
+            }
+        };
+
+        if self.games.len() >= MAX_GAMES {
+            self.games.pop_first();
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        let body = describe(id, &game);
+        self.games.insert(
+            id,
+            ServedGame {
+                game,
+                validation: request.validation,
+            },
+        );
+
+        Response::ok(body)
+    }
+
+    /**
+     * Plays a move on a served game, a refused move being answered with why and counted as a
+     * mistake when the value is wrong.
+     */
+    fn play(&mut self, request: MoveRequest) -> Response {
+        let Some(served) = self.games.get_mut(&request.id) else {
+            return Response::error(404, format!("Unknown game {}.", request.id));
+        };
+
+        let played = match request.value {
+            0 => served.game.clear_cell(request.row, request.column),
+            value => served
+                .game
+                .do_move(request.row, request.column, value, served.validation),
+        };
+        match played {
+            Ok(()) => Response::ok(describe(request.id, &served.game)),
+            Err(e) => game_error(e),
+        }
+    }
+
+    /**
+     * Returns the answer about the grid of a request, a served game or the given puzzle.
+     */
+    fn on_grid(&self, request: &GridRequest, answer: impl FnOnce(&Game) -> Response) -> Response {
+        match (request.id, request.puzzle.as_ref()) {
+            (Some(id), _) => match self.games.get(&id) {
+                Some(served) => answer(&served.game),
+                None => Response::error(404, format!("Unknown game {}.", id)),
+            },
+            (None, Some(puzzle)) => match load(puzzle) {
+                Ok(game) => answer(&game),
+                Err(response) => response,
+            },
+            (None, None) => Response::error(400, "A game id or a puzzle is expected."),
+        }
+    }
+
+    /**
+     * Tells the next logical move, counting a hint used when it is about a served game.
+     */
+    fn hint(&mut self, request: GridRequest) -> Response {
+        let response = self.on_grid(&request, |game| {
+            let Some(hint) = hint::next_hint(game) else {
+                return Response::error(422, "No hint found with the known techniques.");
+            };
+            let position = |index: usize| {
+                let (row, column) = game.coordinates(index);
+                json!({ "row": row, "column": column })
+            };
+            let action = match &hint.action {
+                Action::Place { index, value } => json!({
+                    "place": position(*index),
+                    "value": value,
+                }),
+                Action::Eliminate { cells, value } => json!({
+                    "eliminate": cells.iter().map(|index| position(*index)).collect::<Vec<Value>>(),
+                    "value": value,
+                }),
+            };
+
+            Response::ok(json!({
+                "technique": hint.technique.to_string(),
+                "description": hint.describe(game),
+                "action": action,
+            }))
+        });
+
+        if response.status == 200 {
+            if let Some(served) = request.id.and_then(|id| self.games.get_mut(&id)) {
+                let description = response.body["description"].as_str().unwrap_or_default();
+                served.game.count_hint(description.to_string());
+            }
+        }
+        response
+    }
+
+    /**
+     * Gives the solution of the grid, row after row.
+     */
+    fn solve(&mut self, request: GridRequest) -> Response {
+        self.on_grid(&request, |game| match solution(game) {
+            Ok(solution) => Response::ok(json!({ "solution": solution })),
+            Err(response) => response,
+        })
+    }
+
+    /**
+     * Answers the requests of `listener` one after the other, until the process ends.
+     */
+    pub fn serve(&mut self, listener: TcpListener) {
+        for stream in listener.incoming() {
+            let answered = stream.and_then(|mut stream| self.answer(&mut stream));
+            if let Err(e) = answered {
+                log::warn!("Unable to answer a request: {}", e);
+            }
+        }
+    }
+
+    /**
+     * Reads the request sent on `stream`, then writes the answer to it.
+     */
+    fn answer(&mut self, stream: &mut TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let response = match read_request(stream)? {
+            Ok((method, _, _)) if method == "OPTIONS" => Response {
+                status: 204,
+                body: Value::Null,
+            },
+            Ok((method, path, body)) => {
+                log::debug!("{} {}", method, path);
+                self.handle(&method, &path, &body)
+            }
+            Err(response) => response,
+        };
+
+        let body = match response.body {
+            Value::Null => String::new(),
+            ref body => body.to_string(),
+        };
+        // Web front ends served from elsewhere may call the API
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nAccess-Control-Allow-Methods: POST, OPTIONS\r\nAccess-Control-Allow-Headers: Content-Type\r\nConnection: close\r\n\r\n{}",
+            response.status,
+            response.reason(),
+            body.len(),
+            body
+        )?;
+        stream.flush()
+    }
+}
+
+/**
+ * Parses the JSON body of a request, answering why it is malformed otherwise.
+ */
+fn parse<'a, T: Deserialize<'a>>(body: &'a str) -> Result<T, Response> {
+    serde_json::from_str(body).map_err(|e| Response::error(400, format!("Invalid request: {}", e)))
+}
+
+/**
+ * Reads the method, the path and the body of the HTTP request sent on `stream`, or the answer
+ * to a malformed one.
+ */
+fn read_request(stream: &mut impl Read) -> io::Result<Result<(String, String, String), Response>> {
+    // The head ends with an empty line, the body following it
+    let mut request = Vec::new();
+    let mut buffer = [0; 4096];
+    let head_end = loop {
+        if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end;
+        }
+        if request.len() > MAX_BODY {
+            return Ok(Err(Response::error(413, "The request is too large.")));
+        }
+        match stream.read(&mut buffer)? {
+            0 => return Ok(Err(Response::error(400, "The request is incomplete."))),
+            read => request.extend_from_slice(&buffer[..read]),
+        }
+    };
+
+    let head = String::from_utf8_lossy(&request[..head_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Ok(Err(Response::error(400, "The request line is malformed.")));
+    };
+    let length = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    if length > MAX_BODY {
+        return Ok(Err(Response::error(413, "The request is too large.")));
+    }
+
+    let mut body = request.split_off(head_end + 4);
+    while body.len() < length {
+        match stream.read(&mut buffer)? {
+            0 => return Ok(Err(Response::error(400, "The request is incomplete."))),
+            read => body.extend_from_slice(&buffer[..read]),
+        }
+    }
+    body.truncate(length);
+
+    Ok(Ok((
+        method.to_string(),
+        path.to_string(),
+        String::from_utf8_lossy(&body).to_string(),
+    )))
+}
@@ -0,0 +1,115 @@
+use crate::game::Game;
+
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+/**
+ * Stands for `std::time::Instant`, which panics in WebAssembly: the play time is not counted
+ * there.
+ */
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, Copy)]
+struct Instant;
+
+#[cfg(target_arch = "wasm32")]
+impl Instant {
+    fn now() -> Self {
+        Instant
+    }
+
+    fn elapsed(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+/**
+ * Measures the play time of a game, which only runs while the game is the one being played.
+ */
+#[derive(Default, Clone, Copy)]
+pub struct Timer {
+    /// Play time until the timer was last resumed.
+    played: Duration,
+    /// When the timer was last resumed, `None` while it is paused.
+    resumed_at: Option<Instant>,
+}
+
+impl Timer {
+    /**
+     * Returns a running timer.
+     */
+    pub fn start() -> Self {
+        Timer {
+            played: Duration::ZERO,
+            resumed_at: Some(Instant::now()),
+        }
+    }
+
+    /**
+     * Returns a running timer, `played` being the play time so far.
+     */
+    pub fn resume_from(played: Duration) -> Self {
+        Timer {
+            played,
+            resumed_at: Some(Instant::now()),
+        }
+    }
+
+    /**
+     * Returns the total play time.
+     */
+    pub fn elapsed(&self) -> Duration {
+        self.played + self.resumed_at.map_or(Duration::ZERO, |r| r.elapsed())
+    }
+
+    /**
+     * Returns whether the timer is counting the time, that is not paused.
+     */
+    pub fn is_running(&self) -> bool {
+        self.resumed_at.is_some()
+    }
+
+    /**
+     * Stops counting the time, until the timer is resumed.
+     */
+    pub fn pause(&mut self) {
+        self.played = self.elapsed();
+        self.resumed_at = None;
+    }
+
+    /**
+     * Starts counting the time again.
+     */
+    pub fn resume(&mut self) {
+        if self.resumed_at.is_none() {
+            self.resumed_at = Some(Instant::now());
+        }
+    }
+}
+
+/**
+ * A game that is open but not currently played, its timer paused. Its history, save file and
+ * play time are kept by the game itself.
+ */
+pub struct Session {
+    pub game: Game,
+}
+
+impl Session {
+    /**
+     * Parks `game`, pausing its timer.
+     */
+    pub fn park(mut game: Game) -> Self {
+        game.timer.pause();
+        Session { game }
+    }
+
+    /**
+     * Returns the game, its timer running again.
+     */
+    pub fn resume(self) -> Game {
+        let mut game = self.game;
+        game.timer.resume();
+        game
+    }
+}
@@ -0,0 +1,330 @@
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::Canvas;
+use sdl2::ttf::Font;
+use sdl2::video::Window;
+
+use std::rc::Rc;
+
+use crate::errors::UiError;
+use crate::game::Validation;
+use crate::generator::Difficulty;
+use crate::traits::{Displayable, GUIConfig, ScreenOutcome, ThemePreset};
+
+static COLOR_BCK: Color = Color::BLACK;
+static COLOR_HOVER: Color = Color::RGBA(75, 75, 75, 255);
+static COLOR_FONT: Color = Color::WHITE;
+static COLOR_BAD_MSG: Color = Color::RED;
+
+/// Space between the border of the window and the settings.
+const MARGIN: i32 = 40;
+/// Height of a row of settings.
+const ROW_HEIGHT: i32 = 40;
+/// Sizes of the boxes of the boards offered, for 4x4, 9x9 and 16x16 boards.
+const SIZES: [usize; 3] = [2, 3, 4];
+/// Difficulties offered, in the order they are cycled through.
+const DIFFICULTIES: [Difficulty; 4] = [
+    Difficulty::Easy,
+    Difficulty::Medium,
+    Difficulty::Hard,
+    Difficulty::Expert,
+];
+/// Validation modes offered, in the order they are cycled through.
+const VALIDATIONS: [Validation; 4] = [
+    Validation::Strict,
+    Validation::Solution,
+    Validation::Lenient,
+    Validation::Free,
+];
+/// Steps of the volume, cycled through from silent to full.
+const VOLUME_STEP: u8 = 10;
+
+/// Rows of the screen, below its title.
+const ROW_THEME: usize = 0;
+const ROW_DIFFICULTY: usize = 1;
+const ROW_VALIDATION: usize = 2;
+const ROW_VOLUME: usize = 3;
+const ROW_SIZE: usize = 4;
+const ROW_SAVE: usize = 5;
+const ROW_BACK: usize = 6;
+
+/**
+ * The settings changed on the settings screen.
+ */
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct Settings {
+    pub theme: ThemePreset,
+    pub difficulty: Difficulty,
+    pub validation: Validation,
+    /// Volume of the sounds and the music, from 0 to 100.
+    pub volume: u8,
+    /// Size of the boxes of the generated boards.
+    pub size: usize,
+}
+
+impl Settings {
+    /**
+     * Returns the settings of `config`.
+     */
+    pub fn from_config(config: &GUIConfig) -> Self {
+        Settings {
+            theme: config.theme.preset,
+            difficulty: config.difficulty,
+            validation: config.validation_mode,
+            volume: config.audio.volume.min(100),
+            size: config.game_size,
+        }
+    }
+
+    /**
+     * Writes the settings in `config`, leaving its other settings as they are.
+     */
+    pub fn apply(&self, config: &mut GUIConfig) {
+        config.theme.preset = self.theme;
+        config.difficulty = self.difficulty;
+        config.validation_mode = self.validation;
+        config.audio.volume = self.volume;
+        config.game_size = self.size;
+    }
+}
+
+/**
+ * Returns the value following `current` in `values`, the first one after the last.
+ */
+fn next_of<T: Copy + PartialEq>(values: &[T], current: T) -> T {
+    let next = values
+        .iter()
+        .position(|value| *value == current)
+        .map_or(0, |i| i + 1);
+    values[next % values.len()]
+}
+
+/**
+ * Lets the player change the theme, difficulty, validation mode, volume and board size while
+ * the game runs, the configuration file being saved with them.
+ */
+#[derive(Default)]
+pub struct SettingsScreen<'a> {
+    font: Option<Rc<Font<'a, 'a>>>,
+
+    settings: Settings,
+
+    /// The row under the mouse cursor.
+    hovered_row: Option<usize>,
+    viewport: Option<Rect>,
+    /// Why the settings could not be saved, if they could not.
+    message: Option<String>,
+
+    /// The settings picked by the player, to be applied and saved by the caller.
+    pub chosen: Option<Settings>,
+}
+
+impl<'a> SettingsScreen<'a> {
+    pub fn set_font(&mut self, new_font: Rc<Font<'a, 'a>>) {
+        self.font = Some(new_font);
+    }
+
+    /**
+     * Shows `message` below the settings, e.g. why they could not be saved.
+     */
+    pub fn set_message(&mut self, message: String) {
+        self.message = Some(message);
+    }
+
+    /**
+     * Returns the area of the row `row`.
+     */
+    fn row_rect(&self, row: usize) -> Rect {
+        let width = self.viewport.map_or(0, |v| v.width() as i32);
+        Rect::new(
+            MARGIN,
+            MARGIN + (row as i32 + 1) * ROW_HEIGHT,
+            (width - 2 * MARGIN).max(1) as u32,
+            ROW_HEIGHT as u32,
+        )
+    }
+
+    /**
+     * Returns the row at the given window position, if any.
+     */
+    fn row_at(&self, x: i32, y: i32) -> Option<usize> {
+        (ROW_THEME..=ROW_BACK).find(|row| self.row_rect(*row).contains_point((x, y)))
+    }
+
+    /**
+     * Returns the text of the row `row`.
+     */
+    fn row_text(&self, row: usize) -> String {
+        match row {
+            ROW_THEME => format!("Theme: {:?}", self.settings.theme),
+            ROW_DIFFICULTY => format!("Difficulty: {:?}", self.settings.difficulty),
+            ROW_VALIDATION => format!("Validation: {:?}", self.settings.validation),
+            ROW_VOLUME => format!("Volume: {}", self.settings.volume),
+            ROW_SIZE => format!("Size: {0}x{0}", self.settings.size * self.settings.size),
+            ROW_SAVE => String::from("Save"),
+            _ => String::from("Back"),
+        }
+    }
+
+    /**
+     * Keeps the picked settings for the caller to apply and save them.
+     */
+    fn save(&mut self) -> ScreenOutcome {
+        self.message = None;
+        self.chosen = Some(self.settings);
+
+        ScreenOutcome::SaveSettings
+    }
+
+    /**
+     * Draws `text` with the screen's font, vertically centered in `area`.
+     */
+    fn draw_text(
+        &self,
+        canvas: &mut Canvas<Window>,
+        text: &str,
+        color: Color,
+        area: Rect,
+    ) -> Result<(), UiError> {
+        let texture_creator = canvas.texture_creator();
+        let surface = self
+            .font
+            .as_ref()
+            .unwrap()
+            .render(text)
+            .blended(color)
+            .map_err(UiError::sdl2)?;
+        let texture = texture_creator
+            .create_texture_from_surface(surface)
+            .map_err(UiError::sdl2)?;
+
+        let width = texture.query().width.min(area.width());
+        let height = texture.query().height.min(area.height());
+        canvas
+            .copy(
+                &texture,
+                Rect::new(0, 0, width, height),
+                Rect::new(
+                    area.x(),
+                    area.y() + (area.height() - height) as i32 / 2,
+                    width,
+                    height,
+                ),
+            )
+            .map_err(UiError::sdl2)
+    }
+}
+
+impl<'a> Displayable for SettingsScreen<'a> {
+    fn new() -> Self {
+        SettingsScreen {
+            ..Default::default()
+        }
+    }
+
+    /**
+     * Starts from the settings of `config`, called again every time the screen is opened so
+     * that the settings left without saving are forgotten.
+     */
+    fn init(&mut self, canvas: &mut Canvas<Window>, config: &GUIConfig) -> Result<(), UiError> {
+        self.settings = Settings::from_config(config);
+        if !SIZES.contains(&self.settings.size) {
+            self.settings.size = 3;
+        }
+        self.message = None;
+        self.viewport = Some(canvas.viewport());
+
+        Ok(())
+    }
+
+    fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        canvas.set_draw_color(COLOR_BCK);
+        canvas.clear();
+
+        let title_area = Rect::new(MARGIN, MARGIN, self.row_rect(0).width(), ROW_HEIGHT as u32);
+        self.draw_text(canvas, "Settings", COLOR_FONT, title_area)?;
+
+        for row in ROW_THEME..=ROW_BACK {
+            let area = self.row_rect(row);
+            if self.hovered_row == Some(row) {
+                canvas.set_draw_color(COLOR_HOVER);
+                canvas.fill_rect(area).map_err(UiError::sdl2)?;
+            }
+            self.draw_text(canvas, &self.row_text(row), COLOR_FONT, area)?;
+        }
+
+        if let Some(message) = self.message.as_ref() {
+            let area = self.row_rect(ROW_BACK + 1);
+            self.draw_text(canvas, message, COLOR_BAD_MSG, area)?;
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        self.viewport = Some(canvas.viewport());
+
+        Ok(())
+    }
+
+    fn update(&mut self, event: &Event) -> Result<ScreenOutcome, UiError> {
+        match event {
+            Event::MouseMotion { x, y, .. } => {
+                let hovered_row = self.row_at(*x, *y);
+                if hovered_row != self.hovered_row {
+                    self.hovered_row = hovered_row;
+                    return Ok(ScreenOutcome::Updated);
+                }
+            }
+            // Clicking a setting cycles through its values
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => {
+                let row = self.row_at(*x, *y);
+                let settings = &mut self.settings;
+                match row {
+                    Some(ROW_THEME) => {
+                        settings.theme = match settings.theme {
+                            ThemePreset::Dark => ThemePreset::Light,
+                            ThemePreset::Light => ThemePreset::Dark,
+                        }
+                    }
+                    Some(ROW_DIFFICULTY) => {
+                        settings.difficulty = next_of(&DIFFICULTIES, settings.difficulty)
+                    }
+                    Some(ROW_VALIDATION) => {
+                        settings.validation = next_of(&VALIDATIONS, settings.validation)
+                    }
+                    Some(ROW_VOLUME) => {
+                        settings.volume = match settings.volume >= 100 {
+                            true => 0,
+                            false => (settings.volume / VOLUME_STEP + 1) * VOLUME_STEP,
+                        }
+                    }
+                    Some(ROW_SIZE) => settings.size = next_of(&SIZES, settings.size),
+                    Some(ROW_SAVE) => return Ok(self.save()),
+                    Some(_) => return Ok(ScreenOutcome::Menu),
+                    None => return Ok(ScreenOutcome::Unchanged),
+                }
+                return Ok(ScreenOutcome::Updated);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Return | Keycode::KpEnter),
+                ..
+            } => return Ok(self.save()),
+            Event::KeyDown {
+                keycode: Some(Keycode::Escape),
+                ..
+            } => return Ok(ScreenOutcome::Menu),
+            _ => {}
+        }
+
+        Ok(ScreenOutcome::Unchanged)
+    }
+}
@@ -0,0 +1,230 @@
+use crate::errors::GameError;
+use crate::game::{self, Cell, Game, Variant};
+use crate::killer::Cage;
+
+/// Version of the share code format, its first byte.
+const SHARE_VERSION: u8 = 1;
+/// Digits of the URL-safe base64 alphabet the codes are written with, without padding.
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+/// Number of bits holding the sum of a cage.
+const SUM_BITS: usize = 10;
+
+/**
+ * Returns the number of bits needed to write the numbers up to `n`.
+ */
+fn bits_for(n: usize) -> usize {
+    (usize::BITS - n.leading_zeros()) as usize
+}
+
+/**
+ * Bytes written bit by bit, most significant bits first.
+ */
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    /// Number of bits written.
+    len: usize,
+}
+
+impl BitWriter {
+    /**
+     * Writes the `bits` lowest bits of `value`.
+     */
+    fn write(&mut self, value: usize, bits: usize) {
+        for i in (0..bits).rev() {
+            if self.len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if value >> i & 1 == 1 {
+                *self.bytes.last_mut().unwrap() |= 0x80 >> (self.len % 8);
+            }
+            self.len += 1;
+        }
+    }
+}
+
+/**
+ * Reads the bits of bytes written by a `BitWriter`.
+ */
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    /// Number of bits read.
+    position: usize,
+}
+
+impl BitReader<'_> {
+    /**
+     * Reads a number written on `bits` bits, none when there are not enough bits left.
+     */
+    fn read(&mut self, bits: usize) -> Option<usize> {
+        let mut value = 0;
+        for _ in 0..bits {
+            let byte = self.bytes.get(self.position / 8)?;
+            value = value << 1 | (byte >> (7 - self.position % 8) & 1) as usize;
+            self.position += 1;
+        }
+        Some(value)
+    }
+}
+
+/**
+ * Writes `bytes` in URL-safe base64, without padding.
+ */
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut writer = BitWriter::default();
+    bytes.iter().for_each(|b| writer.write(*b as usize, 8));
+    let mut reader = BitReader {
+        bytes: &writer.bytes,
+        position: 0,
+    };
+
+    let mut code = String::new();
+    while reader.position < writer.len {
+        // The last digit is padded with zeros
+        let left = (writer.len - reader.position).min(6);
+        let digit = reader.read(left).unwrap() << (6 - left);
+        code.push(ALPHABET[digit] as char);
+    }
+    code
+}
+
+/**
+ * Reads URL-safe base64 written by `encode_base64`, none if it holds other characters.
+ */
+fn decode_base64(code: &str) -> Option<Vec<u8>> {
+    let mut writer = BitWriter::default();
+    for c in code.bytes() {
+        let digit = ALPHABET.iter().position(|d| *d == c)?;
+        writer.write(digit, 6);
+    }
+    // The bits of the last digit that do not make up a whole byte are padding
+    writer.bytes.truncate(writer.len / 8);
+    Some(writer.bytes)
+}
+
+impl Game {
+    /**
+     * Returns the puzzle as a share code: the size of the grid, its clues, its variant, and the
+     * regions of jigsaw puzzles and cages of killer ones, packed in a short URL-safe base64
+     * string. The values placed by the player are left out, see `from_share_code`.
+     */
+    pub fn to_share_code(&self) -> String {
+        let cells = self.side_size * self.side_size;
+        let jigsaw = self.is_jigsaw();
+        let killer = !self.cages.is_empty();
+
+        let mut writer = BitWriter::default();
+        writer.write(SHARE_VERSION as usize, 8);
+        writer.write(self.size, 3);
+        let variant = match self.variant {
+            Variant::Classic => 0,
+            Variant::Diagonal => 1,
+            Variant::Hyper => 2,
+        };
+        writer.write(variant, 2);
+        writer.write(jigsaw as usize, 1);
+        writer.write(killer as usize, 1);
+
+        for cell in self.grid.iter() {
+            let clue = match cell.initial() {
+                true => cell.value() as usize,
+                false => 0,
+            };
+            writer.write(clue, bits_for(self.side_size));
+        }
+        if jigsaw {
+            for region in self.regions().iter() {
+                writer.write(*region, bits_for(self.side_size - 1));
+            }
+        }
+        if killer {
+            writer.write(self.cages.len(), bits_for(cells));
+            for cage in self.cages.iter() {
+                writer.write(cage.cells.len(), bits_for(self.side_size));
+                writer.write(cage.sum, SUM_BITS);
+                for cell in cage.cells.iter() {
+                    writer.write(*cell, bits_for(cells - 1));
+                }
+            }
+        }
+
+        encode_base64(&writer.bytes)
+    }
+
+    /**
+     * Creates a game from a share code written by `to_share_code`, saved at `saving_path` if
+     * given. Spaces around the code are ignored.
+     */
+    pub fn from_share_code(code: &str, saving_path: Option<&str>) -> Result<Self, GameError> {
+        let bytes = decode_base64(code.trim()).ok_or(GameError::InvalidPuzzle)?;
+        let mut reader = BitReader {
+            bytes: &bytes,
+            position: 0,
+        };
+        let mut read = |bits| reader.read(bits).ok_or(GameError::InvalidPuzzle);
+
+        if read(8)? != SHARE_VERSION as usize {
+            return Err(GameError::InvalidPuzzle);
+        }
+        let size = read(3)?;
+        if !(2..=5).contains(&size) {
+            return Err(GameError::InvalidPuzzle);
+        }
+        let (side_size, cells) = (size * size, size.pow(4));
+        let variant = match read(2)? {
+            0 => Variant::Classic,
+            1 => Variant::Diagonal,
+            2 => Variant::Hyper,
+            _ => return Err(GameError::InvalidPuzzle),
+        };
+        let (jigsaw, killer) = (read(1)? == 1, read(1)? == 1);
+
+        let mut clues = Vec::with_capacity(cells);
+        for _ in 0..cells {
+            match read(bits_for(side_size))? {
+                clue if clue <= side_size => clues.push(clue as u8),
+                _ => return Err(GameError::InvalidPuzzle),
+            }
+        }
+        let regions = match jigsaw {
+            true => {
+                let regions = (0..cells)
+                    .map(|_| read(bits_for(side_size - 1)))
+                    .collect::<Result<Vec<usize>, GameError>>()?;
+                if !game::valid_regions(&regions, side_size) {
+                    return Err(GameError::InvalidPuzzle);
+                }
+                regions
+            }
+            false => game::boxes(size),
+        };
+        let mut cages = Vec::new();
+        if killer {
+            for _ in 0..read(bits_for(cells))? {
+                let len = read(bits_for(side_size))?;
+                let sum = read(SUM_BITS)?;
+                let cage_cells = (0..len)
+                    .map(|_| read(bits_for(cells - 1)))
+                    .collect::<Result<Vec<usize>, GameError>>()?;
+                if len == 0 || cage_cells.iter().any(|i| *i >= cells) {
+                    return Err(GameError::InvalidPuzzle);
+                }
+                cages.push(Cage {
+                    cells: cage_cells,
+                    sum,
+                });
+            }
+        }
+
+        // The save file is only created once the whole code is known to be valid
+        let mut game = Game::new(size, saving_path)?;
+        for (cell, clue) in game.grid.iter_mut().zip(clues) {
+            *cell = Cell::new(clue, clue != 0);
+        }
+        game.variant = variant;
+        game.set_regions(regions);
+        game.cages = cages;
+
+        Ok(game)
+    }
+}
@@ -1,28 +1,776 @@
 use crate::errors;
-use crate::game::{Cell, Game};
+use crate::game::{Cell, Game, Variant};
+use crate::grader::{self, SolveStep, Technique};
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 pub trait Solver {
     fn solve(&self, game: &mut Game) -> Result<(), errors::SolverError>;
+
+    /**
+     * Counts the solutions of `game`, stopping once `limit` were found, by backtracking unless
+     * the solver has a faster way. The grid is left as it was.
+     */
+    fn count_solutions(&self, game: &mut Game, limit: usize) -> usize {
+        count_solutions(game, limit)
+    }
+
+    /**
+     * Returns whether the solver solves `game` to its single solution, with one count of the
+     * solutions unless the solver has to solve it anyway. The grid is left as it was.
+     */
+    fn has_single_solution(&self, game: &mut Game) -> bool {
+        self.count_solutions(game, 2) == 1
+    }
+
+    /**
+     * Returns the steps solving `game` with human techniques, the ones of the grading ladder
+     * unless the solver uses others, see `grader::solve_with`. They stop where the techniques
+     * are not enough, the grid being left as it was.
+     */
+    fn solve_with_trace(&self, game: &Game) -> Vec<SolveStep> {
+        Human(&grader::LADDER).solve_with_trace(game)
+    }
+}
+
+/// The solver checking the generated puzzles, picked in the configuration.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum SolverKind {
+    /// Exact cover search with dancing links, see `Dlx`.
+    Dlx,
+    /// Candidates tried recursively, see `Backtracking`.
+    #[default]
+    Backtracking,
+    /// Naked singles only, see `Obvious`: every puzzle is then solvable with singles.
+    Obvious,
+    /// The techniques of the grading ladder, see `Human`: every puzzle can then be rated.
+    Human,
+}
+
+impl Solver for SolverKind {
+    fn solve(&self, game: &mut Game) -> Result<(), errors::SolverError> {
+        match self {
+            SolverKind::Dlx => Dlx.solve(game),
+            SolverKind::Backtracking => Backtracking.solve(game),
+            SolverKind::Obvious => Obvious.solve(game),
+            SolverKind::Human => Human(&grader::LADDER).solve(game),
+        }
+    }
+
+    fn count_solutions(&self, game: &mut Game, limit: usize) -> usize {
+        match self {
+            SolverKind::Dlx => Dlx.count_solutions(game, limit),
+            _ => count_solutions(game, limit),
+        }
+    }
+
+    fn has_single_solution(&self, game: &mut Game) -> bool {
+        match self {
+            SolverKind::Dlx => Dlx.has_single_solution(game),
+            SolverKind::Backtracking => Backtracking.has_single_solution(game),
+            SolverKind::Obvious => Obvious.has_single_solution(game),
+            SolverKind::Human => Human(&grader::LADDER).has_single_solution(game),
+        }
+    }
+
+    fn solve_with_trace(&self, game: &Game) -> Vec<SolveStep> {
+        match self {
+            SolverKind::Obvious => Obvious.solve_with_trace(game),
+            _ => Human(&grader::LADDER).solve_with_trace(game),
+        }
+    }
+}
+
+/**
+ * What is known about the validity of a puzzle, as told by the `check` command and written in its
+ * JSON output.
+ */
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct PuzzleCheck {
+    /// The puzzle, see `Game::puzzle_id`, empty when it does not describe a grid.
+    pub puzzle: String,
+    /// Whether the puzzle describes a grid at all.
+    pub valid: bool,
+    /// The (one based) row and column of the clues conflicting with another clue.
+    pub conflicts: Vec<(usize, usize)>,
+    /// Number of solutions, counted up to 2 which stands for several.
+    pub solutions: usize,
+    /// Its grade when it has a single solution, see `grader::Grade`.
+    pub rating: Option<String>,
+}
+
+impl PuzzleCheck {
+    /**
+     * Checks the puzzle of `game`: its conflicting clues, then its number of solutions. The grid
+     * is left as it was.
+     */
+    pub fn new(game: &mut Game) -> Self {
+        let conflicts: Vec<(usize, usize)> = game
+            .clue_conflicts()
+            .into_iter()
+            .map(|i| game.coordinates(i))
+            .map(|(r, c)| (r + 1, c + 1))
+            .collect();
+        // Conflicting clues cannot be solved
+        let solutions = match conflicts.is_empty() {
+            true => count_solutions(game, 2),
+            false => 0,
+        };
+
+        PuzzleCheck {
+            puzzle: game.puzzle_id(),
+            valid: true,
+            conflicts,
+            solutions,
+            rating: (solutions == 1).then(|| game.rating().to_string()),
+        }
+    }
+
+    /**
+     * Returns the check of a puzzle that does not describe a grid.
+     */
+    pub fn invalid() -> Self {
+        PuzzleCheck {
+            puzzle: String::new(),
+            valid: false,
+            conflicts: Vec::new(),
+            solutions: 0,
+            rating: None,
+        }
+    }
+}
+
+impl fmt::Display for PuzzleCheck {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.valid {
+            return write!(f, "invalid puzzle");
+        }
+        if !self.conflicts.is_empty() {
+            let cells: Vec<String> = self
+                .conflicts
+                .iter()
+                .map(|(r, c)| format!("row {} column {}", r, c))
+                .collect();
+            return write!(f, "conflicting clues at {}", cells.join(", "));
+        }
+        match (self.solutions, self.rating.as_ref()) {
+            (0, _) => write!(f, "no solution"),
+            (1, Some(rating)) => write!(f, "unique solution, rated {}", rating),
+            (1, None) => write!(f, "unique solution"),
+            _ => write!(f, "several solutions"),
+        }
+    }
+}
+
+/**
+ * The values used in each row, column and box of a grid, and its diagonals or windows for the
+ * `Variant::Diagonal` and `Variant::Hyper` puzzles, as masks where the bit `n` is set when
+ * `n` is used, kept up to date as values are placed and removed so that the candidates of a cell
+ * never need to be looked for among its neighbors.
+ */
+pub struct Masks {
+    side_size: usize,
+    /// Every value of the grid.
+    all: u32,
+    rows: Vec<u32>,
+    columns: Vec<u32>,
+    groups: Vec<u32>,
+    /// The group of each cell, see `Game::regions`.
+    regions: Vec<usize>,
+    /// Whether the diagonals are units too.
+    diagonal: bool,
+    /// The main diagonal, then the anti-diagonal.
+    diagonals: [u32; 2],
+    /// The window of each cell, if any, see `Game::window_of`.
+    window_of: Vec<Option<usize>>,
+    windows: Vec<u32>,
+}
+
+impl Masks {
+    /**
+     * Returns the masks of the values of `game`.
+     */
+    pub fn new(game: &Game) -> Self {
+        let mut masks = Masks::empty(game);
+        for (index, cell) in game.grid.iter().enumerate() {
+            if cell.value() != 0 {
+                masks.place(index, cell.value());
+            }
+        }
+
+        masks
+    }
+
+    /// Returns the masks of an empty grid of the size of `game`.
+    fn empty(game: &Game) -> Self {
+        Masks {
+            side_size: game.side_size,
+            all: (1..=game.side_size).fold(0, |mask, v| mask | 1 << v),
+            rows: vec![0; game.side_size],
+            columns: vec![0; game.side_size],
+            groups: vec![0; game.side_size],
+            regions: game.regions().to_vec(),
+            diagonal: game.variant == Variant::Diagonal,
+            diagonals: [0; 2],
+            window_of: (0..game.grid.len()).map(|i| game.window_of(i)).collect(),
+            windows: vec![0; game.windows_count()],
+        }
+    }
+
+    /// Returns the row, column and box, or jigsaw region, of the cell at `index`.
+    fn units(&self, index: usize) -> (usize, usize, usize) {
+        (
+            index / self.side_size,
+            index % self.side_size,
+            self.regions[index],
+        )
+    }
+
+    /// Returns whether the cell at `index` is on the main diagonal and on the anti-diagonal,
+    /// both being false when the diagonals are not units.
+    fn on_diagonals(&self, index: usize) -> [bool; 2] {
+        let (r, c) = (index / self.side_size, index % self.side_size);
+        [
+            self.diagonal && r == c,
+            self.diagonal && r + c == self.side_size - 1,
+        ]
+    }
+
+    /// Returns the values the cell at `index` can take, as a mask.
+    pub fn candidates(&self, index: usize) -> u32 {
+        let (r, c, g) = self.units(index);
+        let diagonals = self
+            .on_diagonals(index)
+            .iter()
+            .zip(self.diagonals)
+            .filter(|(on, _)| **on)
+            .fold(0, |mask, (_, d)| mask | d);
+        let window = self.window_of[index].map_or(0, |w| self.windows[w]);
+        self.all & !(self.rows[r] | self.columns[c] | self.groups[g] | diagonals | window)
+    }
+
+    /// Marks `value` as used by the units of the cell at `index`.
+    pub fn place(&mut self, index: usize, value: u8) {
+        let (r, c, g) = self.units(index);
+        self.rows[r] |= 1 << value;
+        self.columns[c] |= 1 << value;
+        self.groups[g] |= 1 << value;
+        for (d, on) in self.on_diagonals(index).into_iter().enumerate() {
+            if on {
+                self.diagonals[d] |= 1 << value;
+            }
+        }
+        if let Some(w) = self.window_of[index] {
+            self.windows[w] |= 1 << value;
+        }
+    }
+
+    /// Marks `value` as free again in the units of the cell at `index`.
+    pub fn remove(&mut self, index: usize, value: u8) {
+        let (r, c, g) = self.units(index);
+        self.rows[r] &= !(1 << value);
+        self.columns[c] &= !(1 << value);
+        self.groups[g] &= !(1 << value);
+        for (d, on) in self.on_diagonals(index).into_iter().enumerate() {
+            if on {
+                self.diagonals[d] &= !(1 << value);
+            }
+        }
+        if let Some(w) = self.window_of[index] {
+            self.windows[w] &= !(1 << value);
+        }
+    }
 }
 
+/**
+ * Returns the values of `mask`, in increasing order.
+ */
+pub fn values_of(mask: u32) -> Vec<u8> {
+    (1..32).filter(|v| mask & 1 << v != 0).collect()
+}
+
+/**
+ * Solves the grids that only need naked singles: cells with a single candidate are filled until
+ * the grid is done.
+ */
 pub struct Obvious;
 
 impl Solver for Obvious {
     fn solve(&self, game: &mut Game) -> Result<(), errors::SolverError> {
-        if game.is_done() {
-            return Ok(());
+        let mut masks = Masks::new(game);
+        loop {
+            let single = (0..game.grid.len())
+                .find(|i| game.grid[*i] == 0 && masks.candidates(*i).count_ones() == 1);
+            match single {
+                Some(index) => {
+                    let value = masks.candidates(index).trailing_zeros() as u8;
+                    game.grid[index] = Cell::new(value, false);
+                    masks.place(index, value);
+                }
+                None if game.is_done() => return Ok(()),
+                None => return Err(errors::SolverError::FailedToSolve),
+            }
+        }
+    }
+
+    fn has_single_solution(&self, game: &mut Game) -> bool {
+        solves_alone(self, game)
+    }
+
+    fn solve_with_trace(&self, game: &Game) -> Vec<SolveStep> {
+        Human(&[Technique::NakedSingle]).solve_with_trace(game)
+    }
+}
+
+/**
+ * Solves the grids that only need the given human techniques, tried in their order at each step,
+ * see `grader::Technique`. They compose: `Human(&[Technique::NakedSingle])` solves like
+ * `Obvious`, while `Human(&grader::LADDER)` solves every grid the grader can rate.
+ */
+pub struct Human<'a>(pub &'a [Technique]);
+
+impl Solver for Human<'_> {
+    fn solve(&self, game: &mut Game) -> Result<(), errors::SolverError> {
+        match grader::solve_with(game, self.0) {
+            (_, true) => Ok(()),
+            (_, false) => Err(errors::SolverError::FailedToSolve),
+        }
+    }
+
+    fn has_single_solution(&self, game: &mut Game) -> bool {
+        solves_alone(self, game)
+    }
+
+    fn solve_with_trace(&self, game: &Game) -> Vec<SolveStep> {
+        grader::trace(game, self.0).0
+    }
+}
+
+/**
+ * Returns whether `solver` solves `game`, leaving its grid as it was: as the human techniques only
+ * place the values forced by the clues, a solved grid is the single solution of the puzzle.
+ */
+fn solves_alone<S: Solver>(solver: &S, game: &mut Game) -> bool {
+    let grid = game.grid.clone();
+    let solved = solver.solve(game).is_ok();
+    game.grid = grid;
+    solved
+}
+
+/**
+ * Counts the solutions of `game`, stopping once `limit` were found. The grid is left as it was.
+ */
+pub fn count_solutions(game: &mut Game, limit: usize) -> usize {
+    let (mut masks, mut nodes) = (Masks::new(game), usize::MAX);
+    search(game, &mut masks, limit, &mut None, &mut nodes)
+}
+
+/**
+ * Returns the values of `candidates` that also fit the sums of the killer cages of the cell at
+ * `index`, see `Game::cage_allows`.
+ */
+fn cage_candidates(game: &Game, index: usize, candidates: u32) -> u32 {
+    if game.cages.is_empty() {
+        return candidates;
+    }
+
+    values_of(candidates)
+        .into_iter()
+        .filter(|v| game.cage_allows(index, *v))
+        .fold(0, |mask, v| mask | 1 << v)
+}
+
+/**
+ * Counts the solutions of `game` like `count_solutions`, trying the candidates of its cells
+ * recursively starting with the cell that has the fewest, and keeps the values of the first one
+ * in `solution`. Each cell tried uses one of the `nodes` left, the search stopping without
+ * counting more once there are none.
+ */
+fn search(
+    game: &mut Game,
+    masks: &mut Masks,
+    limit: usize,
+    solution: &mut Option<Vec<u8>>,
+    nodes: &mut usize,
+) -> usize {
+    if *nodes == 0 {
+        return 0;
+    }
+    *nodes -= 1;
+
+    // Find the empty cell with the fewest candidates
+    let mut best: Option<(usize, u32)> = None;
+    for i in 0..game.grid.len() {
+        if game.grid[i] != 0 {
+            continue;
+        }
+        let candidates = cage_candidates(game, i, masks.candidates(i));
+        if best.is_none_or(|(_, b)| candidates.count_ones() < b.count_ones()) {
+            best = Some((i, candidates));
+            if candidates.count_ones() <= 1 {
+                break;
+            }
+        }
+    }
+
+    let (index, candidates) = match best {
+        Some(best) => best,
+        // The grid is filled, it is a solution if none of its values conflict
+        None => {
+            if !game.is_done() {
+                return 0;
+            }
+            if solution.is_none() {
+                *solution = Some(game.grid.iter().map(|x| x.value()).collect());
+            }
+            return 1;
+        }
+    };
+
+    let mut count = 0;
+    for value in values_of(candidates) {
+        game.grid[index] = Cell::new(value, false);
+        masks.place(index, value);
+        count += search(game, masks, limit - count, solution, nodes);
+        masks.remove(index, value);
+        if count >= limit {
+            break;
+        }
+    }
+    game.grid[index] = Cell::new(0, false);
+
+    count
+}
+
+/**
+ * Solves any grid by trying the candidates of its cells recursively, see `count_solutions`. As a
+ * puzzle must have a single solution, grids with several solutions are not solved. The
+ * candidates not fitting the sums of the killer cages are left out, so that killer puzzles are
+ * solved too.
+ */
+pub struct Backtracking;
+
+impl Solver for Backtracking {
+    fn solve(&self, game: &mut Game) -> Result<(), errors::SolverError> {
+        backtrack(game, usize::MAX)
+    }
+}
+
+/**
+ * Solves `game` like `Backtracking`, giving up once `nodes` cells were tried.
+ */
+fn backtrack(game: &mut Game, mut nodes: usize) -> Result<(), errors::SolverError> {
+    let mut masks = Masks::new(game);
+    let mut solution = None;
+    let count = search(game, &mut masks, 2, &mut solution, &mut nodes);
+    if nodes == 0 {
+        return Err(errors::SolverError::OutOfNodes);
+    }
+    if count != 1 {
+        return Err(errors::SolverError::FailedToSolve);
+    }
+
+    for (cell, value) in game.grid.iter_mut().zip(solution.unwrap()) {
+        if *cell == 0 {
+            *cell = Cell::new(value, false);
+        }
+    }
+
+    Ok(())
+}
+
+/**
+ * The exact cover matrix of a grid for Knuth's Algorithm X, as dancing links: each row is a value
+ * an empty cell can take, each column a constraint still to satisfy (a cell to fill, or a value
+ * missing from a row, a column, a box or a diagonal), and the nodes are linked to their four
+ * neighbors so that covering a column and uncovering it in reverse order are both cheap.
+ *
+ * The node 0 is the root, the nodes `1..=columns` the column headers.
+ */
+struct Links {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// Header of the column of each node.
+    column: Vec<usize>,
+    /// Cell and value of the row of each node.
+    choice: Vec<(usize, u8)>,
+    /// Number of nodes of each column, by header.
+    size: Vec<usize>,
+}
+
+impl Links {
+    /**
+     * Builds the matrix of the empty cells of `game`, or returns `None` if its values already
+     * conflict.
+     */
+    fn new(game: &Game) -> Option<Self> {
+        let side_size = game.side_size;
+        let cells = game.grid.len();
+        let mut masks = Masks::empty(game);
+        for (index, cell) in game.grid.iter().enumerate() {
+            let value = cell.value();
+            if value != 0 {
+                if masks.candidates(index) & 1 << value == 0 {
+                    return None;
+                }
+                masks.place(index, value);
+            }
+        }
+
+        // One column per cell, then per row, column and box for each value, then per diagonal and
+        // per window for each value if they are units
+        let diagonals = match masks.diagonal {
+            true => 2 * side_size,
+            false => 0,
+        };
+        let windows = masks.windows.len() * side_size;
+        let columns = 4 * cells + diagonals + windows;
+        let mut links = Links {
+            left: (0..=columns)
+                .map(|n| (n + columns) % (columns + 1))
+                .collect(),
+            right: (0..=columns).map(|n| (n + 1) % (columns + 1)).collect(),
+            up: (0..=columns).collect(),
+            down: (0..=columns).collect(),
+            column: (0..=columns).collect(),
+            choice: vec![(0, 0); columns + 1],
+            size: vec![0; columns + 1],
+        };
+
+        for index in 0..cells {
+            if game.grid[index] != 0 {
+                continue;
+            }
+            let (r, c, g) = masks.units(index);
+            for value in values_of(masks.candidates(index)) {
+                let v = value as usize - 1;
+                let mut constraints = vec![
+                    index,
+                    cells + r * side_size + v,
+                    2 * cells + c * side_size + v,
+                    3 * cells + g * side_size + v,
+                ];
+                for (d, on) in masks.on_diagonals(index).into_iter().enumerate() {
+                    if on {
+                        constraints.push(4 * cells + d * side_size + v);
+                    }
+                }
+                if let Some(w) = masks.window_of[index] {
+                    constraints.push(4 * cells + diagonals + w * side_size + v);
+                }
+                links.add_row((index, value), &constraints);
+            }
+        }
+
+        // The constraints satisfied by the values of the grid are left out
+        for index in 0..cells {
+            // The constraints of each kind are numbered like the cells, by unit then value
+            let (unit, v) = (index / side_size, index % side_size);
+            let used = |mask: u32| mask & 1 << (v + 1) != 0;
+            let satisfied = [
+                (index, game.grid[index] != 0),
+                (cells + index, used(masks.rows[unit])),
+                (2 * cells + index, used(masks.columns[unit])),
+                (3 * cells + index, used(masks.groups[unit])),
+            ];
+            for (constraint, satisfied) in satisfied {
+                if satisfied {
+                    links.leave_out(constraint);
+                }
+            }
+        }
+        for constraint in 0..diagonals {
+            let (d, v) = (constraint / side_size, constraint % side_size);
+            if masks.diagonals[d] & 1 << (v + 1) != 0 {
+                links.leave_out(4 * cells + constraint);
+            }
+        }
+        for constraint in 0..windows {
+            let (w, v) = (constraint / side_size, constraint % side_size);
+            if masks.windows[w] & 1 << (v + 1) != 0 {
+                links.leave_out(4 * cells + diagonals + constraint);
+            }
+        }
+
+        Some(links)
+    }
+
+    /// Unlinks the header of `constraint` from the other headers, as it is already satisfied.
+    fn leave_out(&mut self, constraint: usize) {
+        let header = constraint + 1;
+        self.right[self.left[header]] = self.right[header];
+        self.left[self.right[header]] = self.left[header];
+    }
+
+    /**
+     * Adds the row of `choice`, with a node in the column of each of its `constraints`.
+     */
+    fn add_row(&mut self, choice: (usize, u8), constraints: &[usize]) {
+        let (first, count) = (self.column.len(), constraints.len());
+        for (i, constraint) in constraints.iter().enumerate() {
+            let (node, header) = (first + i, constraint + 1);
+            self.left.push(first + (i + count - 1) % count);
+            self.right.push(first + (i + 1) % count);
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            self.column.push(header);
+            self.choice.push(choice);
+            self.down[self.up[header]] = node;
+            self.up[header] = node;
+            self.size[header] += 1;
+        }
+    }
+
+    /// Removes the column `header` and the rows having a node in it.
+    fn cover(&mut self, header: usize) {
+        self.right[self.left[header]] = self.right[header];
+        self.left[self.right[header]] = self.left[header];
+        let mut row = self.down[header];
+        while row != header {
+            let mut node = self.right[row];
+            while node != row {
+                self.down[self.up[node]] = self.down[node];
+                self.up[self.down[node]] = self.up[node];
+                self.size[self.column[node]] -= 1;
+                node = self.right[node];
+            }
+            row = self.down[row];
+        }
+    }
+
+    /// Puts back the column `header` removed by `cover`.
+    fn uncover(&mut self, header: usize) {
+        let mut row = self.up[header];
+        while row != header {
+            let mut node = self.left[row];
+            while node != row {
+                self.size[self.column[node]] += 1;
+                self.down[self.up[node]] = node;
+                self.up[self.down[node]] = node;
+                node = self.left[node];
+            }
+            row = self.up[row];
+        }
+        self.right[self.left[header]] = header;
+        self.left[self.right[header]] = header;
+    }
+
+    /**
+     * Counts the exact covers of the matrix, stopping once `limit` were found, covering first the
+     * column with the fewest rows. The choices of the first one are kept in `solution`. Each
+     * column covered uses one of the `nodes` left, the search stopping once there are none.
+     */
+    fn search(
+        &mut self,
+        limit: usize,
+        choices: &mut Vec<(usize, u8)>,
+        solution: &mut Option<Vec<(usize, u8)>>,
+        nodes: &mut usize,
+    ) -> usize {
+        // Every constraint is satisfied
+        if self.right[0] == 0 {
+            if solution.is_none() {
+                *solution = Some(choices.clone());
+            }
+            return 1;
+        }
+
+        let mut header = self.right[0];
+        let mut next = self.right[header];
+        while next != 0 {
+            if self.size[next] < self.size[header] {
+                header = next;
+            }
+            next = self.right[next];
+        }
+        if self.size[header] == 0 || *nodes == 0 {
+            return 0;
+        }
+        *nodes -= 1;
+
+        self.cover(header);
+        let mut count = 0;
+        let mut row = self.down[header];
+        while row != header && count < limit {
+            choices.push(self.choice[row]);
+            let mut node = self.right[row];
+            while node != row {
+                self.cover(self.column[node]);
+                node = self.right[node];
+            }
+
+            count += self.search(limit - count, choices, solution, nodes);
+
+            let mut node = self.left[row];
+            while node != row {
+                self.uncover(self.column[node]);
+                node = self.left[node];
+            }
+            choices.pop();
+            row = self.down[row];
+        }
+        self.uncover(header);
+
+        count
+    }
+}
+
+/**
+ * Solves any grid as an exact cover problem with Knuth's Algorithm X and dancing links, see
+ * `Links`, which is much faster than `Backtracking` to count the solutions of big grids. Like
+ * it, grids with several solutions are not solved. The sums of killer cages not being an exact
+ * cover constraint, killer puzzles are left to `Backtracking`.
+ */
+pub struct Dlx;
+
+impl Solver for Dlx {
+    fn solve(&self, game: &mut Game) -> Result<(), errors::SolverError> {
+        solve_within(game, usize::MAX)
+    }
+
+    fn count_solutions(&self, game: &mut Game, limit: usize) -> usize {
+        if !game.cages.is_empty() {
+            return count_solutions(game, limit);
         }
 
-        for i in 0..(game.side_size * game.side_size) {
-            if game.grid[i] == 0 && game.valids(i).len() == 1 {
-                game.grid[i] = Cell {
-                    value: game.valids(i)[0],
-                    initial: false,
-                };
-                return self.solve(game);
+        match Links::new(game) {
+            Some(mut links) => {
+                let mut nodes = usize::MAX;
+                links.search(limit, &mut Vec::new(), &mut None, &mut nodes)
             }
+            None => 0,
         }
+    }
+}
 
-        Err(errors::SolverError::FailedToSolve)
+/**
+ * Solves `game` like `Dlx`, giving up once `nodes` choices were tried, so that the grids sent by
+ * anyone can't keep the solver busy for long.
+ */
+pub fn solve_within(game: &mut Game, mut nodes: usize) -> Result<(), errors::SolverError> {
+    if !game.cages.is_empty() {
+        return backtrack(game, nodes);
     }
+
+    let mut solution = None;
+    let count = match Links::new(game) {
+        Some(mut links) => links.search(2, &mut Vec::new(), &mut solution, &mut nodes),
+        None => 0,
+    };
+    if nodes == 0 {
+        return Err(errors::SolverError::OutOfNodes);
+    }
+    if count != 1 {
+        return Err(errors::SolverError::FailedToSolve);
+    }
+
+    for (index, value) in solution.unwrap() {
+        game.grid[index] = Cell::new(value, false);
+    }
+
+    Ok(())
 }
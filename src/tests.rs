@@ -1,6 +1,58 @@
 #[cfg(test)]
 mod tests {
+    use crate::annotations;
+    use crate::args::{self, BatchFormat, Command, Mode};
+    use crate::autosave::Autosave;
+    use crate::config;
+    use crate::dialog::{Dialog, DialogOutcome};
+    use crate::editor::Editor;
+    use crate::errors::{self, GameError, UiError};
+    use crate::events::GameEvent;
+    use crate::export::{self, ExportOptions};
+    use crate::favorites::Favorites;
+    #[cfg(feature = "fetch")]
+    use crate::fetch;
+    #[cfg(feature = "ffi")]
+    use crate::ffi;
     use crate::game;
+    use crate::game::{CellDiff, NotesClear, Validation, Variant};
+    use crate::game_screen::{letter_value_from_keycode, GameScreen};
+    use crate::generator;
+    use crate::generator::GenerationConfig;
+    use crate::grader;
+    use crate::hint;
+    use crate::hotseat::HotSeat;
+    use crate::killer::Cage;
+    use crate::layout::{fit_centered, scaled_font_size, Layout};
+    use crate::logging;
+    #[cfg(feature = "net")]
+    use crate::net::{Link, Message, NetEvent, NetGame, NetMode};
+    use crate::packs;
+    use crate::print;
+    use crate::profile::{Profile, Profiles};
+    use crate::race::Race;
+    use crate::replay::Replay;
+    use crate::samurai::{SamuraiGame, SIDE};
+    use crate::saves;
+    use crate::server::{self, Server};
+    use crate::session::{Session, Timer};
+    use crate::settings_screen::Settings;
+    use crate::solver;
+    use crate::solver::{Solver, SolverKind};
+    use crate::theme::Theme;
+    use crate::time_attack::{Split, TimeAttack};
+    use crate::traits::{
+        CliConfig, Displayable, GUIConfig, GridPlacement, InputMode, LayoutConfig, MouseConfig,
+        RightClick, ScreenOutcome, ThemeConfig, ThemePreset, Wheel,
+    };
+    #[cfg(unix)]
+    use crate::tui::{parse_keys, Key};
+    #[cfg(feature = "wasm")]
+    use crate::wasm;
+    use rand::SeedableRng;
+    use sdl2::keyboard::Keycode;
+    use sdl2::pixels::Color;
+    use sdl2::rect::Rect;
 
     #[test]
     fn test_test() {
@@ -14,7 +66,7 @@ mod tests {
         valids.sort();
         assert_eq!(valids, (1..=9).collect::<Vec<u8>>());
 
-        game.do_move(0, 0, 9).unwrap();
+        game.do_move(0, 0, 9, Validation::Strict).unwrap();
         let mut valids = game.valids(0);
         valids.sort();
         assert_eq!(valids, (1..=8).collect::<Vec<u8>>());
@@ -24,13 +76,7 @@ mod tests {
     fn test_nb_empty() {
         let mut game = game::Game::new(3, None).unwrap();
         assert_eq!(game.nb_empty(), 81);
-        game.grid = vec![
-            game::Cell {
-                value: 1,
-                initial: false
-            };
-            81
-        ];
+        game.grid = vec![game::Cell::new(1, false); 81];
         assert_eq!(game.nb_empty(), 0);
     }
 
@@ -38,13 +84,7 @@ mod tests {
     fn test_nb_non_empty() {
         let mut game = game::Game::new(3, None).unwrap();
         assert_eq!(game.nb_non_empty(), 0);
-        game.grid = vec![
-            game::Cell {
-                value: 1,
-                initial: false
-            };
-            81
-        ];
+        game.grid = vec![game::Cell::new(1, false); 81];
         assert_eq!(game.nb_non_empty(), 81);
     }
 
@@ -54,4 +94,3110 @@ mod tests {
         assert_eq!(game.coordinates(0), (0, 0));
         assert_eq!(game.coordinates(9), (1, 0));
     }
+
+    #[test]
+    fn test_cycle_value() {
+        let mut game = game::Game::new(3, None).unwrap();
+        game.do_move(0, 1, 1, Validation::Strict).unwrap();
+
+        // 1 is taken by a neighbor, so cycling starts at 2 and wraps through the empty value
+        assert_eq!(game.cycle_value(0, 0, true).unwrap(), 2);
+        assert_eq!(game.cycle_value(0, 0, false).unwrap(), 0);
+        assert_eq!(game.cycle_value(0, 0, false).unwrap(), 9);
+        assert_eq!(game.grid[0].value(), 9);
+
+        // Cycling an empty cell with no candidate is not a move, the redo stack being kept
+        let mut game = game::Game::new(2, None).unwrap();
+        for (r, c, value) in [(0, 2, 1), (0, 3, 2), (2, 0, 3), (1, 1, 4), (3, 3, 1)] {
+            game.do_move(r, c, value, Validation::Strict).unwrap();
+        }
+        game.undo().unwrap();
+        assert_eq!(game.cycle_value(0, 0, true).unwrap(), 0);
+        assert_eq!((game.history.len(), game.undone.len()), (4, 1));
+    }
+
+    #[test]
+    fn test_layout() {
+        let mut config = LayoutConfig {
+            margin: 40,
+            placement: GridPlacement::Centered,
+            ..Default::default()
+        };
+        let layout = Layout::new(&config, Rect::new(0, 0, 800, 600), 3);
+        assert_eq!(layout.box_size, 57);
+        assert_eq!(layout.grid_x, (800 - 57 * 9) / 2);
+        assert_eq!(layout.cell_at(layout.grid_x, layout.grid_y), Some((0, 0)));
+        assert_eq!(layout.cell_at(layout.grid_x - 1, layout.grid_y), None);
+
+        config.placement = GridPlacement::Anchored;
+        let layout = Layout::new(&config, Rect::new(0, 0, 800, 600), 3);
+        assert_eq!((layout.grid_x, layout.grid_y), (40, 40));
+        assert_eq!(layout.cell_at(40 + 57 * 9 - 1, 40 + 57), Some((1, 8)));
+
+        // A bigger canvas, after a resize or on a high DPI display, gets bigger cells and fonts
+        let layout = Layout::new(&config, Rect::new(0, 0, 1600, 1200), 3);
+        assert_eq!(layout.box_size, 124);
+        assert_eq!(scaled_font_size(30, 600, 600), 30);
+        assert_eq!(scaled_font_size(30, 1200, 600), 60);
+        assert_eq!(scaled_font_size(12, 300, 600), 6);
+        assert_eq!(scaled_font_size(12, 100, 600), 6);
+
+        // The mouse input mode of the configuration
+        let mode: InputMode = serde_json::from_str("\"cell-first\"").unwrap();
+        assert!(mode == InputMode::CellFirst);
+        assert!(InputMode::default() == InputMode::DigitFirst);
+    }
+
+    #[test]
+    fn test_events() {
+        let mut game = game::Game::new(3, None).unwrap();
+        game.do_move(0, 0, 9, Validation::Strict).unwrap();
+        assert!(game.do_move(0, 1, 9, Validation::Strict).is_err());
+
+        let events: Vec<GameEvent> = game.events.iter().map(|e| e.event.clone()).collect();
+        assert_eq!(
+            events,
+            vec![
+                GameEvent::Move {
+                    row: 0,
+                    column: 0,
+                    value: 9
+                },
+                GameEvent::Error(GameError::InvalidValue.to_string()),
+            ]
+        );
+
+        // The hints are counted and logged with their explanation
+        game.count_hint(String::from("Naked single."));
+        assert_eq!(game.hints, 1);
+        assert_eq!(
+            game.events.last().unwrap().event.to_string(),
+            "Hint: Naked single."
+        );
+    }
+
+    #[test]
+    fn test_hot_seat() {
+        let mut game = game::Game::new(3, None).unwrap();
+        game.hot_seat = Some(HotSeat::default());
+
+        game.do_move(0, 0, 9, Validation::Strict).unwrap();
+        assert!(game.do_move(0, 1, 9, Validation::Strict).is_err());
+        game.do_move(0, 1, 8, Validation::Strict).unwrap();
+
+        let hot_seat = game.hot_seat.as_ref().unwrap();
+        assert_eq!(hot_seat.moves, [2, 0]);
+        assert_eq!(hot_seat.mistakes, [0, 1]);
+        assert_eq!(hot_seat.current, 1);
+        assert_eq!(game.events[1].player, Some(1));
+
+        // A typo keeps the turn and is not a mistake
+        assert!(game.do_move(0, 2, 10, Validation::Strict).is_err());
+        assert_eq!(game.hot_seat.as_ref().unwrap().current, 1);
+        assert_eq!(game.hot_seat.as_ref().unwrap().mistakes, [0, 1]);
+        assert_eq!(game.mistakes, 1);
+
+        // The turns and the scores are kept in the save
+        let path =
+            std::env::temp_dir().join(format!("sudoku_hot_seat_{}.game", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut saved = game::Game::from_puzzle("1.3.4...2..1.3.4", Some(path)).unwrap();
+        saved.hot_seat = game.hot_seat.clone();
+        saved.save().unwrap();
+        drop(saved);
+        let resumed = game::Game::from_file(path).unwrap();
+        let hot_seat = resumed.hot_seat.as_ref().unwrap();
+        assert_eq!(hot_seat.moves, [2, 0]);
+        assert_eq!(hot_seat.mistakes, [0, 1]);
+        assert_eq!(hot_seat.current, 1);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_favorites() {
+        let mut game = game::Game::new(2, None).unwrap();
+        game.grid[0] = game::Cell::new(3, true);
+        game.variant = Variant::Diagonal;
+        let code = game.to_share_code();
+
+        let mut favorites = Favorites::default();
+        assert!(favorites.toggle(&game));
+        assert!(favorites.contains(&code));
+        assert_eq!(favorites.puzzles[0].id, "3...............");
+
+        // A favorite is played again with its variant
+        let replayed = favorites.puzzles[0].to_game(None).unwrap();
+        assert_eq!(replayed.puzzle_id(), game.puzzle_id());
+        assert!(replayed.variant == Variant::Diagonal);
+
+        // The same clues in another variant are another puzzle
+        game.variant = Variant::Classic;
+        assert!(!favorites.contains(&game.to_share_code()));
+        game.variant = Variant::Diagonal;
+        assert!(!favorites.toggle(&game));
+        assert!(!favorites.contains(&code));
+
+        // The favorites belong to the profile playing, and are saved with the profiles
+        let folder = std::env::temp_dir().join("rs_sudoku_test_favorites");
+        std::fs::create_dir_all(&folder).unwrap();
+        let folder = folder.to_str().unwrap();
+        let _ = std::fs::remove_file(std::path::Path::new(folder).join("profiles.json"));
+
+        let mut profiles = Profiles::load(folder).unwrap();
+        assert!(profiles.toggle_favorite(&game).unwrap());
+        profiles.add().unwrap();
+        assert!(!profiles.current().favorites.contains(&code));
+        let profiles = Profiles::load(folder).unwrap();
+        assert!(profiles.profiles[0].favorites.contains(&code));
+        assert!(profiles.profiles[1].favorites.puzzles.is_empty());
+    }
+
+    #[test]
+    fn test_profiles() {
+        let folder = std::env::temp_dir().join("rs_sudoku_test_profiles");
+        std::fs::create_dir_all(&folder).unwrap();
+        let folder = folder.to_str().unwrap();
+        let _ = std::fs::remove_file(std::path::Path::new(folder).join("profiles.json"));
+
+        // Without a profiles file, a single default profile plays
+        let mut profiles = Profiles::load(folder).unwrap();
+        assert_eq!(profiles.current().name, "Player");
+        assert_eq!(profiles.add().unwrap().name, "Player 2");
+        assert_eq!(profiles.switch().unwrap().name, "Player");
+        profiles
+            .record(
+                generator::Difficulty::Hard,
+                std::time::Duration::from_secs(300),
+            )
+            .unwrap();
+        let profiles = Profiles::load(folder).unwrap();
+        assert_eq!(profiles.profiles.len(), 2);
+        assert_eq!(profiles.current().solved(), 1);
+        assert_eq!(profiles.profiles[1].solved(), 0);
+
+        // The times are kept by difficulty, the streak counting the days in a row
+        let day = |d| chrono::NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let mut profile = Profile::new("Ann");
+        profile.record(
+            generator::Difficulty::Easy,
+            std::time::Duration::from_secs(200),
+            day(1),
+        );
+        profile.record(
+            generator::Difficulty::Easy,
+            std::time::Duration::from_secs(100),
+            day(2),
+        );
+        profile.record(
+            generator::Difficulty::Easy,
+            std::time::Duration::from_secs(90),
+            day(2),
+        );
+        assert_eq!(profile.streak, 2);
+        profile.record(
+            generator::Difficulty::Medium,
+            std::time::Duration::from_secs(400),
+            day(5),
+        );
+        assert_eq!((profile.streak, profile.best_streak), (1, 2));
+        assert_eq!(
+            profile.summary(generator::Difficulty::Easy),
+            vec![
+                "Ann: 4 solved",
+                "Easy: 3 solved, best 01:30, average 02:10",
+                "Streak: 1 day (best 2)",
+            ]
+        );
+        assert_eq!(
+            profile.summary(generator::Difficulty::Hard)[1],
+            "Hard: none solved"
+        );
+    }
+
+    #[test]
+    fn test_fill_notes() {
+        let mut game = game::Game::new(3, None).unwrap();
+        game.do_move(0, 0, 9, Validation::Strict).unwrap();
+        game.fill_notes().unwrap();
+        assert_eq!(game.grid[0].notes(), 0);
+        assert!(!game.grid[1].has_note(9));
+        assert!(game.grid[1].has_note(1));
+        assert!(game.grid[80].has_note(9));
+
+        // Notes are only maintained with auto notes
+        game.do_move(8, 8, 1, Validation::Strict).unwrap();
+        assert!(game.grid[80 - 1].has_note(1));
+        game.auto_notes = true;
+        game.do_move(8, 7, 2, Validation::Strict).unwrap();
+        assert!(!game.grid[80 - 2].has_note(1));
+        assert!(!game.grid[80 - 2].has_note(2));
+    }
+
+    #[test]
+    fn test_auto_notes() {
+        let path = "/tmp/sudoku_test_auto_notes.game";
+        let mut game = game::Game::from_puzzle("1.3.4...2..1.3.4", Some(path)).unwrap();
+
+        // Turning them on fills the notes, which then follow the moves
+        game.set_auto_notes(true).unwrap();
+        assert_eq!(game.grid[1].notes(), 1 << 2);
+        game.do_move(0, 1, 2, Validation::Strict).unwrap();
+        assert_eq!(game.grid[1].notes(), 0);
+        assert!(!game.grid[game.index(1, 1)].has_note(2));
+
+        // The setting is kept in the save
+        drop(game);
+        let mut game = game::Game::from_file(path).unwrap();
+        assert!(game.auto_notes);
+        game.set_auto_notes(false).unwrap();
+        drop(game);
+        let game = game::Game::from_file(path).unwrap();
+        assert!(!game.auto_notes);
+        drop(game);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_remove_notes() {
+        let mut game = game::Game::new(3, None).unwrap();
+        game.fill_notes().unwrap();
+        game.remove_notes = true;
+
+        // Placing a value removes its notes from its row, column and box only
+        game.do_move(4, 4, 5, Validation::Strict).unwrap();
+        assert!(!game.grid[game.index(4, 0)].has_note(5));
+        assert!(!game.grid[game.index(8, 4)].has_note(5));
+        assert!(!game.grid[game.index(3, 3)].has_note(5));
+        assert!(game.grid[game.index(3, 3)].has_note(4));
+        assert!(game.grid[game.index(0, 0)].has_note(5));
+
+        // Unless it is turned off
+        game.remove_notes = false;
+        game.do_move(0, 0, 1, Validation::Strict).unwrap();
+        assert!(game.grid[game.index(0, 8)].has_note(1));
+    }
+
+    #[test]
+    fn test_toggle_notes() {
+        let mut game = game::Game::new(3, None).unwrap();
+        game.do_move(0, 0, 1, Validation::Strict).unwrap();
+        game.toggle_note(0, 2, 4).unwrap();
+
+        // The note is added to the empty cells unless they all have it, then it is removed
+        let cells = [0, 1, 2];
+        assert!(game.toggle_notes(&cells, 4).unwrap());
+        assert!(game.grid[1].has_note(4) && game.grid[2].has_note(4));
+        assert!(!game.grid[0].has_note(4));
+        assert!(!game.toggle_notes(&cells, 4).unwrap());
+        assert!(!game.grid[1].has_note(4) && !game.grid[2].has_note(4));
+
+        assert!(matches!(
+            game.toggle_notes(&[0], 4),
+            Err(GameError::NonEmptyCell)
+        ));
+        assert!(matches!(
+            game.toggle_notes(&cells, 10),
+            Err(GameError::IllegalValue)
+        ));
+        assert!(matches!(
+            game.toggle_notes(&[81], 4),
+            Err(GameError::IllegalPosition)
+        ));
+    }
+
+    #[test]
+    fn test_marker_colors() {
+        let path = "/tmp/sudoku_test_marker_colors.game";
+        let mut game = game::Game::from_puzzle("1.3.4...2..1.3.4", Some(path)).unwrap();
+
+        // The colors cycle back to none, and go along with the notes and values
+        game.paint(&[1, 5], 2).unwrap();
+        game.toggle_note(0, 1, 2).unwrap();
+        assert_eq!(game.grid[1].color(), 2);
+        assert!(game.grid[1].has_note(2));
+        assert_eq!(game.cycle_color(&[0]).unwrap(), 1);
+        game.paint(&[0], game::MARKER_COLORS.len() as u8).unwrap();
+        assert_eq!(game.cycle_color(&[0]).unwrap(), 0);
+        assert!(game
+            .paint(&[0], game::MARKER_COLORS.len() as u8 + 1)
+            .is_err());
+        assert!(game.paint(&[16], 1).is_err());
+
+        // They are not moves, undoing a move keeps them
+        game.do_move(0, 1, 2, Validation::Strict).unwrap();
+        assert_eq!(game.grid[1].color(), 2);
+        game.undo().unwrap();
+        assert_eq!(game.grid[1].color(), 2);
+        assert_eq!(game.grid[1].value(), 0);
+
+        // They are kept in the save
+        drop(game);
+        let mut game = game::Game::from_file(path).unwrap();
+        assert_eq!(game.grid[5].color(), 2);
+        game.paint(&[1, 5], 0).unwrap();
+        drop(game);
+        let game = game::Game::from_file(path).unwrap();
+        assert!(game.grid.iter().all(|cell| cell.color() == 0));
+        drop(game);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_replay() {
+        let path = "/tmp/sudoku_test_replay.game";
+        let mut game = game::Game::from_puzzle("1.3.4...2..1.3.4", Some(path)).unwrap();
+        assert!(matches!(
+            Replay::new(&game),
+            Err(GameError::NothingToReplay)
+        ));
+
+        // The undone moves are part of the timeline, which is kept in the save
+        game.do_move(0, 1, 3, Validation::Free).unwrap();
+        game.undo().unwrap();
+        game.do_move(0, 1, 2, Validation::Strict).unwrap();
+        assert_eq!(game.timeline.len(), 3);
+        assert!(game.timeline.windows(2).all(|m| m[0].at <= m[1].at));
+        drop(game);
+        let game = game::Game::from_file(path).unwrap();
+        assert_eq!(game.timeline.len(), 3);
+        std::fs::remove_file(path).unwrap();
+
+        // The replay starts from the clues and ends on the grid of the game
+        let mut replay = Replay::new(&game).unwrap();
+        assert_eq!(replay.len(), 3);
+        assert_eq!(replay.grid[1].value(), 0);
+        assert_eq!(replay.step().unwrap().after.value(), 3);
+        assert!(replay
+            .describe()
+            .starts_with("Move 1/3 at 00:00: 3 in row 1, column 2"));
+        assert_eq!(replay.step().unwrap().after.value(), 0);
+        replay.step().unwrap();
+        assert!(replay.is_over() && replay.step().is_none());
+        assert!(replay.grid.iter().zip(&game.grid).all(|(a, b)| a == b));
+        replay.step_back().unwrap();
+        assert_eq!(replay.position(), 2);
+        assert_eq!(replay.grid[1].value(), 0);
+    }
+
+    #[test]
+    fn test_time_attack() {
+        let config = GenerationConfig::default();
+        let mut attack = TimeAttack::new(2, generator::Difficulty::Easy, &config);
+        let again = TimeAttack::new(2, generator::Difficulty::Easy, &config);
+        assert_eq!(attack.len(), 5);
+        assert_eq!(attack.current(), again.current());
+
+        // Only the current puzzle counts, with its penalties
+        let other = game::Game::from_puzzle("1.3.4...2..1.3.4", None).unwrap();
+        assert!(!attack.finish_puzzle(&other));
+        let mut game = game::Game::from_puzzle(attack.current().unwrap(), None).unwrap();
+        SolverKind::Dlx.solve(&mut game).unwrap();
+        game.mistakes = 2;
+        game.hints = 1;
+        assert!(attack.finish_puzzle(&game));
+        assert_eq!(attack.splits[0].penalty_secs(), 50);
+        assert_ne!(attack.current(), again.current());
+        assert!(!attack.is_over());
+        assert_eq!(attack.summary().last().unwrap(), "Score: 00:50");
+
+        // The leaderboard keeps the best runs, the earlier one first on a tie
+        let day = chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let mut profile = Profile::new("Ann");
+        for secs in (0..12).rev() {
+            attack.splits = vec![Split {
+                secs: 100 + secs * 10,
+                ..Default::default()
+            }];
+            let rank = profile.record_time_attack(attack.difficulty, attack.run(day));
+            assert_eq!(rank, Some(0));
+        }
+        attack.splits[0].secs = 120;
+        assert_eq!(
+            profile.record_time_attack(attack.difficulty, attack.run(day)),
+            Some(3)
+        );
+        attack.splits[0].secs = 500;
+        assert_eq!(
+            profile.record_time_attack(attack.difficulty, attack.run(day)),
+            None
+        );
+        let board = &profile.time_attacks[&generator::Difficulty::Easy];
+        assert_eq!(board.len(), 10);
+        assert_eq!(board[0].score, 100);
+        assert_eq!(
+            profile.leaderboard(generator::Difficulty::Easy, 2),
+            ["1. 01:40 on 2024-03-01", "2. 01:50 on 2024-03-01"]
+        );
+    }
+
+    #[test]
+    fn test_race() {
+        let config = GenerationConfig::default();
+        let puzzle = generator::generate_seeded(2, generator::Difficulty::Easy, &config, 1);
+        let solution = puzzle.solution().unwrap();
+        let clues = puzzle.nb_clues();
+        let mut race = Race::new(&puzzle).unwrap();
+
+        // Only the values of the solution are accepted, in the grid of the player only
+        let index = (0..16).find(|i| puzzle.grid[*i].value() == 0).unwrap();
+        let wrong = solution[index] % 4 + 1;
+        assert!(matches!(
+            race.play(0, index / 4, index % 4, wrong),
+            Err(GameError::InvalidValue)
+        ));
+        assert_eq!(race.games[0].mistakes, 1);
+        race.play(0, index / 4, index % 4, solution[index]).unwrap();
+        assert_eq!(race.progress(0), (clues + 1, 16));
+        assert_eq!(race.progress(1), (clues, 16));
+        assert!(!race.is_over());
+
+        // The first player to complete their grid wins
+        for (index, value) in solution.iter().enumerate() {
+            if race.games[1].grid[index].value() == 0 {
+                race.play(1, index / 4, index % 4, *value).unwrap();
+            }
+        }
+        assert_eq!(race.winner(), Some(1));
+        let summary = race.summary();
+        assert!(summary[0].starts_with("Player 2 wins in 00:0"));
+        assert_eq!(
+            summary[1],
+            format!("Player 1: {}/16 cells, 1 mistakes", clues + 1)
+        );
+    }
+
+    #[cfg(feature = "net")]
+    #[test]
+    fn test_network_game() {
+        // Waits for something to happen on the connection
+        let poll = |network: &mut NetGame| -> Vec<NetEvent> {
+            for _ in 0..200 {
+                let events = network.link.poll();
+                if !events.is_empty() {
+                    return events;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+            vec![]
+        };
+
+        // The guest joins the host on the loopback, at a port picked by the system
+        let host = Link::host("127.0.0.1:0").unwrap();
+        let guest = Link::join(&host.local_address().unwrap()).unwrap();
+        let mut host = NetGame::new(host, NetMode::Coop);
+        let mut guest = NetGame::new(guest, NetMode::Versus);
+        assert!(host.link.is_host() && !guest.link.is_host());
+        assert_eq!(poll(&mut host), vec![NetEvent::Connected]);
+
+        // The guest plays the puzzle of the host, with its grid and in its mode
+        let config = GenerationConfig::default();
+        let puzzle = generator::generate_seeded(2, generator::Difficulty::Easy, &config, 1);
+        let solution = puzzle.solution().unwrap();
+        let empty: Vec<usize> = (0..16).filter(|i| puzzle.grid[*i].value() == 0).collect();
+        let mut host_game = game::Game::from_puzzle(&puzzle.puzzle_id(), None).unwrap();
+        let (first, second) = (empty[0], empty[1]);
+        host_game
+            .do_move(first / 4, first % 4, solution[first], Validation::Lenient)
+            .unwrap();
+        host.begin(&host_game);
+        assert!(host.link.send(&host.start_message(&host_game)));
+        let events = poll(&mut guest);
+        let [NetEvent::Received(start @ Message::Start { .. })] = events.as_slice() else {
+            panic!("Expected the puzzle, got {:?}", events);
+        };
+        let mut guest_game = game::Game::from_puzzle(&puzzle.puzzle_id(), None).unwrap();
+        guest.apply(&mut guest_game, start).unwrap();
+        assert_eq!(guest.mode, NetMode::Coop);
+        assert!(guest.is_playing(&guest_game));
+        assert_eq!(guest_game.grid[first].value(), solution[first]);
+
+        // A move of the guest shows on the grid of the host, without being sent back
+        guest_game
+            .do_move(
+                second / 4,
+                second % 4,
+                solution[second],
+                Validation::Lenient,
+            )
+            .unwrap();
+        guest.share(&guest_game);
+        let events = poll(&mut host);
+        let [NetEvent::Received(cell @ Message::Cell { .. })] = events.as_slice() else {
+            panic!("Expected a cell, got {:?}", events);
+        };
+        host.apply(&mut host_game, cell).unwrap();
+        assert_eq!(host_game.grid[second].value(), solution[second]);
+        assert!(matches!(
+            host_game.events.last().unwrap().event,
+            GameEvent::Shared { .. }
+        ));
+        host.share(&host_game);
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        assert!(guest.link.poll().is_empty());
+
+        // The clues can't be changed by the other player
+        let clue = (0..16).find(|i| puzzle.grid[*i].initial()).unwrap();
+        assert!(matches!(
+            host_game.apply_shared_cell(clue, game::Cell::from_bits(0)),
+            Err(GameError::NonEmptyCell)
+        ));
+
+        // Nor can a cell that does not fit the grid be shared
+        let third = empty[2];
+        for bits in [5 << 32, 1, 1 << 5, 1 << 32 | 1 << 40] {
+            let cell = Message::Cell {
+                index: third,
+                cell: bits,
+            };
+            assert!(matches!(
+                host.apply(&mut host_game, &cell),
+                Err(GameError::IllegalValue)
+            ));
+        }
+        assert_eq!(host_game.grid[third].bits(), puzzle.grid[third].bits());
+
+        // The host waits for the guest to come back once it leaves
+        drop(guest);
+        assert_eq!(poll(&mut host), vec![NetEvent::Disconnected]);
+        assert!(!host.link.is_connected());
+
+        // A player sending a line that never ends is dropped
+        let address = host.link.local_address().unwrap();
+        let mut flood = std::net::TcpStream::connect(address).unwrap();
+        std::io::Write::write_all(&mut flood, &[b' '; 100 * 1024]).unwrap();
+        let mut events = vec![];
+        while !events.contains(&NetEvent::Disconnected) {
+            let polled = poll(&mut host);
+            assert!(!polled.is_empty(), "Expected the connection to be dropped");
+            events.extend(polled);
+        }
+        assert_eq!(events, vec![NetEvent::Connected, NetEvent::Disconnected]);
+        assert!(!host.link.is_connected());
+    }
+
+    #[test]
+    fn test_server() {
+        use std::io::{Read, Write};
+
+        let mut server = Server::new(GenerationConfig::default());
+
+        // A game is started from a puzzle, the moves being checked against the rules
+        let puzzle = "1.3.4...2..1.3.4";
+        let started = server.handle("POST", "/new", &format!("{{\"puzzle\": \"{}\"}}", puzzle));
+        assert_eq!(started.status, 200);
+        assert_eq!(started.body["id"], 1);
+        assert_eq!(started.body["side"], 4);
+        assert_eq!(started.body["grid"][0], 1);
+        assert_eq!(started.body["grid"][1], 0);
+
+        let played = server.handle(
+            "POST",
+            "/move",
+            r#"{"id": 1, "row": 0, "column": 1, "value": 2}"#,
+        );
+        assert_eq!(played.status, 200);
+        assert_eq!(played.body["grid"][1], 2);
+        assert_eq!(played.body["done"], false);
+        let refused = server.handle(
+            "POST",
+            "/move",
+            r#"{"id": 1, "row": 0, "column": 3, "value": 1}"#,
+        );
+        assert_eq!(refused.status, 422);
+        assert_eq!(refused.body["error"], "Invalid value for this cell.");
+        let cleared = server.handle(
+            "POST",
+            "/move",
+            r#"{"id": 1, "row": 0, "column": 1, "value": 0}"#,
+        );
+        assert_eq!(cleared.body["grid"][1], 0);
+
+        // The hints are counted on the served games, the solutions found for any puzzle
+        let hint = server.handle("POST", "/hint", r#"{"id": 1}"#);
+        assert_eq!(hint.status, 200);
+        assert!(hint.body["description"].as_str().unwrap().contains("row"));
+        let played = server.handle(
+            "POST",
+            "/move",
+            r#"{"id": 1, "row": 0, "column": 1, "value": 2}"#,
+        );
+        assert_eq!(played.body["hints"], 1);
+        let solved = server.handle("POST", "/solve", r#"{"puzzle": "4..2.2.3.1.4..31"}"#);
+        assert_eq!(
+            solved.body["solution"],
+            serde_json::json!([4, 3, 1, 2, 1, 2, 4, 3, 3, 1, 2, 4, 2, 4, 3, 1])
+        );
+
+        // Generated games get the next id
+        let generated = server.handle("POST", "/new", r#"{"size": 2, "difficulty": "easy"}"#);
+        assert_eq!(generated.body["id"], 2);
+
+        assert_eq!(
+            server
+                .handle(
+                    "POST",
+                    "/move",
+                    r#"{"id": 7, "row": 0, "column": 0, "value": 1}"#
+                )
+                .status,
+            404
+        );
+        assert_eq!(server.handle("POST", "/new", "{not json").status, 400);
+        assert_eq!(server.handle("POST", "/new", r#"{"size": 9}"#).status, 400);
+        assert_eq!(server.handle("POST", "/new", r#"{"size": 5}"#).status, 400);
+        let big = format!(r#"{{"puzzle": "{}"}}"#, ".".repeat(625));
+        assert_eq!(server.handle("POST", "/solve", &big).status, 400);
+        let several = format!(
+            r#"{{"puzzle": "{}", "validation": "solution"}}"#,
+            ".".repeat(16)
+        );
+        assert_eq!(server.handle("POST", "/new", &several).status, 422);
+        assert_eq!(server.handle("POST", "/solve", "{}").status, 400);
+        assert_eq!(server.handle("GET", "/hint", "").status, 405);
+        assert_eq!(server.handle("POST", "/undo", "{}").status, 404);
+
+        // The requests are answered over HTTP
+        let listener = server::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || server.serve(listener));
+        let mut stream = std::net::TcpStream::connect(address).unwrap();
+        let body = r#"{"puzzle": "4..2.2.3.1.4..31"}"#;
+        write!(
+            stream,
+            "POST /solve HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut answer = String::new();
+        stream.read_to_string(&mut answer).unwrap();
+        assert!(answer.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(answer.ends_with(r#"{"solution":[4,3,1,2,1,2,4,3,3,1,2,4,2,4,3,1]}"#));
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_ffi() {
+        use std::ffi::{CStr, CString};
+
+        unsafe {
+            let puzzle = CString::new("4..2.2.3.1.4..31").unwrap();
+            let game = ffi::sudoku_new(puzzle.as_ptr());
+            assert!(!game.is_null());
+            assert_eq!(ffi::sudoku_side(game), 4);
+            assert_eq!(ffi::sudoku_value(game, 0, 0), 4);
+            assert_eq!(ffi::sudoku_value(game, 0, 1), 0);
+            assert_eq!(ffi::sudoku_value(game, 4, 0), ffi::SUDOKU_ILLEGAL_POSITION);
+
+            // The moves are checked against the rules, 0 clearing a cell
+            assert_eq!(
+                ffi::sudoku_do_move(game, 0, 0, 2),
+                ffi::SUDOKU_NON_EMPTY_CELL
+            );
+            assert_eq!(
+                ffi::sudoku_do_move(game, 0, 1, 4),
+                ffi::SUDOKU_INVALID_VALUE
+            );
+            assert_eq!(
+                ffi::sudoku_do_move(game, 0, 1, 5),
+                ffi::SUDOKU_ILLEGAL_VALUE
+            );
+            assert_eq!(ffi::sudoku_do_move(game, 0, 1, 3), ffi::SUDOKU_OK);
+            assert_eq!(ffi::sudoku_value(game, 0, 1), 3);
+            assert_eq!(ffi::sudoku_do_move(game, 0, 1, 0), ffi::SUDOKU_OK);
+            assert_eq!(ffi::sudoku_value(game, 0, 1), 0);
+
+            assert!(!ffi::sudoku_is_done(game));
+            assert_eq!(ffi::sudoku_solve(game), ffi::SUDOKU_OK);
+            assert!(ffi::sudoku_is_done(game));
+            let id = ffi::sudoku_puzzle(game);
+            assert_eq!(CStr::from_ptr(id).to_str().unwrap(), "4..2.2.3.1.4..31");
+            ffi::sudoku_string_free(id);
+            ffi::sudoku_free(game);
+
+            let invalid = CString::new("1.3.").unwrap();
+            assert!(ffi::sudoku_new(invalid.as_ptr()).is_null());
+            assert!(ffi::sudoku_new(std::ptr::null()).is_null());
+            assert_eq!(
+                ffi::sudoku_solve(std::ptr::null_mut()),
+                ffi::SUDOKU_NULL_GAME
+            );
+
+            // A seeded puzzle is always the same
+            let generated = ffi::sudoku_generate(2, 0, 7);
+            let again = ffi::sudoku_generate(2, 0, 7);
+            let (id, id_again) = (ffi::sudoku_puzzle(generated), ffi::sudoku_puzzle(again));
+            assert_eq!(CStr::from_ptr(id), CStr::from_ptr(id_again));
+            ffi::sudoku_string_free(id);
+            ffi::sudoku_string_free(id_again);
+            ffi::sudoku_free(generated);
+            ffi::sudoku_free(again);
+            assert!(ffi::sudoku_generate(6, 0, 7).is_null());
+            assert!(ffi::sudoku_generate(3, 4, 7).is_null());
+        }
+    }
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn test_wasm() {
+        // A seeded puzzle is always the same, and given with its solution
+        let generated = wasm::generate(2, "easy", Some(7)).unwrap();
+        assert_eq!(generated, wasm::generate(2, "easy", Some(7)).unwrap());
+        let record: generator::PuzzleRecord = serde_json::from_str(&generated).unwrap();
+        assert_eq!(record.size, 2);
+        assert_eq!(wasm::solve(&record.puzzle).unwrap(), record.solution);
+        assert!(wasm::generate(6, "easy", None).is_err());
+        assert!(wasm::generate(3, "impossible", None).is_err());
+
+        let check: serde_json::Value =
+            serde_json::from_str(&wasm::validate(&record.puzzle)).unwrap();
+        assert_eq!(check["valid"], true);
+        assert_eq!(check["solutions"], 1);
+        let check: serde_json::Value = serde_json::from_str(&wasm::validate("11..")).unwrap();
+        assert_eq!(check["valid"], false);
+
+        // The clues of the first row conflict
+        assert!(wasm::solve("11..............").is_err());
+        assert!(wasm::solve("not a puzzle").is_err());
+    }
+    #[test]
+    fn test_editor() {
+        let mut editor = Editor::new(2, Variant::Classic).unwrap();
+        assert_eq!(editor.check.solutions, 2);
+        assert!(!editor.is_playable());
+
+        // Two clues of the first row conflict, then the puzzle "4..2.2.3.1.4..31" is built
+        editor.set_clue(0, 0, 4).unwrap();
+        editor.set_clue(0, 1, 4).unwrap();
+        assert_eq!(editor.check.conflicts, vec![(1, 1), (1, 2)]);
+        assert_eq!(editor.check.solutions, 0);
+        editor.set_clue(0, 1, 0).unwrap();
+        for (i, symbol) in "4..2.2.3.1.4..31".chars().enumerate() {
+            if let Some(value) = symbol.to_digit(10) {
+                editor.set_clue(i / 4, i % 4, value as u8).unwrap();
+            }
+        }
+        assert!(editor.check.conflicts.is_empty());
+        assert!(editor.is_playable());
+        assert_eq!(editor.check.puzzle, "4..2.2.3.1.4..31");
+        assert!(editor.set_clue(4, 0, 1).is_err());
+        assert!(editor.set_clue(0, 1, 5).is_err());
+
+        // The solution repeats a 2 on the anti-diagonal, which the diagonal variant forbids
+        editor.set_variant(Variant::Diagonal);
+        assert_eq!(editor.check.solutions, 0);
+        assert!(editor.to_game(None).is_err());
+        editor.set_variant(Variant::Classic);
+
+        // The saved puzzle can be played
+        let path = std::env::temp_dir().join(format!("sudoku_editor_{}.game", std::process::id()));
+        let path = path.to_str().unwrap();
+        editor.save(path).unwrap();
+        let game = game::Game::from_file(path).unwrap();
+        assert_eq!(game.puzzle_id(), "4..2.2.3.1.4..31");
+        drop(game);
+        std::fs::remove_file(path).unwrap();
+        let game = editor.to_game(None).unwrap();
+        assert!(game
+            .grid
+            .iter()
+            .all(|cell| cell.value() == 0 || cell.initial()));
+    }
+    #[test]
+    fn test_clear_notes() {
+        let mut game = game::Game::new(3, None).unwrap();
+        game.fill_notes().unwrap();
+
+        game.clear_notes(NotesClear::Digit(5)).unwrap();
+        assert!(game.grid.iter().all(|c| !c.has_note(5)));
+        assert!(game.grid[0].has_note(4));
+
+        game.clear_notes(NotesClear::Group(4)).unwrap();
+        assert_eq!(game.grid[game.index(4, 4)].notes(), 0);
+        assert_ne!(game.grid[game.index(0, 0)].notes(), 0);
+
+        // Placing a value without auto notes leaves invalid notes behind
+        game.do_move(0, 0, 9, Validation::Strict).unwrap();
+        assert!(game.grid[1].has_note(9));
+        game.clear_notes(NotesClear::Invalid).unwrap();
+        assert!(!game.grid[1].has_note(9));
+        assert!(game.grid[80].has_note(9));
+
+        assert!(game.clear_notes(NotesClear::Row(9)).is_err());
+    }
+
+    #[test]
+    fn test_rollback() {
+        let mut game = game::Game::new(3, None).unwrap();
+        game.fill_rng(0);
+        let solution: Vec<u8> = game.grid.iter().map(|c| c.value()).collect();
+        game.unfill(solver::Obvious, 17, 3);
+        assert_eq!(game.solution().unwrap(), solution);
+
+        // Play two correct moves then a wrong one, if there is room for one
+        let empties: Vec<usize> = game
+            .grid
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.value() == 0)
+            .map(|(i, _)| i)
+            .collect();
+        assert!(empties.len() >= 3);
+        for &i in &empties[0..2] {
+            let (r, c) = game.coordinates(i);
+            game.do_move(r, c, solution[i], Validation::Strict).unwrap();
+        }
+        let (r, c) = game.coordinates(empties[2]);
+        let wrong = (solution[empties[2]] % 9) + 1;
+        game.grid[empties[2]].set_value(wrong);
+        game.history.push(crate::history::Move {
+            index: empties[2],
+            before: game::Cell::new(0, false),
+            after: game.grid[empties[2]],
+            at: 0,
+        });
+
+        assert_eq!(game.rollback().unwrap(), 1);
+        assert_eq!(game.grid[game.index(r, c)].value(), 0);
+        assert_eq!(game.grid[empties[0]].value(), solution[empties[0]]);
+        assert_eq!(game.rollback().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_guesses() {
+        let path = "/tmp/sudoku_test_guesses.game";
+        let mut game = game::Game::new(3, Some(path)).unwrap();
+        game.do_move(0, 0, 1, Validation::Strict).unwrap();
+        game.start_guess();
+        game.do_move(0, 1, 2, Validation::Strict).unwrap();
+        game.start_guess();
+        game.do_move(0, 2, 3, Validation::Strict).unwrap();
+        assert_eq!(game.guesses, vec![1, 2]);
+
+        // The guesses are saved with the history
+        game.save().unwrap();
+        drop(game);
+        let mut game = game::Game::from_file(path).unwrap();
+        assert_eq!(game.guesses, vec![1, 2]);
+        std::fs::remove_file(path).unwrap();
+
+        game.commit_guess().unwrap();
+        assert_eq!(game.discard_guess().unwrap(), 2);
+        assert!(game.guesses.is_empty());
+        assert_eq!(game.grid[0].value(), 1);
+        assert_eq!(game.grid[1].value(), 0);
+        assert_eq!(game.grid[2].value(), 0);
+        assert!(game.commit_guess().is_err());
+    }
+
+    #[test]
+    fn test_generate() {
+        let config = GenerationConfig::default();
+        let mut game = generator::generate(3, generator::Difficulty::Easy, &config);
+        assert!(game.nb_clues() >= 17);
+        assert!(game.solution.is_some());
+        assert_eq!(solver::count_solutions(&mut game, 2), 1);
+        assert!(game.save_path.is_none());
+
+        // Without room for unfilling, no puzzle is hard and the closest one is kept
+        let config = GenerationConfig {
+            min_clues_percent: Some(100),
+            easy_clues_percent: Some(100),
+            medium_clues_percent: Some(100),
+            ..Default::default()
+        };
+        let game = generator::generate(2, generator::Difficulty::Hard, &config);
+        assert_eq!(game.nb_clues(), 16);
+
+        // The pool keeps handing out puzzles of its settings
+        let mut pool = generator::Pool::new(3, generator::Difficulty::Easy, &config);
+        for _ in 0..2 {
+            let game = pool.take();
+            assert_eq!(game.size, 3);
+            assert!(game.solution.is_some());
+        }
+    }
+
+    #[test]
+    fn test_diagonal_variant() {
+        let mut game = game::Game::new(3, None).unwrap();
+        game.variant = Variant::Diagonal;
+        game.do_move(0, 0, 5, Validation::Strict).unwrap();
+        game.do_move(0, 8, 7, Validation::Strict).unwrap();
+        assert!(!game.valids(game.index(8, 8)).contains(&5));
+        assert!(!game.valids(game.index(7, 1)).contains(&7));
+        assert!(game.valids(game.index(7, 1)).contains(&5));
+        assert!(game.do_move(4, 4, 5, Validation::Strict).is_err());
+
+        // A classic solution repeating values on its diagonals is not a diagonal one
+        let mut game = game::Game::new(3, None).unwrap();
+        for index in 0..81 {
+            let (r, c) = (index / 9, index % 9);
+            game.grid[index] = game::Cell::new(((r * 3 + r / 3 + c) % 9 + 1) as u8, true);
+        }
+        assert!(game.is_done());
+        game.variant = Variant::Diagonal;
+        assert!(!game.is_done());
+
+        // Generated puzzles keep their diagonals, every solver agreeing on their solution
+        let config = GenerationConfig {
+            variant: Variant::Diagonal,
+            ..Default::default()
+        };
+        let mut game = generator::generate_seeded(3, generator::Difficulty::Medium, &config, 7);
+        assert_eq!(game.variant, Variant::Diagonal);
+        assert_eq!(SolverKind::Dlx.count_solutions(&mut game, 2), 1);
+        assert_eq!(solver::count_solutions(&mut game, 2), 1);
+        let puzzle = game.puzzle_id();
+        assert!(SolverKind::Human.has_single_solution(&mut game));
+        assert_eq!(game.puzzle_id(), puzzle);
+        let values = |game: &game::Game| game.grid.iter().map(|c| c.value()).collect::<Vec<u8>>();
+        for kind in [SolverKind::Dlx, SolverKind::Backtracking] {
+            let mut solved = game::Game::from_puzzle(&game.puzzle_id(), None).unwrap();
+            solved.variant = Variant::Diagonal;
+            assert!(kind.solve(&mut solved).is_ok());
+            assert!(solved.is_done());
+            assert_eq!(Some(values(&solved)), game.solution);
+        }
+    }
+
+    #[test]
+    fn test_hyper_variant() {
+        let mut game = game::Game::new(3, None).unwrap();
+        assert_eq!(game.windows_count(), 0);
+        game.variant = Variant::Hyper;
+        assert_eq!(game.windows_count(), 4);
+        assert_eq!(game.window_of(game.index(1, 1)), Some(0));
+        assert_eq!(game.window_of(game.index(4, 4)), None);
+        assert_eq!(game.window_of(game.index(7, 3)), Some(2));
+        assert_eq!(game.window_of(game.index(5, 5)), Some(3));
+        assert_eq!(game.window(1).count(), 9);
+
+        // (1, 3) and (3, 2) share the first window, but no row, column or box
+        game.do_move(1, 3, 5, Validation::Strict).unwrap();
+        assert!(!game.valids(game.index(3, 2)).contains(&5));
+        assert!(game.valids(game.index(0, 0)).contains(&5));
+        assert!(game.do_move(3, 2, 5, Validation::Strict).is_err());
+
+        // Generated puzzles keep their windows, every solver agreeing on their solution
+        let config = GenerationConfig {
+            variant: Variant::Hyper,
+            ..Default::default()
+        };
+        let mut game = generator::generate_seeded(3, generator::Difficulty::Medium, &config, 7);
+        assert_eq!(game.variant, Variant::Hyper);
+        assert_eq!(SolverKind::Dlx.count_solutions(&mut game, 2), 1);
+        assert_eq!(solver::count_solutions(&mut game, 2), 1);
+        let values = |game: &game::Game| game.grid.iter().map(|c| c.value()).collect::<Vec<u8>>();
+        for kind in [SolverKind::Dlx, SolverKind::Backtracking] {
+            let mut solved = game::Game::from_puzzle(&game.puzzle_id(), None).unwrap();
+            solved.variant = Variant::Hyper;
+            assert!(kind.solve(&mut solved).is_ok());
+            assert!(solved.is_done());
+            assert_eq!(Some(values(&solved)), game.solution);
+        }
+    }
+
+    #[test]
+    fn test_export() {
+        let mut game = game::Game::from_puzzle("1.3.4...2..1.3.4", None).unwrap();
+        game.do_move(0, 1, 2, Validation::Strict).unwrap();
+        let options = ExportOptions {
+            font: String::from("Noto \"Sans\""),
+            ..Default::default()
+        };
+
+        // The clues in bold, the placed value and the lines, thicker around the boxes
+        let svg = export::to_svg(&game, &options);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<text ").count(), 8);
+        assert_eq!(svg.matches("font-weight=\"bold\"").count(), 7);
+        assert!(svg.contains("font-family=\"Noto &quot;Sans&quot;\""));
+        assert_eq!(svg.matches("<line ").count(), 2 * 4 * 5);
+        assert_eq!(svg.matches("stroke-width=\"3\"").count(), 2 * 4 * 3);
+
+        // Every candidate of the empty cells is marked
+        let candidates: usize = (0..16)
+            .filter(|i| game.grid[*i].value() == 0)
+            .map(|i| game.valids(i).len())
+            .sum();
+        let options = ExportOptions {
+            candidates: true,
+            ..Default::default()
+        };
+        let svg = export::to_svg(&game, &options);
+        assert_eq!(svg.matches("<text ").count(), 8 + candidates);
+
+        // The cross-reference table of the PDF points at its objects
+        let pdf = String::from_utf8(export::to_pdf(&game, &options)).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4"));
+        let xref = pdf
+            .split("startxref\n")
+            .nth(1)
+            .unwrap()
+            .lines()
+            .next()
+            .unwrap();
+        let xref: usize = xref.parse().unwrap();
+        assert!(pdf[xref..].starts_with("xref"));
+        for (i, line) in pdf[xref..].lines().skip(3).take(6).enumerate() {
+            let offset: usize = line[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj", i + 1)));
+        }
+    }
+
+    #[test]
+    fn test_samurai() {
+        // A solved board, every 9x9 grid aligned on its boxes being valid
+        let solved = |r: usize, c: usize| ((3 * (r % 3) + (r / 3) % 3 + c) % 9 + 1) as u8;
+        let text = |empty: &dyn Fn(usize, usize) -> bool| {
+            let samurai = SamuraiGame::new().unwrap();
+            (0..SIDE)
+                .map(|r| {
+                    (0..SIDE)
+                        .map(|c| match (samurai.cell(r, c), empty(r, c)) {
+                            (None, _) => ' ',
+                            (Some(_), true) => '.',
+                            (Some(_), false) => (b'0' + solved(r, c)) as char,
+                        })
+                        .collect::<String>()
+                        + "\n"
+                })
+                .collect::<String>()
+        };
+
+        // The corner boxes of the central grid are shared, the gaps between the grids are not
+        let samurai = SamuraiGame::new().unwrap();
+        assert_eq!(samurai.cells(7, 7), vec![(0, 70), (4, 10)]);
+        assert_eq!(samurai.cells(4, 4), vec![(0, 40)]);
+        assert!(samurai.cells(2, 10).is_empty());
+        assert!(SamuraiGame::from_text("123").is_err());
+
+        let samurai = SamuraiGame::from_text(&text(&|_, _| false)).unwrap();
+        assert!(samurai.is_done());
+        assert_eq!(samurai.to_string().lines().count(), SIDE + 6);
+
+        // A value placed in a shared cell fills both grids, and must fit both
+        let mut samurai = SamuraiGame::from_text(&text(&|r, c| r == c)).unwrap();
+        assert!(!samurai.is_done());
+        assert_eq!(samurai.count_solutions(2), 1);
+        assert_eq!(samurai.valids(7, 7), vec![solved(7, 7)]);
+        assert!(samurai.do_move(7, 7, solved(8, 8)).is_err());
+        samurai.do_move(7, 7, solved(7, 7)).unwrap();
+        assert_eq!(samurai.grids[4].grid[10].value(), solved(7, 7));
+        assert!(samurai.do_move(0, 1, 5).is_err());
+        assert!(samurai.do_move(2, 10, 5).is_err());
+
+        // Solving fills every grid, the shared boxes agreeing
+        let mut samurai = SamuraiGame::from_text(&text(&|r, c| (r * 7 + c) % 3 == 0)).unwrap();
+        assert!(samurai.solve().is_ok());
+        assert!(samurai.is_done());
+    }
+
+    #[test]
+    fn test_killer_cages() {
+        let grid = vec![game::Cell::new(0, false); 16];
+        let cage = Cage {
+            cells: vec![0, 1, 2],
+            sum: 6,
+        };
+        assert!(cage.allows(&grid, 0, 1, 4));
+        assert!(cage.allows(&grid, 0, 3, 4));
+        // The two other cells cannot make up 2 with distinct values
+        assert!(!cage.allows(&grid, 0, 4, 4));
+        // Cells out of the cage are not concerned
+        assert!(cage.allows(&grid, 5, 4, 4));
+
+        // The solved grid, played with horizontal pairs as cages
+        let solution = [1, 2, 3, 4, 3, 4, 1, 2, 2, 1, 4, 3, 4, 3, 2, 1];
+        let mut game = game::Game::new(2, None).unwrap();
+        game.cages = (0..8)
+            .map(|pair| Cage {
+                cells: vec![2 * pair, 2 * pair + 1],
+                sum: solution[2 * pair] as usize + solution[2 * pair + 1] as usize,
+            })
+            .collect();
+        for index in [0, 4, 6, 9, 11, 15] {
+            game.grid[index] = game::Cell::new(solution[index], true);
+        }
+        assert!(!game.valids(1).contains(&3));
+        assert!(game.do_move(0, 1, 3, Validation::Strict).is_err());
+        // The 3 of its box, and the 1 of its cage it does not add up with
+        assert_eq!(game.conflicting_cells(1, 3), vec![0, 4]);
+        game.do_move(0, 1, 2, Validation::Strict).unwrap();
+
+        // Both solvers find the one solution, the dancing links leaving the sums to backtracking
+        assert_eq!(SolverKind::Dlx.count_solutions(&mut game, 2), 1);
+        assert!(SolverKind::Dlx.solve(&mut game).is_ok());
+        assert!(game.is_done());
+        let values: Vec<u8> = game.grid.iter().map(|c| c.value()).collect();
+        assert_eq!(values, solution);
+
+        // A grid breaking a sum is not done
+        game.cages[0].sum = 4;
+        assert!(!game.is_done());
+    }
+
+    #[test]
+    fn test_jigsaw_regions() {
+        let boxes = game::boxes(2);
+        assert_eq!(&boxes[..8], &[0, 0, 1, 1, 0, 0, 1, 1]);
+        assert!(game::valid_regions(&boxes, 4));
+        assert!(!game::valid_regions(&boxes[1..], 4));
+        assert!(!game::valid_regions(&[0; 16], 4));
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let regions = generator::jigsaw_regions(3, &mut rng);
+        assert!(game::valid_regions(&regions, 9));
+        assert_ne!(regions, game::boxes(3));
+
+        // The groups follow the regions, and the colors of touching regions differ
+        let mut game = game::Game::new(3, None).unwrap();
+        assert!(!game.is_jigsaw());
+        game.set_regions(regions.clone());
+        assert!(game.is_jigsaw());
+        let group: Vec<usize> = game.group(0, 0).collect();
+        assert!(group.iter().all(|i| regions[*i] == regions[0]));
+        assert_eq!(group.len(), 9);
+        let colors = game.region_colors();
+        for index in 0..80 {
+            let (r, c) = game.coordinates(index);
+            let right = (c < 8).then(|| index + 1);
+            for other in right.into_iter().chain(Some(index + 9).filter(|i| *i < 81)) {
+                if regions[index] != regions[other] {
+                    assert_ne!(colors[regions[index]], colors[regions[other]]);
+                }
+            }
+            assert_eq!(
+                game.borders(r, c).1,
+                c == 0 || regions[index - 1] != regions[index]
+            );
+        }
+
+        // Generated jigsaw puzzles are solved on their regions, which are kept in the saves
+        let config = GenerationConfig {
+            jigsaw: true,
+            ..Default::default()
+        };
+        let mut puzzle = generator::generate_seeded(3, generator::Difficulty::Medium, &config, 5);
+        assert!(puzzle.is_jigsaw());
+        assert_eq!(SolverKind::Dlx.count_solutions(&mut puzzle, 2), 1);
+        assert_eq!(solver::count_solutions(&mut puzzle, 2), 1);
+
+        let path = std::env::temp_dir().join(format!("sudoku_jigsaw_{}.game", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut game = game::Game::new(3, Some(path)).unwrap();
+        game.grid = puzzle.grid.clone();
+        game.set_regions(puzzle.regions().to_vec());
+        game.save().unwrap();
+        drop(game);
+        let game = game::Game::from_file(path).unwrap();
+        assert_eq!(game.regions(), puzzle.regions());
+        drop(game);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_packed_cell() {
+        assert_eq!(std::mem::size_of::<game::Cell>(), 8);
+
+        let mut cell = game::Cell::new(25, true);
+        cell.set_notes(1 << 25 | 1 << 1);
+        assert_eq!(cell.value(), 25);
+        assert!(cell.initial());
+        assert!(cell.has_note(25) && cell.has_note(1) && !cell.has_note(2));
+
+        cell.set_value(0);
+        assert_eq!(cell.value(), 0);
+        assert!(cell.initial());
+        assert_eq!(cell.notes(), 1 << 25 | 1 << 1);
+    }
+
+    #[test]
+    fn test_packs() {
+        let folder = std::env::temp_dir().join(format!("sudoku_packs_{}", std::process::id()));
+        std::fs::create_dir_all(folder.join("packs")).unwrap();
+        std::fs::write(
+            folder.join("packs").join("small.sdm"),
+            "1.3.4...2..1.3.4\nnot a puzzle\n0234000000000000\n",
+        )
+        .unwrap();
+        assert_eq!(packs::normalize_puzzle("1.3."), None);
+        assert_eq!(packs::normalize_puzzle("1.3.4...2..1.3.!"), None);
+
+        let save_folder = format!("{}/", folder.to_str().unwrap());
+        let loaded = packs::Pack::load_all(&save_folder).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].name, "small");
+        assert_eq!(
+            loaded[0].puzzles,
+            vec!["1.3.4...2..1.3.4", ".234............"]
+        );
+
+        let mut progress = packs::Progress::load(&save_folder).unwrap();
+        assert_eq!(loaded[0].nb_solved(&progress), 0);
+        progress.mark_solved("1.3.4...2..1.3.4").unwrap();
+        let progress = packs::Progress::load(&save_folder).unwrap();
+        assert_eq!(loaded[0].nb_solved(&progress), 1);
+        assert_eq!(
+            loaded[0].next_unsolved(&progress).unwrap(),
+            ".234............"
+        );
+
+        let game = game::Game::from_puzzle(&loaded[0].puzzles[1], None).unwrap();
+        assert_eq!(game.size, 2);
+        assert_eq!(game.puzzle_id(), loaded[0].puzzles[1]);
+        assert!(game::Game::from_puzzle("1.3", None).is_err());
+
+        // The .pack files give a name and metadata, their puzzles being played in order
+        std::fs::write(
+            folder.join("packs").join("first.pack"),
+            r#"{"name": "First steps", "author": "Someone", "difficulty": "easy",
+                "puzzles": ["1.3.4...2..1.3.4", "4..2.2.3.1.4..31", "0234000000000000"]}"#,
+        )
+        .unwrap();
+        let loaded = packs::Pack::load_all(&save_folder).unwrap();
+        assert_eq!(loaded.len(), 2);
+        let pack = &loaded[0];
+        assert_eq!(pack.name, "First steps");
+        assert_eq!(pack.author.as_deref(), Some("Someone"));
+        assert_eq!(pack.description, None);
+        assert_eq!(pack.difficulty, Some(generator::Difficulty::Easy));
+        assert_eq!(pack.puzzles[2], ".234............");
+        assert_eq!(loaded[1].author, None);
+
+        // The first puzzle is solved already, the next unsolved one comes back after the last
+        let mut progress = packs::Progress::load(&save_folder).unwrap();
+        assert_eq!(pack.next_unsolved_from(&progress, 0), Some(1));
+        progress.mark_solved_in(pack, 2, 90).unwrap();
+        progress.mark_solved_in(pack, 2, 120).unwrap();
+        assert_eq!(pack.next_unsolved_from(&progress, 2), Some(1));
+        progress.mark_solved_in(pack, 1, 30).unwrap();
+        assert_eq!(pack.next_unsolved_from(&progress, 0), None);
+        let progress = packs::Progress::load(&save_folder).unwrap();
+        assert_eq!(pack.nb_solved(&progress), 3);
+        assert_eq!(pack.play_time(&progress), 120);
+        assert_eq!(loaded[1].play_time(&progress), 0);
+
+        // Unlike the .sdm files, the .pack files must only hold puzzles
+        let invalid = folder.join("packs").join("invalid.pack");
+        std::fs::write(&invalid, r#"{"puzzles": ["1.3.4...2..1.3.4", "1.3."]}"#).unwrap();
+        assert!(packs::Pack::from_file(&invalid).is_err());
+        std::fs::write(&invalid, r#"{"puzzles": ["1.3.4...2..1.3.4"]}"#).unwrap();
+        assert_eq!(packs::Pack::from_file(&invalid).unwrap().name, "invalid");
+
+        std::fs::remove_dir_all(folder).unwrap();
+    }
+
+    #[test]
+    fn test_saves() {
+        let folder = std::env::temp_dir().join(format!("sudoku_saves_{}", std::process::id()));
+        std::fs::create_dir_all(&folder).unwrap();
+        let path = |name: &str| folder.join(name).to_str().unwrap().to_string();
+
+        let mut older = game::Game::from_puzzle("1.3.4...2..1.3.4", Some(&path("a.game"))).unwrap();
+        older.save().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let mut newer = game::Game::from_puzzle("1.3.4...2..1.3.4", Some(&path("b.game"))).unwrap();
+        newer.do_move(0, 1, 2, Validation::Strict).unwrap();
+        newer.save().unwrap();
+        // Finished games, other files and unreadable saves are left out
+        let mut finished =
+            game::Game::from_puzzle("1234341221434321", Some(&path("c.game"))).unwrap();
+        finished.save().unwrap();
+        std::fs::write(path("notes.txt"), "1.3.4...2..1.3.4").unwrap();
+        std::fs::write(path("broken.game"), "{").unwrap();
+
+        let save_folder = format!("{}/", folder.to_str().unwrap());
+        let saves = saves::SavedGame::list(&save_folder);
+        assert_eq!(saves.len(), 2);
+        assert_eq!(saves[0].path, path("b.game"));
+        assert_eq!(saves[0].size, 2);
+        assert_eq!(saves[0].completion, 50);
+        assert_eq!(saves[1].completion, 43);
+        assert!(saves[0].to_string().starts_with("4x4 - 50% filled - "));
+
+        // The saves are read without being taken over
+        assert!(matches!(
+            game::Game::from_file(&saves[0].path),
+            Err(GameError::SaveLocked)
+        ));
+        drop(newer);
+        assert!(game::Game::from_file(&saves[0].path).is_ok());
+
+        assert!(saves::SavedGame::list("/nonexistent/").is_empty());
+        drop((older, finished));
+        std::fs::remove_dir_all(folder).unwrap();
+    }
+
+    #[test]
+    fn test_autosave() {
+        let folder = std::env::temp_dir().join(format!("sudoku_autosave_{}", std::process::id()));
+        std::fs::create_dir_all(&folder).unwrap();
+        let save_folder = format!("{}/", folder.to_str().unwrap());
+        let path = format!("{}a.game", save_folder);
+        let mut game = game::Game::from_puzzle("1.3.4...2..1.3.4", Some(&path)).unwrap();
+        game.save().unwrap();
+        drop(game);
+
+        // A clean session leaves nothing to recover
+        let mut session = Autosave::start(&save_folder).unwrap();
+        assert_eq!(session.recovered(), None);
+        // Nor does one still running
+        assert_eq!(Autosave::start(&save_folder).unwrap().recovered(), None);
+        session.finish();
+
+        // The session of a process that is gone crashed, its most recent save is recovered
+        std::fs::write(folder.join("4294967295.session"), "").unwrap();
+        let mut session = Autosave::start(&save_folder).unwrap();
+        assert_eq!(session.recovered(), Some(path.clone()));
+        assert_eq!(session.recovered(), None);
+        assert!(!folder.join("4294967295.session").exists());
+        session.finish();
+        assert_eq!(Autosave::start(&save_folder).unwrap().recovered(), None);
+
+        // Games without a save file are not saved
+        let mut game = game::Game::from_puzzle("1.3.4...2..1.3.4", None).unwrap();
+        assert!(Autosave::save(&mut game).is_ok());
+        assert!(game.events.is_empty());
+
+        // Only the saves of the session are logged, not the ones following every move
+        let mut game = game::Game::from_puzzle("1.3.4...2..1.3.4", Some(&path)).unwrap();
+        game.do_move(0, 1, 2, Validation::Strict).unwrap();
+        assert!(game.events.iter().all(|e| e.event != GameEvent::Autosave));
+        Autosave::save(&mut game).unwrap();
+        assert_eq!(game.events.last().unwrap().event, GameEvent::Autosave);
+
+        std::fs::remove_dir_all(folder).unwrap();
+    }
+
+    #[test]
+    fn test_print() {
+        let values = [1, 0, 3, 4, 3, 4, 0, 2, 2, 1, 4, 3, 4, 3, 2, 0];
+        assert_eq!(
+            print::render_grid(2, &values, true),
+            "+-----+-----+\n\
+             | 1 . | 3 4 |\n\
+             | 3 4 | . 2 |\n\
+             +-----+-----+\n\
+             | 2 1 | 4 3 |\n\
+             | 4 3 | 2 . |\n\
+             +-----+-----+\n"
+        );
+        assert!(print::render_grid(2, &values, false).starts_with("┌─────┬─────┐\n│ 1   │ 3 4 │"));
+
+        let puzzles = print::load_puzzles("1.343..2.14.4.21").unwrap();
+        let text = print::render_puzzles(&puzzles, true, true);
+        assert!(text.starts_with("Puzzle 1\n\n"));
+        assert_eq!(text.split('\x0c').count(), 2);
+        assert!(text.contains("Answers\n\nPuzzle 1\n\n+-----+-----+\n| 1 2 | 3 4 |"));
+
+        // The puzzle of a .sdk file is solved as well
+        let path = "/tmp/sudoku_test_print.sdk";
+        std::fs::write(path, "#AJohn Doe\n1.34\n3..2\n.14.\n4.21\n").unwrap();
+        let games = print::load_games(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].as_ref().unwrap().puzzle_id(), "1.343..2.14.4.21");
+        assert!(print::load_games("1.343..2.14.4.2").is_err());
+    }
+
+    #[test]
+    fn test_generation_config() {
+        let config = GenerationConfig::default();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.min_clues(3), 17);
+        assert_eq!(
+            config.max_unfill_attempts(3, generator::Difficulty::Easy),
+            3
+        );
+        assert_eq!(
+            config.max_unfill_attempts(3, generator::Difficulty::Hard),
+            40
+        );
+        assert_eq!(config.clues(generator::Difficulty::Easy, 3), 36..=81);
+        assert_eq!(config.clues(generator::Difficulty::Medium, 3), 29..=35);
+        assert_eq!(config.clues(generator::Difficulty::Hard, 3), 0..=28);
+
+        // Calibrated for each size
+        assert_eq!(config.min_clues(4), 53);
+        assert_eq!(config.clues(generator::Difficulty::Medium, 4), 112..=124);
+        assert_eq!(
+            config.max_unfill_attempts(4, generator::Difficulty::Hard),
+            30
+        );
+
+        // Unless given
+        let config = GenerationConfig {
+            max_unfill_attempts: Some(5),
+            easy_clues_percent: Some(60),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.max_unfill_attempts(4, generator::Difficulty::Hard),
+            5
+        );
+        assert_eq!(config.clues(generator::Difficulty::Medium, 3), 29..=47);
+
+        let config = GenerationConfig {
+            medium_clues_percent: Some(50),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+        let config = GenerationConfig {
+            max_unfill_attempts: Some(0),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        // Games are only played in the sizes the puzzles are generated for
+        assert!(generator::SIZES
+            .into_iter()
+            .all(|size| generator::validate_size(size).is_ok()));
+        for size in [0, 1, 5, 6] {
+            assert!(matches!(
+                generator::validate_size(size),
+                Err(UiError::InvalidGameSize)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_generation_calibration() {
+        // Every size and difficulty is reached within the attempts of the generator
+        let config = GenerationConfig::default();
+        for size in generator::SIZES {
+            for difficulty in [
+                generator::Difficulty::Easy,
+                generator::Difficulty::Medium,
+                generator::Difficulty::Hard,
+                generator::Difficulty::Expert,
+            ] {
+                for seed in 0..3 {
+                    let game = generator::generate_seeded(size, difficulty, &config, seed);
+                    assert!(config.clues(difficulty, size).contains(&game.nb_clues()));
+                    if difficulty == generator::Difficulty::Expert && size > 2 {
+                        assert_eq!(game.rating().difficulty(), difficulty);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        let mut game = game::Game::new(3, None).unwrap();
+        game.fill_rng(0);
+        let solution: Vec<u8> = game.grid.iter().map(|c| c.value()).collect();
+        for i in [0, 1, 2] {
+            game.grid[i] = game::Cell::new(0, false);
+        }
+
+        // The value of the second cell does not belong to the first one, nor twice in the row
+        let wrong = solution[1];
+        assert!(game.do_move(0, 0, wrong, Validation::Solution).is_err());
+        assert_eq!(game.solution.as_ref(), Some(&solution));
+        assert!(game.do_move(0, 0, wrong, Validation::Free).is_ok());
+        assert!(game.do_move(0, 1, wrong, Validation::Strict).is_err());
+        assert!(game.do_move(0, 1, wrong, Validation::Free).is_ok());
+        assert!(game.do_move(0, 2, 10, Validation::Free).is_err());
+        assert!(game
+            .do_move(0, 2, solution[2], Validation::Solution)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_ui_errors() {
+        // Every game error converts, keeping its message when no UI error matches it
+        assert!(matches!(
+            UiError::from(GameError::SaveLocked),
+            UiError::SaveLocked
+        ));
+        let error = UiError::from(GameError::NoSolution);
+        assert!(matches!(error, UiError::Game(GameError::NoSolution)));
+        assert_eq!(error.to_string(), GameError::NoSolution.to_string());
+        assert!(std::error::Error::source(&error).is_none());
+    }
+
+    #[test]
+    fn test_error_sources() {
+        // The underlying I/O error is kept and reported after the game error
+        let error = game::Game::read("data/missing_save_file").err().unwrap();
+        let io_error = match &error {
+            GameError::OpenFileError(io_error) => io_error.to_string(),
+            _ => panic!("Unexpected error {:?}", error),
+        };
+        assert_eq!(
+            errors::report(&UiError::from(error)),
+            format!(
+                "{}: {}",
+                GameError::OpenFileError(std::io::ErrorKind::NotFound.into()),
+                io_error
+            )
+        );
+
+        // SDL2 messages become the source of the UI error
+        let error = UiError::sdl2("Invalid renderer".to_string());
+        assert_eq!(
+            errors::report(&error),
+            "Generic SDL2 Error: Invalid renderer"
+        );
+    }
+
+    #[test]
+    fn test_next_redraw() {
+        // Without a game, the game screen never changes by itself
+        let mut screen = GameScreen::new();
+        assert_eq!(screen.next_redraw(), None);
+
+        // Its clock then ticks every second
+        screen.set_game(game::Game::new(3, None).unwrap());
+        let wait = screen.next_redraw().unwrap();
+        assert!(wait > std::time::Duration::ZERO && wait <= std::time::Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_watch_solver() {
+        let key = |keycode| sdl2::event::Event::KeyDown {
+            timestamp: 0,
+            window_id: 0,
+            keycode: Some(keycode),
+            scancode: None,
+            keymod: sdl2::keyboard::Mod::NOMOD,
+            repeat: false,
+        };
+        let puzzle =
+            "..8..3....9...867....97..2...1...2.6....4..1.....9.5.436.2..7...824.7.35..7......";
+        let mut screen = GameScreen::new();
+        screen.set_game(game::Game::from_puzzle(puzzle, None).unwrap());
+
+        // Watching fills the notes, the first step being played once its delay is over
+        screen.update(&key(Keycode::W)).unwrap();
+        let game = screen.game.as_ref().unwrap();
+        assert!(game.grid.iter().any(|cell| cell.notes() != 0));
+        assert!(screen.next_redraw().is_some());
+
+        // The space key plays the steps one at a time, until the human techniques solve the grid
+        let empty = screen.game.as_ref().unwrap().nb_empty();
+        screen.update(&key(Keycode::Space)).unwrap();
+        assert_eq!(screen.game.as_ref().unwrap().hints, 1);
+        while !screen.is_over() {
+            assert!(!screen.watch_tick());
+            screen.update(&key(Keycode::Space)).unwrap();
+        }
+        assert!(screen.game.as_ref().unwrap().hints >= empty);
+        assert!(matches!(
+            screen.update(&key(Keycode::Space)).unwrap(),
+            ScreenOutcome::Unchanged
+        ));
+    }
+
+    #[test]
+    fn test_back_to_menu() {
+        let key = |keycode| sdl2::event::Event::KeyDown {
+            timestamp: 0,
+            window_id: 0,
+            keycode: Some(keycode),
+            scancode: None,
+            keymod: sdl2::keyboard::Mod::NOMOD,
+            repeat: false,
+        };
+        let puzzle =
+            "..8..3....9...867....97..2...1...2.6....4..1.....9.5.436.2..7...824.7.35..7......";
+        let mut screen = GameScreen::new();
+        screen.set_game(game::Game::from_puzzle(puzzle, None).unwrap());
+        assert!(matches!(
+            screen.update(&key(Keycode::M)).unwrap(),
+            ScreenOutcome::BackToMenu
+        ));
+
+        // The menu is reached from a paused game too
+        screen.update(&key(Keycode::P)).unwrap();
+        assert!(matches!(
+            screen.update(&key(Keycode::M)).unwrap(),
+            ScreenOutcome::BackToMenu
+        ));
+        screen.update(&key(Keycode::P)).unwrap();
+
+        // Leaving stops watching the solver, the grid being left as it is
+        screen.update(&key(Keycode::W)).unwrap();
+        assert!(matches!(
+            screen.update(&key(Keycode::M)).unwrap(),
+            ScreenOutcome::BackToMenu
+        ));
+        assert!(!screen.watch_tick());
+        assert_eq!(screen.game.as_ref().unwrap().hints, 0);
+    }
+
+    #[test]
+    fn test_dialog() {
+        let key = |keycode| sdl2::event::Event::KeyDown {
+            timestamp: 0,
+            window_id: 0,
+            keycode: Some(keycode),
+            scancode: None,
+            keymod: sdl2::keyboard::Mod::NOMOD,
+            repeat: false,
+        };
+        let mut dialog = Dialog::new("Abandon current game?", ScreenOutcome::ChooseNewGame);
+        assert_eq!(dialog.update(&key(Keycode::A)), DialogOutcome::Unchanged);
+        assert_eq!(
+            dialog.update(&key(Keycode::Y)),
+            DialogOutcome::Confirmed(ScreenOutcome::ChooseNewGame)
+        );
+        assert_eq!(
+            dialog.update(&key(Keycode::Escape)),
+            DialogOutcome::Cancelled
+        );
+
+        // Exporting again asks before overwriting the image, the grid waiting for the answer
+        let path = std::env::temp_dir().join(format!("sudoku_dialog_{}.game", std::process::id()));
+        let image = path.with_extension("svg");
+        let puzzle =
+            "..8..3....9...867....97..2...1...2.6....4..1.....9.5.436.2..7...824.7.35..7......";
+        let mut screen = GameScreen::new();
+        screen.set_game(game::Game::from_puzzle(puzzle, path.to_str()).unwrap());
+        screen.update(&key(Keycode::E)).unwrap();
+        assert!(image.exists() && !screen.has_dialog());
+        std::fs::write(&image, "").unwrap();
+
+        screen.update(&key(Keycode::E)).unwrap();
+        assert!(screen.has_dialog());
+        assert!(matches!(
+            screen.update(&key(Keycode::M)).unwrap(),
+            ScreenOutcome::Unchanged
+        ));
+        screen.update(&key(Keycode::N)).unwrap();
+        assert!(!screen.has_dialog());
+        assert!(std::fs::read_to_string(&image).unwrap().is_empty());
+
+        screen.update(&key(Keycode::E)).unwrap();
+        screen.update(&key(Keycode::Y)).unwrap();
+        assert!(!screen.has_dialog());
+        assert!(!std::fs::read_to_string(&image).unwrap().is_empty());
+        drop(screen);
+        std::fs::remove_file(image).unwrap();
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut game = game::Game::new(3, None).unwrap();
+        game.fill_rng(0);
+        let solution: Vec<u8> = game.grid.iter().map(|c| c.value()).collect();
+        game.solution = Some(solution.clone());
+        assert!(game.is_filled());
+
+        game.grid[0] = game::Cell::new(0, false);
+        game.grid[1] = game::Cell::new(solution[2], false);
+        assert!(!game.is_filled());
+
+        let diff = game.diff().unwrap();
+        assert_eq!(diff[0], CellDiff::Empty(solution[0]));
+        assert_eq!(diff[1], CellDiff::Wrong(solution[1]));
+        assert!(diff[2..].iter().all(|d| *d == CellDiff::Correct));
+    }
+
+    #[test]
+    fn test_toggle_note() {
+        let path = "/tmp/sudoku_test_notes.game";
+        let mut game = game::Game::new(3, Some(path)).unwrap();
+        game.do_move(0, 0, 1, Validation::Strict).unwrap();
+
+        assert!(game.toggle_note(0, 1, 4).unwrap());
+        assert!(game.toggle_note(0, 1, 7).unwrap());
+        assert!(!game.toggle_note(0, 1, 4).unwrap());
+        assert!(matches!(
+            game.toggle_note(0, 0, 4),
+            Err(GameError::NonEmptyCell)
+        ));
+        assert!(matches!(
+            game.toggle_note(0, 1, 10),
+            Err(GameError::IllegalValue)
+        ));
+
+        // The notes are kept in the save
+        drop(game);
+        let game = game::Game::from_file(path).unwrap();
+        assert_eq!(game.grid[1].notes(), 1 << 7);
+        assert_eq!(game.grid[0].notes(), 0);
+        drop(game);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let path = "/tmp/sudoku_test_undo.game";
+        let mut game = game::Game::new(3, Some(path)).unwrap();
+        assert!(matches!(game.undo(), Err(GameError::NothingToUndo)));
+        game.do_move(0, 0, 1, Validation::Strict).unwrap();
+        game.do_move(0, 1, 2, Validation::Strict).unwrap();
+        game.do_move(0, 2, 3, Validation::Strict).unwrap();
+
+        game.undo().unwrap();
+        game.undo().unwrap();
+        assert_eq!(game.grid[0].value(), 1);
+        assert_eq!(game.grid[1].value(), 0);
+        assert_eq!(game.grid[2].value(), 0);
+        game.redo().unwrap();
+        assert_eq!(game.grid[1].value(), 2);
+
+        // The history survives resuming the game
+        drop(game);
+        let mut game = game::Game::from_file(path).unwrap();
+        game.redo().unwrap();
+        assert_eq!(game.grid[2].value(), 3);
+        assert!(matches!(game.redo(), Err(GameError::NothingToRedo)));
+        game.undo().unwrap();
+        game.undo().unwrap();
+        game.undo().unwrap();
+        assert_eq!(game.grid[0].value(), 0);
+
+        // A new move forgets the undone ones
+        game.do_move(4, 4, 5, Validation::Strict).unwrap();
+        assert!(matches!(game.redo(), Err(GameError::NothingToRedo)));
+        drop(game);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_backtracking() {
+        use crate::solver::Solver;
+
+        // Out of reach of the obvious solver
+        let puzzle =
+            "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+        let mut game = game::Game::from_puzzle(puzzle, None).unwrap();
+        assert!(solver::Obvious.solve(&mut game).is_err());
+        let mut game = game::Game::from_puzzle(puzzle, None).unwrap();
+        assert!(solver::Backtracking.solve(&mut game).is_ok());
+        assert!(game.is_done());
+        assert_eq!(game.grid[0].value(), 4);
+
+        // A grid with several solutions is not a puzzle
+        let mut game = game::Game::new(2, None).unwrap();
+        assert_eq!(solver::count_solutions(&mut game, 5), 5);
+        assert!(solver::Backtracking.solve(&mut game).is_err());
+        assert!(!game.is_filled());
+
+        // What the 'check' command tells about a puzzle
+        let check = solver::PuzzleCheck::new(&mut game);
+        assert_eq!((check.solutions, check.rating.as_ref()), (2, None));
+        assert_eq!(check.to_string(), "several solutions");
+        let mut game = game::Game::from_puzzle("11..............", None).unwrap();
+        let check = solver::PuzzleCheck::new(&mut game);
+        assert_eq!(check.conflicts, vec![(1, 1), (1, 2)]);
+        assert_eq!(check.solutions, 0);
+        assert_eq!(
+            check.to_string(),
+            "conflicting clues at row 1 column 1, row 1 column 2"
+        );
+        let json = serde_json::to_string(&check).unwrap();
+        assert!(json.contains("\"conflicts\":[[1,1],[1,2]],\"solutions\":0"));
+    }
+
+    #[test]
+    fn test_clear_cell() {
+        let mut game = game::Game::from_puzzle(&format!("1{}", ".".repeat(15)), None).unwrap();
+        game.do_move(1, 1, 2, Validation::Strict).unwrap();
+        game.clear_cell(1, 1).unwrap();
+        assert_eq!(game.grid[game.index(1, 1)].value(), 0);
+        assert!(matches!(
+            game.clear_cell(0, 0),
+            Err(GameError::NonEmptyCell)
+        ));
+        assert!(matches!(
+            game.clear_cell(4, 0),
+            Err(GameError::IllegalPosition)
+        ));
+
+        // Clearing is a move that can be undone
+        game.undo().unwrap();
+        assert_eq!(game.grid[game.index(1, 1)].value(), 2);
+
+        // A placed value can be replaced, even by itself, but not a value of the puzzle
+        game.do_move(1, 1, 2, Validation::Strict).unwrap();
+        game.do_move(1, 1, 3, Validation::Strict).unwrap();
+        assert_eq!(game.grid[game.index(1, 1)].value(), 3);
+        assert!(matches!(
+            game.do_move(0, 0, 2, Validation::Strict),
+            Err(GameError::NonEmptyCell)
+        ));
+    }
+
+    #[test]
+    fn test_hints() {
+        // The last value of a row
+        let mut game = game::Game::from_puzzle("123.............", None).unwrap();
+        let hint = hint::next_hint(&game).unwrap();
+        assert_eq!(hint.technique, grader::Technique::NakedSingle);
+        assert_eq!(hint.action, hint::Action::Place { index: 3, value: 4 });
+
+        // Following the hints solves an easy puzzle
+        game = generator::generate(3, generator::Difficulty::Easy, &GenerationConfig::default());
+        while let Some(hint) = hint::next_hint(&game) {
+            if let hint::Action::Place { index, value } = hint.action {
+                let (r, c) = game.coordinates(index);
+                game.do_move(r, c, value, Validation::Solution).unwrap();
+            }
+        }
+        assert!(game.is_done());
+
+        // 1 is confined to the first row of the first box, so not noted elsewhere in that row
+        let mut game = game::Game::new(3, None).unwrap();
+        for (i, value) in [(9, 2), (10, 3), (11, 4), (18, 5), (19, 6), (20, 7)] {
+            game.grid[i] = game::Cell::new(value, true);
+        }
+        game.grid[4].set_notes(0b11_1111_1110);
+        let hint = hint::next_hint(&game).unwrap();
+        assert_eq!(hint.technique, grader::Technique::PointingPair);
+        assert_eq!(
+            hint.action,
+            hint::Action::Eliminate {
+                cells: vec![4],
+                value: 1
+            }
+        );
+        assert_eq!(hint.cells, vec![0, 1, 2]);
+        assert!(hint.describe(&game).contains("row 1"));
+
+        // The first two cells of a row can only take 1 and 2, which the third cannot take then
+        let mut game = game::Game::new(3, None).unwrap();
+        game.grid[0].set_notes(0b110);
+        game.grid[1].set_notes(0b110);
+        game.grid[2].set_notes(0b1110);
+        let hint = hint::next_hint(&game).unwrap();
+        assert_eq!(hint.technique, grader::Technique::NakedPair);
+        assert_eq!(
+            hint.action,
+            hint::Action::Eliminate {
+                cells: vec![2],
+                value: 1
+            }
+        );
+        assert_eq!(hint.cells, vec![0, 1]);
+        assert!(hint.describe(&game).contains("can only take 1 and 2"));
+
+        // 1 and 2 can only go in the first two cells of the first row, which cannot take 3 then
+        let mut game = game::Game::new(3, None).unwrap();
+        for (i, value) in [(12, 1), (13, 2), (24, 1), (25, 2), (29, 1), (38, 2)] {
+            game.grid[i] = game::Cell::new(value, true);
+        }
+        game.grid[0].set_notes(0b1110);
+        let hint = hint::next_hint(&game).unwrap();
+        assert_eq!(hint.technique, grader::Technique::HiddenPair);
+        assert_eq!(
+            hint.action,
+            hint::Action::Eliminate {
+                cells: vec![0],
+                value: 3
+            }
+        );
+        assert_eq!(hint.cells, vec![0, 1]);
+        assert!(hint
+            .describe(&game)
+            .contains("in row 1, 1 and 2 can only go in"));
+
+        // The path of the 'solve --trace' command places every value of an easy puzzle
+        let mut game = game::Game::from_puzzle("1.343..2.14.4.21", None).unwrap();
+        let path = hint::solve_path(&mut game);
+        assert_eq!(path.len(), 6);
+        assert!(game.is_done());
+    }
+
+    #[test]
+    fn test_cells_for() {
+        let mut game = game::Game::new(2, None).unwrap();
+        game.do_move(0, 0, 1, Validation::Strict).unwrap();
+        game.do_move(3, 3, 1, Validation::Strict).unwrap();
+
+        // Neither in the first or last row, column or box
+        assert_eq!(game.cells_for(1), vec![game.index(1, 2), game.index(2, 1)]);
+        assert_eq!(game.cells_for(2).len(), 14);
+    }
+
+    #[test]
+    fn test_save_lock() {
+        let path = std::env::temp_dir().join(format!("sudoku_lock_{}.game", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let mut game = game::Game::new(3, Some(path)).unwrap();
+        game.save().unwrap();
+        assert!(matches!(
+            game::Game::new(3, Some(path)),
+            Err(GameError::SaveLocked)
+        ));
+        assert!(matches!(
+            game::Game::from_file(path),
+            Err(GameError::SaveLocked)
+        ));
+
+        // Released once the game is dropped
+        drop(game);
+        let game = game::Game::from_file(path).unwrap();
+        drop(game);
+
+        // A lock left by a process that is gone is taken over, whatever the id it holds
+        std::fs::write(format!("{}.lock", path), u32::MAX.to_string()).unwrap();
+        assert!(game::Game::from_file(path).is_ok());
+        std::fs::write(format!("{}.lock", path), std::process::id().to_string()).unwrap();
+        assert!(game::Game::from_file(path).is_ok());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_sessions() {
+        let mut timer = Timer::start();
+        let game = game::Game::new(2, None).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let session = Session::park(game);
+        let parked = session.game.timer.elapsed();
+        assert!(parked >= std::time::Duration::from_millis(20));
+        assert!(!session.game.timer.is_running());
+
+        // A parked game's time does not run
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        assert_eq!(session.game.timer.elapsed(), parked);
+
+        let game = session.resume();
+        assert_eq!(game.size, 2);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        assert!(game.timer.elapsed() > parked);
+
+        timer.pause();
+        timer.resume();
+        assert!(timer.elapsed() >= std::time::Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_import_fpuzzles() {
+        let mut grid = vec![vec![serde_json::json!({}); 9]; 9];
+        grid[0][0] = serde_json::json!({"value": 5, "given": true, "c": "#FF000080"});
+        grid[0][1] = serde_json::json!({"centerPencilMarks": [1, 2], "givenPencilMarks": [3]});
+        let json = serde_json::json!({
+            "size": 9,
+            "grid": grid,
+            "killercage": [{"cells": ["R1C2", "R2C2"], "value": "7"}],
+            "text": [{"cells": ["r9c9"], "value": "A"}, {"cells": ["R10C1"], "value": "B"}],
+        })
+        .to_string();
+
+        let path = "/tmp/sudoku_test_import.game";
+        let game = annotations::import_fpuzzles(&json, Some(path)).unwrap();
+        assert!(game.grid[0].initial() && game.grid[0].value() == 5);
+        assert_eq!(game.grid[1].notes(), 0b1110);
+        // Killer cages with a sum are cages of the game, enforced rather than only drawn
+        assert_eq!(
+            game.cages,
+            vec![Cage {
+                cells: vec![1, 10],
+                sum: 7,
+            }]
+        );
+        let expected = annotations::Annotations {
+            cages: vec![],
+            texts: vec![annotations::Region {
+                cells: vec![80],
+                label: "A".to_string(),
+            }],
+            colors: vec![(0, "#FF000080".to_string())],
+        };
+        assert_eq!(game.annotations.as_ref(), Some(&expected));
+
+        // The annotations are kept in the save
+        let mut game = game;
+        game.save().unwrap();
+        drop(game);
+        let game = game::Game::from_file(path).unwrap();
+        assert_eq!(game.annotations, Some(expected));
+        assert_eq!(game.cages.len(), 1);
+        drop(game);
+        std::fs::remove_file(path).unwrap();
+
+        // Only square sizes of 2 to 5 are supported
+        let json = serde_json::json!({"size": 6, "grid": vec![vec![serde_json::json!({}); 6]; 6]});
+        assert!(matches!(
+            annotations::import_fpuzzles(&json.to_string(), None),
+            Err(GameError::InvalidPuzzle)
+        ));
+
+        // A puzzle with a value too large for its grid leaves no save behind
+        let mut grid = vec![vec![serde_json::json!({}); 4]; 4];
+        grid[3][3] = serde_json::json!({"value": 5});
+        let json = serde_json::json!({"size": 4, "grid": grid});
+        assert!(matches!(
+            annotations::import_fpuzzles(&json.to_string(), Some(path)),
+            Err(GameError::InvalidPuzzle)
+        ));
+        assert!(!std::path::Path::new(path).exists());
+    }
+
+    #[test]
+    fn test_args() {
+        let parse = |line: &str| {
+            let words: Vec<String> = line.split_whitespace().map(String::from).collect();
+            args::parse(&words)
+        };
+
+        assert_eq!(
+            parse("play config.json GUI puzzle.json"),
+            Ok(Command::Play {
+                config: Some("config.json".to_string()),
+                mode: Mode::Gui,
+                puzzle: Some("puzzle.json".to_string()),
+            })
+        );
+        // Without a subcommand, the arguments are the ones of play
+        assert_eq!(
+            parse("config.json CLI"),
+            Ok(Command::Play {
+                config: Some("config.json".to_string()),
+                mode: Mode::Cli,
+                puzzle: None,
+            })
+        );
+        assert_eq!(
+            parse("generate --difficulty hard --count 5 --out puzzles.sdm"),
+            Ok(Command::Generate {
+                size: 3,
+                difficulty: generator::Difficulty::Hard,
+                count: 5,
+                out: Some("puzzles.sdm".to_string()),
+                format: BatchFormat::Lines,
+            })
+        );
+        assert_eq!(
+            parse("generate --count 2 --format json"),
+            Ok(Command::Generate {
+                size: 3,
+                difficulty: generator::Difficulty::Medium,
+                count: 2,
+                out: None,
+                format: BatchFormat::Json,
+            })
+        );
+        assert!(parse("generate --format xml").is_err());
+        assert_eq!(
+            parse("solve puzzle.sdk --solver dlx --trace"),
+            Ok(Command::Solve {
+                source: "puzzle.sdk".to_string(),
+                solver: SolverKind::Dlx,
+                trace: true,
+                explain: false,
+            })
+        );
+        assert_eq!(
+            parse("solve puzzle.sdk --explain --solver human"),
+            Ok(Command::Solve {
+                source: "puzzle.sdk".to_string(),
+                solver: SolverKind::Human,
+                trace: false,
+                explain: true,
+            })
+        );
+        assert!(parse("solve puzzle.sdk --solver magic").is_err());
+        assert_eq!(
+            parse("check puzzle.sdk --json"),
+            Ok(Command::Check {
+                source: "puzzle.sdk".to_string(),
+                json: true,
+            })
+        );
+        assert_eq!(
+            parse("check puzzles.sdm"),
+            Ok(Command::Check {
+                source: "puzzles.sdm".to_string(),
+                json: false,
+            })
+        );
+        assert_eq!(
+            parse("print puzzles.sdm --ascii"),
+            Ok(Command::Print {
+                source: "puzzles.sdm".to_string(),
+                with_solutions: false,
+                ascii: true,
+            })
+        );
+        assert_eq!(
+            parse("export a.game a.pdf --cell-size 30 --candidates"),
+            Ok(Command::Export {
+                source: "a.game".to_string(),
+                out: "a.pdf".to_string(),
+                options: ExportOptions {
+                    cell_size: 30,
+                    candidates: true,
+                    ..Default::default()
+                },
+            })
+        );
+        assert!(parse("export a.game").is_err());
+        assert!(parse("export a.game a.svg --cell-size 0").is_err());
+        assert_eq!(
+            parse("import-code AQ3 --out a.game"),
+            Ok(Command::ImportCode {
+                code: "AQ3".to_string(),
+                out: Some("a.game".to_string()),
+            })
+        );
+        assert!(parse("import-code").is_err());
+        assert_eq!(
+            parse("replay a.game"),
+            Ok(Command::Replay {
+                source: "a.game".to_string(),
+            })
+        );
+        assert!(parse("replay").is_err());
+        assert_eq!(
+            parse("serve --address 0.0.0.0:9000"),
+            Ok(Command::Serve {
+                address: "0.0.0.0:9000".to_string(),
+            })
+        );
+        assert_eq!(
+            parse("serve"),
+            Ok(Command::Serve {
+                address: "127.0.0.1:8080".to_string(),
+            })
+        );
+        assert!(parse("serve --port 9000").is_err());
+        assert_eq!(
+            parse("pack play first.pack"),
+            Ok(Command::PackPlay {
+                config: None,
+                pack: "first.pack".to_string(),
+            })
+        );
+        assert_eq!(
+            parse("pack play config.json first.pack"),
+            Ok(Command::PackPlay {
+                config: Some("config.json".to_string()),
+                pack: "first.pack".to_string(),
+            })
+        );
+        assert!(parse("pack first.pack").is_err());
+
+        // Without a path, the configuration of the platform folder is used
+        assert_eq!(
+            parse("GUI puzzle.json"),
+            Ok(Command::Play {
+                config: None,
+                mode: Mode::Gui,
+                puzzle: Some("puzzle.json".to_string()),
+            })
+        );
+        assert_eq!(parse("--init-config"), Ok(Command::InitConfig));
+        assert_eq!(
+            parse("config.json TUI"),
+            Ok(Command::Play {
+                config: Some("config.json".to_string()),
+                mode: Mode::Tui,
+                puzzle: None,
+            })
+        );
+
+        assert!(parse("").is_err());
+        assert!(parse("solve").is_err());
+        assert!(parse("config.json TTY").is_err());
+        assert!(parse("generate --count many").is_err());
+        assert!(parse("generate --size 7").is_err());
+        assert!(parse("print puzzles.sdm --color").is_err());
+    }
+
+    #[cfg(feature = "fetch")]
+    #[test]
+    fn test_daily_puzzle() {
+        use std::io::{Read, Write};
+
+        let board = r#"{"board":[[1,0,3,0],[4,0,0,0],[2,0,0,1],[0,3,0,4]]}"#;
+        assert_eq!(fetch::parse_answer(board).unwrap(), "1.3.4...2..1.3.4");
+        assert_eq!(
+            fetch::parse_answer(r#"{"puzzle":"1.3.4...2..1.3.4"}"#).unwrap(),
+            "1.3.4...2..1.3.4"
+        );
+        assert!(fetch::parse_answer(r#"{"board":[[1,1]]}"#).is_err());
+
+        // A local endpoint answering the board in two chunks, once
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/board", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let (first, second) = board.split_at(10);
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n{}\r\n{:x}\r\n{}\r\n0\r\n\r\n",
+                first.len(),
+                first,
+                second.len(),
+                second
+            )
+            .unwrap();
+        });
+
+        let folder = std::env::temp_dir().join(format!("sudoku_daily_{}", std::process::id()));
+        std::fs::create_dir_all(&folder).unwrap();
+        let save_folder = format!("{}/", folder.to_str().unwrap());
+        let game = fetch::daily_puzzle(&url, &save_folder, None).unwrap();
+        assert_eq!(game.puzzle_id(), "1.3.4...2..1.3.4");
+        server.join().unwrap();
+
+        // The endpoint is not asked again the same day, and the last puzzle is kept offline
+        let game = fetch::daily_puzzle(&url, &save_folder, None).unwrap();
+        assert_eq!(game.puzzle_id(), "1.3.4...2..1.3.4");
+        std::fs::write(
+            folder.join("daily.json"),
+            r#"{"date":"2000-01-01","puzzle":"1234341221434321"}"#,
+        )
+        .unwrap();
+        let game = fetch::daily_puzzle(&url, &save_folder, None).unwrap();
+        assert_eq!(game.puzzle_id(), "1234341221434321");
+        std::fs::remove_dir_all(folder).unwrap();
+        assert!(fetch::daily_puzzle(&url, &save_folder, None).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_tui_keys() {
+        assert_eq!(
+            parse_keys(b"\x1b[A\x1b[D5\x1b[3~q"),
+            vec![
+                Key::Up,
+                Key::Left,
+                Key::Char('5'),
+                Key::Delete,
+                Key::Char('q')
+            ]
+        );
+        // Unknown sequences are left out, a lone escape quits
+        assert_eq!(
+            parse_keys(b"\x1b[15~m\x1b"),
+            vec![Key::Char('m'), Key::Escape]
+        );
+        assert_eq!(parse_keys(b"\x7f"), vec![Key::Delete]);
+    }
+
+    #[test]
+    fn test_default_config() {
+        let text = config::default_config(
+            std::path::Path::new("/home/me/.config/sudocurs/games"),
+            std::path::Path::new("/home/me/.config/sudocurs/resources"),
+        );
+        assert!(text.contains("// Size of the game window"));
+
+        // The commented configuration suits both interfaces once its comments are left out
+        let json = config::strip_comments(&text);
+        let gui: GUIConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(gui.save_folder_path, "/home/me/.config/sudocurs/games/");
+        assert_eq!(
+            gui.font_path,
+            "/home/me/.config/sudocurs/resources/roboto_font.ttf"
+        );
+        assert_eq!(gui.screenshot_key, "F12");
+        assert_eq!(gui.mouse, MouseConfig::default());
+        assert_eq!((gui.audio.volume, gui.audio.muted), (80, false));
+        let mouse: MouseConfig = serde_json::from_str(r#"{"right_click": "erase"}"#).unwrap();
+        assert_eq!(
+            (mouse.right_click, mouse.wheel),
+            (RightClick::Erase, Wheel::Cycle)
+        );
+        let cli: CliConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(cli.game_size, 3);
+
+        // Older configurations keep F12 for the screenshots
+        let older = json.replace("\"screenshot_key\": \"F12\",", "");
+        let gui: GUIConfig = serde_json::from_str(&older).unwrap();
+        assert_eq!(gui.screenshot_key, "F12");
+
+        // Without the images of the buttons, they are drawn as text
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value
+            .as_object_mut()
+            .unwrap()
+            .retain(|key, _| !key.starts_with("btn_"));
+        let gui: GUIConfig = serde_json::from_value(value).unwrap();
+        assert!(gui.btn_resume_path.is_empty() && gui.btn_exit_hover_path.is_empty());
+    }
+
+    #[test]
+    fn test_save_settings() {
+        let text = config::default_config(
+            std::path::Path::new("/home/me/.config/sudocurs/games"),
+            std::path::Path::new("/home/me/.config/sudocurs/resources"),
+        );
+        let mut gui: GUIConfig = serde_json::from_str(&config::strip_comments(&text)).unwrap();
+        let mut settings = Settings::from_config(&gui);
+        assert_eq!(settings.volume, 80);
+
+        settings.theme = ThemePreset::Light;
+        settings.difficulty = generator::Difficulty::Expert;
+        settings.validation = Validation::Free;
+        settings.volume = 30;
+        settings.size = 2;
+        settings.apply(&mut gui);
+        assert_eq!(Settings::from_config(&gui), settings);
+        assert_eq!(gui.screenshot_key, "F12");
+
+        // The saved configuration reads back with the new settings and the others untouched
+        let path =
+            std::env::temp_dir().join(format!("sudoku_settings_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        config::write_config(path, &gui).unwrap();
+        let saved: GUIConfig = serde_json::from_str(&config::read_config(path).unwrap()).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(Settings::from_config(&saved), settings);
+        assert_eq!(saved.save_folder_path, gui.save_folder_path);
+        assert_eq!(saved.mouse, gui.mouse);
+    }
+
+    #[test]
+    fn test_themes() {
+        // Without any theme settings, the dark preset is used
+        assert_eq!(
+            Theme::from_config(&ThemeConfig::default()).ok(),
+            Some(Theme::dark())
+        );
+        assert_eq!(Theme::preset(ThemePreset::Light), Theme::light());
+
+        // The colors set replace the ones of the preset
+        let config: ThemeConfig = serde_json::from_str(
+            r##"{"preset": "light", "highlight": "#FF6E32", "error": "#A01E1E80"}"##,
+        )
+        .unwrap();
+        assert_eq!(config.preset, ThemePreset::Light);
+        let theme = Theme::from_config(&config).unwrap();
+        assert_eq!(theme.highlight, Color::RGBA(255, 110, 50, 255));
+        assert_eq!(theme.error, Color::RGBA(160, 30, 30, 128));
+        assert_eq!(theme.background, Theme::light().background);
+
+        let config = ThemeConfig {
+            lines: Some(String::from("yellow")),
+            ..Default::default()
+        };
+        assert!(Theme::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_sdk() {
+        let puzzle =
+            "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+        let rows: Vec<&str> = (0..9).map(|r| &puzzle[r * 9..(r + 1) * 9]).collect();
+        let sdk = format!("#AJohn Doe\n#DA hard one\n#C\n{}\n", rows.join("\n"));
+
+        let game = game::Game::from_sdk(&sdk, None).unwrap();
+        assert_eq!(game.puzzle_id(), puzzle);
+        assert_eq!(
+            game.metadata,
+            vec![
+                ('A', "John Doe".to_string()),
+                ('D', "A hard one".to_string()),
+                ('C', String::new())
+            ]
+        );
+        assert_eq!(game.to_sdk(), sdk);
+
+        // The format is detected when opening a file, which then becomes a regular save
+        let path = "/tmp/sudoku_test_sdk.txt";
+        std::fs::write(path, &sdk).unwrap();
+        let mut game = game::Game::from_file(path).unwrap();
+        assert_eq!(game.puzzle_id(), puzzle);
+        game.do_move(0, 1, 1, Validation::Free).unwrap();
+        drop(game);
+        let game = game::Game::from_file(path).unwrap();
+        assert_eq!(game.grid[1].value(), 1);
+        drop(game);
+        std::fs::remove_file(path).unwrap();
+
+        // Unless it has the .sdk extension, the puzzle being left untouched and the progress
+        // saved next to it, where it is resumed from
+        let (path, progress) = ("/tmp/sudoku_test_sdk.sdk", "/tmp/sudoku_test_sdk.game");
+        std::fs::write(path, &sdk).unwrap();
+        let mut game = game::Game::from_file(path).unwrap();
+        game.do_move(0, 1, 1, Validation::Free).unwrap();
+        game.toggle_note(0, 2, 6).unwrap();
+        let moves = game.history.len();
+        drop(game);
+        assert_eq!(std::fs::read_to_string(path).unwrap(), sdk);
+        let game = game::Game::from_file(path).unwrap();
+        assert_eq!(game.grid[1].value(), 1);
+        assert!(game.grid[2].has_note(6));
+        assert_eq!(game.history.len(), moves);
+        assert_eq!(game.metadata.len(), 3);
+        drop(game);
+
+        // Another puzzle of the same name starts over
+        std::fs::write(path, sdk.replace("4.....8.5", "5.....8.4")).unwrap();
+        let game = game::Game::from_file(path).unwrap();
+        assert_eq!(game.grid[1].value(), 0);
+        drop(game);
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(progress).unwrap();
+
+        assert!(game::Game::from_sdk("#ANobody\n123\n", None).is_err());
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = "1,0,3,0\n4,0,0,0\n2,0,0,1\n0,3,0,4\n";
+        let game = game::Game::from_csv(csv, None).unwrap();
+        assert_eq!(game.puzzle_id(), "1.3.4...2..1.3.4");
+        assert_eq!(game.to_csv(), csv);
+        // Spreadsheets may leave the empty cells out, or separate the values with semicolons
+        let game = game::Game::from_csv("1;;3;\n4;;;\n\n2;;;1\n;3;;4\n", None).unwrap();
+        assert_eq!(game.to_csv(), csv);
+
+        // The flags tell the placed values from the clues
+        let flagged = "1,2,3,0,ININ\n4,0,0,0,INNN\n2,0,0,1,INNI\n0,3,0,4,NINI\n";
+        let game = game::Game::from_csv(flagged, None).unwrap();
+        assert_eq!(game.puzzle_id(), "1.3.4...2..1.3.4");
+        assert_eq!(game.grid[1].value(), 2);
+        assert!(!game.grid[1].initial());
+        assert_eq!(game.to_csv(), flagged);
+
+        for csv in [
+            "",
+            "1,0,3\n4,0,0\n2,0,0",
+            "1,0,3,5\n4,0,0,0\n2,0,0,1\n0,3,0,4",
+            "1,0,3,0,IN\n4,0,0,0\n2,0,0,1\n0,3,0,4",
+            "a,b,c,d\n4,0,0,0\n2,0,0,1\n0,3,0,4",
+        ] {
+            assert!(matches!(
+                game::Game::from_csv(csv, None),
+                Err(GameError::InvalidPuzzle)
+            ));
+        }
+
+        // Files with the .csv extension are opened, their progress being saved next to them
+        let path = std::env::temp_dir().join(format!("sudoku_csv_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        let progress = path.replace(".csv", ".game");
+        std::fs::write(path, flagged).unwrap();
+        let mut game = game::Game::from_file(path).unwrap();
+        game.do_move(0, 3, 4, Validation::Free).unwrap();
+        game.toggle_note(1, 1, 3).unwrap();
+        drop(game);
+        assert_eq!(std::fs::read_to_string(path).unwrap(), flagged);
+        let game = game::Game::from_file(path).unwrap();
+        assert_eq!(game.grid[3].value(), 4);
+        assert!(game.grid[5].has_note(3));
+        assert_eq!(game.history.len(), 1);
+        drop(game);
+        std::fs::remove_file(&progress).unwrap();
+        let game = game::Game::read(path).unwrap();
+        assert_eq!(game.grid[1].value(), 2);
+        assert_eq!(
+            print::load_games(path).unwrap()[0]
+                .as_ref()
+                .unwrap()
+                .nb_non_empty(),
+            7
+        );
+
+        // And written by the export command
+        let out = std::env::temp_dir().join(format!("sudoku_csv_{}_out.CSV", std::process::id()));
+        let out = out.to_str().unwrap();
+        export::export(&game, out, &ExportOptions::default()).unwrap();
+        assert_eq!(std::fs::read_to_string(out).unwrap(), game.to_csv());
+        std::fs::remove_file(out).unwrap();
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_save_format() {
+        let path = "/tmp/sudoku_test_save_format.game";
+        let mut game = game::Game::from_puzzle("1.3.4...2..1.3.4", Some(path)).unwrap();
+        game.difficulty = Some(generator::Difficulty::Hard);
+        game.timer = Timer::resume_from(std::time::Duration::from_secs(75));
+        game.selected_index = Some(1);
+        game.toggle_note(0, 1, 2).unwrap();
+        game.do_move(1, 1, 3, Validation::Free).unwrap();
+        game.do_move(3, 0, 2, Validation::Free).unwrap();
+        game.undo().unwrap();
+        drop(game);
+
+        // The save is versioned JSON
+        let content = std::fs::read_to_string(path).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(json["version"], 1);
+
+        let game = game::Game::from_file(path).unwrap();
+        assert_eq!(game.puzzle_id(), "1.3.4...2..1.3.4");
+        assert_eq!(game.selected_index, Some(1));
+        assert!(game.grid[1].has_note(2));
+        assert_eq!(game.history.len(), 1);
+        assert_eq!(game.undone.len(), 1);
+        assert_eq!(game.timer.elapsed().as_secs(), 75);
+        assert_eq!(game.difficulty, Some(generator::Difficulty::Hard));
+        drop(game);
+
+        // Saves of the previous versions can still be resumed
+        let cells = format!("1/I,0/N,0/N,2/N{}", ",0/N".repeat(77));
+        let notes = format!("0,4{}", ",0".repeat(79));
+        let legacy = format!(
+            "game_size: 3\nselected: 3\ncells: {}\nnotes: {}\n",
+            cells, notes
+        );
+        std::fs::write(path, legacy).unwrap();
+        let game = game::Game::from_file(path).unwrap();
+        assert_eq!(game.puzzle_id(), format!("1{}", ".".repeat(80)));
+        assert_eq!(game.grid[3].value(), 2);
+        assert_eq!(game.selected_value, Some(2));
+        assert!(game.grid[1].has_note(2));
+        drop(game);
+
+        // Saves of unknown versions are refused
+        std::fs::write(path, content.replace("\"version\":1", "\"version\":99")).unwrap();
+        assert!(matches!(
+            game::Game::from_file(path),
+            Err(GameError::IncorrectSaveFile)
+        ));
+
+        // As are the cells and the annotations not fitting the grid
+        let tampered = [
+            ("/cells/2", serde_json::json!(200u64 << 32)),
+            ("/cells/2", serde_json::json!(1)),
+            ("/cells/2", serde_json::json!(1 << 5)),
+            ("/solution", serde_json::json!(vec![2; 16])),
+            ("/guesses", serde_json::json!([2])),
+            ("/guesses", serde_json::json!([1, 0])),
+            (
+                "/annotations",
+                serde_json::json!({"cages": [], "texts": [], "colors": [[16, "#FF0000"]]}),
+            ),
+        ];
+        for (pointer, value) in tampered {
+            let mut json = json.clone();
+            *json.pointer_mut(pointer).unwrap() = value;
+            std::fs::write(path, json.to_string()).unwrap();
+            assert!(matches!(
+                game::Game::from_file(path),
+                Err(GameError::IncorrectSaveFile)
+            ));
+        }
+
+        // The solution found for the moves checked against it is kept
+        let mut json = json.clone();
+        let solution = vec![1, 2, 3, 4, 4, 3, 2, 1, 2, 4, 3, 1, 1, 3, 2, 4];
+        json["solution"] = serde_json::json!(solution);
+        std::fs::write(path, json.to_string()).unwrap();
+        let game = game::Game::from_file(path).unwrap();
+        assert_eq!(game.solution, Some(solution));
+        drop(game);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_masks() {
+        // The candidates kept in the masks are the valid values of the cells
+        let mut game = game::Game::from_puzzle("1.3.4...2..1.3.4", None).unwrap();
+        let mut masks = solver::Masks::new(&game);
+        for index in 0..game.grid.len() {
+            let mut valids = game.valids(index);
+            valids.sort();
+            if game.grid[index] == 0 {
+                assert_eq!(solver::values_of(masks.candidates(index)), valids);
+            }
+        }
+        assert_eq!(masks.candidates(5), 0b100);
+        masks.place(1, 2);
+        assert_eq!(masks.candidates(5), 0);
+        masks.remove(1, 2);
+        assert_eq!(solver::values_of(masks.candidates(5)), vec![2]);
+
+        // Big grids are filled without running out of stack
+        for size in [4, 5] {
+            game = game::Game::new(size, None).unwrap();
+            assert!(game.fill_rng(0));
+            assert!(game.is_done());
+        }
+    }
+
+    #[test]
+    fn test_dlx() {
+        use crate::solver::{Solver, SolverKind};
+
+        // Same solution as the backtracking solver
+        let puzzle =
+            "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+        let mut game = game::Game::from_puzzle(puzzle, None).unwrap();
+        let mut expected = game::Game::from_puzzle(puzzle, None).unwrap();
+        assert!(solver::Backtracking.solve(&mut expected).is_ok());
+        assert_eq!(solver::Dlx.count_solutions(&mut game, 2), 1);
+        assert!(solver::Dlx.solve(&mut game).is_ok());
+        assert!(game.is_done());
+        assert_eq!(game.puzzle_id(), expected.puzzle_id());
+
+        // The solver gives up once it tried as many choices as it was allowed
+        let mut game = game::Game::from_puzzle(puzzle, None).unwrap();
+        assert!(matches!(
+            solver::solve_within(&mut game, 10),
+            Err(errors::SolverError::OutOfNodes)
+        ));
+        assert!(solver::solve_within(&mut game, 100_000).is_ok());
+        assert_eq!(game.puzzle_id(), expected.puzzle_id());
+
+        // Grids with several solutions or conflicting values are not solved
+        let mut game = game::Game::new(2, None).unwrap();
+        assert_eq!(solver::Dlx.count_solutions(&mut game, 5), 5);
+        assert_eq!(solver::Dlx.count_solutions(&mut game, 1000), 288);
+        assert!(solver::Dlx.solve(&mut game).is_err());
+        let mut game = game::Game::from_puzzle("11..............", None).unwrap();
+        assert_eq!(solver::Dlx.count_solutions(&mut game, 2), 0);
+
+        // Big grids are counted like the backtracking solver does
+        let mut game = game::Game::new(5, None).unwrap();
+        game.fill_rng(0);
+        let cells = game.grid.len();
+        for index in (0..cells).step_by(3) {
+            game.grid[index] = game::Cell::new(0, false);
+        }
+        let count = solver::Dlx.count_solutions(&mut game, 2);
+        assert!(count >= 1);
+        assert_eq!(count, solver::count_solutions(&mut game, 2));
+
+        // The solver of the generation is picked in the configuration
+        let config: GenerationConfig = serde_json::from_str(r#"{"solver": "dlx"}"#).unwrap();
+        assert_eq!(config.solver, SolverKind::Dlx);
+        let game = generator::generate_seeded(3, generator::Difficulty::Hard, &config, 7);
+        let mut copy = game::Game::from_puzzle(&game.puzzle_id(), None).unwrap();
+        assert_eq!(solver::count_solutions(&mut copy, 2), 1);
+    }
+
+    #[test]
+    fn test_generate_batch() {
+        let config = GenerationConfig::default();
+        let puzzles = generator::generate_batch(2, generator::Difficulty::Medium, &config, 5);
+        assert_eq!(puzzles.len(), 5);
+        for puzzle in puzzles.iter() {
+            let mut copy = game::Game::from_puzzle(&puzzle.puzzle_id(), None).unwrap();
+            assert_eq!(solver::count_solutions(&mut copy, 2), 1);
+        }
+
+        // The JSON records carry the solution and the rating of the puzzles
+        let record = generator::PuzzleRecord::new(&puzzles[0]);
+        assert_eq!(record.solution.len(), 16);
+        assert!(!record.solution.contains('.'));
+        assert_eq!(record.difficulty, Some(generator::Difficulty::Medium));
+        let json = serde_json::to_string(&vec![record.clone()]).unwrap();
+        let records: Vec<generator::PuzzleRecord> = serde_json::from_str(&json).unwrap();
+        assert_eq!(records, vec![record]);
+    }
+
+    #[test]
+    fn test_generate_seeded() {
+        let config = GenerationConfig::default();
+        let game = generator::generate_seeded(2, generator::Difficulty::Hard, &config, 42);
+        let again = generator::generate_seeded(2, generator::Difficulty::Hard, &config, 42);
+        assert_eq!(game.puzzle_id(), again.puzzle_id());
+        assert_eq!(game.difficulty, Some(generator::Difficulty::Hard));
+
+        // The puzzle is valid, with a single solution
+        let mut copy = game::Game::from_puzzle(&game.puzzle_id(), None).unwrap();
+        assert_eq!(solver::count_solutions(&mut copy, 2), 1);
+        let solution = game.solution.unwrap();
+        assert!(game
+            .grid
+            .iter()
+            .zip(solution)
+            .all(|(cell, value)| *cell == 0 || *cell == value));
+    }
+
+    #[test]
+    fn test_generate_seeded_grids() {
+        // The puzzles of these seeds, which must not change from a run or a platform to another
+        let config = GenerationConfig::default();
+        for (size, difficulty, seed, puzzle) in [
+            (2, generator::Difficulty::Hard, 42, ".....3.4....1.2."),
+            (
+                3,
+                generator::Difficulty::Easy,
+                1,
+                ".9....5.4..17.....4....5.81.75.6893.96.37.15..385.146754...76197.26...436....3.75",
+            ),
+            (
+                3,
+                generator::Difficulty::Medium,
+                7,
+                "...27....319.584.2..59..8.348...6.515.......4..2.....8....6....1...2.9.59..5...8.",
+            ),
+            (
+                4,
+                generator::Difficulty::Easy,
+                2,
+                "A6.CF4.19.B..52.F5....C3.D279AG....GEA6.3.....4..327.9D5..E.8.1C.CA6..5..794.1F...5...47FC.6..B3.GF..8.9.A5.4...E1.4CF2..3G......79...BE.F.C...4..3192.4.57.G...BF...3..124.......G2718FBE.95.3A.A.9..EG.46..3D.G2.3........B457..7.46.8C..1AG.28.4F2.9.7.A3.E6.",
+            ),
+        ] {
+            let game = generator::generate_seeded(size, difficulty, &config, seed);
+            assert_eq!(game.puzzle_id(), puzzle, "seed {}", seed);
+        }
+
+        // Along with the regions of jigsaw puzzles
+        let config = GenerationConfig {
+            jigsaw: true,
+            ..GenerationConfig::default()
+        };
+        let game = generator::generate_seeded(3, generator::Difficulty::Medium, &config, 3);
+        assert_eq!(
+            game.puzzle_id(),
+            ".9.2..1...4.9....5..57..4.87......9....6....16.4....8..823..9.7.36891.7497..382.."
+        );
+        let regions: String = game.regions().iter().map(|r| r.to_string()).collect();
+        assert_eq!(
+            regions,
+            "300011222300111222300115552300411522366455555367444444367774888366777778366688888"
+        );
+
+        let config = GenerationConfig {
+            variant: Variant::Diagonal,
+            ..GenerationConfig::default()
+        };
+        let game = generator::generate_seeded(3, generator::Difficulty::Medium, &config, 9);
+        assert_eq!(
+            game.puzzle_id(),
+            ".3.78....47.23.9...8....32...8...2..3..1.2.8.........9..45..8....3.7...6....4..72"
+        );
+    }
+
+    #[test]
+    fn test_mistakes() {
+        let path = "/tmp/sudoku_test_mistakes.game";
+        let mut game = game::Game::from_puzzle("1.3.4...2..1.3.4", Some(path)).unwrap();
+
+        // Only the values breaking the rules count as mistakes
+        assert!(game.do_move(0, 1, 1, Validation::Strict).is_err());
+        assert!(game.do_move(0, 0, 2, Validation::Strict).is_err());
+        assert!(game.do_move(0, 1, 2, Validation::Strict).is_ok());
+        assert_eq!(game.mistakes, 1);
+
+        // They are kept in the save, along with the hints used
+        game.hints = 2;
+        game.save().unwrap();
+        drop(game);
+        let game = game::Game::from_file(path).unwrap();
+        assert_eq!((game.mistakes, game.hints), (1, 2));
+        drop(game);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_grader() {
+        use crate::grader::Technique;
+
+        // The hardest technique needed gives the grade, and the label of the puzzle
+        for (puzzle, hardest, difficulty) in [
+            (
+                "4.....938.32.941...953..24.37.6.9..4529..16736.47.3.9.957..83....39..4..24..3.7.9",
+                Technique::PointingPair,
+                generator::Difficulty::Medium,
+            ),
+            (
+                "720096003000205000080004020000000060106503807040000000030800090000702000200430018",
+                Technique::HiddenPair,
+                generator::Difficulty::Medium,
+            ),
+            (
+                "1.....569492.561.8.561.924...964.8.1.64.1....218.356.4.4.5...169.5.614.2621.....5",
+                Technique::XWing,
+                generator::Difficulty::Expert,
+            ),
+            (
+                "..8..3....9...867....97..2...1...2.6....4..1.....9.5.436.2..7...824.7.35..7......",
+                Technique::XYWing,
+                generator::Difficulty::Expert,
+            ),
+            (
+                "..43..7.2..65..94...........7.49..136...3...9.19........265.17....72.5.4......2..",
+                Technique::Swordfish,
+                generator::Difficulty::Expert,
+            ),
+        ] {
+            let game = game::Game::from_puzzle(puzzle, None).unwrap();
+            let grade = game.rating();
+            assert!(grade.solved);
+            assert_eq!(grade.hardest, Some(hardest));
+            assert_eq!(grade.difficulty(), difficulty);
+        }
+
+        // The techniques compose into solvers, which fill the grid when they are enough
+        let puzzle =
+            "1.....569492.561.8.561.924...964.8.1.64.1....218.356.4.4.5...169.5.614.2621.....5";
+        let mut game = game::Game::from_puzzle(puzzle, None).unwrap();
+        assert!(solver::Human(&[Technique::NakedSingle])
+            .solve(&mut game)
+            .is_err());
+        assert!(!game.is_filled());
+        let (steps, solved) = grader::solve_with(&mut game, &grader::LADDER);
+        assert!(solved && game.is_done());
+        assert!(steps.iter().any(|step| step.technique == Technique::XWing));
+        let mut game = game::Game::from_puzzle(puzzle, None).unwrap();
+        assert!(SolverKind::Human.solve(&mut game).is_ok());
+        assert!(game.is_done());
+
+        // The trace places every missing value, the X-wing removing candidates on the way
+        let game = game::Game::from_puzzle(puzzle, None).unwrap();
+        let steps = SolverKind::Dlx.solve_with_trace(&game);
+        assert_eq!(
+            steps.iter().filter(|step| step.placed.is_some()).count(),
+            game.nb_empty()
+        );
+        let x_wing = steps
+            .iter()
+            .find(|step| step.technique == Technique::XWing)
+            .unwrap();
+        assert!(x_wing.placed.is_none() && !x_wing.eliminated.is_empty());
+        assert!(x_wing.describe(&game).starts_with("X-wing: remove "));
+        let placing = steps.iter().find(|step| step.placed.is_some()).unwrap();
+        let (index, value) = placing.placed.unwrap();
+        assert!(placing.describe(&game).ends_with(&format!(
+            "place {} at row {}, column {}.",
+            value,
+            index / 9 + 1,
+            index % 9 + 1
+        )));
+        assert!(!game.is_filled());
+        assert!(SolverKind::Obvious.solve_with_trace(&game).len() < steps.len());
+
+        // Puzzles beyond the ladder get the highest score
+        let game = game::Game::from_puzzle(
+            "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..",
+            None,
+        )
+        .unwrap();
+        assert!(!game.rating().solved);
+        assert_eq!(game.rating().score(), 12);
+        assert_eq!(
+            game.rating().to_string(),
+            "Expert (beyond the known techniques)"
+        );
+
+        // Expert puzzles are generated until the grader rates one as such
+        let game = generator::generate_seeded(
+            3,
+            generator::Difficulty::Expert,
+            &GenerationConfig::default(),
+            7,
+        );
+        assert_eq!(game.rating().difficulty(), generator::Difficulty::Expert);
+
+        // Only the initial values are graded
+        let mut game = game::Game::from_puzzle("1.3.4...2..1.3.4", None).unwrap();
+        let rating = game.rating();
+        game.do_move(0, 1, 2, Validation::Strict).unwrap();
+        assert_eq!(grader::grade(&game), rating);
+    }
+
+    #[test]
+    fn test_large_grids() {
+        // The values above 9 are written and typed as letters from A
+        assert_eq!(game::value_symbol(16), 'G');
+        assert_eq!(game::symbol_value('g'), Some(16));
+        assert_eq!(letter_value_from_keycode(Keycode::A, 16), Some(10));
+        assert_eq!(letter_value_from_keycode(Keycode::G, 16), Some(16));
+        assert_eq!(letter_value_from_keycode(Keycode::H, 16), None);
+        assert_eq!(letter_value_from_keycode(Keycode::P, 25), Some(25));
+        assert_eq!(letter_value_from_keycode(Keycode::A, 9), None);
+        assert_eq!(letter_value_from_keycode(Keycode::Num1, 16), None);
+
+        // Two character values keep the columns of the text grid aligned
+        let mut game = game::Game::new(4, None).unwrap();
+        game.do_move(0, 0, 16, Validation::Strict).unwrap();
+        game.do_move(0, 1, 9, Validation::Strict).unwrap();
+        let text = game.to_string();
+        let lengths: Vec<usize> = text
+            .lines()
+            .skip(2)
+            .map(|l| String::from_utf8(strip_colors(l)).unwrap().chars().count())
+            .collect();
+        assert!(lengths.iter().all(|l| *l == lengths[0]));
+
+        // Textures bigger than the cells are shrunk, the others only centered
+        let cell = Rect::new(10, 10, 20, 20);
+        assert_eq!(fit_centered(40, 20, cell), Rect::new(10, 15, 20, 10));
+        assert_eq!(fit_centered(10, 10, cell), Rect::new(15, 15, 10, 10));
+    }
+
+    /// Returns `text` without its terminal color codes.
+    fn strip_colors(text: &str) -> Vec<u8> {
+        let mut plain = Vec::new();
+        let mut escaped = false;
+        for byte in text.bytes() {
+            match (escaped, byte) {
+                (false, 0x1b) => escaped = true,
+                (true, b'm') => escaped = false,
+                (false, _) => plain.push(byte),
+                _ => {}
+            }
+        }
+
+        plain
+    }
+
+    #[test]
+    fn test_validation_modes() {
+        let mut game = game::Game::from_puzzle("1.3.4...2..1.3.4", None).unwrap();
+
+        // The cells a value conflicts with, flashed in the GUI when it is refused
+        assert_eq!(game.conflicting_cells(1, 1), vec![0]);
+        assert_eq!(game.conflicting_cells(5, 3), vec![13]);
+        assert!(game.conflicting_cells(5, 2).is_empty());
+        assert_eq!(game.remaining_counts(), vec![2, 3, 2, 2]);
+
+        // Lenient mode accepts a conflicting value, but counts and reports it
+        assert!(game.do_move(0, 1, 1, Validation::Lenient).is_ok());
+        assert_eq!(game.mistakes, 1);
+        assert_eq!(game.conflicts(), vec![1]);
+        assert!(game.do_move(0, 3, 2, Validation::Lenient).is_ok());
+        assert_eq!(game.mistakes, 1);
+
+        // Without validation nothing is counted
+        assert!(game.do_move(1, 1, 4, Validation::Free).is_ok());
+        assert_eq!(game.mistakes, 1);
+        assert_eq!(game.conflicts(), vec![1, 5]);
+        assert_eq!(game.remaining_counts(), vec![1, 2, 2, 1]);
+
+        // 'off' is accepted in the configuration
+        let validation: Validation = serde_json::from_str("\"off\"").unwrap();
+        assert_eq!(validation, Validation::Free);
+    }
+
+    #[test]
+    fn test_share_code() {
+        // Only the clues are shared, not the values placed by the player
+        let mut game = game::Game::from_puzzle("1.3.4...2..1.3.4", None).unwrap();
+        game.do_move(0, 1, 4, Validation::Free).unwrap();
+        let code = game.to_share_code();
+        assert!(code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        let shared = game::Game::from_share_code(&code, None).unwrap();
+        assert_eq!(shared.puzzle_id(), "1.3.4...2..1.3.4");
+        assert_eq!(shared.grid[1].value(), 0);
+        assert_eq!(shared.variant, Variant::Classic);
+        assert!(!shared.is_jigsaw() && shared.cages.is_empty());
+
+        // A 9x9 puzzle fits in far fewer characters than its 81 cells
+        let config = GenerationConfig {
+            jigsaw: true,
+            ..Default::default()
+        };
+        let mut puzzle = generator::generate_seeded(3, generator::Difficulty::Medium, &config, 5);
+        puzzle.variant = Variant::Diagonal;
+        puzzle.cages = vec![
+            Cage {
+                cells: vec![0, 1, 9],
+                sum: 12,
+            },
+            Cage {
+                cells: vec![80],
+                sum: 4,
+            },
+        ];
+        let code = puzzle.to_share_code();
+        let shared = game::Game::from_share_code(&format!(" {}\n", code), None).unwrap();
+        assert_eq!(shared.puzzle_id(), puzzle.puzzle_id());
+        assert_eq!(shared.variant, Variant::Diagonal);
+        assert_eq!(shared.regions(), puzzle.regions());
+        assert_eq!(shared.cages, puzzle.cages);
+
+        // Codes of another version, with other characters or cut short are refused
+        for code in [
+            &code[..code.len() / 2],
+            "not a code!",
+            "",
+            &code.replacen('A', "B", 1),
+        ] {
+            assert!(matches!(
+                game::Game::from_share_code(code, None),
+                Err(GameError::InvalidPuzzle)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_from_text() {
+        // Pasted puzzles may be split in rows, drawn with borders or be share codes
+        let puzzle = "1.3.4...2..1.3.4";
+        let game = game::Game::from_text(&format!("{}\n", puzzle), None).unwrap();
+        assert_eq!(game.puzzle_id(), puzzle);
+        let printed =
+            print::render_grid(2, &[1, 0, 3, 0, 4, 0, 0, 0, 2, 0, 0, 1, 0, 3, 0, 4], true);
+        let game = game::Game::from_text(&printed, None).unwrap();
+        assert_eq!(game.puzzle_id(), puzzle);
+        let game = game::Game::from_text(&game.to_share_code(), None).unwrap();
+        assert_eq!(game.puzzle_id(), puzzle);
+
+        let printed =
+            print::render_grid(2, &[1, 0, 3, 0, 4, 0, 0, 0, 2, 0, 0, 1, 0, 3, 0, 4], false);
+        let game = game::Game::from_text(&printed, None).unwrap();
+        assert_eq!(game.puzzle_id(), puzzle);
+
+        // Grids pasted from forums, with a title, borders and any of the blanks
+        let puzzle =
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        let forum = "Puzzle #12 (rated 3.5)\n\
+            +-------+-------+-------+\n\
+            | 5 3 * | 0 7 0 | . . . |\n\
+            | 6 _ . | 1 9 5 | . . . |\n\
+            | . 9 8 | . . . | . 6 . |\n\
+            +-------+-------+-------+\n\
+            | 8 . . | . 6 . | . . 3 |\n\
+            | 4 . . | 8 . 3 | . . 1 |\n\
+            | 7 . . | . 2 . | . . 6 |\n\
+            |-------+-------+-------|\n\
+            | . 6 . | . . . | 2 8 . |\n\
+            | . . . | 4 1 9 | . . 5 |\n\
+            | . . . | . 8 . | . 7 9 |\n\
+            +-------+-------+-------+\n\
+            Good luck!";
+        assert_eq!(
+            game::Game::from_text(forum, None).unwrap().puzzle_id(),
+            puzzle
+        );
+        let rows: Vec<String> = puzzle
+            .as_bytes()
+            .chunks(9)
+            .map(|row| String::from_utf8(row.to_vec()).unwrap().replace('.', "0"))
+            .collect();
+        let spaced = rows
+            .iter()
+            .map(|row| row.chars().map(String::from).collect::<Vec<_>>().join(" "))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(
+            game::Game::from_text(&spaced, None).unwrap().puzzle_id(),
+            puzzle
+        );
+        // The empty cells left blank, a column per cell between the borders
+        let blank = rows
+            .iter()
+            .map(|row| {
+                let row = row.replace('0', " ");
+                format!("|{}|{}|{}|", &row[0..3], &row[3..6], &row[6..9])
+            })
+            .collect::<Vec<_>>()
+            .join("\n---+---+---\n");
+        assert_eq!(
+            game::Game::from_text(&blank, None).unwrap().puzzle_id(),
+            puzzle
+        );
+
+        for text in ["", "1.3.4", "hello world"] {
+            assert!(matches!(
+                game::Game::from_text(text, None),
+                Err(GameError::InvalidPuzzle)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_timings() {
+        // The timings shown by the debug overlay are the last ones measured
+        for i in 0..10 {
+            assert_eq!(logging::timed("test timing", || i * 2), i * 2);
+        }
+        let timings = logging::timings();
+        assert!(timings.len() <= 6);
+        assert!(timings.iter().any(|(label, _)| *label == "test timing"));
+    }
 }
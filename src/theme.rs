@@ -0,0 +1,101 @@
+use sdl2::pixels::Color;
+
+use crate::errors::UiError;
+use crate::game_screen::parse_color;
+use crate::traits::{ThemeConfig, ThemePreset};
+
+/**
+ * The colors the game screen is drawn with.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    /// Color of the window behind the grid.
+    pub background: Color,
+    /// Color of the lines of the grid.
+    pub lines: Color,
+    /// Color of the texts around the grid, and of the notes.
+    pub text: Color,
+    /// Color of the values of the puzzle.
+    pub initial: Color,
+    /// Color of the values placed by the player.
+    pub entered: Color,
+    /// Color of the cells holding a value placed by the player.
+    pub entered_cell: Color,
+    /// Color of the cells holding the highlighted value, and of the selection.
+    pub highlight: Color,
+    /// Color of the cells holding a conflicting value.
+    pub error: Color,
+}
+
+impl Theme {
+    /**
+     * Light values and yellow lines on a black background.
+     */
+    pub fn dark() -> Self {
+        Theme {
+            background: Color::BLACK,
+            lines: Color::RGBA(255, 220, 0, 255),
+            text: Color::WHITE,
+            initial: Color::WHITE,
+            entered: Color::WHITE,
+            entered_cell: Color::RGBA(75, 75, 75, 255),
+            highlight: Color::RGBA(255, 110, 50, 255),
+            error: Color::RGBA(160, 30, 30, 255),
+        }
+    }
+
+    /**
+     * Dark values and lines on an off-white background, the entered values being blue.
+     */
+    pub fn light() -> Self {
+        Theme {
+            background: Color::RGBA(245, 245, 240, 255),
+            lines: Color::RGBA(40, 40, 40, 255),
+            text: Color::BLACK,
+            initial: Color::BLACK,
+            entered: Color::RGBA(30, 80, 190, 255),
+            entered_cell: Color::RGBA(215, 225, 240, 255),
+            highlight: Color::RGBA(255, 200, 120, 255),
+            error: Color::RGBA(240, 150, 150, 255),
+        }
+    }
+
+    /**
+     * Returns the built-in theme `preset`.
+     */
+    pub fn preset(preset: ThemePreset) -> Self {
+        match preset {
+            ThemePreset::Dark => Theme::dark(),
+            ThemePreset::Light => Theme::light(),
+        }
+    }
+
+    /**
+     * Returns the preset of `config`, with the colors it sets replacing the preset ones.
+     */
+    pub fn from_config(config: &ThemeConfig) -> Result<Self, UiError> {
+        let mut theme = Theme::preset(config.preset);
+        for (color, text) in [
+            (&mut theme.background, &config.background),
+            (&mut theme.lines, &config.lines),
+            (&mut theme.text, &config.text),
+            (&mut theme.initial, &config.initial),
+            (&mut theme.entered, &config.entered),
+            (&mut theme.entered_cell, &config.entered_cell),
+            (&mut theme.highlight, &config.highlight),
+            (&mut theme.error, &config.error),
+        ] {
+            if let Some(text) = text {
+                *color = parse_color(text).ok_or(UiError::InvalidThemeColor)?;
+            }
+        }
+
+        Ok(theme)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
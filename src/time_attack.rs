@@ -0,0 +1,174 @@
+use crate::game::Game;
+use crate::generator::{self, Difficulty, GenerationConfig};
+use crate::profile::{clock, DATE_FORMAT};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Number of puzzles to solve in a time attack.
+pub const PUZZLES: usize = 5;
+/// Seed of the first puzzle of a time attack, the next ones following it, so that every run of
+/// a size and a difficulty plays the same puzzles.
+const FIRST_SEED: u64 = 7_041;
+/// Seconds added to the time of a puzzle for each mistake.
+pub const MISTAKE_PENALTY_SECS: u64 = 10;
+/// Seconds added to the time of a puzzle for each hint used.
+pub const HINT_PENALTY_SECS: u64 = 30;
+
+/**
+ * How a puzzle of a time attack was solved.
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Split {
+    /// The play time of the puzzle, in seconds.
+    pub secs: u64,
+    pub mistakes: usize,
+    pub hints: usize,
+}
+
+impl Split {
+    /**
+     * Returns the seconds added to the play time for the mistakes and the hints.
+     */
+    pub fn penalty_secs(&self) -> u64 {
+        self.mistakes as u64 * MISTAKE_PENALTY_SECS + self.hints as u64 * HINT_PENALTY_SECS
+    }
+
+    /**
+     * Returns the play time with the penalties, in seconds.
+     */
+    pub fn score(&self) -> u64 {
+        self.secs + self.penalty_secs()
+    }
+}
+
+/**
+ * A finished time attack, as kept on the leaderboard of a profile.
+ */
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Run {
+    /// The day of the run, written `YYYY-MM-DD`.
+    pub date: String,
+    pub splits: Vec<Split>,
+    /// The play time of all the puzzles with the penalties, in seconds: the lower the better.
+    pub score: u64,
+}
+
+/**
+ * A fixed set of puzzles of a difficulty to solve one after the other against the clock, each
+ * solved puzzle giving a split.
+ */
+pub struct TimeAttack {
+    pub difficulty: Difficulty,
+    /// The puzzles to solve, written like `Game::puzzle_id`.
+    puzzles: Vec<String>,
+    /// The solved puzzles, in order.
+    pub splits: Vec<Split>,
+}
+
+impl TimeAttack {
+    /**
+     * Returns a time attack on the `PUZZLES` puzzles of grids of `size` and of `difficulty`,
+     * the same ones for every run.
+     */
+    pub fn new(size: usize, difficulty: Difficulty, config: &GenerationConfig) -> Self {
+        let puzzles = (0..PUZZLES as u64)
+            .map(|i| generator::generate_seeded(size, difficulty, config, FIRST_SEED + i))
+            .map(|game| game.puzzle_id())
+            .collect();
+
+        TimeAttack {
+            difficulty,
+            puzzles,
+            splits: Vec::new(),
+        }
+    }
+
+    /**
+     * Returns the number of puzzles to solve.
+     */
+    pub fn len(&self) -> usize {
+        self.puzzles.len()
+    }
+
+    /**
+     * Returns whether there is no puzzle to solve, which `new` does not allow.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.puzzles.is_empty()
+    }
+
+    /**
+     * Returns the puzzle to solve now, `None` once they are all solved.
+     */
+    pub fn current(&self) -> Option<&str> {
+        self.puzzles.get(self.splits.len()).map(String::as_str)
+    }
+
+    /**
+     * Returns whether every puzzle was solved.
+     */
+    pub fn is_over(&self) -> bool {
+        self.splits.len() == self.puzzles.len()
+    }
+
+    /**
+     * Counts the solved `game` as the split of the current puzzle, returning whether it was the
+     * current puzzle: another game does not count.
+     */
+    pub fn finish_puzzle(&mut self, game: &Game) -> bool {
+        if self.current() != Some(game.puzzle_id().as_str()) {
+            return false;
+        }
+
+        self.splits.push(Split {
+            secs: game.timer.elapsed().as_secs(),
+            mistakes: game.mistakes,
+            hints: game.hints,
+        });
+        true
+    }
+
+    /**
+     * Returns the play time of the solved puzzles with their penalties, in seconds.
+     */
+    pub fn score(&self) -> u64 {
+        self.splits.iter().map(Split::score).sum()
+    }
+
+    /**
+     * Returns the run to keep on the leaderboard, finished on `today`.
+     */
+    pub fn run(&self, today: NaiveDate) -> Run {
+        Run {
+            date: today.format(DATE_FORMAT).to_string(),
+            splits: self.splits.clone(),
+            score: self.score(),
+        }
+    }
+
+    /**
+     * Describes the splits of the solved puzzles, one per line, and the score.
+     */
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "Time attack: {} puzzles, {:?}",
+            self.len(),
+            self.difficulty
+        )];
+        for (i, split) in self.splits.iter().enumerate() {
+            lines.push(match split.penalty_secs() {
+                0 => format!("Puzzle {}: {}", i + 1, clock(split.secs)),
+                penalty => format!(
+                    "Puzzle {}: {} (+{} penalty)",
+                    i + 1,
+                    clock(split.secs),
+                    clock(penalty)
+                ),
+            });
+        }
+        lines.push(format!("Score: {}", clock(self.score())));
+
+        lines
+    }
+}
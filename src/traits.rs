@@ -1,4 +1,6 @@
 use crate::errors::UiError;
+use crate::game::Validation;
+use crate::generator::{Difficulty, GenerationConfig};
 
 use sdl2::event::Event;
 use sdl2::render::Canvas;
@@ -10,11 +12,186 @@ pub struct CliConfig {
     /// Games save folder
     pub save_folder_path: String,
 
-    /// Game to resume
-    pub game_resume_path: String,
-
     /// Game size
     pub game_size: usize,
+
+    /// Whether two players take turns on the same board.
+    #[serde(default)]
+    pub hot_seat: bool,
+
+    /// Whether the notes of the new games are kept equal to the candidates after every move.
+    #[serde(default)]
+    pub auto_notes: bool,
+
+    /// Whether placing a value removes its notes from the row, column and box of its cell.
+    #[serde(default = "default_remove_notes")]
+    pub remove_notes: bool,
+
+    /// How the moves are checked: against the other values, against the solution, counting the
+    /// conflicting ones without refusing them, or not at all.
+    #[serde(default)]
+    pub validation_mode: Validation,
+
+    /// Difficulty of the generated puzzles.
+    #[serde(default)]
+    pub difficulty: Difficulty,
+
+    /// Minimum clues, unfill attempts and clues of each difficulty of the generated puzzles.
+    #[serde(default)]
+    pub generation: GenerationConfig,
+}
+
+/// Where the grid is placed in the game window.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GridPlacement {
+    /// The grid is centered, companion widgets go below it.
+    #[default]
+    Centered,
+    /// The grid is anchored to the top left corner, companion widgets go in a side panel.
+    Anchored,
+}
+
+/// How the values are placed with the mouse in the game window.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum InputMode {
+    /// A digit is picked first, in the grid or the digits panel, then placed in every empty cell
+    /// clicked next.
+    #[default]
+    DigitFirst,
+    /// An empty cell is selected first, then filled with the digit clicked in the digits panel.
+    CellFirst,
+}
+
+/// What a right-click on a cell of the grid does.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum RightClick {
+    /// Erases the value the player placed in the cell, or toggles the note of the highlighted
+    /// value in the empty cell.
+    #[default]
+    Note,
+    /// Only erases the value the player placed in the cell.
+    Erase,
+    /// Does nothing.
+    Off,
+}
+
+/// What the mouse wheel does over a cell of the grid.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Wheel {
+    /// Cycles the value of the cell through its candidates, upwards to the next one.
+    #[default]
+    Cycle,
+    /// Cycles the value of the cell through its candidates, downwards to the next one.
+    Reversed,
+    /// Does nothing.
+    Off,
+}
+
+/// What the mouse buttons and wheel do on the grid, besides the left-click selecting a cell.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct MouseConfig {
+    pub right_click: RightClick,
+    pub wheel: Wheel,
+}
+
+/// The sound effects and the background music, played when built with the `audio` feature. The
+/// sounds and the music are left out when their path is empty.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AudioConfig {
+    /// Sound played when a value is placed.
+    pub place_sound_path: String,
+    /// Sound played when a move is refused.
+    pub error_sound_path: String,
+    /// Sound played when the grid is solved.
+    pub victory_sound_path: String,
+    /// Music looped in the background.
+    pub music_path: String,
+    /// Volume of the sounds and of the music, from 0 to 100.
+    pub volume: u8,
+    /// Whether the game starts muted, the `F9` key muting it or not.
+    pub muted: bool,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        AudioConfig {
+            place_sound_path: String::new(),
+            error_sound_path: String::new(),
+            victory_sound_path: String::new(),
+            music_path: String::new(),
+            volume: 80,
+            muted: false,
+        }
+    }
+}
+
+/// The built-in color themes of the game screen.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemePreset {
+    /// Light values and yellow lines on a black background.
+    #[default]
+    Dark,
+    /// Dark values and lines on an off-white background.
+    Light,
+}
+
+/// The colors of the game screen: a preset, some of its colors being replaced by the ones set,
+/// written `#RRGGBB` or `#RRGGBBAA`.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ThemeConfig {
+    /// Built-in theme the colors start from.
+    pub preset: ThemePreset,
+    /// Color of the window behind the grid.
+    pub background: Option<String>,
+    /// Color of the lines of the grid.
+    pub lines: Option<String>,
+    /// Color of the texts around the grid, and of the notes.
+    pub text: Option<String>,
+    /// Color of the values of the puzzle.
+    pub initial: Option<String>,
+    /// Color of the values placed by the player.
+    pub entered: Option<String>,
+    /// Color of the cells holding a value placed by the player.
+    pub entered_cell: Option<String>,
+    /// Color of the cells holding the highlighted value, and of the selection.
+    pub highlight: Option<String>,
+    /// Color of the cells holding a conflicting value.
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct LayoutConfig {
+    /// Minimal margin around the grid, in pixels.
+    pub margin: u32,
+    /// Thickness of the lines between cells.
+    pub thin_line: u32,
+    /// Thickness of the lines between groups.
+    pub thick_line: u32,
+    /// Where the grid is placed in the window.
+    pub placement: GridPlacement,
+    /// Width of the side panel when the grid is anchored.
+    pub side_panel_width: u32,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        LayoutConfig {
+            margin: 40,
+            thin_line: 1,
+            thick_line: 3,
+            placement: GridPlacement::Centered,
+            side_panel_width: 200,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -22,45 +199,182 @@ pub struct GUIConfig {
     /// Path of the games-save folder.
     pub save_folder_path: String,
 
-    /// Path of the game to resume.
-    pub game_resume_path: String,
-
     /// Size of the board.
     pub game_size: usize,
 
+    /// Whether two players take turns on the same board.
+    #[serde(default)]
+    pub hot_seat: bool,
+
+    /// Whether the notes of the new games are kept equal to the candidates after every move.
+    #[serde(default)]
+    pub auto_notes: bool,
+
+    /// Whether placing a value removes its notes from the row, column and box of its cell.
+    #[serde(default = "default_remove_notes")]
+    pub remove_notes: bool,
+
+    /// How the moves are checked: against the other values, against the solution, counting the
+    /// conflicting ones without refusing them, or not at all.
+    #[serde(default)]
+    pub validation_mode: Validation,
+
+    /// Difficulty of the generated puzzles.
+    #[serde(default)]
+    pub difficulty: Difficulty,
+
+    /// Minimum clues, unfill attempts and clues of each difficulty of the generated puzzles.
+    #[serde(default)]
+    pub generation: GenerationConfig,
+
     /// Horizontal resolution of the game window.
     pub res_x: usize,
     /// Vertical resolution of the game window.
     pub res_y: usize,
 
+    /// Path of the window icon, if empty the default icon is kept.
+    #[serde(default)]
+    pub window_icon_path: String,
+
+    /// Margins, line thicknesses and placement of the grid.
+    #[serde(default)]
+    pub layout: LayoutConfig,
+
+    /// Whether a digit or a cell is picked first to place a value with the mouse.
+    #[serde(default)]
+    pub input_mode: InputMode,
+
+    /// What the right-click and the mouse wheel do on the grid.
+    #[serde(default)]
+    pub mouse: MouseConfig,
+
+    /// Delay between the steps of the solver watched with the `W` key, in milliseconds, halved
+    /// or doubled by the `+` and `-` keys while watching.
+    #[serde(default = "default_watch_step_ms")]
+    pub watch_step_ms: u64,
+
+    /// Colors of the game screen, switched with the other preset by the `T` key.
+    #[serde(default)]
+    pub theme: ThemeConfig,
+
     /// Path of the font used to draw the game board.
     pub font_path: String,
 
-    /// Buttons images paths
+    /// Sound effects, background music and their volume.
+    #[serde(default)]
+    pub audio: AudioConfig,
+
+    /// Name of the key saving a screenshot of the window in the save folder, as SDL2 names
+    /// them, `F12` by default. An empty name disables the screenshots.
+    #[serde(default = "default_screenshot_key")]
+    pub screenshot_key: String,
+
+    /// HTTP endpoint the daily puzzle is downloaded from, with the `fetch` feature. The main
+    /// screen only offers it when set.
+    #[serde(default)]
+    pub daily_puzzle_url: String,
+
+    /// Buttons images paths, the buttons without one being drawn as text
+    #[serde(default)]
     pub btn_resume_path: String,
+    #[serde(default)]
     pub btn_new_game_path: String,
+    #[serde(default)]
     pub btn_exit_path: String,
+    #[serde(default)]
     pub btn_resume_hover_path: String,
+    #[serde(default)]
     pub btn_new_game_hover_path: String,
+    #[serde(default)]
     pub btn_exit_hover_path: String,
 }
 
+/// The key saving screenshots when the configuration does not name one.
+fn default_screenshot_key() -> String {
+    String::from("F12")
+}
+
+/// Placing a value removes its notes from the cells it sees unless the configuration says
+/// otherwise.
+fn default_remove_notes() -> bool {
+    true
+}
+
+/// The delay between the watched solver steps when the configuration does not set one.
+fn default_watch_step_ms() -> u64 {
+    1000
+}
+
 pub trait Ui {
     fn new_random_game(&mut self) -> Result<(), UiError>;
+    fn load_puzzle(&mut self, path: &str) -> Result<(), UiError>;
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum ScreenOutcome {
     Unchanged,
     Updated,
     Resume,
     NewGame,
+    /// Open the puzzle pack browser.
+    Packs,
+    /// Open the saved games browser.
+    Saves,
+    /// Resume the game picked in the saved games browser.
+    Load,
+    /// Start the puzzle picked in the pack browser.
+    Play,
+    /// Open the new game screen, to pick the options of the next game.
+    ChooseNewGame,
+    /// Generate a game with the options picked on the new game screen.
+    Generate,
+    /// Go back to the main menu.
+    Menu,
+    /// Leave the game for the main menu, once it is saved.
+    BackToMenu,
+    /// Play the daily puzzle.
+    Daily,
+    /// Play the puzzle in the system clipboard.
+    Paste,
+    /// Open the settings screen.
+    Settings,
+    /// Apply and save the settings picked on the settings screen.
+    SaveSettings,
+    /// Make the next profile the playing one.
+    SwitchProfile,
+    /// Add a profile and make it the playing one.
+    AddProfile,
+    /// Replay the solved game move by move on the game screen.
+    Replay,
+    /// Add the puzzle played to the favorites of the profile playing, or remove it.
+    Favorite,
+    /// Start a time attack, on a fixed set of puzzles against the clock.
+    TimeAttack,
+    /// Start a split-screen race of two players on the same puzzle.
+    Race,
+    /// Open the editor, to build a puzzle by hand.
+    Editor,
+    /// Play the puzzle built in the editor.
+    PlayEdited,
+    /// Open the lobby, to play a puzzle with another instance over the network.
+    Lobby,
+    /// Wait for the other player on the address picked in the lobby.
+    #[cfg(feature = "net")]
+    Host,
+    /// Join the other player at the address picked in the lobby.
+    #[cfg(feature = "net")]
+    Join,
     Exit,
 }
 
 pub trait Displayable {
-    fn new() -> Self;
+    fn new() -> Self
+    where
+        Self: Sized;
     fn init(&mut self, canvas: &mut Canvas<Window>, config: &GUIConfig) -> Result<(), UiError>;
+    /// Draws the screen on `canvas`, which the GUI presents once the debug overlay is drawn.
     fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError>;
+    /// Recomputes the geometry of the screen once the canvas changed size.
+    fn resize(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError>;
     fn update(&mut self, event: &Event) -> Result<ScreenOutcome, UiError>;
 }
@@ -0,0 +1,605 @@
+use crate::annotations;
+use crate::autosave::Autosave;
+use crate::config;
+use crate::errors::{GameError, UiError};
+use crate::game::{symbol_value, value_symbol, CellDiff, Game, Validation, MARKER_COLORS};
+use crate::generator;
+use crate::hint;
+use crate::hotseat::HotSeat;
+use crate::profile::Profiles;
+use crate::saves::SavedGame;
+use crate::traits::{CliConfig, Ui};
+
+use colored::*;
+use std::fs;
+use std::io;
+use std::io::prelude::*;
+
+/// Keys of the interface, shown in the status bar.
+const KEYS: &str = "arrows: move  1-9/A-G: value  0/del: clear  m: notes mode  a: fill notes  \
+                    o: auto notes  k: color  u/r: undo/redo  h: hint  p: pause  f: favorite  \
+                    g: give up  n: next  q: quit";
+
+/// Background colors of the jigsaw regions, neighboring regions never sharing theirs.
+const REGION_COLORS: [(u8, u8, u8); 6] = [
+    (20, 30, 60),
+    (20, 55, 30),
+    (60, 30, 20),
+    (50, 50, 20),
+    (20, 50, 55),
+    (50, 25, 55),
+];
+
+/**
+ * A key read from the terminal.
+ */
+#[derive(Debug, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Delete,
+    Escape,
+    Char(char),
+}
+
+/**
+ * Parses the bytes read from the terminal into keys, the arrows and the delete key being sent as
+ * escape sequences. Unknown sequences are left out.
+ */
+pub fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+    let mut keys = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let (key, length) = match &bytes[i..] {
+            [0x1b, b'[', b'A', ..] => (Some(Key::Up), 3),
+            [0x1b, b'[', b'B', ..] => (Some(Key::Down), 3),
+            [0x1b, b'[', b'C', ..] => (Some(Key::Right), 3),
+            [0x1b, b'[', b'D', ..] => (Some(Key::Left), 3),
+            [0x1b, b'[', b'3', b'~', ..] => (Some(Key::Delete), 4),
+            // Unknown sequences go up to their final letter or tilde
+            [0x1b, b'[', rest @ ..] => {
+                let end = rest
+                    .iter()
+                    .position(|b| b.is_ascii_alphabetic() || *b == b'~')
+                    .map_or(rest.len(), |p| p + 1);
+                (None, 2 + end)
+            }
+            [0x1b, ..] => (Some(Key::Escape), 1),
+            [0x7f | 0x08, ..] => (Some(Key::Delete), 1),
+            [byte, ..] => (Some(Key::Char(*byte as char)), 1),
+            [] => break,
+        };
+        keys.extend(key);
+        i += length;
+    }
+
+    keys
+}
+
+/**
+ * Puts the terminal in raw mode on the alternate screen, reads being given up after a second so
+ * that the clock keeps going, and restores it when dropped.
+ */
+struct RawTerminal {
+    original: libc::termios,
+}
+
+impl RawTerminal {
+    fn enable() -> io::Result<Self> {
+        // SAFETY: the termios structure is filled by tcgetattr before being used
+        let mut termios = unsafe { std::mem::zeroed::<libc::termios>() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let original = termios;
+
+        // Keys are read one by one, without echo nor signals, Ctrl-C quitting like 'q'
+        termios.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG | libc::IEXTEN);
+        termios.c_iflag &= !(libc::IXON | libc::ICRNL);
+        termios.c_cc[libc::VMIN] = 0;
+        termios.c_cc[libc::VTIME] = 10;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        print!("\x1b[?1049h\x1b[?25l");
+        io::stdout().flush()?;
+
+        Ok(RawTerminal { original })
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &self.original) };
+        print!("\x1b[?25h\x1b[?1049l");
+        io::stdout().flush().ok();
+    }
+}
+
+/**
+ * A full-screen terminal interface: the cursor is moved around the grid with the arrows, the
+ * values and notes are typed in place, and a status bar follows the game.
+ */
+pub struct Tui {
+    /// Loaded configuration file, the one of the CLI.
+    config: CliConfig,
+    /// The game being played.
+    game: Game,
+    /// Row and column of the cursor.
+    cursor: (usize, usize),
+    /// Whether the typed values toggle notes instead of being placed.
+    notes_mode: bool,
+    /// Whether the grid is hidden and the clock stopped.
+    paused: bool,
+    /// How each cell compares to the solution, once the player gave up or filled the grid
+    /// without their moves being checked.
+    diff: Option<Vec<CellDiff>>,
+    /// Whether the game is over, the player having given up or filled the grid.
+    ended: bool,
+    /// The last message and whether it is good news rather than an error.
+    message: Option<(String, bool)>,
+    /// The players, the favorite puzzles being the ones of the profile playing.
+    profiles: Profiles,
+    /// Puzzles generated in the background with the configured settings.
+    pool: generator::Pool,
+    /// The running session, to recover its game if it crashes.
+    autosave: Autosave,
+}
+
+impl Tui {
+    pub fn new(config_path: &str) -> Result<Self, UiError> {
+        let config_txt = match config::read_config(config_path) {
+            Ok(config) => config,
+            Err(e) => {
+                log::error!(
+                    "Error while loading the configuration file {}: {}",
+                    config_path,
+                    e
+                );
+
+                return Err(UiError::LoadConfigError(e));
+            }
+        };
+
+        let config: CliConfig = match serde_json::from_str(&config_txt) {
+            Ok(config) => config,
+            Err(e) => {
+                log::error!(
+                    "Error while loading the configuration file {}: {}",
+                    config_path,
+                    e
+                );
+
+                return Err(UiError::ConfigSyntaxError(e));
+            }
+        };
+        config.generation.validate()?;
+        generator::validate_size(config.game_size)?;
+
+        // The game is replaced by a saved or generated one when the game starts
+        let game = Game::new(config.game_size, None)?;
+        let profiles = Profiles::load(&config.save_folder_path)?;
+        let pool = generator::Pool::new(config.game_size, config.difficulty, &config.generation);
+        let autosave = Autosave::start(&config.save_folder_path)?;
+
+        Ok(Tui {
+            config,
+            game,
+            cursor: (0, 0),
+            notes_mode: false,
+            paused: false,
+            diff: None,
+            ended: false,
+            message: None,
+            profiles,
+            pool,
+            autosave,
+        })
+    }
+
+    /**
+     * Resumes the game of the previous session if it crashed, or otherwise the most recent saved
+     * game, returning whether one was resumed.
+     */
+    fn resume_game(&mut self) -> bool {
+        let path = match self.autosave.recovered() {
+            Some(path) => path,
+            None => match SavedGame::list(&self.config.save_folder_path).first() {
+                Some(save) => save.path.clone(),
+                None => return false,
+            },
+        };
+        match Game::from_file(&path) {
+            Ok(game) => {
+                self.play(game);
+                self.message = Some((String::from("Resumed the last game."), true));
+                true
+            }
+            Err(e) => {
+                self.message = Some((format!("Unable to resume the game {}: {}", path, e), false));
+                false
+            }
+        }
+    }
+
+    /**
+     * Makes `game` the one being played, with the configured players and notes.
+     */
+    fn play(&mut self, mut game: Game) {
+        // The turns of a resumed hot-seat game are kept
+        game.hot_seat = game
+            .hot_seat
+            .take()
+            .or_else(|| self.config.hot_seat.then(HotSeat::default));
+        game.remove_notes = self.config.remove_notes;
+        self.game = game;
+        self.cursor = (0, 0);
+        self.diff = None;
+        self.ended = false;
+        self.paused = false;
+        self.message = None;
+    }
+
+    /**
+     * Main loop: draws the screen, then handles the keys typed, until the player quits.
+     */
+    pub fn run(&mut self) -> Result<(), UiError> {
+        // Unless a puzzle was imported, go back to the last game or start with a random one
+        if self.game.save_path.is_none() && !self.resume_game() {
+            self.new_random_game()?;
+        }
+
+        let terminal = RawTerminal::enable().map_err(UiError::TerminalError)?;
+        let mut buffer = [0; 64];
+        'session: loop {
+            print!("{}", self.render());
+            io::stdout().flush().ok();
+
+            // Nothing read after a second, the screen is drawn again for the clock
+            let read = io::stdin().read(&mut buffer).unwrap_or(0);
+            for key in parse_keys(&buffer[..read]) {
+                if !self.handle_key(key)? {
+                    break 'session;
+                }
+            }
+        }
+        drop(terminal);
+
+        // Keep the play time of the game, then end the session cleanly
+        if let Err(e) = Autosave::save(&mut self.game) {
+            log::error!("{}", e);
+        }
+        self.autosave.finish();
+
+        Ok(())
+    }
+
+    /**
+     * Handles a key typed by the player, returning whether they go on playing.
+     */
+    fn handle_key(&mut self, key: Key) -> Result<bool, UiError> {
+        let last = self.game.side_size - 1;
+        let (r, c) = self.cursor;
+
+        // While paused, any key resumes the game
+        if self.paused {
+            self.paused = false;
+            self.game.timer.resume();
+            return Ok(true);
+        }
+
+        match key {
+            Key::Escape | Key::Char('q' | '\u{3}') => return Ok(false),
+            Key::Up => self.cursor = (r.checked_sub(1).unwrap_or(last), c),
+            Key::Down => self.cursor = ((r + 1) % (last + 1), c),
+            Key::Left => self.cursor = (r, c.checked_sub(1).unwrap_or(last)),
+            Key::Right => self.cursor = (r, (c + 1) % (last + 1)),
+            Key::Char('n') => {
+                if let Err(e) = self.new_random_game() {
+                    self.message = Some((e.to_string(), false));
+                }
+            }
+            _ if self.ended => {}
+            Key::Delete | Key::Char('0' | '.') => {
+                let outcome = self.game.clear_cell(r, c);
+                self.report(outcome);
+            }
+            Key::Char(symbol @ ('1'..='9' | 'A'..='Z')) => {
+                let value = symbol_value(symbol).unwrap();
+                if value as usize > self.game.side_size {
+                    return Ok(true);
+                }
+                match self.notes_mode {
+                    true => {
+                        let outcome = self.game.toggle_note(r, c, value);
+                        self.report(outcome);
+                    }
+                    false => {
+                        let outcome = self.game.do_move(r, c, value, self.config.validation_mode);
+                        self.report(outcome);
+                        if self.game.is_filled() {
+                            self.end(false);
+                        }
+                    }
+                }
+            }
+            Key::Char('m') => self.notes_mode = !self.notes_mode,
+            Key::Char('a') => {
+                let outcome = self.game.fill_notes();
+                self.report(outcome);
+            }
+            Key::Char('o') => {
+                let on = !self.game.auto_notes;
+                self.message = Some(match self.game.set_auto_notes(on) {
+                    Ok(()) if on => (String::from("Automatic notes on."), true),
+                    Ok(()) => (String::from("Automatic notes off."), true),
+                    Err(e) => (e.to_string(), false),
+                });
+            }
+            Key::Char('k') => {
+                let index = self.game.index(r, c);
+                let outcome = self.game.cycle_color(&[index]);
+                self.report(outcome);
+            }
+            Key::Char('u') => {
+                let outcome = self.game.undo();
+                self.report(outcome);
+            }
+            Key::Char('r') => {
+                let outcome = self.game.redo();
+                self.report(outcome);
+            }
+            Key::Char('h') => {
+                self.message = match hint::next_hint(&self.game) {
+                    Some(hint) => {
+                        let description = hint.describe(&self.game);
+                        self.game.count_hint(description.clone());
+                        Some((description, true))
+                    }
+                    None => Some((
+                        String::from("No hint found with the known techniques."),
+                        false,
+                    )),
+                };
+            }
+            Key::Char('p') => {
+                self.paused = true;
+                self.game.timer.pause();
+            }
+            Key::Char('f') => {
+                self.message = Some(match self.profiles.toggle_favorite(&self.game) {
+                    Ok(true) => (String::from("Added this puzzle to the favorites."), true),
+                    Ok(false) => (
+                        String::from("Removed this puzzle from the favorites."),
+                        true,
+                    ),
+                    Err(e) => (e.to_string(), false),
+                });
+            }
+            Key::Char('g') => self.end(true),
+            _ => {}
+        }
+
+        Ok(true)
+    }
+
+    /**
+     * Shows the error of `outcome`, if any, the previous message being cleared otherwise.
+     */
+    fn report<T>(&mut self, outcome: Result<T, GameError>) {
+        self.message = outcome.err().map(|e| (e.to_string(), false));
+    }
+
+    /**
+     * Ends the game, comparing the grid to the solution unless the moves were checked as they
+     * were played.
+     */
+    fn end(&mut self, gave_up: bool) {
+        let done = self.game.is_done();
+        if gave_up || !done || self.config.validation_mode == Validation::Free {
+            match self.game.diff() {
+                Ok(diff) => self.diff = Some(diff),
+                Err(e) => self.message = Some((e.to_string(), false)),
+            }
+        }
+        self.game.timer.pause();
+        self.ended = true;
+
+        let mut message = String::from(match (gave_up, done) {
+            (true, _) => "You gave up.",
+            (false, true) => "Grid completed !",
+            (false, false) => "The grid is filled but contains mistakes.",
+        });
+        if let Some(hot_seat) = self.game.hot_seat.as_ref() {
+            message.push(' ');
+            message.push_str(&hot_seat.summary());
+        }
+        message.push_str(" Press n for the next puzzle.");
+        self.message = Some((message, done && !gave_up));
+    }
+
+    /**
+     * Returns how the cell at `index` is drawn: its symbol, three characters wide, colored after
+     * the cursor, the highlighted value, the conflicts and the comparison to the solution. On
+     * jigsaw grids, the cell is shaded with the color of its region among `region_colors`.
+     */
+    fn render_cell(&self, index: usize, conflicts: &[usize], region_colors: &[usize]) -> String {
+        let cell = &self.game.grid[index];
+        let (r, c) = self.game.coordinates(index);
+        let cursor_index = self.game.index(self.cursor.0, self.cursor.1);
+        let cursor_value = self.game.grid[cursor_index].value();
+
+        let text = match (cell.value(), self.diff.as_ref().map(|d| d[index])) {
+            (_, Some(CellDiff::Empty(expected) | CellDiff::Wrong(expected))) => {
+                format!(" {} ", value_symbol(expected))
+            }
+            (0, _) if cell.notes() != 0 => String::from(" · "),
+            (0, _) if cell.color() != 0 => String::from(" * "),
+            (0, _) => String::from("   "),
+            (value, _) => format!(" {} ", value_symbol(value)),
+        };
+        let mut text = match (cell.value(), self.diff.as_ref().map(|d| d[index])) {
+            (_, Some(CellDiff::Empty(_))) => text.bright_black(),
+            (_, Some(CellDiff::Wrong(_))) => text.bright_red(),
+            (_, Some(CellDiff::Correct)) if !cell.initial() => text.bright_green(),
+            // The cells painted with a marker color are written in it
+            (_, None) if cell.color() != 0 => {
+                let (red, green, blue) = MARKER_COLORS[cell.color() as usize - 1];
+                text.truecolor(red, green, blue).bold()
+            }
+            (0, _) => text.bright_black(),
+            (value, _) if value == cursor_value => text.bright_yellow().bold(),
+            _ if cell.initial() => text.bold(),
+            _ => text.cyan(),
+        };
+
+        // The row, column and box of the cursor are shaded, the conflicts shown on red
+        let (cr, cc) = self.cursor;
+        let same_box = self.game.regions()[index] == self.game.regions()[cursor_index];
+        if (r, c) == self.cursor {
+            text = text.reversed();
+        } else if self.diff.is_none() && conflicts.contains(&index) {
+            text = text.on_red();
+        } else if r == cr || c == cc || same_box {
+            text = text.on_truecolor(45, 45, 45);
+        } else if self.game.is_jigsaw() {
+            let (red, green, blue) =
+                REGION_COLORS[region_colors[self.game.regions()[index]] % REGION_COLORS.len()];
+            text = text.on_truecolor(red, green, blue);
+        }
+
+        text.to_string()
+    }
+
+    /**
+     * Returns the whole screen: the grid, then the status bar with the notes of the cursor's
+     * cell, the last message and the keys.
+     */
+    fn render(&self) -> String {
+        let mut screen = String::from("\x1b[H\x1b[2J");
+        let (size, side) = (self.game.size, self.game.side_size);
+        let elapsed = self.game.timer.elapsed().as_secs();
+
+        if self.paused {
+            screen.push_str("Paused, press any key to resume.\n");
+            return screen;
+        }
+
+        // The grid, the boxes being outlined, the jigsaw regions being shaded instead
+        let conflicts = match self.config.validation_mode == Validation::Lenient {
+            true => self.game.conflicts(),
+            false => vec![],
+        };
+        let (jigsaw, region_colors) = (self.game.is_jigsaw(), self.game.region_colors());
+        let border = format!("{}+\n", format!("+{}", "-".repeat(3 * size)).repeat(size))
+            .bright_blue()
+            .to_string();
+        for r in 0..side {
+            if r == 0 || (r % size == 0 && !jigsaw) {
+                screen.push_str(&border);
+            }
+            for c in 0..side {
+                match c % size {
+                    0 if c == 0 || !jigsaw => screen.push_str(&"|".bright_blue().to_string()),
+                    0 => screen.push(' '),
+                    _ => (),
+                }
+                screen.push_str(&self.render_cell(
+                    self.game.index(r, c),
+                    &conflicts,
+                    &region_colors,
+                ));
+            }
+            screen.push_str(&format!("{}\n", "|".bright_blue()));
+        }
+        screen.push_str(&border);
+
+        // The status bar
+        let mut status = format!(
+            "{} - {:02}:{:02} - {}% - {}",
+            match self.notes_mode {
+                true => "Notes",
+                false => "Values",
+            },
+            elapsed / 60,
+            elapsed % 60,
+            self.game.completion(),
+            self.game.rating()
+        );
+        if !self.game.guesses.is_empty() {
+            status.push_str(&format!(" - Guess depth {}", self.game.guesses.len()));
+        }
+        if self.game.auto_notes {
+            status.push_str(" - Auto notes");
+        }
+        if let Some(hot_seat) = self.game.hot_seat.as_ref() {
+            status.push_str(&format!(" - Player {}'s turn", hot_seat.current + 1));
+        }
+        screen.push_str(&format!("{}\n", status.reversed()));
+
+        let cell = &self.game.grid[self.game.index(self.cursor.0, self.cursor.1)];
+        let notes: Vec<String> = (1..=side as u8)
+            .filter(|v| cell.value() == 0 && cell.has_note(*v))
+            .map(|v| value_symbol(v).to_string())
+            .collect();
+        screen.push_str(&format!(
+            "Row {}, column {} - Notes: {}\n",
+            self.cursor.0 + 1,
+            self.cursor.1 + 1,
+            notes.join(" ")
+        ));
+        match self.message.as_ref() {
+            Some((message, true)) => screen.push_str(&format!("{}\n", message.bright_green())),
+            Some((message, false)) => screen.push_str(&format!("{}\n", message.bright_red())),
+            None => screen.push('\n'),
+        }
+        screen.push_str(&KEYS.bright_black().to_string());
+
+        screen
+    }
+}
+
+impl Ui for Tui {
+    /**
+     * Plays the next puzzle of the pool, saved in the save folder.
+     */
+    fn new_random_game(&mut self) -> Result<(), UiError> {
+        let current_utc = chrono::offset::Utc::now();
+        let saving_path = format!("{}{}.game", self.config.save_folder_path, current_utc);
+
+        let mut game = Game::new(self.config.game_size, Some(&saving_path))?;
+        let puzzle = self.pool.take();
+        game.set_regions(puzzle.regions().to_vec());
+        game.grid = puzzle.grid;
+        game.solution = puzzle.solution;
+        game.variant = puzzle.variant;
+        self.play(game);
+        if self.config.auto_notes {
+            self.game.auto_notes = true;
+            self.game.fill_notes()?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Plays the f-puzzles JSON puzzle at `path`.
+     */
+    fn load_puzzle(&mut self, path: &str) -> Result<(), UiError> {
+        let json = fs::read_to_string(path).map_err(|_| UiError::ImportPuzzleError)?;
+        let current_utc = chrono::offset::Utc::now();
+        let saving_path = format!("{}{}.game", self.config.save_folder_path, current_utc);
+
+        self.play(annotations::import_fpuzzles(&json, Some(&saving_path))?);
+        if self.config.auto_notes {
+            self.game.auto_notes = true;
+            self.game.fill_notes()?;
+        }
+        self.game.save()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,289 @@
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::Canvas;
+use sdl2::ttf::Font;
+use sdl2::video::Window;
+
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::errors::UiError;
+use crate::game::Game;
+use crate::time_attack::TimeAttack;
+use crate::traits::{Displayable, GUIConfig, ScreenOutcome};
+
+static COLOR_BCK: Color = Color::BLACK;
+static COLOR_HOVER: Color = Color::RGBA(75, 75, 75, 255);
+static COLOR_FONT: Color = Color::WHITE;
+static COLOR_TITLE: Color = Color::GREEN;
+static COLOR_BAD_MSG: Color = Color::RED;
+
+/// Space between the border of the window and the text.
+const MARGIN: i32 = 40;
+/// Height of a line of text or of a button.
+const ROW_HEIGHT: i32 = 40;
+
+/// Buttons of the screen, below the results.
+const BUTTON_NEW_GAME: usize = 0;
+const BUTTON_REPLAY: usize = 1;
+const BUTTON_FAVORITE: usize = 2;
+const BUTTON_MENU: usize = 3;
+
+/**
+ * Congratulates the player once a grid is solved, with how the game went, and lets them start
+ * a new game, replay the solved one, add its puzzle to the favorites or go back to the main
+ * menu.
+ */
+#[derive(Default)]
+pub struct VictoryScreen<'a> {
+    font: Option<Rc<Font<'a, 'a>>>,
+
+    /// Lines describing the solved game: play time, mistakes, hints...
+    results: Vec<String>,
+    /// Whether the solved puzzle is one of the favorites of the profile playing.
+    favorite: bool,
+    /// Why the favorites could not be changed, if they could not.
+    message: Option<String>,
+
+    /// The button under the mouse cursor.
+    hovered_button: Option<usize>,
+    viewport: Option<Rect>,
+}
+
+impl<'a> VictoryScreen<'a> {
+    pub fn set_font(&mut self, new_font: Rc<Font<'a, 'a>>) {
+        self.font = Some(new_font);
+    }
+
+    /**
+     * Shows the results of `game`, solved in `elapsed`, followed by the `stats` of the profile
+     * playing.
+     */
+    pub fn set_results(&mut self, game: &Game, elapsed: Duration, stats: &[String]) {
+        let elapsed = elapsed.as_secs();
+        self.results = vec![
+            format!("Time: {:02}:{:02}", elapsed / 60, elapsed % 60),
+            format!("Mistakes: {}", game.mistakes),
+            format!("Hints used: {}", game.hints),
+        ];
+        if let Some(difficulty) = game.difficulty {
+            self.results.push(format!("Difficulty: {:?}", difficulty));
+        }
+        self.results.push(format!("Rating: {}", game.rating()));
+        if let Some(hot_seat) = game.hot_seat.as_ref() {
+            self.results
+                .extend(hot_seat.summary().lines().map(String::from));
+        }
+        self.results.extend(stats.iter().cloned());
+        self.hovered_button = None;
+        self.message = None;
+    }
+
+    /**
+     * Shows the splits and the score of the finished `attack` rather than the results of its
+     * last puzzle, then its `rank` among the time attacks of the profile playing and the
+     * `leaderboard`.
+     */
+    pub fn set_time_attack_results(
+        &mut self,
+        attack: &TimeAttack,
+        rank: Option<usize>,
+        leaderboard: &[String],
+    ) {
+        self.results = attack.summary();
+        self.results.push(match rank {
+            Some(rank) => format!("Rank {} on the leaderboard", rank + 1),
+            None => String::from("Not on the leaderboard"),
+        });
+        self.results.extend(leaderboard.iter().cloned());
+        self.hovered_button = None;
+        self.message = None;
+    }
+
+    /**
+     * Shows whether the solved puzzle is a favorite, offering to add or remove it.
+     */
+    pub fn set_favorite(&mut self, favorite: bool) {
+        self.favorite = favorite;
+        self.message = None;
+    }
+
+    /**
+     * Shows `message` below the buttons, e.g. why the favorites could not be saved.
+     */
+    pub fn set_message(&mut self, message: String) {
+        self.message = Some(message);
+    }
+
+    /**
+     * Adds `line` at the top of the results, such as who won a network versus game.
+     */
+    #[cfg(feature = "net")]
+    pub fn add_result(&mut self, line: String) {
+        self.results.insert(0, line);
+    }
+
+    /**
+     * Returns the area of the `row`-th line of the screen, the title being the first one.
+     */
+    fn row_rect(&self, row: usize) -> Rect {
+        let width = self.viewport.map_or(0, |v| v.width() as i32);
+        Rect::new(
+            MARGIN,
+            MARGIN + row as i32 * ROW_HEIGHT,
+            (width - 2 * MARGIN).max(1) as u32,
+            ROW_HEIGHT as u32,
+        )
+    }
+
+    /**
+     * Returns the area of the button `button`, below the results.
+     */
+    fn button_rect(&self, button: usize) -> Rect {
+        self.row_rect(self.results.len() + 2 + button)
+    }
+
+    /**
+     * Returns the button at the given window position, if any.
+     */
+    fn button_at(&self, x: i32, y: i32) -> Option<usize> {
+        [BUTTON_NEW_GAME, BUTTON_REPLAY, BUTTON_FAVORITE, BUTTON_MENU]
+            .into_iter()
+            .find(|button| self.button_rect(*button).contains_point((x, y)))
+    }
+
+    /**
+     * Draws `text` with the screen's font, vertically centered in `area`.
+     */
+    fn draw_text(
+        &self,
+        canvas: &mut Canvas<Window>,
+        text: &str,
+        color: Color,
+        area: Rect,
+    ) -> Result<(), UiError> {
+        let texture_creator = canvas.texture_creator();
+        let surface = self
+            .font
+            .as_ref()
+            .unwrap()
+            .render(text)
+            .blended(color)
+            .map_err(UiError::sdl2)?;
+        let texture = texture_creator
+            .create_texture_from_surface(surface)
+            .map_err(UiError::sdl2)?;
+
+        let width = texture.query().width.min(area.width());
+        let height = texture.query().height.min(area.height());
+        canvas
+            .copy(
+                &texture,
+                Rect::new(0, 0, width, height),
+                Rect::new(
+                    area.x(),
+                    area.y() + (area.height() - height) as i32 / 2,
+                    width,
+                    height,
+                ),
+            )
+            .map_err(UiError::sdl2)
+    }
+}
+
+impl<'a> Displayable for VictoryScreen<'a> {
+    fn new() -> Self {
+        VictoryScreen {
+            ..Default::default()
+        }
+    }
+
+    fn init(&mut self, canvas: &mut Canvas<Window>, _config: &GUIConfig) -> Result<(), UiError> {
+        self.viewport = Some(canvas.viewport());
+
+        Ok(())
+    }
+
+    fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        canvas.set_draw_color(COLOR_BCK);
+        canvas.clear();
+
+        self.draw_text(canvas, "Grid completed !", COLOR_TITLE, self.row_rect(0))?;
+        for (i, line) in self.results.iter().enumerate() {
+            self.draw_text(canvas, line, COLOR_FONT, self.row_rect(i + 1))?;
+        }
+
+        let favorite = match self.favorite {
+            true => "Remove from favorites (F)",
+            false => "Add to favorites (F)",
+        };
+        for (button, text) in [
+            (BUTTON_NEW_GAME, "New Game"),
+            (BUTTON_REPLAY, "Replay (R)"),
+            (BUTTON_FAVORITE, favorite),
+            (BUTTON_MENU, "Main Menu"),
+        ] {
+            let area = self.button_rect(button);
+            if self.hovered_button == Some(button) {
+                canvas.set_draw_color(COLOR_HOVER);
+                canvas.fill_rect(area).map_err(UiError::sdl2)?;
+            }
+            self.draw_text(canvas, text, COLOR_FONT, area)?;
+        }
+
+        if let Some(message) = self.message.as_ref() {
+            let area = self.button_rect(BUTTON_MENU + 1);
+            self.draw_text(canvas, message, COLOR_BAD_MSG, area)?;
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        self.viewport = Some(canvas.viewport());
+
+        Ok(())
+    }
+
+    fn update(&mut self, event: &Event) -> Result<ScreenOutcome, UiError> {
+        match event {
+            Event::MouseMotion { x, y, .. } => {
+                let hovered_button = self.button_at(*x, *y);
+                if hovered_button != self.hovered_button {
+                    self.hovered_button = hovered_button;
+                    return Ok(ScreenOutcome::Updated);
+                }
+            }
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => match self.button_at(*x, *y) {
+                Some(BUTTON_NEW_GAME) => return Ok(ScreenOutcome::ChooseNewGame),
+                Some(BUTTON_REPLAY) => return Ok(ScreenOutcome::Replay),
+                Some(BUTTON_FAVORITE) => return Ok(ScreenOutcome::Favorite),
+                Some(_) => return Ok(ScreenOutcome::Menu),
+                None => {}
+            },
+            Event::KeyDown {
+                keycode: Some(Keycode::N),
+                ..
+            } => return Ok(ScreenOutcome::ChooseNewGame),
+            Event::KeyDown {
+                keycode: Some(Keycode::R),
+                ..
+            } => return Ok(ScreenOutcome::Replay),
+            Event::KeyDown {
+                keycode: Some(Keycode::F),
+                ..
+            } => return Ok(ScreenOutcome::Favorite),
+            _ => {}
+        }
+
+        Ok(ScreenOutcome::Unchanged)
+    }
+}
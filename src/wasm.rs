@@ -0,0 +1,56 @@
+//! Bindings of the engine for the web pages, built with
+//! `cargo build --lib --target wasm32-unknown-unknown --no-default-features --features wasm`
+//! then `wasm-bindgen --target web`. The puzzles are written like `Game::puzzle_id`, and the
+//! errors are thrown as strings.
+
+use crate::game::{value_symbol, Game};
+use crate::generator::{self, Difficulty, GenerationConfig, PuzzleRecord};
+use crate::solver::PuzzleCheck;
+
+use wasm_bindgen::prelude::*;
+
+/**
+ * Generates a puzzle of the given size (2 to 4, see `generator::SIZES`) and difficulty (`easy`,
+ * `medium`, `hard` or `expert`), always the same one for a given `seed`. Returns it as JSON, like
+ * `sudocurs generate --format json`.
+ */
+#[wasm_bindgen]
+pub fn generate(size: usize, difficulty: &str, seed: Option<u32>) -> Result<String, String> {
+    if !generator::SIZES.contains(&size) {
+        return Err(String::from("The size must be between 2 and 4."));
+    }
+    let difficulty: Difficulty = serde_json::from_value(difficulty.into())
+        .map_err(|_| String::from("The difficulty must be easy, medium, hard or expert."))?;
+
+    let config = GenerationConfig::default();
+    let game = match seed {
+        Some(seed) => generator::generate_seeded(size, difficulty, &config, seed.into()),
+        None => generator::generate(size, difficulty, &config),
+    };
+    serde_json::to_string(&PuzzleRecord::new(&game)).map_err(|e| e.to_string())
+}
+
+/**
+ * Returns the solution of `puzzle`, written like it.
+ */
+#[wasm_bindgen]
+pub fn solve(puzzle: &str) -> Result<String, String> {
+    let game = Game::from_puzzle(puzzle, None).map_err(|e| e.to_string())?;
+    let solution = game.solution().map_err(|e| e.to_string())?;
+
+    Ok(solution.into_iter().map(value_symbol).collect())
+}
+
+/**
+ * Checks `puzzle`: whether it describes a grid, its conflicting clues, its number of solutions
+ * and its grade. Returns the check as JSON, like `sudocurs check --json`.
+ */
+#[wasm_bindgen]
+pub fn validate(puzzle: &str) -> String {
+    let check = match Game::from_puzzle(puzzle, None) {
+        Ok(mut game) => PuzzleCheck::new(&mut game),
+        Err(_) => PuzzleCheck::invalid(),
+    };
+
+    serde_json::to_string(&check).unwrap()
+}
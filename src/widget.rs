@@ -0,0 +1,313 @@
+use sdl2::image::LoadSurface;
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::rect::{Point, Rect};
+use sdl2::render::{Canvas, Texture};
+use sdl2::surface::Surface;
+use sdl2::ttf::Font;
+use sdl2::video::Window;
+
+use crate::errors::{self, UiError};
+use crate::traits::ScreenOutcome;
+
+/// Colors of the buttons drawn as text when their images are missing, and of them hovered.
+static COLOR_LABEL: Color = Color::WHITE;
+static COLOR_LABEL_BCK: Color = Color::RGBA(0, 90, 90, 255);
+static COLOR_LABEL_HOVER_BCK: Color = Color::RGBA(255, 110, 50, 255);
+/// Smallest size of the buttons drawn as text, so that the entries of a menu line up.
+const LABEL_SIZE: (u32, u32) = (260, 60);
+/// Space between the text of a button and its border.
+const LABEL_PADDING: u32 = 12;
+
+/**
+ * A button drawn with a texture, and another one while the mouse is over it, telling the screen
+ * what to do once it is clicked.
+ */
+pub struct Button {
+    texture: Texture,
+    hover_texture: Texture,
+    /// Where the button is drawn, sized after its texture.
+    pub rect: Rect,
+    /// Whether the mouse is over the button.
+    pub hovered: bool,
+    /// Whether the button is drawn and clicked, a hidden one keeping its place in its column.
+    pub visible: bool,
+    /// What the screen does once the button is clicked.
+    pub on_click: ScreenOutcome,
+}
+
+/**
+ * Loads the image at `path` as a texture of `canvas`.
+ */
+fn load_texture(canvas: &Canvas<Window>, path: &str) -> Result<Texture, UiError> {
+    let surface = Surface::from_file(path).map_err(|e| UiError::LoadSpriteError(e.into()))?;
+    canvas
+        .texture_creator()
+        .create_texture_from_surface(&surface)
+        .map_err(UiError::sdl2)
+}
+
+/**
+ * Renders `text` with `font` in `color` as a texture of `canvas`.
+ */
+#[cfg(feature = "fetch")]
+fn render_text(
+    canvas: &Canvas<Window>,
+    font: &Font,
+    text: &str,
+    color: Color,
+) -> Result<Texture, UiError> {
+    let surface = font.render(text).blended(color).map_err(UiError::sdl2)?;
+    canvas
+        .texture_creator()
+        .create_texture_from_surface(&surface)
+        .map_err(UiError::sdl2)
+}
+
+/**
+ * Renders `text` with `font` centered on a `background` rectangle, as a texture of `canvas`.
+ */
+fn render_label(
+    canvas: &Canvas<Window>,
+    font: &Font,
+    text: &str,
+    background: Color,
+) -> Result<Texture, UiError> {
+    let text = font
+        .render(text)
+        .blended(COLOR_LABEL)
+        .map_err(UiError::sdl2)?;
+    let width = LABEL_SIZE.0.max(text.width() + 2 * LABEL_PADDING);
+    let height = LABEL_SIZE.1.max(text.height() + 2 * LABEL_PADDING);
+    let mut surface =
+        Surface::new(width, height, PixelFormatEnum::ARGB8888).map_err(UiError::sdl2)?;
+    surface.fill_rect(None, background).map_err(UiError::sdl2)?;
+    let position = Rect::new(
+        (width - text.width()) as i32 / 2,
+        (height - text.height()) as i32 / 2,
+        text.width(),
+        text.height(),
+    );
+    text.blit(None, &mut surface, position)
+        .map_err(UiError::sdl2)?;
+
+    canvas
+        .texture_creator()
+        .create_texture_from_surface(&surface)
+        .map_err(UiError::sdl2)
+}
+
+impl Button {
+    /**
+     * Returns a button drawn with `texture`, and `hover_texture` while hovered, its place being
+     * set by its column.
+     */
+    pub fn new(texture: Texture, hover_texture: Texture, on_click: ScreenOutcome) -> Self {
+        let query = texture.query();
+        Button {
+            texture,
+            hover_texture,
+            rect: Rect::new(0, 0, query.width, query.height),
+            hovered: false,
+            visible: true,
+            on_click,
+        }
+    }
+
+    /**
+     * Returns a button drawn with the images at `path` and `hover_path`.
+     */
+    pub fn from_files(
+        canvas: &Canvas<Window>,
+        path: &str,
+        hover_path: &str,
+        on_click: ScreenOutcome,
+    ) -> Result<Self, UiError> {
+        Ok(Button::new(
+            load_texture(canvas, path)?,
+            load_texture(canvas, hover_path)?,
+            on_click,
+        ))
+    }
+
+    /**
+     * Returns a button showing `label` with `font` on a plain background, for the images that
+     * are missing.
+     */
+    pub fn labeled(
+        canvas: &Canvas<Window>,
+        font: &Font,
+        label: &str,
+        on_click: ScreenOutcome,
+    ) -> Result<Self, UiError> {
+        Ok(Button::new(
+            render_label(canvas, font, label, COLOR_LABEL_BCK)?,
+            render_label(canvas, font, label, COLOR_LABEL_HOVER_BCK)?,
+            on_click,
+        ))
+    }
+
+    /**
+     * Returns a button drawn with the images at `path` and `hover_path`, or showing `label` when
+     * they are not configured or cannot be loaded, so that the game runs without them.
+     */
+    pub fn from_files_or_label(
+        canvas: &Canvas<Window>,
+        (path, hover_path): (&str, &str),
+        font: &Font,
+        label: &str,
+        on_click: ScreenOutcome,
+    ) -> Result<Self, UiError> {
+        if !path.is_empty() && !hover_path.is_empty() {
+            match Button::from_files(canvas, path, hover_path, on_click) {
+                Ok(button) => return Ok(button),
+                Err(e) => log::warn!(
+                    "{}, the '{}' button is drawn as text",
+                    errors::report(&e),
+                    label
+                ),
+            }
+        }
+
+        Button::labeled(canvas, font, label, on_click)
+    }
+
+    /**
+     * Returns a button showing `text` with `font`, in `color` and in `hover_color` while
+     * hovered.
+     */
+    #[cfg(feature = "fetch")]
+    pub fn from_text(
+        canvas: &Canvas<Window>,
+        font: &Font,
+        text: &str,
+        (color, hover_color): (Color, Color),
+        on_click: ScreenOutcome,
+    ) -> Result<Self, UiError> {
+        Ok(Button::new(
+            render_text(canvas, font, text, color)?,
+            render_text(canvas, font, text, hover_color)?,
+            on_click,
+        ))
+    }
+
+    /**
+     * Returns whether the visible button is at the given window position.
+     */
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        self.visible && self.rect.contains_point(Point::new(x, y))
+    }
+
+    /**
+     * Draws the button, if it is visible.
+     */
+    pub fn draw(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        if !self.visible {
+            return Ok(());
+        }
+        let texture = match self.hovered {
+            true => &self.hover_texture,
+            false => &self.texture,
+        };
+        canvas.copy(texture, None, self.rect).map_err(UiError::sdl2)
+    }
+}
+
+/**
+ * Buttons stacked in a column centered on the screen, the way the main menu lays out its
+ * entries.
+ */
+#[derive(Default)]
+pub struct Column {
+    buttons: Vec<Button>,
+    /// Space between two buttons.
+    spacing: u32,
+}
+
+impl Column {
+    /**
+     * Returns an empty column, its buttons `spacing` apart.
+     */
+    pub fn new(spacing: u32) -> Self {
+        Column {
+            buttons: Vec::new(),
+            spacing,
+        }
+    }
+
+    /**
+     * Adds `button` below the others. The column must be laid out again.
+     */
+    pub fn push(&mut self, button: Button) {
+        self.buttons.push(button);
+    }
+
+    /**
+     * Returns the button of `outcome`, if there is one.
+     */
+    pub fn button_mut(&mut self, outcome: ScreenOutcome) -> Option<&mut Button> {
+        self.buttons
+            .iter_mut()
+            .find(|button| button.on_click == outcome)
+    }
+
+    /**
+     * Returns the area of the column, none while it has no button.
+     */
+    pub fn rect(&self) -> Option<Rect> {
+        let first = self.buttons.first()?.rect;
+        Some(
+            self.buttons
+                .iter()
+                .fold(first, |area, b| area.union(b.rect)),
+        )
+    }
+
+    /**
+     * Places the buttons one below the other, the column being centered in `viewport`.
+     */
+    pub fn layout(&mut self, viewport: Rect) {
+        let gaps = self.spacing * self.buttons.len().saturating_sub(1) as u32;
+        let height = self.buttons.iter().map(|b| b.rect.height()).sum::<u32>() + gaps;
+        let center_x = viewport.x() + viewport.width() as i32 / 2;
+        let mut y = viewport.y() + (viewport.height() as i32 - height as i32) / 2;
+        for button in self.buttons.iter_mut() {
+            let (width, height) = button.rect.size();
+            button.rect = Rect::new(center_x - width as i32 / 2, y, width, height);
+            y += (height + self.spacing) as i32;
+        }
+    }
+
+    /**
+     * Highlights the button under the mouse at the given window position. Returns whether one
+     * changed.
+     */
+    pub fn hover(&mut self, x: i32, y: i32) -> bool {
+        let mut changed = false;
+        for button in self.buttons.iter_mut() {
+            let hovered = button.contains(x, y);
+            changed |= hovered != button.hovered;
+            button.hovered = hovered;
+        }
+
+        changed
+    }
+
+    /**
+     * Returns the outcome of the button clicked at the given window position, if any.
+     */
+    pub fn clicked(&self, x: i32, y: i32) -> Option<ScreenOutcome> {
+        self.buttons
+            .iter()
+            .find(|button| button.contains(x, y))
+            .map(|button| button.on_click)
+    }
+
+    /**
+     * Draws the visible buttons.
+     */
+    pub fn draw(&self, canvas: &mut Canvas<Window>) -> Result<(), UiError> {
+        self.buttons
+            .iter()
+            .try_for_each(|button| button.draw(canvas))
+    }
+}
@@ -0,0 +1,132 @@
+//! Behavior of the solvers and of the grader on a corpus of known puzzles: ones with a single
+//! solution of every difficulty, unsolvable ones and ones with several solutions.
+
+use rs_sudoku::game::{self, Game};
+use rs_sudoku::generator::{Difficulty, PuzzleRecord};
+use rs_sudoku::grader::Technique;
+use rs_sudoku::solver::{PuzzleCheck, Solver, SolverKind};
+
+/// Puzzles with a single solution, from easy to expert, with their solution and grade.
+const SOLVABLE: &str = include_str!("fixtures/solvable.json");
+/// Puzzles without any solution, one per line, the first one with conflicting clues.
+const UNSOLVABLE: &str = include_str!("fixtures/unsolvable.sdm");
+/// Puzzles with several solutions, one per line.
+const MULTIPLE: &str = include_str!("fixtures/multiple.sdm");
+
+const SOLVERS: [SolverKind; 4] = [
+    SolverKind::Dlx,
+    SolverKind::Backtracking,
+    SolverKind::Obvious,
+    SolverKind::Human,
+];
+
+fn solvable() -> Vec<PuzzleRecord> {
+    serde_json::from_str(SOLVABLE).unwrap()
+}
+
+/**
+ * Returns the values of the grid of `game`, written like the solutions of the records.
+ */
+fn values(game: &Game) -> String {
+    game.grid
+        .iter()
+        .map(|cell| game::value_symbol(cell.value()))
+        .collect()
+}
+
+#[test]
+fn test_corpus_difficulties() {
+    let records = solvable();
+    for difficulty in [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ] {
+        assert!(
+            records
+                .iter()
+                .filter(|record| record.difficulty == Some(difficulty))
+                .count()
+                >= 2
+        );
+    }
+}
+
+#[test]
+fn test_corpus_grader() {
+    for record in solvable() {
+        let game = Game::from_puzzle(&record.puzzle, None).unwrap();
+        let grade = game.rating();
+        assert_eq!(
+            Some(grade.difficulty()),
+            record.difficulty,
+            "{}",
+            record.puzzle
+        );
+        assert_eq!(grade.to_string(), record.rating, "{}", record.puzzle);
+        assert_eq!(grade.score(), record.score, "{}", record.puzzle);
+        assert_eq!(game.nb_clues(), record.clues);
+    }
+}
+
+#[test]
+fn test_corpus_solvable() {
+    for record in solvable() {
+        let puzzle = Game::from_puzzle(&record.puzzle, None).unwrap();
+        let grade = puzzle.rating();
+        for kind in SOLVERS {
+            let mut game = Game::from_puzzle(&record.puzzle, None).unwrap();
+            assert_eq!(kind.count_solutions(&mut game, 2), 1, "{:?}", kind);
+
+            let solved = kind.solve(&mut game).is_ok();
+            let expected = match kind {
+                SolverKind::Dlx | SolverKind::Backtracking => true,
+                // Naked singles only
+                SolverKind::Obvious => {
+                    grade.solved && matches!(grade.hardest, None | Some(Technique::NakedSingle))
+                }
+                SolverKind::Human => grade.solved,
+            };
+            assert_eq!(solved, expected, "{:?} on {}", kind, record.puzzle);
+            if solved {
+                assert_eq!(values(&game), record.solution, "{:?}", kind);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_corpus_unsolvable() {
+    for (i, puzzle) in UNSOLVABLE.lines().enumerate() {
+        let mut game = Game::from_puzzle(puzzle, None).unwrap();
+        let check = PuzzleCheck::new(&mut game);
+        assert_eq!(check.conflicts.is_empty(), i != 0, "{}", puzzle);
+        assert_eq!(check.solutions, 0);
+        assert!(!game.rating().solved);
+
+        for kind in SOLVERS {
+            let mut game = Game::from_puzzle(puzzle, None).unwrap();
+            assert_eq!(kind.count_solutions(&mut game, 2), 0, "{:?}", kind);
+            assert!(kind.solve(&mut game).is_err(), "{:?} on {}", kind, puzzle);
+        }
+    }
+}
+
+#[test]
+fn test_corpus_multiple() {
+    for puzzle in MULTIPLE.lines() {
+        let mut game = Game::from_puzzle(puzzle, None).unwrap();
+        let check = PuzzleCheck::new(&mut game);
+        assert!(check.conflicts.is_empty());
+        assert_eq!(check.solutions, 2);
+        assert_eq!(check.rating, None);
+
+        // Only a puzzle with a single solution is solved
+        for kind in SOLVERS {
+            let mut game = Game::from_puzzle(puzzle, None).unwrap();
+            assert_eq!(kind.count_solutions(&mut game, 2), 2, "{:?}", kind);
+            assert!(kind.solve(&mut game).is_err(), "{:?} on {}", kind, puzzle);
+        }
+    }
+}